@@ -128,6 +128,8 @@ class TrainingSample:
     def value(self) -> float: ...
     @property
     def player(self) -> PlayerId: ...
+    @property
+    def values(self) -> List[float]: ...
     def __repr__(self) -> str: ...
     def state_numpy(self) -> NDArray[np.float32]: ...
     def policy_numpy(self) -> NDArray[np.float32]: ...
@@ -145,12 +147,58 @@ class ExperienceBuffer:
     def clear(self) -> None: ...
     def to_training_samples(self) -> List[TrainingSample]: ...
     def sample_batch(self, batch_size: int, seed: int) -> List[TrainingSample]: ...
+    def set_replay(
+        self,
+        priority_exponent: float = 0.6,
+        importance_exponent: float = 0.4,
+        recency_decay: float = 1.0,
+        stratify: str = "none",
+        phase_buckets: int = 3,
+    ) -> None: ...
+    def enable_seat_rotation(
+        self, player_count: int, features_per_player: int = 5
+    ) -> None: ...
+    @property
+    def generation(self) -> int: ...
+    @generation.setter
+    def generation(self, generation: int) -> None: ...
+    def sample_replay(
+        self, batch_size: int, seed: int
+    ) -> List[Tuple[Tuple[int, int], TrainingSample, float]]: ...
+    def update_priorities(
+        self, ids: List[Tuple[int, int]], priorities: List[float]
+    ) -> int: ...
     def to_numpy_batch(self) -> Tuple[
         NDArray[np.float32],  # states: [N, state_dim]
         NDArray[np.float32],  # policies: [N, action_dim]
         NDArray[np.float32],  # values: [N]
     ]: ...
 
+# Agents
+
+class Agent:
+    """A player for SimpleGame seats: random, MCTS, network or callback."""
+    @staticmethod
+    def random(name: str = "random") -> Agent: ...
+    @staticmethod
+    def mcts(iterations: int = 100, name: str = "mcts") -> Agent: ...
+    @staticmethod
+    def network_policy(
+        network: PolicyValueNetwork, temperature: float = 0.0, name: str = "policy"
+    ) -> Agent: ...
+    @staticmethod
+    def network_mcts(
+        network: PolicyValueNetwork, iterations: int = 100, name: str = "network_mcts"
+    ) -> Agent: ...
+    @staticmethod
+    def callback(
+        callback: Callable[[PlayerId, List[Action]], Optional[int]], name: str = "callback"
+    ) -> Agent: ...
+    @property
+    def name(self) -> str: ...
+    def last_action_probs(self) -> Optional[List[Tuple[Action, float]]]: ...
+    def __repr__(self) -> str: ...
+
 # Self-play
 
 class SelfPlayConfig:
@@ -187,6 +235,7 @@ class SimpleGameWorker:
     ) -> None: ...
     def play_game(self, seed: int) -> Trajectory: ...
     def play_game_with_network(self, seed: int, network: PolicyValueNetwork) -> Trajectory: ...
+    def play_game_with_agents(self, seed: int, agents: List[Agent]) -> Trajectory: ...
     def play_games(self, count: int, base_seed: int) -> List[Trajectory]: ...
     def play_games_with_network(
         self, count: int, base_seed: int, network: PolicyValueNetwork
@@ -207,6 +256,8 @@ class SimpleGame:
     ) -> None: ...
     def legal_actions(self) -> List[Action]: ...
     def apply_action(self, action: Action) -> None: ...
+    def validate_action(self, action: Action) -> Optional[str]: ...
+    def select_action(self, agent: Agent, seed: int = 0) -> Optional[Action]: ...
     def is_terminal(self) -> Optional[PlayerId]: ...
     def has_winner(self) -> bool: ...
     @property
@@ -0,0 +1,281 @@
+//! Agents that do not search: random, greedy, scripted and replay.
+
+use crate::core::{Action, GameRng, GameState, PlayerId};
+use crate::rules::RulesEngine;
+use crate::training::Trajectory;
+
+use super::traits::Agent;
+
+/// Agent that plays uniformly random legal actions.
+#[derive(Clone, Debug)]
+pub struct RandomAgent {
+    name: String,
+}
+
+impl RandomAgent {
+    /// Create a random agent.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl<E: RulesEngine> Agent<E> for RandomAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        let actions = engine.legal_actions(state, player);
+        GameRng::new(seed).choose(&actions).cloned()
+    }
+}
+
+/// Agent that plays the action whose resulting state scores best under a
+/// heuristic (one-ply lookahead).
+///
+/// The heuristic scores a state from the given player's perspective; ties
+/// go to the first legal action.
+pub struct GreedyAgent<H> {
+    name: String,
+    heuristic: H,
+}
+
+impl<H> GreedyAgent<H>
+where
+    H: Fn(&GameState, PlayerId) -> f64 + Send,
+{
+    /// Create a greedy agent.
+    pub fn new(name: impl Into<String>, heuristic: H) -> Self {
+        Self {
+            name: name.into(),
+            heuristic,
+        }
+    }
+}
+
+impl<E, H> Agent<E> for GreedyAgent<H>
+where
+    E: RulesEngine + Clone,
+    H: Fn(&GameState, PlayerId) -> f64 + Send,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        _seed: u64,
+    ) -> Option<Action> {
+        // Fork lookahead states from a copy so the live RNG is untouched
+        let mut root = state.snapshot();
+        let mut best: Option<(f64, Action)> = None;
+        for action in engine.legal_actions(state, player) {
+            let mut next = root.clone_state();
+            engine.clone().apply_action(&mut next, player, &action);
+            let score = (self.heuristic)(&next, player);
+            if best.as_ref().is_none_or(|(s, _)| score > *s) {
+                best = Some((score, action));
+            }
+        }
+        best.map(|(_, action)| action)
+    }
+}
+
+/// Agent that follows a fixed rule.
+///
+/// The script receives the state, the acting player and the legal actions,
+/// and returns the index of the action to play.
+pub struct ScriptedAgent<F> {
+    name: String,
+    script: F,
+}
+
+impl<F> ScriptedAgent<F>
+where
+    F: FnMut(&GameState, PlayerId, &[Action]) -> Option<usize> + Send,
+{
+    /// Create a scripted agent.
+    pub fn new(name: impl Into<String>, script: F) -> Self {
+        Self {
+            name: name.into(),
+            script,
+        }
+    }
+}
+
+impl<E, F> Agent<E> for ScriptedAgent<F>
+where
+    E: RulesEngine,
+    F: FnMut(&GameState, PlayerId, &[Action]) -> Option<usize> + Send,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        _seed: u64,
+    ) -> Option<Action> {
+        let actions = engine.legal_actions(state, player);
+        let index = (self.script)(state, player, &actions)?;
+        actions.get(index).cloned()
+    }
+}
+
+/// Agent that replays a recorded sequence of its own actions.
+///
+/// Each game restarts from the beginning of the sequence. Returns `None`
+/// when the sequence is exhausted or the next recorded action is illegal.
+#[derive(Clone, Debug)]
+pub struct ReplayAgent {
+    name: String,
+    actions: Vec<Action>,
+    cursor: usize,
+}
+
+impl ReplayAgent {
+    /// Create a replay agent from an action sequence.
+    pub fn new(name: impl Into<String>, actions: Vec<Action>) -> Self {
+        Self {
+            name: name.into(),
+            actions,
+            cursor: 0,
+        }
+    }
+
+    /// Replay the actions `player` took in a trajectory.
+    pub fn from_trajectory(name: impl Into<String>, trajectory: &Trajectory, player: PlayerId) -> Self {
+        let actions = trajectory
+            .player_steps(player)
+            .map(|step| step.action_taken.clone())
+            .collect();
+        Self::new(name, actions)
+    }
+
+    /// Get the number of actions not yet played this game.
+    pub fn remaining(&self) -> usize {
+        self.actions.len() - self.cursor
+    }
+}
+
+impl<E: RulesEngine> Agent<E> for ReplayAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn new_game(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        _seed: u64,
+    ) -> Option<Action> {
+        let action = self.actions.get(self.cursor)?.clone();
+        if !engine.legal_actions(state, player).contains(&action) {
+            return None;
+        }
+        self.cursor += 1;
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+
+    fn game() -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(42)
+    }
+
+    #[test]
+    fn test_random_agent_is_deterministic() {
+        let (engine, state) = game();
+        let player = state.public.active_player;
+        let mut agent = RandomAgent::new("random");
+
+        let a = agent.select_action(&engine, &state, player, 7);
+        let b = agent.select_action(&engine, &state, player, 7);
+        assert!(a.is_some());
+        assert_eq!(a, b);
+        assert_eq!(Agent::<SimpleGame>::name(&agent), "random");
+    }
+
+    #[test]
+    fn test_greedy_agent_maximizes_heuristic() {
+        let (engine, mut state) = game();
+        let player = state.public.active_player;
+        let opponent = PlayerId::new(1 - player.0);
+
+        // Prefer states where the opponent has less life
+        let mut agent = GreedyAgent::new("greedy", move |s: &GameState, _| {
+            -(s.public.get_player_state(opponent, "life", 0) as f64)
+        });
+        let chosen = agent
+            .select_action(&engine, &state, player, 0)
+            .unwrap();
+
+        let actions = engine.legal_actions(&state, player);
+        let mut life_after = |action: &Action| {
+            let mut next = state.clone_state();
+            engine.clone().apply_action(&mut next, player, action);
+            next.public.get_player_state(opponent, "life", 0)
+        };
+        let best = actions
+            .iter()
+            .map(&mut life_after)
+            .min()
+            .unwrap();
+        assert_eq!(life_after(&chosen), best);
+    }
+
+    #[test]
+    fn test_scripted_agent() {
+        let (engine, state) = game();
+        let player = state.public.active_player;
+        let mut last = ScriptedAgent::new("last", |_: &GameState, _: PlayerId, actions: &[Action]| {
+            actions.len().checked_sub(1)
+        });
+
+        let actions = engine.legal_actions(&state, player);
+        let chosen = last.select_action(&engine, &state, player, 0);
+        assert_eq!(chosen.as_ref(), actions.last());
+    }
+
+    #[test]
+    fn test_replay_agent() {
+        let (engine, state) = game();
+        let player = state.public.active_player;
+        let first = engine.legal_actions(&state, player)[0].clone();
+
+        let mut agent = ReplayAgent::new("replay", vec![first.clone()]);
+        assert_eq!(
+            agent.select_action(&engine, &state, player, 0),
+            Some(first.clone())
+        );
+        assert_eq!(agent.remaining(), 0);
+        assert_eq!(agent.select_action(&engine, &state, player, 0), None);
+
+        Agent::<SimpleGame>::new_game(&mut agent);
+        assert_eq!(agent.remaining(), 1);
+    }
+}
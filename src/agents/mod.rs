@@ -0,0 +1,24 @@
+//! Agents: anything that can take a seat in a game.
+//!
+//! Every player — random baseline, heuristic bot, search, neural network,
+//! scripted test double or human front-end — implements the [`Agent`]
+//! trait. Self-play, the [`Arena`](crate::training::Arena) and the Python
+//! bindings all drive games through it.
+//!
+//! ## Provided agents
+//!
+//! - [`RandomAgent`]: uniformly random legal actions
+//! - [`GreedyAgent`]: one-ply lookahead on a heuristic
+//! - [`ScriptedAgent`]: a closure picks the action
+//! - [`ReplayAgent`]: replays a recorded action sequence
+//! - [`MctsAgent`]: pure MCTS with random rollouts
+//! - [`NetworkMctsAgent`]: PUCT search with network priors
+//! - [`NetworkPolicyAgent`]: plays straight from a network's policy head
+
+pub mod traits;
+pub mod basic;
+pub mod search;
+
+pub use traits::{Agent, SearchResult};
+pub use basic::{GreedyAgent, RandomAgent, ReplayAgent, ScriptedAgent};
+pub use search::{MctsAgent, NetworkMctsAgent, NetworkPolicyAgent};
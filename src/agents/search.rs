@@ -0,0 +1,296 @@
+//! Agents backed by tree search or a policy network.
+
+use crate::core::{Action, GameRng, GameState, PlayerId};
+use crate::mcts::{MCTSConfig, MCTSSearch, PUCT};
+use crate::nn::{policy_to_priors, PolicyValueNetwork, StateEncoder};
+use crate::rules::RulesEngine;
+
+use super::traits::{Agent, SearchResult};
+
+/// Capture the statistics of a finished search.
+fn search_result<E: RulesEngine + Clone>(search: &MCTSSearch<E>) -> SearchResult {
+    SearchResult {
+        action_probs: search.action_probabilities(),
+        root_value: search.root_value(),
+    }
+}
+
+/// Agent that plays the most-visited action of a pure MCTS search.
+#[derive(Clone, Debug)]
+pub struct MctsAgent {
+    name: String,
+    iterations: u32,
+    config: MCTSConfig,
+    last: Option<SearchResult>,
+}
+
+impl MctsAgent {
+    /// Create an MCTS agent with the default (greedy) search config.
+    pub fn new(name: impl Into<String>, iterations: u32) -> Self {
+        Self {
+            name: name.into(),
+            iterations,
+            config: MCTSConfig::default(),
+            last: None,
+        }
+    }
+
+    /// Set the search config (builder pattern). The seed is overridden per move.
+    pub fn with_config(mut self, config: MCTSConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl<E: RulesEngine + Clone> Agent<E> for MctsAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        let config = self.config.clone().with_seed(seed);
+        let mut search = MCTSSearch::new(engine.clone(), config);
+        let action = search.search(&mut state.snapshot(), player, self.iterations);
+        self.last = Some(search_result(&search));
+        action
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        self.last.clone()
+    }
+}
+
+/// Agent that runs PUCT search guided by a policy/value network's priors.
+pub struct NetworkMctsAgent<N> {
+    name: String,
+    network: N,
+    encoder: Box<dyn StateEncoder>,
+    iterations: u32,
+    config: MCTSConfig,
+    last: Option<SearchResult>,
+}
+
+impl<N: PolicyValueNetwork> NetworkMctsAgent<N> {
+    /// Create a network-guided MCTS agent.
+    pub fn new(
+        name: impl Into<String>,
+        network: N,
+        encoder: Box<dyn StateEncoder>,
+        iterations: u32,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            network,
+            encoder,
+            iterations,
+            config: MCTSConfig::default(),
+            last: None,
+        }
+    }
+
+    /// Set the search config (builder pattern). The seed is overridden per move.
+    pub fn with_config(mut self, config: MCTSConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl<E: RulesEngine + Clone, N: PolicyValueNetwork> Agent<E> for NetworkMctsAgent<N> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        let actions = engine.legal_actions(state, player);
+        let (policy, _value) = self.network.predict(&self.encoder.encode(state, player));
+        let priors = policy_to_priors(&actions, &policy);
+
+        let config = self.config.clone().with_seed(seed);
+        let mut search = MCTSSearch::new(engine.clone(), config).with_selection(PUCT);
+        let action = search.search_with_priors(&mut state.snapshot(), player, self.iterations, &priors);
+        self.last = Some(search_result(&search));
+        action
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        self.last.clone()
+    }
+}
+
+/// Agent that plays directly from a network's policy head, without search.
+///
+/// With temperature 0 (the default) it plays the highest-prior action
+/// (ties go to the first); otherwise it samples proportional to
+/// `prior^(1/temperature)`.
+pub struct NetworkPolicyAgent<N> {
+    name: String,
+    network: N,
+    encoder: Box<dyn StateEncoder>,
+    temperature: f64,
+    last: Option<SearchResult>,
+}
+
+impl<N: PolicyValueNetwork> NetworkPolicyAgent<N> {
+    /// Create a greedy network policy agent.
+    pub fn new(name: impl Into<String>, network: N, encoder: Box<dyn StateEncoder>) -> Self {
+        Self {
+            name: name.into(),
+            network,
+            encoder,
+            temperature: 0.0,
+            last: None,
+        }
+    }
+
+    /// Set the sampling temperature (builder pattern).
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature.max(0.0);
+        self
+    }
+}
+
+impl<E: RulesEngine, N: PolicyValueNetwork> Agent<E> for NetworkPolicyAgent<N> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        let actions = engine.legal_actions(state, player);
+        if actions.is_empty() {
+            self.last = None;
+            return None;
+        }
+
+        let (policy, _value) = self.network.predict(&self.encoder.encode(state, player));
+        let priors = policy_to_priors(&actions, &policy);
+
+        let index = if self.temperature == 0.0 {
+            let mut best = 0;
+            for (i, (_, prior)) in priors.iter().enumerate() {
+                if *prior > priors[best].1 {
+                    best = i;
+                }
+            }
+            best
+        } else {
+            let weights: Vec<f32> = priors
+                .iter()
+                .map(|(_, p)| (*p as f64).powf(1.0 / self.temperature) as f32)
+                .collect();
+            GameRng::new(seed).choose_weighted(&weights).unwrap_or(0)
+        };
+
+        self.last = Some(SearchResult {
+            action_probs: priors.iter().map(|(a, p)| (a.clone(), *p as f64)).collect(),
+            root_value: None,
+        });
+        Some(priors[index].0.clone())
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        self.last.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+    use crate::nn::{EncodedState, SimpleGameEncoder, UniformPolicyZeroValue};
+
+    fn game() -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(42)
+    }
+
+    /// Network that puts all its policy mass on one action index.
+    struct PreferIndex(usize);
+
+    impl PolicyValueNetwork for PreferIndex {
+        fn predict(&self, _encoded: &EncodedState) -> (Vec<f32>, Vec<f32>) {
+            let mut policy = vec![0.0; self.0 + 1];
+            policy[self.0] = 1.0;
+            (policy, vec![0.0, 0.0])
+        }
+    }
+
+    #[test]
+    fn test_mcts_agent_records_search_result() {
+        let (engine, state) = game();
+        let player = state.public.active_player;
+        let mut agent = MctsAgent::new("mcts", 50);
+        assert!(Agent::<SimpleGame>::search_result(&agent).is_none());
+
+        let action = agent.select_action(&engine, &state, player, 3).unwrap();
+        let result = Agent::<SimpleGame>::search_result(&agent).unwrap();
+        assert!(result.action_probs.iter().any(|(a, _)| a == &action));
+        let total: f64 = result.action_probs.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(result.root_value.is_some());
+    }
+
+    #[test]
+    fn test_network_mcts_agent() {
+        let (engine, state) = game();
+        let player = state.public.active_player;
+        let mut agent = NetworkMctsAgent::new(
+            "net-mcts",
+            UniformPolicyZeroValue::new(10, 2),
+            Box::new(SimpleGameEncoder::new(2, 10)),
+            30,
+        );
+
+        assert!(agent.select_action(&engine, &state, player, 1).is_some());
+        assert!(Agent::<SimpleGame>::search_result(&agent).is_some());
+    }
+
+    #[test]
+    fn test_network_policy_agent_greedy_and_sampled() {
+        let (engine, state) = game();
+        let player = state.public.active_player;
+        let actions = engine.legal_actions(&state, player);
+        assert!(actions.len() > 1);
+
+        let mut greedy = NetworkPolicyAgent::new(
+            "policy",
+            PreferIndex(1),
+            Box::new(SimpleGameEncoder::new(2, 10)),
+        );
+        let chosen = greedy.select_action(&engine, &state, player, 0);
+        assert_eq!(chosen.as_ref(), actions.get(1));
+        let result = Agent::<SimpleGame>::search_result(&greedy).unwrap();
+        assert_eq!(result.action_probs.len(), actions.len());
+        assert!(result.root_value.is_none());
+
+        let mut sampled = NetworkPolicyAgent::new(
+            "policy",
+            UniformPolicyZeroValue::new(10, 2),
+            Box::new(SimpleGameEncoder::new(2, 10)),
+        )
+        .with_temperature(1.0);
+        let a = sampled.select_action(&engine, &state, player, 9);
+        let b = sampled.select_action(&engine, &state, player, 9);
+        assert!(a.is_some());
+        assert_eq!(a, b);
+    }
+}
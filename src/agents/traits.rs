@@ -0,0 +1,126 @@
+//! The common agent interface.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Action, GameState, PlayerId, PlayerMap};
+use crate::rules::RulesEngine;
+
+/// Search statistics from an agent's most recent decision.
+///
+/// Self-play records these as training targets.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// Probability of each legal action (visit distribution or policy).
+    pub action_probs: Vec<(Action, f64)>,
+
+    /// Root value estimate per player, if the agent computed one.
+    pub root_value: Option<PlayerMap<f64>>,
+}
+
+/// A player: bot, human or script.
+///
+/// Drivers (self-play, the arena, the game runner) call [`new_game`]
+/// once per game, [`select_action`] whenever the agent's seat must act,
+/// [`observe_action`] for every action applied (including the agent's
+/// own), and [`game_over`] once the game ends.
+///
+/// [`new_game`]: Agent::new_game
+/// [`select_action`]: Agent::select_action
+/// [`observe_action`]: Agent::observe_action
+/// [`game_over`]: Agent::game_over
+pub trait Agent<E: RulesEngine>: Send {
+    /// Display name used in reports.
+    fn name(&self) -> &str;
+
+    /// Called before each game starts.
+    fn new_game(&mut self) {}
+
+    /// Choose an action for `player`, or `None` if there is nothing to do.
+    ///
+    /// `seed` is unique per game and move, for deterministic randomness.
+    /// Agents that search or look ahead work on a
+    /// [`snapshot`](GameState::snapshot) of the state.
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action>;
+
+    /// Called after any player's action has been applied.
+    fn observe_action(&mut self, _state: &GameState, _player: PlayerId, _action: &Action) {}
+
+    /// Called once the game is over, with each player's reward.
+    fn game_over(&mut self, _state: &GameState, _rewards: &PlayerMap<f64>) {}
+
+    /// Statistics from the most recent [`select_action`](Agent::select_action),
+    /// for agents that search or evaluate a policy.
+    fn search_result(&self) -> Option<SearchResult> {
+        None
+    }
+}
+
+impl<E: RulesEngine, A: Agent<E> + ?Sized> Agent<E> for Box<A> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn new_game(&mut self) {
+        (**self).new_game();
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        (**self).select_action(engine, state, player, seed)
+    }
+
+    fn observe_action(&mut self, state: &GameState, player: PlayerId, action: &Action) {
+        (**self).observe_action(state, player, action);
+    }
+
+    fn game_over(&mut self, state: &GameState, rewards: &PlayerMap<f64>) {
+        (**self).game_over(state, rewards);
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        (**self).search_result()
+    }
+}
+
+impl<E: RulesEngine, A: Agent<E> + ?Sized> Agent<E> for &mut A {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn new_game(&mut self) {
+        (**self).new_game();
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &E,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        (**self).select_action(engine, state, player, seed)
+    }
+
+    fn observe_action(&mut self, state: &GameState, player: PlayerId, action: &Action) {
+        (**self).observe_action(state, player, action);
+    }
+
+    fn game_over(&mut self, state: &GameState, rewards: &PlayerMap<f64>) {
+        (**self).game_over(state, rewards);
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        (**self).search_result()
+    }
+}
@@ -11,17 +11,120 @@
 //! - `IntList`: Number lists (mana cost breakdown)
 //! - `TextList`: String lists (keywords, types)
 
+use std::sync::{OnceLock, RwLock};
+
 use rustc_hash::FxHashMap;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Key for accessing card attributes.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct AttributeKey(pub String);
+///
+/// Keys are interned when created with [`AttributeKey::new`] (which card
+/// registration and deserialization use): each distinct name maps to a
+/// small integer for the life of the process, so keys are `Copy` and
+/// compare in O(1). A key carries its name, and attribute maps can be
+/// searched by `&str` directly, so looking up an attribute by name never
+/// touches the intern table. Keys serialize as their names.
+#[derive(Clone, Copy)]
+pub struct AttributeKey {
+    id: u32,
+    name: &'static str,
+}
+
+/// Process-wide table of attribute key names.
+///
+/// Names are leaked when first interned; only names that appear on
+/// cards or are passed to `AttributeKey::new` are interned.
+#[derive(Default)]
+struct Interner {
+    ids: FxHashMap<&'static str, u32>,
+    names: Vec<&'static str>,
+}
+
+fn interner() -> &'static RwLock<Interner> {
+    static INTERNER: OnceLock<RwLock<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
 
 impl AttributeKey {
-    /// Create a new attribute key.
-    pub fn new(key: impl Into<String>) -> Self {
-        Self(key.into())
+    /// Get the key for a name, interning it if needed.
+    pub fn new(key: impl AsRef<str>) -> Self {
+        let key = key.as_ref();
+        if let Some(found) = Self::lookup(key) {
+            return found;
+        }
+
+        let mut interner = interner().write().unwrap_or_else(|e| e.into_inner());
+        if let Some(&id) = interner.ids.get(key) {
+            return Self { id, name: interner.names[id as usize] };
+        }
+        let id = u32::try_from(interner.names.len()).expect("too many attribute keys");
+        let name: &'static str = Box::leak(key.to_owned().into_boxed_str());
+        interner.names.push(name);
+        interner.ids.insert(name, id);
+        Self { id, name }
+    }
+
+    /// Get the key for a name only if it has been interned.
+    #[must_use]
+    pub fn lookup(key: &str) -> Option<Self> {
+        let interner = interner().read().unwrap_or_else(|e| e.into_inner());
+        interner
+            .ids
+            .get_key_value(key)
+            .map(|(&name, &id)| Self { id, name })
+    }
+
+    /// Get the key's name.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        self.name
+    }
+}
+
+// Equal ids mean equal names, so hashing the name is consistent with
+// `Eq` and lets `Attributes` be searched by `&str` through `Borrow`.
+impl PartialEq for AttributeKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for AttributeKey {}
+
+impl std::hash::Hash for AttributeKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl std::borrow::Borrow<str> for AttributeKey {
+    fn borrow(&self) -> &str {
+        self.name
+    }
+}
+
+impl std::fmt::Debug for AttributeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AttributeKey").field(&self.as_str()).finish()
+    }
+}
+
+impl std::fmt::Display for AttributeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AttributeKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AttributeKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::new(name))
     }
 }
 
@@ -33,7 +136,7 @@ impl From<&str> for AttributeKey {
 
 impl From<String> for AttributeKey {
     fn from(s: String) -> Self {
-        Self(s)
+        Self::new(s)
     }
 }
 
@@ -55,6 +158,18 @@ pub enum AttributeValue {
 }
 
 impl AttributeValue {
+    /// Get the type of this value.
+    #[must_use]
+    pub fn attribute_type(&self) -> AttributeType {
+        match self {
+            AttributeValue::Int(_) => AttributeType::Int,
+            AttributeValue::Bool(_) => AttributeType::Bool,
+            AttributeValue::Text(_) => AttributeType::Text,
+            AttributeValue::IntList(_) => AttributeType::IntList,
+            AttributeValue::TextList(_) => AttributeType::TextList,
+        }
+    }
+
     /// Get as integer if this is an Int value.
     #[must_use]
     pub fn as_int(&self) -> Option<i64> {
@@ -101,6 +216,34 @@ impl AttributeValue {
     }
 }
 
+/// The type of an [`AttributeValue`], without the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AttributeType {
+    /// Integer value.
+    Int,
+    /// Boolean flag.
+    Bool,
+    /// Text value.
+    Text,
+    /// List of integers.
+    IntList,
+    /// List of strings.
+    TextList,
+}
+
+impl std::fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AttributeType::Int => "int",
+            AttributeType::Bool => "bool",
+            AttributeType::Text => "text",
+            AttributeType::IntList => "int list",
+            AttributeType::TextList => "text list",
+        };
+        f.write_str(name)
+    }
+}
+
 // Convenient From implementations
 impl From<i64> for AttributeValue {
     fn from(v: i64) -> Self {
@@ -156,6 +299,32 @@ mod tests {
         let key1 = AttributeKey::new("power");
         let key2: AttributeKey = "power".into();
         assert_eq!(key1, key2);
+        assert_eq!(key1.as_str(), "power");
+        assert_ne!(key1, AttributeKey::new("toughness"));
+        assert_eq!(format!("{:?}", key1), "AttributeKey(\"power\")");
+    }
+
+    #[test]
+    fn test_attribute_key_interning() {
+        assert_eq!(AttributeKey::lookup("never-interned-key"), None);
+        let key = AttributeKey::new(String::from("interned-key"));
+        assert_eq!(AttributeKey::lookup("interned-key"), Some(key));
+
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, "\"interned-key\"");
+        assert_eq!(serde_json::from_str::<AttributeKey>(&json).unwrap(), key);
+
+        // Looking attributes up by name does not intern it
+        let card = crate::cards::CardDefinition::new(crate::cards::CardId::new(1), "Bear", crate::cards::CardTypeId::new(0));
+        assert!(card.get_attr("looked-up-only").is_none());
+        assert_eq!(AttributeKey::lookup("looked-up-only"), None);
+    }
+
+    #[test]
+    fn test_attribute_type() {
+        assert_eq!(AttributeValue::Int(1).attribute_type(), AttributeType::Int);
+        assert_eq!(AttributeValue::TextList(Vec::new()).attribute_type(), AttributeType::TextList);
+        assert_eq!(AttributeType::IntList.to_string(), "int list");
     }
 
     #[test]
@@ -197,12 +366,17 @@ mod tests {
         attrs.insert("flying".into(), true.into());
 
         assert_eq!(
-            attrs.get(&"power".into()).and_then(|v| v.as_int()),
+            attrs.get(&AttributeKey::from("power")).and_then(|v| v.as_int()),
             Some(3)
         );
         assert_eq!(
-            attrs.get(&"flying".into()).and_then(|v| v.as_bool()),
+            attrs.get(&AttributeKey::from("flying")).and_then(|v| v.as_bool()),
             Some(true)
         );
+
+        // Lookups by name need no key
+        assert_eq!(attrs.get("power").and_then(|v| v.as_int()), Some(3));
+        assert_eq!(attrs.get("never-interned-name"), None);
+        assert_eq!(AttributeKey::lookup("never-interned-name"), None);
     }
 }
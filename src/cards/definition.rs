@@ -116,7 +116,13 @@ impl CardDefinition {
     /// Get an attribute value.
     #[must_use]
     pub fn get_attr(&self, key: &str) -> Option<&AttributeValue> {
-        self.attributes.get(&AttributeKey::new(key))
+        self.attributes.get(key)
+    }
+
+    /// Get an attribute value by key.
+    #[must_use]
+    pub fn get(&self, key: AttributeKey) -> Option<&AttributeValue> {
+        self.attributes.get(&key)
     }
 
     /// Get an integer attribute with a default value.
@@ -171,8 +177,8 @@ mod tests {
         assert_eq!(card.id, CardId::new(1));
         assert_eq!(card.get_int("cost", 0), 3);
         assert_eq!(card.get_int("power", 0), 2);
-        assert_eq!(card.get_bool("flying", false), true);
-        assert_eq!(card.get_bool("trample", false), false); // default
+        assert!(card.get_bool("flying", false));
+        assert!(!card.get_bool("trample", false)); // default
     }
 
     #[test]
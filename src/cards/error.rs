@@ -0,0 +1,102 @@
+//! Errors from registering card definitions.
+
+use super::attributes::{AttributeKey, AttributeType};
+use super::definition::CardId;
+
+/// Why a card definition was rejected by a [`CardRegistry`](super::CardRegistry).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CardError {
+    /// A card with this ID is already registered.
+    DuplicateId(CardId),
+
+    /// A required attribute is missing.
+    MissingAttribute { card: CardId, key: AttributeKey },
+
+    /// An attribute has a different type than the schema declares.
+    WrongType {
+        card: CardId,
+        key: AttributeKey,
+        expected: AttributeType,
+        found: AttributeType,
+    },
+
+    /// An integer attribute (or list element) is outside its declared range.
+    OutOfRange {
+        card: CardId,
+        key: AttributeKey,
+        value: i64,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+
+    /// An attribute the schema does not declare for this card type.
+    UnknownAttribute {
+        card: CardId,
+        key: AttributeKey,
+        /// A declared key with a similar name, if any.
+        suggestion: Option<AttributeKey>,
+    },
+}
+
+impl CardError {
+    /// The card this error is about.
+    #[must_use]
+    pub fn card(&self) -> CardId {
+        match self {
+            CardError::DuplicateId(card)
+            | CardError::MissingAttribute { card, .. }
+            | CardError::WrongType { card, .. }
+            | CardError::OutOfRange { card, .. }
+            | CardError::UnknownAttribute { card, .. } => *card,
+        }
+    }
+}
+
+impl std::fmt::Display for CardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardError::DuplicateId(card) => write!(f, "{} is already registered", card),
+            CardError::MissingAttribute { card, key } => {
+                write!(f, "{}: required attribute '{}' is missing", card, key)
+            }
+            CardError::WrongType {
+                card,
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: attribute '{}' should be {} but is {}",
+                card, key, expected, found
+            ),
+            CardError::OutOfRange {
+                card,
+                key,
+                value,
+                min,
+                max,
+            } => {
+                write!(f, "{}: attribute '{}' value {} is outside ", card, key, value)?;
+                match (min, max) {
+                    (Some(min), Some(max)) => write!(f, "{}..={}", min, max),
+                    (Some(min), None) => write!(f, "{}..", min),
+                    (None, Some(max)) => write!(f, "..={}", max),
+                    (None, None) => write!(f, "its range"),
+                }
+            }
+            CardError::UnknownAttribute {
+                card,
+                key,
+                suggestion,
+            } => {
+                write!(f, "{}: attribute '{}' is not in the schema", card, key)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean '{}'?)", suggestion)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
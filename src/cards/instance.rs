@@ -143,10 +143,10 @@ impl CardInstance {
         self.state.insert(key.into(), value);
     }
 
-    /// Modify a state value by delta.
+    /// Modify a state value by delta, saturating at the `i64` bounds.
     pub fn modify_state(&mut self, key: &str, delta: i64) {
         let current = self.get_state(key, 0);
-        self.state.insert(key.to_string(), current + delta);
+        self.state.insert(key.to_string(), current.saturating_add(delta));
     }
 
     /// Check if a state flag is set (non-zero).
@@ -7,6 +7,7 @@
 //! - `CardDefinition`: Static card data with generic attributes
 //! - `CardInstance`: Runtime card state (zone, owner, counters)
 //! - `CardRegistry`: Card definition lookup
+//! - `AttributeSchema`: Declared attribute keys, types and ranges per card type
 //!
 //! ## Neutral Cards
 //!
@@ -15,10 +16,14 @@
 
 pub mod attributes;
 pub mod definition;
+pub mod error;
 pub mod instance;
 pub mod registry;
+pub mod schema;
 
-pub use attributes::{AttributeKey, AttributeValue, Attributes};
+pub use attributes::{AttributeKey, AttributeType, AttributeValue, Attributes};
 pub use definition::{CardDefinition, CardId, CardTypeId};
 pub use instance::CardInstance;
+pub use error::CardError;
 pub use registry::CardRegistry;
+pub use schema::{AttributeSchema, AttributeSpec, CardTypeSchema};
@@ -2,10 +2,14 @@
 //!
 //! The `CardRegistry` stores all card definitions for a game.
 //! It provides fast lookup by `CardId` and supports iteration.
+//! An optional `AttributeSchema` validates cards as they are registered.
 
 use rustc_hash::FxHashMap;
 
+use super::attributes::{AttributeKey, AttributeValue};
 use super::definition::{CardDefinition, CardId, CardTypeId};
+use super::error::CardError;
+use super::schema::AttributeSchema;
 
 /// Registry of card definitions.
 ///
@@ -30,6 +34,7 @@ use super::definition::{CardDefinition, CardId, CardTypeId};
 pub struct CardRegistry {
     cards: FxHashMap<CardId, CardDefinition>,
     next_id: u32,
+    schema: Option<AttributeSchema>,
 }
 
 impl CardRegistry {
@@ -39,20 +44,92 @@ impl CardRegistry {
         Self::default()
     }
 
+    /// Validate cards against a schema from now on (builder pattern).
+    ///
+    /// Panics if an already registered card violates it; use
+    /// [`set_schema`](Self::set_schema) to handle that case.
+    #[must_use]
+    pub fn with_schema(mut self, schema: AttributeSchema) -> Self {
+        if let Err(errors) = self.set_schema(schema) {
+            panic!("Registered cards violate attribute schema: {}", join(&errors));
+        }
+        self
+    }
+
+    /// Validate cards against a schema from now on.
+    ///
+    /// Fails, keeping the current schema, if an already registered card
+    /// violates the new one.
+    pub fn set_schema(&mut self, schema: AttributeSchema) -> Result<(), Vec<CardError>> {
+        let mut errors: Vec<CardError> = self
+            .sorted_ids()
+            .into_iter()
+            .flat_map(|id| schema.validate(&self.cards[&id]))
+            .collect();
+        if !errors.is_empty() {
+            errors.sort_by_key(|e| e.card().0);
+            return Err(errors);
+        }
+        self.schema = Some(schema);
+        Ok(())
+    }
+
+    /// Get the attribute schema, if any.
+    #[must_use]
+    pub fn schema(&self) -> Option<&AttributeSchema> {
+        self.schema.as_ref()
+    }
+
+    /// Check a card against the schema without registering it.
+    ///
+    /// Does not check for duplicate IDs.
+    #[must_use]
+    pub fn validate(&self, card: &CardDefinition) -> Vec<CardError> {
+        self.schema
+            .as_ref()
+            .map(|schema| schema.validate(card))
+            .unwrap_or_default()
+    }
+
+    /// Register a card definition, reporting every problem with it.
+    ///
+    /// Use this for cards loaded at runtime.
+    pub fn try_register(&mut self, card: CardDefinition) -> Result<(), Vec<CardError>> {
+        let mut errors = Vec::new();
+        if self.cards.contains_key(&card.id) {
+            errors.push(CardError::DuplicateId(card.id));
+        }
+        errors.extend(self.validate(&card));
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        self.cards.insert(card.id, card);
+        Ok(())
+    }
+
     /// Register a card definition.
     ///
-    /// Panics if a card with the same ID already exists.
+    /// Panics if a card with the same ID already exists or the card
+    /// violates the schema; use [`try_register`](Self::try_register) for
+    /// data that may be invalid.
     pub fn register(&mut self, card: CardDefinition) {
         if self.cards.contains_key(&card.id) {
             panic!("Card with ID {:?} already registered", card.id);
         }
-        self.cards.insert(card.id, card);
+        let id = card.id;
+        if let Err(errors) = self.try_register(card) {
+            panic!("Card {} violates attribute schema: {}", id, join(&errors));
+        }
     }
 
     /// Register a card and return a mutable reference.
     ///
-    /// Useful for building cards incrementally.
+    /// Useful for building cards incrementally. Panics if the registry has
+    /// a schema, since later edits would not be validated.
     pub fn register_mut(&mut self, card: CardDefinition) -> &mut CardDefinition {
+        if self.schema.is_some() {
+            panic!("register_mut bypasses the attribute schema; register the finished card instead");
+        }
         let id = card.id;
         self.register(card);
         self.cards.get_mut(&id).unwrap()
@@ -60,14 +137,20 @@ impl CardRegistry {
 
     /// Register a card with an auto-assigned ID.
     ///
-    /// Returns the assigned ID.
-    pub fn register_auto(&mut self, name: impl Into<String>, card_type: CardTypeId) -> CardId {
+    /// Returns the assigned ID. The ID is only used up if the card is
+    /// registered.
+    pub fn register_auto(
+        &mut self,
+        name: impl Into<String>,
+        card_type: CardTypeId,
+        attributes: impl IntoIterator<Item = (AttributeKey, AttributeValue)>,
+    ) -> Result<CardId, Vec<CardError>> {
         let id = CardId::new(self.next_id);
+        let mut card = CardDefinition::new(id, name, card_type);
+        card.attributes.extend(attributes);
+        self.try_register(card)?;
         self.next_id += 1;
-
-        let card = CardDefinition::new(id, name, card_type);
-        self.register(card);
-        id
+        Ok(id)
     }
 
     /// Get a card definition by ID.
@@ -119,11 +202,64 @@ impl CardRegistry {
     {
         self.cards.values().filter(move |c| predicate(c))
     }
+
+    /// Find cards whose attribute `key` equals `value`.
+    ///
+    /// Useful for set and rarity queries, e.g. `find_by_attr("rarity", "Rare")`.
+    pub fn find_by_attr<'a>(
+        &'a self,
+        key: &'a str,
+        value: impl Into<AttributeValue>,
+    ) -> impl Iterator<Item = &'a CardDefinition> {
+        let value = value.into();
+        self.cards
+            .values()
+            .filter(move |c| c.get_attr(key) == Some(&value))
+    }
+
+    /// Card IDs in ascending order.
+    #[must_use]
+    pub fn sorted_ids(&self) -> Vec<CardId> {
+        let mut ids: Vec<CardId> = self.cards.keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+        ids
+    }
+
+    /// Merge another registry into this one.
+    ///
+    /// Fails without changing anything if any card ID is present in both
+    /// or an incoming card violates this registry's schema. Errors are
+    /// ordered by card ID.
+    pub fn merge(&mut self, other: CardRegistry) -> Result<(), Vec<CardError>> {
+        let mut errors = Vec::new();
+        for id in other.sorted_ids() {
+            if self.cards.contains_key(&id) {
+                errors.push(CardError::DuplicateId(id));
+            }
+            errors.extend(self.validate(&other.cards[&id]));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        self.next_id = self.next_id.max(other.next_id);
+        self.cards.extend(other.cards);
+        Ok(())
+    }
+}
+
+fn join(errors: &[CardError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cards::{AttributeSpec, AttributeType};
 
     #[test]
     fn test_register_and_get() {
@@ -143,8 +279,8 @@ mod tests {
     fn test_register_auto() {
         let mut registry = CardRegistry::new();
 
-        let id1 = registry.register_auto("Card A", CardTypeId::new(0));
-        let id2 = registry.register_auto("Card B", CardTypeId::new(0));
+        let id1 = registry.register_auto("Card A", CardTypeId::new(0), []).unwrap();
+        let id2 = registry.register_auto("Card B", CardTypeId::new(0), []).unwrap();
 
         assert_eq!(id1, CardId::new(0));
         assert_eq!(id2, CardId::new(1));
@@ -212,6 +348,112 @@ mod tests {
         assert!(names.contains(&&"B".to_string()));
     }
 
+    #[test]
+    fn test_find_by_attr() {
+        let mut registry = CardRegistry::new();
+        registry.register(
+            CardDefinition::new(CardId::new(1), "A", CardTypeId::new(0)).with_attr("rarity", "Rare"),
+        );
+        registry.register(
+            CardDefinition::new(CardId::new(2), "B", CardTypeId::new(0)).with_attr("rarity", "Common"),
+        );
+        registry.register(CardDefinition::new(CardId::new(3), "C", CardTypeId::new(0)));
+
+        let rares: Vec<_> = registry.find_by_attr("rarity", "Rare").collect();
+        assert_eq!(rares.len(), 1);
+        assert_eq!(rares[0].name, "A");
+        assert_eq!(registry.find_by_attr("rarity", 1i32).count(), 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base = CardRegistry::new();
+        base.register(CardDefinition::new(CardId::new(1), "A", CardTypeId::new(0)));
+
+        let mut expansion = CardRegistry::new();
+        expansion.register(CardDefinition::new(CardId::new(3), "C", CardTypeId::new(0)));
+        expansion.register(CardDefinition::new(CardId::new(2), "B", CardTypeId::new(0)));
+        base.merge(expansion).unwrap();
+        assert_eq!(base.sorted_ids(), vec![CardId::new(1), CardId::new(2), CardId::new(3)]);
+
+        let mut clash = CardRegistry::new();
+        clash.register(CardDefinition::new(CardId::new(4), "D", CardTypeId::new(0)));
+        clash.register(CardDefinition::new(CardId::new(2), "B2", CardTypeId::new(0)));
+        clash.register(CardDefinition::new(CardId::new(1), "A2", CardTypeId::new(0)));
+        assert_eq!(
+            base.merge(clash),
+            Err(vec![CardError::DuplicateId(CardId::new(1)), CardError::DuplicateId(CardId::new(2))])
+        );
+        assert_eq!(base.len(), 3);
+        assert!(!base.contains(CardId::new(4)));
+    }
+
+    fn schema() -> AttributeSchema {
+        AttributeSchema::new().with_attr(AttributeSpec::new("cost", AttributeType::Int).required())
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let mut registry = CardRegistry::new().with_schema(schema());
+
+        let ok = CardDefinition::new(CardId::new(1), "Ok", CardTypeId::new(0)).with_attr("cost", 1i32);
+        registry.try_register(ok.clone()).unwrap();
+
+        let typo = CardDefinition::new(CardId::new(1), "Typo", CardTypeId::new(0)).with_attr("cots", 1i32);
+        let errors = registry.try_register(typo).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0], CardError::DuplicateId(CardId::new(1)));
+        assert!(matches!(errors[1], CardError::MissingAttribute { .. }));
+        assert!(matches!(errors[2], CardError::UnknownAttribute { suggestion: Some(_), .. }));
+        assert_eq!(registry.len(), 1);
+
+        // Incoming cards are checked against the receiving registry's schema
+        let mut other = CardRegistry::new();
+        other.register(CardDefinition::new(CardId::new(2), "Free", CardTypeId::new(0)));
+        assert!(matches!(
+            registry.merge(other).unwrap_err()[..],
+            [CardError::MissingAttribute { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_set_schema_checks_existing_cards() {
+        let mut registry = CardRegistry::new();
+        registry.register(CardDefinition::new(CardId::new(1), "Free", CardTypeId::new(0)));
+
+        assert!(registry.set_schema(schema()).is_err());
+        assert!(registry.schema().is_none());
+        let optional = AttributeSchema::new().with_attr(AttributeSpec::new("cost", AttributeType::Int));
+        assert!(registry.set_schema(optional).is_ok());
+        assert!(registry.schema().is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "violates attribute schema")]
+    fn test_register_panics_on_schema_violation() {
+        let mut registry = CardRegistry::new().with_schema(schema());
+        registry.register(CardDefinition::new(CardId::new(1), "Free", CardTypeId::new(0)));
+    }
+
+    #[test]
+    fn test_register_auto_with_schema() {
+        let mut registry = CardRegistry::new().with_schema(schema());
+
+        let errors = registry.register_auto("Free", CardTypeId::new(0), []).unwrap_err();
+        assert!(matches!(errors[..], [CardError::MissingAttribute { .. }]));
+        assert!(registry.is_empty());
+
+        let cost = [("cost".into(), AttributeValue::Int(2))];
+        assert_eq!(registry.register_auto("Ogre", CardTypeId::new(0), cost), Ok(CardId::new(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "bypasses the attribute schema")]
+    fn test_register_mut_rejects_schema() {
+        let mut registry = CardRegistry::new().with_schema(schema());
+        registry.register_mut(CardDefinition::new(CardId::new(1), "Ok", CardTypeId::new(0)).with_attr("cost", 1i32));
+    }
+
     #[test]
     fn test_contains() {
         let mut registry = CardRegistry::new();
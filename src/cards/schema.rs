@@ -0,0 +1,375 @@
+//! Attribute schemas: which attributes each card type has.
+//!
+//! `Attributes` is an untyped bag, so a typo like `"powr"` would silently
+//! fall back to `get_int`'s default. An [`AttributeSchema`] declares the
+//! keys, types, integer ranges and required-ness of attributes, either for
+//! every card or per [`CardTypeId`]. A [`CardRegistry`](super::CardRegistry)
+//! with a schema rejects cards that do not match it.
+//!
+//! ## Example
+//!
+//! ```
+//! use rust_ccg::cards::{
+//!     AttributeSchema, AttributeSpec, AttributeType, CardDefinition, CardId, CardRegistry,
+//!     CardTypeId,
+//! };
+//!
+//! const CREATURE: CardTypeId = CardTypeId::new(0);
+//!
+//! let schema = AttributeSchema::new()
+//!     .with_attr(AttributeSpec::new("cost", AttributeType::Int).required().with_range(0, 15))
+//!     .with_type_attr(CREATURE, AttributeSpec::new("power", AttributeType::Int).required());
+//! let mut registry = CardRegistry::new().with_schema(schema);
+//!
+//! let typo = CardDefinition::new(CardId::new(1), "Goblin", CREATURE)
+//!     .with_attr("cost", 1i32)
+//!     .with_attr("powr", 2i32);
+//! let errors = registry.try_register(typo).unwrap_err();
+//! assert_eq!(errors.len(), 2); // "power" missing, "powr" unknown
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::attributes::{AttributeKey, AttributeType, AttributeValue};
+use super::definition::{CardDefinition, CardTypeId};
+use super::error::CardError;
+
+/// Declaration of one attribute.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeSpec {
+    /// Attribute key.
+    pub key: AttributeKey,
+
+    /// Expected value type.
+    #[serde(rename = "type")]
+    pub value_type: AttributeType,
+
+    /// Must every card of the type have this attribute?
+    #[serde(default)]
+    pub required: bool,
+
+    /// Smallest allowed integer (or integer list element).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+
+    /// Largest allowed integer (or integer list element).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+}
+
+impl AttributeSpec {
+    /// Declare an optional attribute.
+    pub fn new(key: impl Into<AttributeKey>, value_type: AttributeType) -> Self {
+        Self {
+            key: key.into(),
+            value_type,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Make the attribute required (builder pattern).
+    #[must_use]
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Restrict integers to `min..=max` (builder pattern).
+    #[must_use]
+    pub fn with_range(mut self, min: i64, max: i64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Restrict integers to at least `min` (builder pattern).
+    #[must_use]
+    pub fn with_min(mut self, min: i64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Restrict integers to at most `max` (builder pattern).
+    #[must_use]
+    pub fn with_max(mut self, max: i64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    fn check(&self, card: &CardDefinition, value: &AttributeValue, errors: &mut Vec<CardError>) {
+        let found = value.attribute_type();
+        if found != self.value_type {
+            errors.push(CardError::WrongType {
+                card: card.id,
+                key: self.key,
+                expected: self.value_type,
+                found,
+            });
+            return;
+        }
+
+        let ints: &[i64] = match value {
+            AttributeValue::Int(v) => std::slice::from_ref(v),
+            AttributeValue::IntList(v) => v,
+            _ => &[],
+        };
+        let in_range = |v: i64| self.min.is_none_or(|min| v >= min) && self.max.is_none_or(|max| v <= max);
+        if let Some(&value) = ints.iter().find(|&&v| !in_range(v)) {
+            errors.push(CardError::OutOfRange {
+                card: card.id,
+                key: self.key,
+                value,
+                min: self.min,
+                max: self.max,
+            });
+        }
+    }
+}
+
+/// Attributes declared for one card type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardTypeSchema {
+    /// The card type.
+    pub card_type: CardTypeId,
+    /// Attributes cards of this type have in addition to the common ones.
+    pub attributes: Vec<AttributeSpec>,
+}
+
+/// Declared attributes for every card type.
+///
+/// Type-specific declarations override common ones with the same key.
+/// Unless [`allow_unknown`](Self::allow_unknown) is set, attributes not
+/// declared for a card's type are rejected.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeSchema {
+    /// Attributes shared by all card types.
+    #[serde(default)]
+    pub common: Vec<AttributeSpec>,
+
+    /// Per-type attributes.
+    #[serde(default)]
+    pub types: Vec<CardTypeSchema>,
+
+    /// Accept attributes the schema does not declare.
+    #[serde(default)]
+    pub allow_unknown: bool,
+}
+
+impl AttributeSchema {
+    /// Create an empty schema.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare an attribute for all card types (builder pattern).
+    #[must_use]
+    pub fn with_attr(mut self, spec: AttributeSpec) -> Self {
+        self.common.push(spec);
+        self
+    }
+
+    /// Declare an attribute for one card type (builder pattern).
+    #[must_use]
+    pub fn with_type_attr(mut self, card_type: CardTypeId, spec: AttributeSpec) -> Self {
+        match self.types.iter_mut().find(|t| t.card_type == card_type) {
+            Some(entry) => entry.attributes.push(spec),
+            None => self.types.push(CardTypeSchema {
+                card_type,
+                attributes: vec![spec],
+            }),
+        }
+        self
+    }
+
+    /// Accept attributes the schema does not declare (builder pattern).
+    #[must_use]
+    pub fn allow_unknown(mut self) -> Self {
+        self.allow_unknown = true;
+        self
+    }
+
+    /// Get the declaration of `key` for a card type.
+    #[must_use]
+    pub fn spec(&self, card_type: CardTypeId, key: AttributeKey) -> Option<&AttributeSpec> {
+        self.type_specs(card_type)
+            .find(|spec| spec.key == key)
+            .or_else(|| self.common.iter().find(|spec| spec.key == key))
+    }
+
+    /// All declarations that apply to a card type.
+    pub fn specs_for(&self, card_type: CardTypeId) -> impl Iterator<Item = &AttributeSpec> {
+        let overridden = move |key: AttributeKey| self.type_specs(card_type).any(|spec| spec.key == key);
+        self.type_specs(card_type)
+            .chain(self.common.iter().filter(move |spec| !overridden(spec.key)))
+    }
+
+    /// Check a card against the schema. Returns every problem found.
+    #[must_use]
+    pub fn validate(&self, card: &CardDefinition) -> Vec<CardError> {
+        let mut errors = Vec::new();
+
+        for spec in self.specs_for(card.card_type) {
+            match card.get(spec.key) {
+                Some(value) => spec.check(card, value, &mut errors),
+                None if spec.required => errors.push(CardError::MissingAttribute {
+                    card: card.id,
+                    key: spec.key,
+                }),
+                None => {}
+            }
+        }
+
+        if !self.allow_unknown {
+            let mut unknown: Vec<AttributeKey> = card
+                .attributes
+                .keys()
+                .copied()
+                .filter(|&key| self.spec(card.card_type, key).is_none())
+                .collect();
+            unknown.sort_by_key(|key| key.as_str());
+            for key in unknown {
+                errors.push(CardError::UnknownAttribute {
+                    card: card.id,
+                    key,
+                    suggestion: self.suggest(card.card_type, key),
+                });
+            }
+        }
+
+        errors
+    }
+
+    fn type_specs(&self, card_type: CardTypeId) -> impl Iterator<Item = &AttributeSpec> {
+        self.types
+            .iter()
+            .filter(move |t| t.card_type == card_type)
+            .flat_map(|t| t.attributes.iter())
+    }
+
+    /// The closest declared key within two edits, if any.
+    fn suggest(&self, card_type: CardTypeId, key: AttributeKey) -> Option<AttributeKey> {
+        self.specs_for(card_type)
+            .map(|spec| (edit_distance(key.as_str(), spec.key.as_str()), spec.key))
+            .filter(|&(distance, _)| distance <= 2)
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, key)| key)
+    }
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::CardId;
+
+    const CREATURE: CardTypeId = CardTypeId::new(0);
+    const SPELL: CardTypeId = CardTypeId::new(1);
+
+    fn schema() -> AttributeSchema {
+        AttributeSchema::new()
+            .with_attr(AttributeSpec::new("cost", AttributeType::Int).required().with_range(0, 10))
+            .with_attr(AttributeSpec::new("keywords", AttributeType::TextList))
+            .with_type_attr(CREATURE, AttributeSpec::new("power", AttributeType::Int).required().with_min(0))
+            .with_type_attr(CREATURE, AttributeSpec::new("split", AttributeType::IntList).with_max(5))
+            .with_type_attr(SPELL, AttributeSpec::new("cost", AttributeType::Int).with_range(0, 3))
+    }
+
+    #[test]
+    fn test_valid_cards() {
+        let schema = schema();
+        let goblin = CardDefinition::new(CardId::new(1), "Goblin", CREATURE)
+            .with_attr("cost", 1i32)
+            .with_attr("power", 2i32)
+            .with_attr("keywords", vec!["Haste".to_string()]);
+        assert!(schema.validate(&goblin).is_empty());
+
+        // Spell cost is optional for spells
+        let bolt = CardDefinition::new(CardId::new(2), "Bolt", SPELL);
+        assert!(schema.validate(&bolt).is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let schema = schema();
+        let card = CardDefinition::new(CardId::new(3), "Broken", CREATURE)
+            .with_attr("cost", 11i32)
+            .with_attr("powr", 2i32)
+            .with_attr("split", vec![1i64, 6])
+            .with_attr("keywords", "Haste");
+
+        let errors = schema.validate(&card);
+        let card = CardId::new(3);
+        assert_eq!(
+            errors,
+            vec![
+                CardError::MissingAttribute { card, key: "power".into() },
+                CardError::OutOfRange { card, key: "split".into(), value: 6, min: None, max: Some(5) },
+                CardError::OutOfRange { card, key: "cost".into(), value: 11, min: Some(0), max: Some(10) },
+                CardError::WrongType {
+                    card,
+                    key: "keywords".into(),
+                    expected: AttributeType::TextList,
+                    found: AttributeType::Text,
+                },
+                CardError::UnknownAttribute { card, key: "powr".into(), suggestion: Some("power".into()) },
+            ]
+        );
+        assert_eq!(
+            errors[4].to_string(),
+            "Card(3): attribute 'powr' is not in the schema (did you mean 'power'?)"
+        );
+        assert_eq!(errors[2].to_string(), "Card(3): attribute 'cost' value 11 is outside 0..=10");
+    }
+
+    #[test]
+    fn test_type_override_and_unknown() {
+        let schema = schema();
+        let spell = CardDefinition::new(CardId::new(4), "Big Spell", SPELL).with_attr("cost", 5i32);
+        assert!(matches!(
+            schema.validate(&spell)[..],
+            [CardError::OutOfRange { max: Some(3), .. }]
+        ));
+
+        // Creature-only attributes are unknown on spells
+        let spell = CardDefinition::new(CardId::new(5), "Odd Spell", SPELL).with_attr("power", 1i32);
+        assert!(matches!(
+            schema.validate(&spell)[..],
+            [CardError::UnknownAttribute { suggestion: None, .. }]
+        ));
+        assert!(schema.allow_unknown().validate(&spell).is_empty());
+    }
+
+    #[test]
+    fn test_schema_serialization() {
+        let schema = schema();
+        let json = serde_json::to_string(&schema).unwrap();
+        assert!(json.contains(r#""key":"power","type":"Int""#));
+        assert_eq!(serde_json::from_str::<AttributeSchema>(&json).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("power", "power"), 0);
+        assert_eq!(edit_distance("powr", "power"), 1);
+        assert_eq!(edit_distance("toughness", "tuffness"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -89,7 +89,7 @@ impl Action {
 /// - Action history in MCTS (opponent consistency)
 /// - Replay/debugging
 /// - Training data
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ActionRecord {
     /// The player who took this action.
     pub player: PlayerId,
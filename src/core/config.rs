@@ -39,9 +39,10 @@ impl std::fmt::Display for ZoneId {
 }
 
 /// Zone visibility rules.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ZoneVisibility {
     /// All cards visible to all players (battlefield).
+    #[default]
     Public,
     /// Cards visible only to the zone owner (hand).
     OwnerOnly,
@@ -58,7 +59,7 @@ pub enum ZoneVisibility {
 ///
 /// Games define their zones at startup. The engine uses these configs
 /// to determine visibility, ordering, and other zone properties.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ZoneConfig {
     /// Unique identifier for this zone.
     pub id: ZoneId,
@@ -67,15 +68,19 @@ pub struct ZoneConfig {
     pub name: String,
 
     /// Zone owner. `None` for shared zones (battlefield, market).
+    #[serde(default)]
     pub owner: Option<PlayerId>,
 
     /// Visibility rules for cards in this zone.
+    #[serde(default)]
     pub visibility: ZoneVisibility,
 
     /// Is card order significant? (true for library, stack, false for battlefield).
+    #[serde(default)]
     pub ordered: bool,
 
     /// Maximum cards allowed. `None` for unlimited.
+    #[serde(default)]
     pub max_cards: Option<usize>,
 }
 
@@ -159,7 +164,7 @@ impl std::fmt::Display for TemplateId {
 ///
 /// Games define their action types at startup. Each template specifies
 /// how many entity pointers the action requires.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TemplateConfig {
     /// Unique identifier for this template.
     pub id: TemplateId,
@@ -175,12 +180,14 @@ pub struct TemplateConfig {
     /// - "Attack": 1 pointer (the attacker)
     /// - "Block": 2 pointers (blocker, attacker being blocked)
     /// - "Cast spell on target": 2 pointers (spell, target)
+    #[serde(default)]
     pub pointer_count: usize,
 
     /// Can this action have additional variable pointers?
     ///
     /// True for actions like "multi-target spell" where the number
     /// of targets varies.
+    #[serde(default)]
     pub variable_pointers: bool,
 }
 
@@ -226,7 +233,7 @@ impl PhaseId {
 /// Complete game configuration.
 ///
 /// Games provide this at startup to configure the engine.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Hash)]
 pub struct GameConfig {
     /// Number of players (1-255).
     pub player_count: usize,
@@ -287,6 +294,15 @@ impl GameConfig {
     pub fn get_template(&self, id: TemplateId) -> Option<&TemplateConfig> {
         self.templates.iter().find(|t| t.id == id)
     }
+
+    /// Stable fingerprint of this configuration.
+    ///
+    /// Equal configs give equal fingerprints on every run and platform,
+    /// so replays can check they are played back under the same rules.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        super::hash::stable_hash(self)
+    }
 }
 
 #[cfg(test)]
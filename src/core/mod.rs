@@ -9,6 +9,8 @@ pub mod rng;
 pub mod config;
 pub mod action;
 pub mod state;
+pub mod hash;
+pub mod pending;
 
 pub use entity::EntityId;
 pub use player::{PlayerId, PlayerMap};
@@ -16,3 +18,5 @@ pub use rng::{GameRng, GameRngState};
 pub use config::{ZoneId, ZoneConfig, ZoneVisibility, TemplateId, TemplateConfig, PhaseId, GameConfig};
 pub use action::{Action, ActionRecord};
 pub use state::{PublicState, GameState};
+pub use hash::{StableHasher, stable_hash};
+pub use pending::{PendingDecision, PendingDecisions};
@@ -0,0 +1,185 @@
+//! Decisions resolution is waiting on.
+//!
+//! Resolution can pause for player input, e.g. a choice made while an
+//! effect resolves or targets for a triggered effect. The modules that
+//! pause resolution define the decision types; core only stores them.
+//! [`PendingDecisions`] holds at most one decision of each kind and
+//! clones and hashes them without knowing the concrete types, so core does
+//! not depend on the modules built on top of it.
+
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// A decision that can be stored on the game state.
+///
+/// Implemented by the types other modules keep in [`PendingDecisions`].
+pub trait PendingDecision: Clone + Debug + Hash + Send + Sync + 'static {
+    /// Stable name of the decision kind.
+    ///
+    /// Orders decisions in the state hash, so it must be unique and must
+    /// not change between versions.
+    const KIND: &'static str;
+}
+
+/// Object-safe form of [`PendingDecision`].
+trait AnyDecision: Debug + Send + Sync {
+    fn kind(&self) -> &'static str;
+    fn hash_into(&self, hasher: &mut dyn Hasher);
+    fn clone_box(&self) -> Box<dyn AnyDecision>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: PendingDecision> AnyDecision for T {
+    fn kind(&self) -> &'static str {
+        T::KIND
+    }
+
+    fn hash_into(&self, mut hasher: &mut dyn Hasher) {
+        self.hash(&mut hasher);
+    }
+
+    fn clone_box(&self) -> Box<dyn AnyDecision> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// The pending decisions on a game state, at most one of each kind.
+///
+/// Kept in [`PendingDecision::KIND`] order, so the hash does not depend on
+/// the order decisions were made in.
+#[derive(Debug, Default)]
+pub struct PendingDecisions {
+    decisions: Vec<Box<dyn AnyDecision>>,
+}
+
+impl PendingDecisions {
+    /// Create an empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the pending decision of type `T`.
+    #[must_use]
+    pub fn get<T: PendingDecision>(&self) -> Option<&T> {
+        self.position::<T>()
+            .ok()
+            .and_then(|i| self.decisions[i].as_any().downcast_ref())
+    }
+
+    /// Get the pending decision of type `T` mutably.
+    pub fn get_mut<T: PendingDecision>(&mut self) -> Option<&mut T> {
+        let i = self.position::<T>().ok()?;
+        self.decisions[i].as_any_mut().downcast_mut()
+    }
+
+    /// Set the pending decision of type `T`, replacing any previous one.
+    pub fn set<T: PendingDecision>(&mut self, decision: T) {
+        match self.position::<T>() {
+            Ok(i) => self.decisions[i] = Box::new(decision),
+            Err(i) => self.decisions.insert(i, Box::new(decision)),
+        }
+    }
+
+    /// Take the pending decision of type `T`, leaving none.
+    pub fn take<T: PendingDecision>(&mut self) -> Option<T> {
+        let i = self.position::<T>().ok()?;
+        self.decisions.remove(i).into_any().downcast().ok().map(|decision| *decision)
+    }
+
+    /// Check if no decision is pending.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.decisions.is_empty()
+    }
+
+    fn position<T: PendingDecision>(&self) -> Result<usize, usize> {
+        self.decisions.binary_search_by(|decision| decision.kind().cmp(T::KIND))
+    }
+}
+
+impl Clone for PendingDecisions {
+    fn clone(&self) -> Self {
+        Self {
+            decisions: self.decisions.iter().map(|decision| decision.clone_box()).collect(),
+        }
+    }
+}
+
+impl Hash for PendingDecisions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.decisions.len().hash(state);
+        for decision in &self.decisions {
+            decision.kind().hash(state);
+            decision.hash_into(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stable_hash;
+
+    #[derive(Clone, Debug, PartialEq, Hash)]
+    struct Pick(u32);
+
+    impl PendingDecision for Pick {
+        const KIND: &'static str = "pick";
+    }
+
+    #[derive(Clone, Debug, PartialEq, Hash)]
+    struct Aim(u32);
+
+    impl PendingDecision for Aim {
+        const KIND: &'static str = "aim";
+    }
+
+    #[test]
+    fn test_one_per_kind() {
+        let mut pending = PendingDecisions::new();
+        assert!(pending.is_empty());
+
+        pending.set(Pick(1));
+        pending.set(Aim(2));
+        pending.set(Pick(3));
+        assert_eq!(pending.get::<Pick>(), Some(&Pick(3)));
+        assert_eq!(pending.get::<Aim>(), Some(&Aim(2)));
+
+        pending.get_mut::<Aim>().unwrap().0 = 4;
+        let copy = pending.clone();
+        assert_eq!(pending.take::<Aim>(), Some(Aim(4)));
+        assert_eq!(pending.take::<Aim>(), None);
+        assert_eq!(copy.get::<Aim>(), Some(&Aim(4)));
+    }
+
+    #[test]
+    fn test_hash_ignores_order() {
+        let mut a = PendingDecisions::new();
+        a.set(Pick(1));
+        a.set(Aim(2));
+        let mut b = PendingDecisions::new();
+        b.set(Aim(2));
+        b.set(Pick(1));
+        assert_eq!(stable_hash(&a), stable_hash(&b));
+
+        b.set(Aim(3));
+        assert_ne!(stable_hash(&a), stable_hash(&b));
+        assert_ne!(stable_hash(&PendingDecisions::new()), stable_hash(&a));
+    }
+}
@@ -98,6 +98,11 @@ impl GameRng {
         self.inner.gen_range(range)
     }
 
+    /// Generate a random float in `[0, 1)`.
+    pub fn gen_f64(&mut self) -> f64 {
+        self.inner.gen::<f64>()
+    }
+
     /// Generate a random boolean with given probability of true.
     pub fn gen_bool(&mut self, probability: f64) -> bool {
         self.inner.gen_bool(probability)
@@ -15,6 +15,9 @@
 //! - Zone manager (card locations)
 //! - Private hands and decks
 //! - RNG
+//! - Pending decisions
+
+use std::hash::{Hash, Hasher};
 
 use im::{HashSet as ImHashSet, Vector};
 use rustc_hash::FxHashMap;
@@ -22,6 +25,8 @@ use serde::{Deserialize, Serialize};
 
 use super::action::ActionRecord;
 use super::config::PhaseId;
+use super::hash::StableHasher;
+use super::pending::PendingDecisions;
 use super::player::{PlayerId, PlayerMap};
 use super::rng::GameRng;
 use crate::cards::{CardId, CardInstance};
@@ -138,10 +143,10 @@ impl PublicState {
         self.player_state[player].insert(key.into(), value);
     }
 
-    /// Modify a player state value by delta.
+    /// Modify a player state value by delta, saturating at the `i64` bounds.
     pub fn modify_player_state(&mut self, player: PlayerId, key: &str, delta: i64) {
         let current = self.get_player_state(player, key, 0);
-        self.player_state[player].insert(key.to_string(), current + delta);
+        self.player_state[player].insert(key.to_string(), current.saturating_add(delta));
     }
 
     // === Turn State ===
@@ -210,6 +215,13 @@ impl PublicState {
     }
 }
 
+/// Hash a string-keyed map in key order.
+fn hash_sorted<H: Hasher>(map: &FxHashMap<String, i64>, hasher: &mut H) {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable();
+    entries.hash(hasher);
+}
+
 /// Full game state including private information.
 pub struct GameState {
     /// Public state (observable by all).
@@ -232,6 +244,9 @@ pub struct GameState {
 
     /// Next entity ID to allocate.
     next_entity_id: u32,
+
+    /// Decisions resolution is waiting on.
+    pending: PendingDecisions,
 }
 
 impl GameState {
@@ -246,6 +261,7 @@ impl GameState {
             cards: FxHashMap::default(),
             rng: GameRng::new(seed),
             next_entity_id: crate::core::EntityId::first_non_player(player_count),
+            pending: PendingDecisions::new(),
         }
     }
 
@@ -266,10 +282,15 @@ impl GameState {
 
     /// Add a card instance.
     pub fn add_card(&mut self, card: CardInstance) {
+        self.add_card_at(card, None);
+    }
+
+    /// Add a card instance at a position in its (ordered) zone.
+    pub fn add_card_at(&mut self, card: CardInstance, position: Option<crate::zones::ZonePosition>) {
         let entity_id = card.entity_id;
         let zone = card.zone;
         self.cards.insert(entity_id, card);
-        self.zones.add_to_zone(entity_id, zone, None);
+        self.zones.add_to_zone(entity_id, zone, position);
     }
 
     /// Get a card instance.
@@ -283,6 +304,28 @@ impl GameState {
         self.cards.get_mut(&entity_id)
     }
 
+    /// Remove a card instance from the game, including its zone.
+    pub fn remove_card(&mut self, entity_id: crate::core::EntityId) -> Option<CardInstance> {
+        self.zones.remove(entity_id);
+        self.cards.remove(&entity_id)
+    }
+
+    // === Pending Decisions ===
+
+    /// Get the decisions resolution is waiting on.
+    ///
+    /// The effects and stack modules add typed accessors, such as
+    /// `pending_choice` and `pending_targets`.
+    #[must_use]
+    pub fn pending(&self) -> &PendingDecisions {
+        &self.pending
+    }
+
+    /// Get the pending decisions mutably.
+    pub fn pending_mut(&mut self) -> &mut PendingDecisions {
+        &mut self.pending
+    }
+
     // === Hands ===
 
     /// Get a player's hand.
@@ -343,12 +386,73 @@ impl GameState {
         self.rng.shuffle(&mut self.decks[player]);
     }
 
+    // === Fingerprinting ===
+
+    /// Stable hash of the full game state.
+    ///
+    /// Covers public state, zones, hands, decks, card instances, entity
+    /// allocation, the RNG position and any pending decision, with map
+    /// entries hashed in sorted order so the result is the same on every
+    /// run and platform. The RNG fork counter is excluded: searching agents
+    /// fork the RNG without changing the game, so a replay played back
+    /// without them must still match.
+    ///
+    /// Pending decisions are hashed whole. A pending choice covers the
+    /// deciding player, options and bounds, the effect applied to the
+    /// selection, its origin and the effects still to resolve after it.
+    /// Pending targets cover the controller, valid targets and bounds, and
+    /// the full triggered effect: trigger, source, effects, target spec,
+    /// modifiers, triggering event and timing.
+    #[must_use]
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        let public = &self.public;
+
+        public.player_count.hash(&mut hasher);
+        public.phase.hash(&mut hasher);
+        public.turn_number.hash(&mut hasher);
+        public.action_sequence.hash(&mut hasher);
+        public.active_player.hash(&mut hasher);
+        public.priority_players.hash(&mut hasher);
+        for player in public.player_ids() {
+            hash_sorted(&public.player_state[player], &mut hasher);
+            public.hand_sizes[player].hash(&mut hasher);
+
+            let mut known: Vec<u32> = public.known_hand_cards[player].iter().map(|c| c.0).collect();
+            known.sort_unstable();
+            known.hash(&mut hasher);
+
+            self.hands[player].hash(&mut hasher);
+            self.decks[player].hash(&mut hasher);
+        }
+        hash_sorted(&public.turn_state, &mut hasher);
+        public.action_history.len().hash(&mut hasher);
+        for record in &public.action_history {
+            record.hash(&mut hasher);
+        }
+
+        self.zones.hash(&mut hasher);
+        let mut cards: Vec<&CardInstance> = self.cards.values().collect();
+        cards.sort_by_key(|card| card.entity_id.0);
+        cards.hash(&mut hasher);
+
+        let rng = self.rng.state();
+        rng.seed.hash(&mut hasher);
+        rng.word_pos.hash(&mut hasher);
+        self.next_entity_id.hash(&mut hasher);
+        self.pending.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
     // === Cloning ===
 
     /// Clone the game state (for MCTS).
     ///
     /// Uses persistent data structures where possible for efficiency.
     /// Takes `&mut self` because forking the RNG advances the fork counter.
+    /// The copy gets a forked RNG, so it does not reproduce the original's
+    /// future randomness; use [`snapshot`](Self::snapshot) for that.
     #[must_use]
     pub fn clone_state(&mut self) -> Self {
         Self {
@@ -359,6 +463,25 @@ impl GameState {
             cards: self.cards.clone(),
             rng: self.rng.fork(),
             next_entity_id: self.next_entity_id,
+            pending: self.pending.clone(),
+        }
+    }
+
+    /// Exact copy of the game state, including the RNG position.
+    ///
+    /// Applying the same actions to a snapshot and to the original gives
+    /// identical states. Used for undo history and debugging.
+    #[must_use]
+    pub fn snapshot(&self) -> Self {
+        Self {
+            public: self.public.clone(),
+            zones: self.zones.clone(),
+            hands: self.hands.clone(),
+            decks: self.decks.clone(),
+            cards: self.cards.clone(),
+            rng: self.rng.clone(),
+            next_entity_id: self.next_entity_id,
+            pending: self.pending.clone(),
         }
     }
 }
@@ -438,6 +561,50 @@ mod tests {
         assert_eq!(state.hand(PlayerId::new(0)).len(), 0);
     }
 
+    #[test]
+    fn test_state_hash() {
+        let build = || {
+            let mut state = GameState::new(2, 42);
+            state.set_deck(PlayerId::new(0), vec![CardId::new(1), CardId::new(2)]);
+            state.public.set_player_state(PlayerId::new(1), "life", 20);
+            state.public.set_player_state(PlayerId::new(1), "mana", 3);
+            state
+        };
+        let mut a = build();
+        let b = build();
+        assert_eq!(a.state_hash(), b.state_hash());
+
+        // Forking the RNG for a search does not change the game
+        let before = a.state_hash();
+        let _ = a.clone_state();
+        assert_eq!(a.state_hash(), before);
+
+        a.draw_card(PlayerId::new(0));
+        assert_ne!(a.state_hash(), before);
+
+        // Consuming randomness does
+        let mut c = build();
+        c.rng.gen_bool(0.5);
+        assert_ne!(c.state_hash(), b.state_hash());
+    }
+
+    #[test]
+    fn test_snapshot_reproduces_randomness() {
+        let mut state = GameState::new(2, 7);
+        state.set_deck(PlayerId::new(0), (1..=10).map(CardId::new).collect());
+
+        let mut exact = state.snapshot();
+        let mut forked = state.clone_state();
+        assert_eq!(exact.state_hash(), state.state_hash());
+
+        state.shuffle_deck(PlayerId::new(0));
+        exact.shuffle_deck(PlayerId::new(0));
+        forked.shuffle_deck(PlayerId::new(0));
+        assert_eq!(exact.deck(PlayerId::new(0)), state.deck(PlayerId::new(0)));
+        assert_eq!(exact.state_hash(), state.state_hash());
+        assert_ne!(forked.state_hash(), state.state_hash());
+    }
+
     #[test]
     fn test_game_state_deck_and_draw() {
         let mut state = GameState::new(2, 42);
@@ -476,6 +643,22 @@ mod tests {
         assert_eq!(e2.0, 5);
     }
 
+    #[test]
+    fn test_game_state_remove_card() {
+        let mut state = GameState::new(2, 42);
+        let entity = state.alloc_entity();
+        let zone = crate::core::ZoneId::new(3);
+        state.add_card(CardInstance::new(entity, CardId::new(7), PlayerId::new(0), zone));
+
+        let removed = state.remove_card(entity).unwrap();
+
+        assert_eq!(removed.card_id, CardId::new(7));
+        assert!(state.get_card(entity).is_none());
+        assert!(!state.zones.contains(entity));
+        assert_eq!(state.zones.zone_size(zone), 0);
+        assert!(state.remove_card(entity).is_none());
+    }
+
     #[test]
     fn test_game_state_clone() {
         let mut state = GameState::new(2, 42);
@@ -0,0 +1,669 @@
+//! Card sets: card pools kept outside the game definition.
+//!
+//! A [`CardSet`] is a named list of cards that can be loaded from the same
+//! formats as a [`GameDefinition`](super::GameDefinition), or from a CSV
+//! spreadsheet export (`csv` feature). Sets are merged into a
+//! [`CardRegistry`] with conflict detection on [`CardId`].
+//!
+//! ## CSV layout
+//!
+//! One row per card. `id`, `name` and `card_type` columns are required;
+//! every other column is an attribute, typed by a suffix on its header:
+//!
+//! ```text
+//! id,name,card_type,cost:int,flying:bool,rarity,keywords:text_list
+//! 1,Goblin,0,1,false,Common,Haste;Menace
+//! 2,Dragon,0,6,true,Rare,
+//! ```
+//!
+//! Types are `int`, `bool`, `text` (the default), `int_list` and
+//! `text_list`; list items are separated by `;`. The type is whatever
+//! follows the last `:`, so a text key containing `:` is written with an
+//! explicit `:text` suffix. Empty cells leave the attribute unset, so
+//! empty text and list values cannot be written.
+//!
+//! Surrounding spaces are trimmed from headers and cells, except for text
+//! attributes, which are kept as written. Names and text list items with
+//! surrounding spaces cannot be written.
+
+use std::path::Path;
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::cards::{AttributeValue, CardError, CardId, CardRegistry};
+
+use super::error::{DefinitionError, DefinitionResult, ValidationError};
+use super::format::{self, DefinitionFormat};
+use super::game::CardDef;
+
+/// A named collection of cards.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CardSet {
+    /// Set name, stamped on each card as the [`SET_ATTRIBUTE`](Self::SET_ATTRIBUTE).
+    #[serde(default)]
+    pub name: String,
+
+    /// Cards in this set.
+    #[serde(default)]
+    pub cards: Vec<CardDef>,
+}
+
+impl CardSet {
+    /// Attribute recording which set a card came from.
+    pub const SET_ATTRIBUTE: &'static str = "set";
+
+    /// Create an empty set.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            cards: Vec::new(),
+        }
+    }
+
+    /// Add a card (builder pattern).
+    #[must_use]
+    pub fn with_card(mut self, card: CardDef) -> Self {
+        self.cards.push(card);
+        self
+    }
+
+    /// Capture every card in a registry, ordered by ID.
+    #[must_use]
+    pub fn from_registry(name: impl Into<String>, registry: &CardRegistry) -> Self {
+        Self {
+            name: name.into(),
+            cards: registry
+                .sorted_ids()
+                .into_iter()
+                .map(|id| CardDef::from_definition(registry.get_unchecked(id)))
+                .collect(),
+        }
+    }
+
+    /// Number of cards.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Check if the set has no cards.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Parse a set from text.
+    pub fn parse(text: &str, format: DefinitionFormat) -> DefinitionResult<Self> {
+        format.parse(text)
+    }
+
+    /// Read a set file, picking the format from its extension.
+    ///
+    /// A `.csv` file's set name is its file stem.
+    pub fn load(path: impl AsRef<Path>) -> DefinitionResult<Self> {
+        let path = path.as_ref();
+        if is_csv(path) {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            return Self::from_csv(name, &std::fs::read_to_string(path)?);
+        }
+        format::read_file(path)
+    }
+
+    /// Write the set as text.
+    pub fn write(&self, format: DefinitionFormat) -> DefinitionResult<String> {
+        format.write(self)
+    }
+
+    /// Save to a file, picking the format from its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> DefinitionResult<()> {
+        let path = path.as_ref();
+        if is_csv(path) {
+            std::fs::write(path, self.to_csv()?)?;
+            return Ok(());
+        }
+        format::write_file(path, self)
+    }
+
+    /// Cards with the set name stamped on any that lack one.
+    #[must_use]
+    pub fn stamped_cards(&self) -> Vec<CardDef> {
+        self.cards
+            .iter()
+            .map(|card| {
+                let mut card = card.clone();
+                if !self.name.is_empty() {
+                    card.attributes
+                        .entry(Self::SET_ATTRIBUTE.to_string())
+                        .or_insert_with(|| AttributeValue::Text(self.name.clone()));
+                }
+                card
+            })
+            .collect()
+    }
+
+    /// Add this set's cards to a registry.
+    ///
+    /// Fails without changing the registry if a card ID is already
+    /// registered or repeated within the set, or a card violates the
+    /// registry's attribute schema. Abilities are not part of a
+    /// [`CardDefinition`](crate::cards::CardDefinition); load sets through a
+    /// [`GameDefinition`](super::GameDefinition) to keep them.
+    pub fn register_into(&self, registry: &mut CardRegistry) -> DefinitionResult<()> {
+        let mut incoming = CardRegistry::new();
+        let mut names: FxHashMap<CardId, &str> = FxHashMap::default();
+        let mut errors = Vec::new();
+        for (card, stamped) in self.cards.iter().zip(self.stamped_cards()) {
+            if let Some(existing) = names.get(&card.id) {
+                errors.push(ValidationError::CardConflict {
+                    id: card.id.0,
+                    existing: existing.to_string(),
+                    incoming: card.name.clone(),
+                });
+                continue;
+            }
+            names.insert(card.id, &card.name);
+            incoming.register(stamped.to_definition());
+        }
+
+        if let Err(card_errors) = registry.merge(incoming) {
+            for error in card_errors {
+                errors.push(match error {
+                    CardError::DuplicateId(id) => ValidationError::CardConflict {
+                        id: id.0,
+                        existing: registry.get_unchecked(id).name.clone(),
+                        incoming: names[&id].to_string(),
+                    },
+                    error => ValidationError::Card(error),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DefinitionError::Invalid(errors))
+        }
+    }
+
+    /// Merge several sets into a new registry.
+    ///
+    /// Sets are added in order, each all-or-nothing. Conflicts from every
+    /// set are reported; on error no registry is returned.
+    pub fn merge<'a>(sets: impl IntoIterator<Item = &'a CardSet>) -> DefinitionResult<CardRegistry> {
+        let mut registry = CardRegistry::new();
+        let mut errors = Vec::new();
+        for set in sets {
+            match set.register_into(&mut registry) {
+                Ok(()) => {}
+                Err(DefinitionError::Invalid(conflicts)) => errors.extend(conflicts),
+                Err(e) => return Err(e),
+            }
+        }
+        if errors.is_empty() {
+            Ok(registry)
+        } else {
+            Err(DefinitionError::Invalid(errors))
+        }
+    }
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// CSV card lists.
+#[cfg(feature = "csv")]
+mod csv_table {
+    use std::collections::BTreeMap;
+
+    use crate::cards::{AttributeValue, CardId, CardTypeId};
+
+    use super::super::error::{DefinitionError, DefinitionResult};
+    use super::super::game::CardDef;
+    use super::CardSet;
+
+    /// Attribute column types for CSV card lists.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum ColumnType {
+        Int,
+        Bool,
+        Text,
+        IntList,
+        TextList,
+    }
+
+    impl ColumnType {
+        fn parse(suffix: &str) -> Option<Self> {
+            match suffix {
+                "int" => Some(Self::Int),
+                "bool" => Some(Self::Bool),
+                "text" => Some(Self::Text),
+                "int_list" => Some(Self::IntList),
+                "text_list" => Some(Self::TextList),
+                _ => None,
+            }
+        }
+
+        fn of(value: &AttributeValue) -> Self {
+            match value {
+                AttributeValue::Int(_) => Self::Int,
+                AttributeValue::Bool(_) => Self::Bool,
+                AttributeValue::Text(_) => Self::Text,
+                AttributeValue::IntList(_) => Self::IntList,
+                AttributeValue::TextList(_) => Self::TextList,
+            }
+        }
+
+        fn suffix(self) -> &'static str {
+            match self {
+                Self::Int => "int",
+                Self::Bool => "bool",
+                Self::Text => "text",
+                Self::IntList => "int_list",
+                Self::TextList => "text_list",
+            }
+        }
+
+        fn read(self, cell: &str) -> Result<AttributeValue, String> {
+            let int = |s: &str| {
+                s.trim()
+                    .parse::<i64>()
+                    .map_err(|_| format!("'{}' is not an integer", s.trim()))
+            };
+            let items = || cell.split(';').map(str::trim);
+            Ok(match self {
+                Self::Int => AttributeValue::Int(int(cell)?),
+                Self::Bool => match cell.trim().to_ascii_lowercase().as_str() {
+                    "true" | "yes" | "1" => AttributeValue::Bool(true),
+                    "false" | "no" | "0" => AttributeValue::Bool(false),
+                    _ => return Err(format!("'{}' is not a boolean", cell.trim())),
+                },
+                Self::Text => AttributeValue::Text(cell.to_string()),
+                Self::IntList => AttributeValue::IntList(items().map(int).collect::<Result<_, _>>()?),
+                Self::TextList => AttributeValue::TextList(items().map(str::to_string).collect()),
+            })
+        }
+
+        fn write(value: &AttributeValue) -> Result<String, String> {
+            // An empty cell reads back as "unset", so empty values can't round-trip.
+            let empty = match value {
+                AttributeValue::Text(v) => v.is_empty(),
+                AttributeValue::IntList(v) => v.is_empty(),
+                AttributeValue::TextList(v) => v.is_empty(),
+                AttributeValue::Int(_) | AttributeValue::Bool(_) => false,
+            };
+            if empty {
+                return Err("value is empty".to_string());
+            }
+            Ok(match value {
+                AttributeValue::Int(v) => v.to_string(),
+                AttributeValue::Bool(v) => v.to_string(),
+                AttributeValue::Text(v) => v.clone(),
+                AttributeValue::IntList(v) => {
+                    v.iter().map(i64::to_string).collect::<Vec<_>>().join(";")
+                }
+                AttributeValue::TextList(v) => {
+                    if let Some(item) = v.iter().find(|item| item.contains(';')) {
+                        return Err(format!("list item '{}' contains ';'", item));
+                    }
+                    if let Some(item) = v.iter().find(|item| item.trim() != item.as_str()) {
+                        return Err(format!("list item '{}' has surrounding spaces", item));
+                    }
+                    v.join(";")
+                }
+            })
+        }
+    }
+
+    impl CardSet {
+        /// Parse a CSV card list (see the [module docs](super)).
+        pub fn from_csv(name: impl Into<String>, text: &str) -> DefinitionResult<Self> {
+            let csv_error = |line: u64, message: String| DefinitionError::Csv { line, message };
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::Headers)
+                .from_reader(text.as_bytes());
+
+            let headers = reader
+                .headers()
+                .map_err(|e| csv_error(1, e.to_string()))?
+                .clone();
+            let column = |name: &str| headers.iter().position(|h| h == name);
+            let (Some(id_col), Some(name_col), Some(type_col)) =
+                (column("id"), column("name"), column("card_type"))
+            else {
+                return Err(csv_error(1, "missing id, name or card_type column".to_string()));
+            };
+
+            let mut attributes = Vec::new();
+            for (index, header) in headers.iter().enumerate() {
+                if [id_col, name_col, type_col].contains(&index) {
+                    continue;
+                }
+                let (key, ty) = match header.rsplit_once(':') {
+                    Some((key, suffix)) => match ColumnType::parse(suffix.trim()) {
+                        Some(ty) => (key.trim(), ty),
+                        None => {
+                            return Err(csv_error(1, format!("unknown column type '{}'", suffix)))
+                        }
+                    },
+                    None => (header, ColumnType::Text),
+                };
+                attributes.push((index, key.to_string(), ty));
+            }
+
+            let mut cards = Vec::new();
+            for record in reader.records() {
+                let record = record.map_err(|e| {
+                    let line = e.position().map_or(0, |p| p.line());
+                    csv_error(line, e.to_string())
+                })?;
+                let line = record.position().map_or(0, |p| p.line());
+                let cell = |index: usize| record.get(index).unwrap_or("").trim();
+                let number = |index: usize, what: &str| {
+                    cell(index)
+                        .parse::<u32>()
+                        .map_err(|_| csv_error(line, format!("{} '{}' is not a number", what, cell(index))))
+                };
+
+                let mut card = CardDef {
+                    id: CardId::new(number(id_col, "id")?),
+                    name: cell(name_col).to_string(),
+                    card_type: CardTypeId::new(number(type_col, "card_type")?),
+                    attributes: BTreeMap::new(),
+                    abilities: Vec::new(),
+                };
+                for (index, key, ty) in &attributes {
+                    let value = match ty {
+                        ColumnType::Text => record.get(*index).unwrap_or(""),
+                        _ => cell(*index),
+                    };
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let value = ty
+                        .read(value)
+                        .map_err(|message| csv_error(line, format!("column '{}': {}", key, message)))?;
+                    card.attributes.insert(key.clone(), value);
+                }
+                cards.push(card);
+            }
+
+            Ok(Self {
+                name: name.into(),
+                cards,
+            })
+        }
+
+        /// Write the set as a CSV card list.
+        ///
+        /// Fails if a card has abilities, an attribute has different types on
+        /// different cards, a text or list attribute is empty (it would read
+        /// back as unset), or a name or text list item would not read back
+        /// the same (surrounding spaces, or `;` in a list item).
+        pub fn to_csv(&self) -> DefinitionResult<String> {
+            let csv_error = |message: String| DefinitionError::Csv { line: 0, message };
+
+            let mut columns: BTreeMap<&str, ColumnType> = BTreeMap::new();
+            for card in &self.cards {
+                if !card.abilities.is_empty() {
+                    return Err(csv_error(format!("card '{}' has abilities", card.name)));
+                }
+                if card.name.trim() != card.name {
+                    return Err(csv_error(format!("card '{}' has surrounding spaces in its name", card.name)));
+                }
+                for (key, value) in &card.attributes {
+                    let ty = ColumnType::of(value);
+                    if *columns.entry(key.as_str()).or_insert(ty) != ty {
+                        return Err(csv_error(format!("attribute '{}' has mixed types", key)));
+                    }
+                }
+            }
+
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            let mut header = vec!["id".to_string(), "name".to_string(), "card_type".to_string()];
+            header.extend(columns.iter().map(|(key, ty)| match ty {
+                ColumnType::Text if !key.contains(':') => key.to_string(),
+                _ => format!("{}:{}", key, ty.suffix()),
+            }));
+            writer.write_record(&header).map_err(|e| csv_error(e.to_string()))?;
+
+            for card in &self.cards {
+                let mut row = vec![card.id.0.to_string(), card.name.clone(), card.card_type.0.to_string()];
+                for key in columns.keys() {
+                    let cell = match card.attributes.get(*key) {
+                        Some(value) => ColumnType::write(value)
+                            .map_err(|message| {
+                                csv_error(format!("card '{}': attribute '{}': {}", card.name, key, message))
+                            })?,
+                        None => String::new(),
+                    };
+                    row.push(cell);
+                }
+                writer.write_record(&row).map_err(|e| csv_error(e.to_string()))?;
+            }
+
+            let bytes = writer.into_inner().map_err(|e| csv_error(e.to_string()))?;
+            String::from_utf8(bytes).map_err(|e| csv_error(e.to_string()))
+        }
+    }
+}
+
+#[cfg(not(feature = "csv"))]
+impl CardSet {
+    /// Parse a CSV card list (requires the `csv` feature).
+    pub fn from_csv(_name: impl Into<String>, _text: &str) -> DefinitionResult<Self> {
+        Err(csv_unsupported())
+    }
+
+    /// Write the set as a CSV card list (requires the `csv` feature).
+    pub fn to_csv(&self) -> DefinitionResult<String> {
+        Err(csv_unsupported())
+    }
+}
+
+#[cfg(not(feature = "csv"))]
+fn csv_unsupported() -> DefinitionError {
+    DefinitionError::Csv {
+        line: 0,
+        message: "CSV support is not enabled in this build".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{CardDefinition, CardTypeId};
+
+    fn card(id: u32, name: &str) -> CardDef {
+        CardDef::from_definition(&CardDefinition::new(CardId::new(id), name, CardTypeId::new(0)))
+    }
+
+    fn core_set() -> CardSet {
+        let mut goblin = card(1, "Goblin");
+        goblin.attributes.insert("rarity".to_string(), "Common".into());
+        let mut dragon = card(2, "Dragon");
+        dragon.attributes.insert("rarity".to_string(), "Rare".into());
+        CardSet::new("Core").with_card(goblin).with_card(dragon)
+    }
+
+    #[test]
+    fn test_register_stamps_set() {
+        let mut registry = CardRegistry::new();
+        core_set().register_into(&mut registry).unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.find_by_attr(CardSet::SET_ATTRIBUTE, "Core").count(), 2);
+        let rares: Vec<_> = registry.find_by_attr("rarity", "Rare").collect();
+        assert_eq!(rares.len(), 1);
+        assert_eq!(rares[0].name, "Dragon");
+    }
+
+    #[test]
+    fn test_merge_detects_conflicts() {
+        let expansion = CardSet::new("Expansion")
+            .with_card(card(3, "Wyvern"))
+            .with_card(card(2, "Drake"))
+            .with_card(card(3, "Wyrm"));
+
+        let Err(DefinitionError::Invalid(errors)) = CardSet::merge([&core_set(), &expansion]) else {
+            panic!("expected conflicts");
+        };
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::CardConflict {
+                    id: 3,
+                    existing: "Wyvern".to_string(),
+                    incoming: "Wyrm".to_string(),
+                },
+                ValidationError::CardConflict {
+                    id: 2,
+                    existing: "Dragon".to_string(),
+                    incoming: "Drake".to_string(),
+                },
+            ]
+        );
+
+        // A failed merge leaves the registry untouched
+        let mut registry = CardRegistry::new();
+        core_set().register_into(&mut registry).unwrap();
+        assert!(expansion.register_into(&mut registry).is_err());
+        assert_eq!(registry.len(), 2);
+
+        let expansion = CardSet::new("Expansion").with_card(card(3, "Wyvern"));
+        let registry = CardSet::merge([&core_set(), &expansion]).unwrap();
+        assert_eq!(registry.find_by_attr(CardSet::SET_ATTRIBUTE, "Expansion").count(), 1);
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let registry = CardSet::merge([&core_set()]).unwrap();
+        let saved = CardSet::from_registry("Core", &registry);
+        assert_eq!(saved.cards.iter().map(|c| c.id.0).collect::<Vec<_>>(), vec![1, 2]);
+
+        let text = saved.write(DefinitionFormat::Json).unwrap();
+        let loaded = CardSet::parse(&text, DefinitionFormat::Json).unwrap();
+        assert_eq!(loaded, saved);
+        assert_eq!(
+            CardSet::merge([&loaded]).unwrap().get(CardId::new(2)).unwrap().get_text("rarity"),
+            Some("Rare")
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv() {
+        let text = "id,name,card_type,cost:int,flying:bool,rarity,keywords:text_list,split:int_list\n\
+                    1, Goblin ,0,1,no,Common,Haste;Menace,\n\
+                    2,Dragon,0,6,TRUE,Rare,,3;3\n";
+        let set = CardSet::from_csv("Core", text).unwrap();
+        assert_eq!(set.len(), 2);
+
+        let registry = CardSet::merge([&set]).unwrap();
+        let goblin = registry.get(CardId::new(1)).unwrap();
+        assert_eq!(goblin.name, "Goblin");
+        assert_eq!(goblin.get_int("cost", 0), 1);
+        assert!(!goblin.get_bool("flying", true));
+        assert_eq!(
+            goblin.get_attr("keywords").and_then(|v| v.as_text_list()),
+            Some(&["Haste".to_string(), "Menace".to_string()][..])
+        );
+        assert!(goblin.get_attr("split").is_none());
+        let dragon = registry.get(CardId::new(2)).unwrap();
+        assert_eq!(dragon.get_attr("split").and_then(|v| v.as_int_list()), Some(&[3, 3][..]));
+
+        let written = set.to_csv().unwrap();
+        assert_eq!(CardSet::from_csv("Core", &written).unwrap(), set);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_errors() {
+        let bad_int = "id,name,card_type,cost:int\n1,Goblin,0,one\n";
+        let Err(DefinitionError::Csv { line, message }) = CardSet::from_csv("Core", bad_int) else {
+            panic!("expected CSV error");
+        };
+        assert_eq!(line, 2);
+        assert!(message.contains("cost"));
+
+        let bad_type = "id,name,card_type,cost:float\n";
+        assert!(matches!(
+            CardSet::from_csv("Core", bad_type),
+            Err(DefinitionError::Csv { line: 1, .. })
+        ));
+        assert!(CardSet::from_csv("Core", "id,name\n1,Goblin\n").is_err());
+
+        let mut mixed = core_set();
+        mixed.cards[1].attributes.insert("rarity".to_string(), 3i32.into());
+        assert!(mixed.to_csv().is_err());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_empty_values() {
+        let empties = [
+            AttributeValue::Text(String::new()),
+            AttributeValue::IntList(Vec::new()),
+            AttributeValue::TextList(Vec::new()),
+        ];
+        for empty in empties {
+            let mut set = core_set();
+            set.cards[0].attributes.insert("extra".to_string(), empty);
+            let Err(DefinitionError::Csv { message, .. }) = set.to_csv() else {
+                panic!("expected CSV error");
+            };
+            assert!(message.contains("extra"));
+        }
+
+        // Non-empty values of every type survive a round trip
+        let mut set = core_set();
+        let values = [
+            ("cost", AttributeValue::Int(-2)),
+            ("flying", AttributeValue::Bool(false)),
+            ("rarity", AttributeValue::Text("Common".to_string())),
+            ("split", AttributeValue::IntList(vec![0])),
+            ("keywords", AttributeValue::TextList(vec!["Haste".to_string()])),
+        ];
+        for (key, value) in values {
+            set.cards[0].attributes.insert(key.to_string(), value);
+        }
+        let written = set.to_csv().unwrap();
+        assert_eq!(CardSet::from_csv("Core", &written).unwrap(), set);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_text_round_trip() {
+        let mut set = core_set();
+        let values = [
+            ("flavor", AttributeValue::Text("  Fire and blood ".to_string())),
+            ("set:code", AttributeValue::Text("M21".to_string())),
+            ("mana:cost", AttributeValue::Int(3)),
+        ];
+        for (key, value) in values {
+            set.cards[0].attributes.insert(key.to_string(), value);
+        }
+        let written = set.to_csv().unwrap();
+        assert!(written.lines().next().unwrap().contains("set:code:text"));
+        assert_eq!(CardSet::from_csv("Core", &written).unwrap(), set);
+
+        // Text cells keep their spaces; other cells are trimmed
+        let text = "id,name,card_type,flavor,cost:int\n1,Goblin,0, Sneaky ,  2 \n";
+        let goblin = &CardSet::from_csv("Core", text).unwrap().cards[0];
+        assert_eq!(goblin.attributes["flavor"], AttributeValue::Text(" Sneaky ".to_string()));
+        assert_eq!(goblin.attributes["cost"], AttributeValue::Int(2));
+
+        let mut spaced = core_set();
+        spaced.cards[0].attributes.insert("keywords".to_string(), vec![" Haste".to_string()].into());
+        assert!(spaced.to_csv().is_err());
+        let mut spaced = core_set();
+        spaced.cards[0].name = "Goblin ".to_string();
+        assert!(spaced.to_csv().is_err());
+    }
+}
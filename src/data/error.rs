@@ -0,0 +1,169 @@
+//! Errors from loading and validating definition files.
+
+use std::io;
+use std::path::PathBuf;
+
+use crate::cards::CardError;
+
+use super::format::DefinitionFormat;
+
+/// A problem found while validating a definition.
+///
+/// `context` names the item containing the bad value, e.g.
+/// `card 'Goblin' ability 'Rally'`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Player count outside 1-255.
+    InvalidPlayerCount(usize),
+
+    /// Two items of the same kind share an ID.
+    DuplicateId { kind: &'static str, id: u64 },
+
+    /// Two items of the same kind share a name.
+    DuplicateName { kind: &'static str, name: String },
+
+    /// A reference to an ID that is not defined.
+    UnknownReference {
+        context: String,
+        kind: &'static str,
+        id: u64,
+    },
+
+    /// A reference to a player seat that does not exist.
+    PlayerOutOfRange { context: String, player: u8 },
+
+    /// A trigger or ability that listens for no events.
+    NoEvents { context: String },
+
+    /// A global trigger with a target spec: it has no controller to
+    /// choose the targets.
+    TargetWithoutController { context: String },
+
+    /// A card that does not match the attribute schema.
+    Card(CardError),
+
+    /// A card ID defined by two different cards, e.g. in merged sets.
+    CardConflict {
+        id: u32,
+        existing: String,
+        incoming: String,
+    },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::InvalidPlayerCount(count) => {
+                write!(f, "player count {} is not between 1 and 255", count)
+            }
+            ValidationError::DuplicateId { kind, id } => write!(f, "duplicate {} id {}", kind, id),
+            ValidationError::DuplicateName { kind, name } => {
+                write!(f, "duplicate {} name '{}'", kind, name)
+            }
+            ValidationError::UnknownReference { context, kind, id } => {
+                write!(f, "{} refers to unknown {} {}", context, kind, id)
+            }
+            ValidationError::PlayerOutOfRange { context, player } => {
+                write!(f, "{} refers to player {} which is out of range", context, player)
+            }
+            ValidationError::NoEvents { context } => {
+                write!(f, "{} does not listen for any event", context)
+            }
+            ValidationError::TargetWithoutController { context } => {
+                write!(f, "{} has a target but no controller to choose it", context)
+            }
+            ValidationError::Card(error) => write!(f, "{}", error),
+            ValidationError::CardConflict {
+                id,
+                existing,
+                incoming,
+            } => write!(
+                f,
+                "card id {} is used by both '{}' and '{}'",
+                id, existing, incoming
+            ),
+        }
+    }
+}
+
+/// Errors from reading, writing, or validating definition files.
+#[derive(Debug)]
+pub enum DefinitionError {
+    /// Underlying I/O failure.
+    Io(io::Error),
+
+    /// The file extension does not name a known format.
+    UnknownFormat(PathBuf),
+
+    /// The format is known but this build lacks the feature for it.
+    UnsupportedFormat(DefinitionFormat),
+
+    /// The text is not a valid definition.
+    Parse {
+        format: DefinitionFormat,
+        message: String,
+    },
+
+    /// A bad row or column in a CSV card list.
+    ///
+    /// `line` is 1-based; 0 means the problem is not tied to one row.
+    Csv { line: u64, message: String },
+
+    /// The definition could not be written.
+    Encode {
+        format: DefinitionFormat,
+        message: String,
+    },
+
+    /// The definition parsed but failed validation.
+    Invalid(Vec<ValidationError>),
+}
+
+impl std::fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefinitionError::Io(e) => write!(f, "I/O error: {}", e),
+            DefinitionError::UnknownFormat(path) => {
+                write!(f, "cannot tell the format of {}", path.display())
+            }
+            DefinitionError::UnsupportedFormat(format) => {
+                write!(f, "{} support is not enabled in this build", format)
+            }
+            DefinitionError::Parse { format, message } => {
+                write!(f, "invalid {} definition: {}", format, message)
+            }
+            DefinitionError::Csv { line: 0, message } => write!(f, "invalid CSV: {}", message),
+            DefinitionError::Csv { line, message } => {
+                write!(f, "invalid CSV at line {}: {}", line, message)
+            }
+            DefinitionError::Encode { format, message } => {
+                write!(f, "failed to write {} definition: {}", format, message)
+            }
+            DefinitionError::Invalid(errors) => {
+                write!(f, "definition has {} error(s)", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DefinitionError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DefinitionError {
+    fn from(e: io::Error) -> Self {
+        DefinitionError::Io(e)
+    }
+}
+
+/// Result alias for definition operations.
+pub type DefinitionResult<T> = Result<T, DefinitionError>;
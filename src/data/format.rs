@@ -0,0 +1,145 @@
+//! Text formats for definition files.
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::error::{DefinitionError, DefinitionResult};
+
+/// Text format of a definition file.
+///
+/// JSON is always available; TOML and RON need the `toml` and `ron`
+/// features.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DefinitionFormat {
+    /// JSON.
+    Json,
+    /// TOML (requires the `toml` feature).
+    Toml,
+    /// Rusty Object Notation (requires the `ron` feature).
+    Ron,
+}
+
+impl DefinitionFormat {
+    /// Guess the format from a file extension.
+    #[must_use]
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "ron" => Some(Self::Ron),
+            _ => None,
+        }
+    }
+
+    /// Whether this build can read and write this format.
+    #[must_use]
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::Json => true,
+            Self::Toml => cfg!(feature = "toml"),
+            Self::Ron => cfg!(feature = "ron"),
+        }
+    }
+
+    /// Parse a value from text in this format.
+    pub fn parse<T: DeserializeOwned>(self, text: &str) -> DefinitionResult<T> {
+        let parse_error = |message: String| DefinitionError::Parse {
+            format: self,
+            message,
+        };
+        match self {
+            Self::Json => serde_json::from_str(text).map_err(|e| parse_error(e.to_string())),
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::from_str(text).map_err(|e| parse_error(e.to_string())),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::from_str(text).map_err(|e| parse_error(e.to_string())),
+            #[allow(unreachable_patterns)]
+            _ => Err(DefinitionError::UnsupportedFormat(self)),
+        }
+    }
+
+    /// Write a value as text in this format.
+    pub fn write<T: Serialize>(self, value: &T) -> DefinitionResult<String> {
+        let encode_error = |message: String| DefinitionError::Encode {
+            format: self,
+            message,
+        };
+        match self {
+            Self::Json => {
+                serde_json::to_string_pretty(value).map_err(|e| encode_error(e.to_string()))
+            }
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::to_string_pretty(value).map_err(|e| encode_error(e.to_string())),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map_err(|e| encode_error(e.to_string())),
+            #[allow(unreachable_patterns)]
+            _ => Err(DefinitionError::UnsupportedFormat(self)),
+        }
+    }
+}
+
+impl std::fmt::Display for DefinitionFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Json => "JSON",
+            Self::Toml => "TOML",
+            Self::Ron => "RON",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Read a file, picking the format from its extension.
+pub(crate) fn read_file<T: DeserializeOwned>(path: &Path) -> DefinitionResult<T> {
+    let format = DefinitionFormat::from_path(path)
+        .ok_or_else(|| DefinitionError::UnknownFormat(path.to_path_buf()))?;
+    if !format.is_supported() {
+        return Err(DefinitionError::UnsupportedFormat(format));
+    }
+    let text = std::fs::read_to_string(path)?;
+    format.parse(&text)
+}
+
+/// Write a file, picking the format from its extension.
+pub(crate) fn write_file<T: Serialize>(path: &Path, value: &T) -> DefinitionResult<()> {
+    let format = DefinitionFormat::from_path(path)
+        .ok_or_else(|| DefinitionError::UnknownFormat(path.to_path_buf()))?;
+    std::fs::write(path, format.write(value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(DefinitionFormat::from_path("game.json"), Some(DefinitionFormat::Json));
+        assert_eq!(DefinitionFormat::from_path("dir/Game.TOML"), Some(DefinitionFormat::Toml));
+        assert_eq!(DefinitionFormat::from_path("game.ron"), Some(DefinitionFormat::Ron));
+        assert_eq!(DefinitionFormat::from_path("game.yaml"), None);
+        assert_eq!(DefinitionFormat::from_path("game"), None);
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let result: DefinitionResult<u32> = DefinitionFormat::Toml.parse("x = 1");
+        if !DefinitionFormat::Toml.is_supported() {
+            assert!(matches!(result, Err(DefinitionError::UnsupportedFormat(_))));
+        }
+        assert!(DefinitionFormat::Json.is_supported());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let result: DefinitionResult<u32> = DefinitionFormat::Json.parse("{");
+        assert!(matches!(
+            result,
+            Err(DefinitionError::Parse { format: DefinitionFormat::Json, .. })
+        ));
+    }
+}
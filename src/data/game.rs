@@ -0,0 +1,1221 @@
+//! Game definitions: zones, templates, phases, events, cards and abilities.
+//!
+//! A [`GameDefinition`] mirrors what a game would otherwise wire up in
+//! Rust. IDs are the same numeric IDs the engine uses, so effects and
+//! trigger conditions are written exactly as [`Effect`] and
+//! [`TriggerCondition`] serialize. [`GameDefinition::build`] checks every
+//! cross-reference and produces a [`LoadedGame`].
+//!
+//! ## Example (JSON)
+//!
+//! ```json
+//! {
+//!   "name": "Skirmish",
+//!   "player_count": 2,
+//!   "zones": [{ "id": 0, "name": "Battlefield" }],
+//!   "event_types": [{ "id": 1, "name": "TurnStart" }],
+//!   "card_types": [{ "id": 0, "name": "Creature" }],
+//!   "cards": [{
+//!     "id": 1, "name": "Goblin", "card_type": 0,
+//!     "attributes": { "power": 2, "haste": true },
+//!     "abilities": [{
+//!       "name": "Rally",
+//!       "events": [1],
+//!       "effects": [{ "ModifyCardState": { "key": "power", "delta": 1 } }]
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! Attribute values are written plainly: integers, booleans, strings, and
+//! lists of integers or strings.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::cards::{AttributeSchema, AttributeValue, CardDefinition, CardId, CardRegistry, CardTypeId};
+use crate::core::{
+    EntityId, GameConfig, PhaseId, PlayerId, TemplateConfig, TemplateId, ZoneConfig, ZoneId,
+};
+use crate::effects::{Effect, EntityPredicate, PlayerMatch, PlayerRef, TargetFilter, TargetSpec, TargetType, ValueExpr};
+use crate::triggers::{
+    EventModifier, EventTypeConfig, EventTypeId, Trigger, TriggerCondition, TriggerId,
+    TriggerRegistry, TriggerTiming,
+};
+
+use super::card_set::CardSet;
+use super::error::{DefinitionError, DefinitionResult, ValidationError};
+use super::format::{self, DefinitionFormat};
+
+/// A named game phase.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseDef {
+    /// Phase identifier.
+    pub id: PhaseId,
+    /// Human-readable name.
+    pub name: String,
+}
+
+/// A named card type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardTypeDef {
+    /// Card type identifier.
+    pub id: CardTypeId,
+    /// Human-readable name.
+    pub name: String,
+}
+
+/// A triggered ability: the events it listens for and what it does.
+///
+/// Abilities on a card become [`Trigger`]s when the card enters play
+/// (see [`LoadedGame::register_abilities`]); top-level abilities are
+/// global triggers registered at load time.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AbilityDef {
+    /// Human-readable name.
+    pub name: String,
+
+    /// Event types that fire this ability.
+    pub events: Vec<EventTypeId>,
+
+    /// Additional condition beyond the event type.
+    #[serde(default = "always", skip_serializing_if = "is_always")]
+    pub condition: TriggerCondition,
+
+    /// When in event resolution the ability fires.
+    #[serde(default)]
+    pub timing: TriggerTiming,
+
+    /// Effects to execute.
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+
+    /// Targets the controller chooses for the effects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<TargetSpec>,
+
+    /// Changes to the pending event (`Before` abilities only).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<EventModifier>,
+
+    /// Ordering among simultaneous triggers (higher first).
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Limited number of uses. `None` = unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uses: Option<u32>,
+}
+
+fn always() -> TriggerCondition {
+    TriggerCondition::Always
+}
+
+fn is_always(condition: &TriggerCondition) -> bool {
+    *condition == TriggerCondition::Always
+}
+
+impl AbilityDef {
+    /// Build an unowned trigger for this ability.
+    ///
+    /// The trigger has ID 0, so [`TriggerRegistry::register`] assigns one.
+    #[must_use]
+    pub fn to_trigger(&self) -> Trigger {
+        let mut events = self.events.iter().copied();
+        let first = events.next().unwrap_or(EventTypeId::new(0));
+        let mut trigger = Trigger::new(TriggerId::new(0), self.name.clone(), first)
+            .with_condition(self.condition.clone())
+            .with_timing(self.timing)
+            .with_priority(self.priority);
+        for event in events {
+            trigger = trigger.also_on(event);
+        }
+        trigger.effects = self.effects.clone();
+        trigger.target = self.target.clone();
+        trigger.modifiers = self.modifiers.clone();
+        trigger.uses_remaining = self.uses;
+        trigger
+    }
+}
+
+/// A card and its abilities.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CardDef {
+    /// Card identifier.
+    pub id: CardId,
+
+    /// Card name.
+    pub name: String,
+
+    /// Card type (must be declared in `card_types`).
+    pub card_type: CardTypeId,
+
+    /// Game-specific attributes.
+    #[serde(default, with = "plain_attributes", skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, AttributeValue>,
+
+    /// Triggered abilities.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub abilities: Vec<AbilityDef>,
+}
+
+impl CardDef {
+    /// Describe an engine card definition (without abilities).
+    #[must_use]
+    pub fn from_definition(card: &CardDefinition) -> Self {
+        Self {
+            id: card.id,
+            name: card.name.clone(),
+            card_type: card.card_type,
+            attributes: card
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.as_str().to_string(), value.clone()))
+                .collect(),
+            abilities: Vec::new(),
+        }
+    }
+
+    /// Build the engine card definition.
+    #[must_use]
+    pub fn to_definition(&self) -> CardDefinition {
+        let mut card = CardDefinition::new(self.id, self.name.clone(), self.card_type);
+        for (key, value) in &self.attributes {
+            card = card.with_attr(key.as_str(), value.clone());
+        }
+        card
+    }
+}
+
+/// Attribute values written without enum tags (`power = 3`).
+mod plain_attributes {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::cards::AttributeValue;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Plain {
+        Bool(bool),
+        Int(i64),
+        Text(String),
+        IntList(Vec<i64>),
+        TextList(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(
+        attributes: &BTreeMap<String, AttributeValue>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let plain: BTreeMap<&String, Plain> = attributes
+            .iter()
+            .map(|(key, value)| {
+                let value = match value.clone() {
+                    AttributeValue::Bool(v) => Plain::Bool(v),
+                    AttributeValue::Int(v) => Plain::Int(v),
+                    AttributeValue::Text(v) => Plain::Text(v),
+                    AttributeValue::IntList(v) => Plain::IntList(v),
+                    AttributeValue::TextList(v) => Plain::TextList(v),
+                };
+                (key, value)
+            })
+            .collect();
+        plain.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, AttributeValue>, D::Error> {
+        let plain = BTreeMap::<String, Plain>::deserialize(deserializer)?;
+        Ok(plain
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Plain::Bool(v) => AttributeValue::Bool(v),
+                    Plain::Int(v) => AttributeValue::Int(v),
+                    Plain::Text(v) => AttributeValue::Text(v),
+                    Plain::IntList(v) => AttributeValue::IntList(v),
+                    Plain::TextList(v) => AttributeValue::TextList(v),
+                };
+                (key, value)
+            })
+            .collect())
+    }
+}
+
+/// A complete game definition as read from a file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameDefinition {
+    /// Game name.
+    #[serde(default)]
+    pub name: String,
+
+    /// Number of players.
+    pub player_count: usize,
+
+    /// Zones. Names must be unique per owner.
+    #[serde(default)]
+    pub zones: Vec<ZoneConfig>,
+
+    /// Action templates.
+    #[serde(default)]
+    pub templates: Vec<TemplateConfig>,
+
+    /// Phases.
+    #[serde(default)]
+    pub phases: Vec<PhaseDef>,
+
+    /// Starting phase. Defaults to the first listed phase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_phase: Option<PhaseId>,
+
+    /// Event types that triggers can listen for.
+    #[serde(default)]
+    pub event_types: Vec<EventTypeConfig>,
+
+    /// Card types.
+    #[serde(default)]
+    pub card_types: Vec<CardTypeDef>,
+
+    /// Declared card attributes. Cards are checked against it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<AttributeSchema>,
+
+    /// Cards.
+    #[serde(default)]
+    pub cards: Vec<CardDef>,
+
+    /// Global triggers, registered at load time. They have no controller,
+    /// so they cannot have a target.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<AbilityDef>,
+}
+
+impl GameDefinition {
+    /// Create an empty definition.
+    pub fn new(name: impl Into<String>, player_count: usize) -> Self {
+        Self {
+            name: name.into(),
+            player_count,
+            zones: Vec::new(),
+            templates: Vec::new(),
+            phases: Vec::new(),
+            initial_phase: None,
+            event_types: Vec::new(),
+            card_types: Vec::new(),
+            schema: None,
+            cards: Vec::new(),
+            triggers: Vec::new(),
+        }
+    }
+
+    /// Parse a definition from text. Does not validate.
+    pub fn parse(text: &str, format: DefinitionFormat) -> DefinitionResult<Self> {
+        format.parse(text)
+    }
+
+    /// Read a definition file, picking the format from its extension.
+    /// Does not validate.
+    pub fn load(path: impl AsRef<Path>) -> DefinitionResult<Self> {
+        format::read_file(path.as_ref())
+    }
+
+    /// Write the definition as text.
+    pub fn write(&self, format: DefinitionFormat) -> DefinitionResult<String> {
+        format.write(self)
+    }
+
+    /// Save to a file, picking the format from its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> DefinitionResult<()> {
+        format::write_file(path.as_ref(), self)
+    }
+
+    /// Append the cards of a set, stamping each with the set name.
+    ///
+    /// Clashing card IDs are reported by [`validate`](Self::validate).
+    pub fn add_card_set(&mut self, set: &CardSet) {
+        self.cards.extend(set.stamped_cards());
+    }
+
+    /// Check IDs are unique and every reference points at something
+    /// defined. Reports all problems at once.
+    pub fn validate(&self) -> DefinitionResult<()> {
+        let errors = Validator::new(self).run();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DefinitionError::Invalid(errors))
+        }
+    }
+
+    /// Validate and build the engine configuration and registries.
+    pub fn build(&self) -> DefinitionResult<LoadedGame> {
+        self.validate()?;
+
+        let initial_phase = self
+            .initial_phase
+            .or_else(|| self.phases.first().map(|phase| phase.id))
+            .unwrap_or_default();
+        let mut config = GameConfig::new(self.player_count).with_initial_phase(initial_phase);
+        config.zones = self.zones.clone();
+        config.templates = self.templates.clone();
+
+        let mut cards = match &self.schema {
+            Some(schema) => CardRegistry::new().with_schema(schema.clone()),
+            None => CardRegistry::new(),
+        };
+        let mut abilities = FxHashMap::default();
+        for card in &self.cards {
+            cards
+                .try_register(card.to_definition())
+                .map_err(|errors| DefinitionError::Invalid(errors.into_iter().map(ValidationError::Card).collect()))?;
+            if !card.abilities.is_empty() {
+                abilities.insert(card.id, card.abilities.iter().map(AbilityDef::to_trigger).collect());
+            }
+        }
+
+        let mut triggers = TriggerRegistry::new();
+        for ability in &self.triggers {
+            triggers.register(ability.to_trigger());
+        }
+
+        Ok(LoadedGame {
+            name: self.name.clone(),
+            config,
+            cards,
+            triggers,
+            phases: self.phases.clone(),
+            event_types: self.event_types.clone(),
+            card_types: self.card_types.clone(),
+            abilities,
+        })
+    }
+}
+
+/// A validated game definition, ready to play.
+#[derive(Clone, Debug)]
+pub struct LoadedGame {
+    /// Game name.
+    pub name: String,
+    /// Engine configuration.
+    pub config: GameConfig,
+    /// Card definitions.
+    pub cards: CardRegistry,
+    /// Global triggers.
+    pub triggers: TriggerRegistry,
+    /// Phases.
+    pub phases: Vec<PhaseDef>,
+    /// Event types.
+    pub event_types: Vec<EventTypeConfig>,
+    /// Card types.
+    pub card_types: Vec<CardTypeDef>,
+    abilities: FxHashMap<CardId, Vec<Trigger>>,
+}
+
+impl LoadedGame {
+    /// Read, validate and build a definition file.
+    pub fn load(path: impl AsRef<Path>) -> DefinitionResult<Self> {
+        GameDefinition::load(path)?.build()
+    }
+
+    /// Unowned triggers for a card's abilities.
+    #[must_use]
+    pub fn abilities(&self, card: CardId) -> &[Trigger] {
+        self.abilities.get(&card).map_or(&[], Vec::as_slice)
+    }
+
+    /// Register a card's abilities for an instance entering play.
+    ///
+    /// Each trigger gets `source` and `controller` set and a fresh ID.
+    pub fn register_abilities(
+        &self,
+        registry: &mut TriggerRegistry,
+        card: CardId,
+        source: EntityId,
+        controller: PlayerId,
+    ) -> Vec<TriggerId> {
+        self.abilities(card)
+            .iter()
+            .map(|trigger| {
+                registry.register(
+                    trigger
+                        .clone()
+                        .with_source(source)
+                        .with_controller(controller),
+                )
+            })
+            .collect()
+    }
+
+    /// Find a zone by name and owner.
+    #[must_use]
+    pub fn zone(&self, name: &str, owner: Option<PlayerId>) -> Option<ZoneId> {
+        self.config
+            .zones
+            .iter()
+            .find(|zone| zone.name == name && zone.owner == owner)
+            .map(|zone| zone.id)
+    }
+
+    /// Find a template by name.
+    #[must_use]
+    pub fn template(&self, name: &str) -> Option<TemplateId> {
+        self.config
+            .templates
+            .iter()
+            .find(|template| template.name == name)
+            .map(|template| template.id)
+    }
+
+    /// Find a phase by name.
+    #[must_use]
+    pub fn phase(&self, name: &str) -> Option<PhaseId> {
+        self.phases.iter().find(|phase| phase.name == name).map(|phase| phase.id)
+    }
+
+    /// Find an event type by name.
+    #[must_use]
+    pub fn event_type(&self, name: &str) -> Option<EventTypeId> {
+        self.event_types
+            .iter()
+            .find(|event| event.name == name)
+            .map(|event| event.id)
+    }
+
+    /// Find a card type by name.
+    #[must_use]
+    pub fn card_type(&self, name: &str) -> Option<CardTypeId> {
+        self.card_types
+            .iter()
+            .find(|card_type| card_type.name == name)
+            .map(|card_type| card_type.id)
+    }
+
+    /// Find a card by name.
+    #[must_use]
+    pub fn card(&self, name: &str) -> Option<&CardDefinition> {
+        self.cards.find(|card| card.name == name).next()
+    }
+}
+
+/// Collects validation errors for a definition.
+struct Validator<'a> {
+    def: &'a GameDefinition,
+    zones: FxHashSet<ZoneId>,
+    event_types: FxHashSet<EventTypeId>,
+    card_types: FxHashSet<CardTypeId>,
+    cards: FxHashSet<CardId>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'a> Validator<'a> {
+    fn new(def: &'a GameDefinition) -> Self {
+        Self {
+            def,
+            zones: def.zones.iter().map(|zone| zone.id).collect(),
+            event_types: def.event_types.iter().map(|event| event.id).collect(),
+            card_types: def.card_types.iter().map(|card_type| card_type.id).collect(),
+            cards: def.cards.iter().map(|card| card.id).collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<ValidationError> {
+        let def = self.def;
+        if def.player_count == 0 || def.player_count > 255 {
+            self.errors.push(ValidationError::InvalidPlayerCount(def.player_count));
+        }
+
+        self.unique("zone", def.zones.iter().map(|z| (u64::from(z.id.0), None)));
+        self.unique_names(
+            "zone",
+            def.zones
+                .iter()
+                .map(|z| (z.name.as_str(), z.owner.map(|p| p.0))),
+        );
+        self.unique("template", def.templates.iter().map(|t| (u64::from(t.id.0), None)));
+        self.unique_names("template", def.templates.iter().map(|t| (t.name.as_str(), None)));
+        self.unique("phase", def.phases.iter().map(|p| (u64::from(p.id.0), None)));
+        self.unique_names("phase", def.phases.iter().map(|p| (p.name.as_str(), None)));
+        self.unique("event type", def.event_types.iter().map(|e| (u64::from(e.id.0), None)));
+        self.unique_names("event type", def.event_types.iter().map(|e| (e.name.as_str(), None)));
+        self.unique("card type", def.card_types.iter().map(|c| (u64::from(c.id.0), None)));
+        self.unique_names("card type", def.card_types.iter().map(|c| (c.name.as_str(), None)));
+        self.unique("card", def.cards.iter().map(|c| (u64::from(c.id.0), None)));
+
+        for zone in &def.zones {
+            if let Some(owner) = zone.owner {
+                self.player(&format!("zone '{}'", zone.name), owner);
+            }
+        }
+
+        if let Some(phase) = def.initial_phase {
+            if !def.phases.iter().any(|p| p.id == phase) {
+                self.errors.push(ValidationError::UnknownReference {
+                    context: "initial_phase".to_string(),
+                    kind: "phase",
+                    id: u64::from(phase.0),
+                });
+            }
+        }
+
+        if let Some(schema) = &def.schema {
+            for entry in &schema.types {
+                if !self.card_types.contains(&entry.card_type) {
+                    self.unknown("schema", "card type", u64::from(entry.card_type.0));
+                }
+            }
+        }
+
+        for card in &def.cards {
+            let context = format!("card '{}'", card.name);
+            if !self.card_types.contains(&card.card_type) {
+                self.unknown(&context, "card type", u64::from(card.card_type.0));
+            }
+            if let Some(schema) = &def.schema {
+                let errors = schema.validate(&card.to_definition());
+                self.errors.extend(errors.into_iter().map(ValidationError::Card));
+            }
+            for ability in &card.abilities {
+                self.ability(&format!("{} ability '{}'", context, ability.name), ability);
+            }
+        }
+        for ability in &def.triggers {
+            let context = format!("trigger '{}'", ability.name);
+            self.ability(&context, ability);
+            if ability.target.is_some() {
+                self.errors.push(ValidationError::TargetWithoutController { context });
+            }
+        }
+
+        self.errors
+    }
+
+    fn unique(&mut self, kind: &'static str, ids: impl Iterator<Item = (u64, Option<u8>)>) {
+        let mut seen = FxHashSet::default();
+        for id in ids {
+            if !seen.insert(id) {
+                self.errors.push(ValidationError::DuplicateId { kind, id: id.0 });
+            }
+        }
+    }
+
+    fn unique_names<'n>(
+        &mut self,
+        kind: &'static str,
+        names: impl Iterator<Item = (&'n str, Option<u8>)>,
+    ) {
+        let mut seen = FxHashSet::default();
+        for name in names {
+            if !seen.insert(name) {
+                self.errors.push(ValidationError::DuplicateName {
+                    kind,
+                    name: name.0.to_string(),
+                });
+            }
+        }
+    }
+
+    fn unknown(&mut self, context: &str, kind: &'static str, id: u64) {
+        self.errors.push(ValidationError::UnknownReference {
+            context: context.to_string(),
+            kind,
+            id,
+        });
+    }
+
+    fn zone(&mut self, context: &str, zone: ZoneId) {
+        if !self.zones.contains(&zone) {
+            self.unknown(context, "zone", u64::from(zone.0));
+        }
+    }
+
+    fn event_type(&mut self, context: &str, event: EventTypeId) {
+        if !self.event_types.contains(&event) {
+            self.unknown(context, "event type", u64::from(event.0));
+        }
+    }
+
+    fn card(&mut self, context: &str, card: CardId) {
+        if !self.cards.contains(&card) {
+            self.unknown(context, "card", u64::from(card.0));
+        }
+    }
+
+    fn player(&mut self, context: &str, player: PlayerId) {
+        if player.index() >= self.def.player_count {
+            self.errors.push(ValidationError::PlayerOutOfRange {
+                context: context.to_string(),
+                player: player.0,
+            });
+        }
+    }
+
+    fn ability(&mut self, context: &str, ability: &AbilityDef) {
+        if ability.events.is_empty() {
+            self.errors.push(ValidationError::NoEvents {
+                context: context.to_string(),
+            });
+        }
+        for &event in &ability.events {
+            self.event_type(context, event);
+        }
+        self.condition(context, &ability.condition);
+        for effect in &ability.effects {
+            self.effect(context, effect);
+        }
+        if let Some(spec) = &ability.target {
+            self.target_spec(context, spec);
+        }
+        for modifier in &ability.modifiers {
+            self.modifier(context, modifier);
+        }
+    }
+
+    fn modifier(&mut self, context: &str, modifier: &EventModifier) {
+        match modifier {
+            EventModifier::SetZone { zone, .. } => self.zone(context, *zone),
+            EventModifier::AddValue { .. }
+            | EventModifier::SetValue { .. }
+            | EventModifier::MultiplyValue { .. }
+            | EventModifier::Retarget(_)
+            | EventModifier::RetargetToSource
+            | EventModifier::RetargetToController
+            | EventModifier::Cancel => {}
+        }
+    }
+
+    fn condition(&mut self, context: &str, condition: &TriggerCondition) {
+        match condition {
+            TriggerCondition::EventType(event) => self.event_type(context, *event),
+            TriggerCondition::AnyEventType(events) => {
+                for &event in events {
+                    self.event_type(context, event);
+                }
+            }
+            TriggerCondition::SourceControlledBy(player)
+            | TriggerCondition::TargetControlledBy(player)
+            | TriggerCondition::ForPlayer(player) => self.player(context, *player),
+            TriggerCondition::SourceInZone(zone) | TriggerCondition::TargetInZone(zone) => {
+                self.zone(context, *zone)
+            }
+            TriggerCondition::All(conditions) | TriggerCondition::Any(conditions) => {
+                for condition in conditions {
+                    self.condition(context, condition);
+                }
+            }
+            TriggerCondition::Not(condition) => self.condition(context, condition),
+            TriggerCondition::SourceMatches(predicate)
+            | TriggerCondition::TargetMatches(predicate)
+            | TriggerCondition::PlayerMatches(predicate) => self.predicate(context, predicate),
+            TriggerCondition::SourceIs(_)
+            | TriggerCondition::TargetIs(_)
+            | TriggerCondition::ValueAtLeast { .. }
+            | TriggerCondition::ValueAtMost { .. }
+            | TriggerCondition::ValueInRange { .. }
+            | TriggerCondition::HasTag(_)
+            | TriggerCondition::NotTag(_)
+            | TriggerCondition::Always
+            | TriggerCondition::Never
+            | TriggerCondition::Custom(_) => {}
+        }
+    }
+
+    fn effect(&mut self, context: &str, effect: &Effect) {
+        match effect {
+            Effect::MoveCard { destination, .. } => self.zone(context, *destination),
+            Effect::DrawCards {
+                count,
+                from_zone,
+                to_zone,
+            } => {
+                self.value(context, count);
+                for zone in from_zone.iter().chain(to_zone) {
+                    self.zone(context, *zone);
+                }
+            }
+            Effect::ShuffleZone { zone } => self.zone(context, *zone),
+            Effect::CreateCard {
+                card_id,
+                zone,
+                owner,
+                ..
+            } => {
+                self.card(context, *card_id);
+                if let Some(zone) = zone {
+                    self.zone(context, *zone);
+                }
+                self.player_ref(context, owner);
+            }
+            Effect::CopyCard { zone, owner, .. } => {
+                if let Some(zone) = zone {
+                    self.zone(context, *zone);
+                }
+                self.player_ref(context, owner);
+            }
+            Effect::TransformCard { into } => self.card(context, *into),
+            Effect::RemoveFromGame => {}
+            Effect::Batch(effects) => {
+                for effect in effects {
+                    self.effect(context, effect);
+                }
+            }
+            Effect::Conditional { effect, .. } => self.effect(context, effect),
+            Effect::Choose { chooser, spec, effect } => {
+                self.player_ref(context, chooser);
+                self.target_spec(context, spec);
+                self.effect(context, effect);
+            }
+            Effect::ModifyPlayerState { delta: value, .. }
+            | Effect::SetPlayerState { value, .. }
+            | Effect::ModifyCardState { delta: value, .. }
+            | Effect::SetCardState { value, .. }
+            | Effect::ModifyTurnState { delta: value, .. }
+            | Effect::SetTurnState { value, .. } => self.value(context, value),
+        }
+    }
+
+    fn value(&mut self, context: &str, value: &ValueExpr) {
+        match value {
+            ValueExpr::ZoneSize(zone) => self.zone(context, *zone),
+            ValueExpr::CardsInZone { zone, owner } => {
+                self.zone(context, *zone);
+                self.player_ref(context, owner);
+            }
+            ValueExpr::PlayerState { player, .. }
+            | ValueExpr::HandSize(player)
+            | ValueExpr::DeckSize(player) => self.player_ref(context, player),
+            ValueExpr::Add(a, b)
+            | ValueExpr::Sub(a, b)
+            | ValueExpr::Mul(a, b)
+            | ValueExpr::Div(a, b) => {
+                self.value(context, a);
+                self.value(context, b);
+            }
+            ValueExpr::Neg(value) => self.value(context, value),
+            ValueExpr::Min(values) | ValueExpr::Max(values) => {
+                for value in values {
+                    self.value(context, value);
+                }
+            }
+            ValueExpr::TurnState(_)
+            | ValueExpr::CardState { .. }
+            | ValueExpr::CardAttribute { .. }
+            | ValueExpr::EventValue(_)
+            | ValueExpr::Const(_) => {}
+        }
+    }
+
+    fn target_spec(&mut self, context: &str, spec: &TargetSpec) {
+        if let TargetType::Card { zones } = &spec.target_type {
+            for zone in zones {
+                self.zone(context, *zone);
+            }
+        }
+        for filter in &spec.filters {
+            match filter {
+                TargetFilter::InZone(zone) => self.zone(context, *zone),
+                TargetFilter::SpecificPlayer(player)
+                | TargetFilter::OwnedBy(player)
+                | TargetFilter::ControlledBy(player) => self.player(context, *player),
+                TargetFilter::HasCardType(card_type) if !self.card_types.contains(card_type) => {
+                    self.unknown(context, "card type", u64::from(card_type.0));
+                }
+                TargetFilter::Matches(predicate) => self.predicate(context, predicate),
+                _ => {}
+            }
+        }
+    }
+
+    fn predicate(&mut self, context: &str, predicate: &EntityPredicate) {
+        match predicate {
+            EntityPredicate::All(predicates) | EntityPredicate::Any(predicates) => {
+                for predicate in predicates {
+                    self.predicate(context, predicate);
+                }
+            }
+            EntityPredicate::Not(predicate) => self.predicate(context, predicate),
+            EntityPredicate::InZone(zone) => self.zone(context, *zone),
+            EntityPredicate::IsPlayer(PlayerMatch::Player(player))
+            | EntityPredicate::OwnedBy(PlayerMatch::Player(player))
+            | EntityPredicate::ControlledBy(PlayerMatch::Player(player)) => self.player(context, *player),
+            EntityPredicate::CardType(card_type) if !self.card_types.contains(card_type) => {
+                self.unknown(context, "card type", u64::from(card_type.0));
+            }
+            _ => {}
+        }
+    }
+
+    fn player_ref(&mut self, context: &str, player: &PlayerRef) {
+        if let PlayerRef::Player(player) = player {
+            self.player(context, *player);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{GameState, ZoneVisibility};
+    use crate::triggers::GameEvent;
+
+    const GAME_JSON: &str = r#"{
+        "name": "Skirmish",
+        "player_count": 2,
+        "zones": [
+            { "id": 0, "name": "Battlefield" },
+            { "id": 1, "name": "Hand", "owner": 0, "visibility": "OwnerOnly" },
+            { "id": 2, "name": "Hand", "owner": 1, "visibility": "OwnerOnly" },
+            { "id": 3, "name": "Graveyard", "ordered": true }
+        ],
+        "templates": [
+            { "id": 0, "name": "Pass" },
+            { "id": 1, "name": "Play", "pointer_count": 1 }
+        ],
+        "phases": [
+            { "id": 1, "name": "Main" },
+            { "id": 2, "name": "End" }
+        ],
+        "event_types": [
+            { "id": 1, "name": "TurnStart" },
+            { "id": 2, "name": "CardDied", "description": "A card went to the graveyard" }
+        ],
+        "card_types": [{ "id": 0, "name": "Creature" }],
+        "cards": [
+            {
+                "id": 1, "name": "Goblin", "card_type": 0,
+                "attributes": { "power": 2, "haste": true, "tribe": "Goblin", "costs": [1, 0], "keywords": ["Haste"] },
+                "abilities": [{
+                    "name": "Rally",
+                    "events": [1],
+                    "condition": { "ForPlayer": 0 },
+                    "effects": [{ "ModifyCardState": { "key": "power", "delta": 1 } }],
+                    "uses": 3
+                }]
+            },
+            { "id": 2, "name": "Wall", "card_type": 0 }
+        ],
+        "triggers": [{
+            "name": "Bury",
+            "events": [2],
+            "timing": "After",
+            "effects": [{ "MoveCard": { "destination": 3, "position": null } }]
+        }]
+    }"#;
+
+    fn definition() -> GameDefinition {
+        GameDefinition::parse(GAME_JSON, DefinitionFormat::Json).unwrap()
+    }
+
+    #[test]
+    fn test_build() {
+        let game = definition().build().unwrap();
+
+        assert_eq!(game.name, "Skirmish");
+        assert_eq!(game.config.player_count, 2);
+        assert_eq!(game.config.zones.len(), 4);
+        assert_eq!(game.config.initial_phase, PhaseId::new(1));
+        assert_eq!(game.zone("Hand", Some(PlayerId::new(1))), Some(ZoneId::new(2)));
+        assert_eq!(game.zone("Hand", None), None);
+        assert_eq!(game.config.get_zone(ZoneId::new(0)).unwrap().visibility, ZoneVisibility::Public);
+        assert!(game.config.get_zone(ZoneId::new(3)).unwrap().ordered);
+        assert_eq!(game.template("Play"), Some(TemplateId::new(1)));
+        assert_eq!(game.config.get_template(TemplateId::new(1)).unwrap().pointer_count, 1);
+        assert_eq!(game.phase("End"), Some(PhaseId::new(2)));
+        assert_eq!(game.event_type("CardDied"), Some(EventTypeId::new(2)));
+        assert_eq!(game.card_type("Creature"), Some(CardTypeId::new(0)));
+
+        let goblin = game.card("Goblin").unwrap();
+        assert_eq!(goblin.get_int("power", 0), 2);
+        assert!(goblin.get_bool("haste", false));
+        assert_eq!(goblin.get_text("tribe"), Some("Goblin"));
+        assert_eq!(goblin.get_attr("costs").and_then(|v| v.as_int_list()), Some(&[1, 0][..]));
+        assert_eq!(
+            goblin.get_attr("keywords").and_then(|v| v.as_text_list()),
+            Some(&["Haste".to_string()][..])
+        );
+        assert_eq!(game.cards.len(), 2);
+        assert_eq!(game.abilities(CardId::new(1)).len(), 1);
+        assert!(game.abilities(CardId::new(2)).is_empty());
+    }
+
+    #[test]
+    fn test_triggers_fire() {
+        let game = definition().build().unwrap();
+        let state = GameState::new(2, 42);
+
+        let died = GameEvent::new(EventTypeId::new(2)).with_target(EntityId(7));
+        let fired = game.triggers.find_triggers(&died, &state, TriggerTiming::After, None);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].effects, vec![Effect::move_to(ZoneId::new(3))]);
+
+        let mut registry = game.triggers.clone();
+        let ids = game.register_abilities(&mut registry, CardId::new(1), EntityId(10), PlayerId::new(0));
+        assert_eq!(ids.len(), 1);
+        let rally = registry.get(ids[0]).unwrap();
+        assert_eq!(rally.source, Some(EntityId(10)));
+        assert_eq!(rally.uses_remaining, Some(3));
+
+        let ours = GameEvent::for_player(EventTypeId::new(1), PlayerId::new(0));
+        let theirs = GameEvent::for_player(EventTypeId::new(1), PlayerId::new(1));
+        assert_eq!(registry.find_triggers(&ours, &state, TriggerTiming::After, None).len(), 1);
+        assert!(registry.find_triggers(&theirs, &state, TriggerTiming::After, None).is_empty());
+    }
+
+    #[test]
+    fn test_validation_reports_all_errors() {
+        let mut def = definition();
+        def.zones.push(ZoneConfig::new(ZoneId::new(0), "Exile"));
+        def.zones.push(ZoneConfig::new(ZoneId::new(9), "Hand").with_owner(PlayerId::new(0)));
+        def.zones.push(ZoneConfig::new(ZoneId::new(10), "Deck").with_owner(PlayerId::new(5)));
+        def.initial_phase = Some(PhaseId::new(7));
+        def.cards[1].card_type = CardTypeId::new(4);
+        def.cards[0].abilities[0].events = vec![EventTypeId::new(1), EventTypeId::new(8)];
+        def.triggers[0].effects = vec![Effect::batch([Effect::move_to(ZoneId::new(42))])];
+        def.triggers.push(AbilityDef {
+            name: "Silent".to_string(),
+            events: Vec::new(),
+            condition: TriggerCondition::TargetInZone(ZoneId::new(50))
+                .negate()
+                .and(TriggerCondition::SourceMatches(EntityPredicate::any([
+                    EntityPredicate::InZone(ZoneId::new(0)),
+                    EntityPredicate::CardType(CardTypeId::new(5)).negate(),
+                ]))),
+            timing: TriggerTiming::Before,
+            effects: vec![
+                Effect::damage(ValueExpr::ZoneSize(ZoneId::new(70))),
+                Effect::create_in_hand(CardId::new(99)),
+            ],
+            target: Some(
+                TargetSpec::single_card([ZoneId::new(80)]).with_filter(TargetFilter::HasCardType(CardTypeId::new(6))),
+            ),
+            modifiers: vec![EventModifier::SetZone { index: 1, zone: ZoneId::new(60) }],
+            priority: 0,
+            uses: None,
+        });
+
+        let Err(DefinitionError::Invalid(errors)) = def.build() else {
+            panic!("expected validation errors");
+        };
+        let expected = [
+            ValidationError::DuplicateId { kind: "zone", id: 0 },
+            ValidationError::DuplicateName { kind: "zone", name: "Hand".to_string() },
+            ValidationError::PlayerOutOfRange { context: "zone 'Deck'".to_string(), player: 5 },
+            ValidationError::UnknownReference {
+                context: "initial_phase".to_string(),
+                kind: "phase",
+                id: 7,
+            },
+            ValidationError::UnknownReference {
+                context: "card 'Goblin' ability 'Rally'".to_string(),
+                kind: "event type",
+                id: 8,
+            },
+            ValidationError::UnknownReference {
+                context: "card 'Wall'".to_string(),
+                kind: "card type",
+                id: 4,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Bury'".to_string(),
+                kind: "zone",
+                id: 42,
+            },
+            ValidationError::NoEvents { context: "trigger 'Silent'".to_string() },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "zone",
+                id: 50,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "card type",
+                id: 5,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "zone",
+                id: 70,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "card",
+                id: 99,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "zone",
+                id: 80,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "card type",
+                id: 6,
+            },
+            ValidationError::UnknownReference {
+                context: "trigger 'Silent'".to_string(),
+                kind: "zone",
+                id: 60,
+            },
+            ValidationError::TargetWithoutController { context: "trigger 'Silent'".to_string() },
+        ];
+        assert_eq!(errors, expected);
+    }
+
+    #[test]
+    fn test_add_card_set() {
+        let mut def = definition();
+        let set = CardSet::new("Expansion")
+            .with_card(CardDef::from_definition(&CardDefinition::new(
+                CardId::new(3),
+                "Ogre",
+                CardTypeId::new(0),
+            )))
+            .with_card(CardDef::from_definition(&CardDefinition::new(
+                CardId::new(2),
+                "Tower",
+                CardTypeId::new(0),
+            )));
+        def.add_card_set(&set);
+
+        let Err(DefinitionError::Invalid(errors)) = def.validate() else {
+            panic!("expected a duplicate card id");
+        };
+        assert_eq!(errors, vec![ValidationError::DuplicateId { kind: "card", id: 2 }]);
+
+        def.cards.pop();
+        let game = def.build().unwrap();
+        assert_eq!(game.card("Ogre").unwrap().get_text(CardSet::SET_ATTRIBUTE), Some("Expansion"));
+        assert_eq!(game.card("Goblin").unwrap().get_text(CardSet::SET_ATTRIBUTE), None);
+    }
+
+    #[test]
+    fn test_schema() {
+        let mut def = definition();
+        let schema = r#"{
+            "common": [{ "key": "power", "type": "Int", "required": true, "min": 0 }],
+            "types": [{ "card_type": 0, "attributes": [
+                { "key": "haste", "type": "Bool" },
+                { "key": "tribe", "type": "Text" },
+                { "key": "costs", "type": "IntList" },
+                { "key": "keywords", "type": "TextList" }
+            ]}]
+        }"#;
+        def.schema = Some(serde_json::from_str(schema).unwrap());
+
+        let Err(DefinitionError::Invalid(errors)) = def.validate() else {
+            panic!("expected schema errors");
+        };
+        // The Wall has no power
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "Card(2): required attribute 'power' is missing"
+        );
+
+        def.cards[1].attributes.insert("power".to_string(), 0i32.into());
+        let game = def.build().unwrap();
+        assert!(game.cards.schema().is_some());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let def = definition();
+        let text = def.write(DefinitionFormat::Json).unwrap();
+        assert_eq!(GameDefinition::parse(&text, DefinitionFormat::Json).unwrap(), def);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("ccg_definition_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.json");
+
+        definition().save(&path).unwrap();
+        let game = LoadedGame::load(&path).unwrap();
+        assert_eq!(game.cards.len(), 2);
+
+        let unknown = dir.join("game.txt");
+        assert!(matches!(
+            GameDefinition::load(&unknown),
+            Err(DefinitionError::UnknownFormat(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let text = r#"
+            name = "Skirmish"
+            player_count = 2
+
+            [[zones]]
+            id = 0
+            name = "Battlefield"
+
+            [[event_types]]
+            id = 1
+            name = "TurnStart"
+
+            [[card_types]]
+            id = 0
+            name = "Creature"
+
+            [[cards]]
+            id = 1
+            name = "Goblin"
+            card_type = 0
+            attributes = { power = 2, haste = true }
+
+            [[cards.abilities]]
+            name = "Rally"
+            events = [1]
+            condition = { ForPlayer = 0 }
+            effects = [{ ModifyCardState = { key = "power", delta = 1 } }]
+        "#;
+        let def = GameDefinition::parse(text, DefinitionFormat::Toml).unwrap();
+        let game = def.build().unwrap();
+        assert_eq!(game.card("Goblin").unwrap().get_int("power", 0), 2);
+        assert_eq!(game.abilities(CardId::new(1)).len(), 1);
+
+        let full = definition();
+        let text = full.write(DefinitionFormat::Toml).unwrap();
+        assert_eq!(GameDefinition::parse(&text, DefinitionFormat::Toml).unwrap(), full);
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_ron() {
+        let text = r#"(
+            name: "Skirmish",
+            player_count: 2,
+            zones: [(id: (0), name: "Battlefield")],
+            event_types: [(id: (1), name: "TurnStart")],
+            card_types: [(id: (0), name: "Creature")],
+            cards: [(
+                id: (1),
+                name: "Goblin",
+                card_type: (0),
+                attributes: { "power": 2 },
+                abilities: [(
+                    name: "Rally",
+                    events: [(1)],
+                    effects: [MoveCard(destination: (0), position: None)],
+                )],
+            )],
+        )"#;
+        let def = GameDefinition::parse(text, DefinitionFormat::Ron).unwrap();
+        let game = def.build().unwrap();
+        assert_eq!(game.card("Goblin").unwrap().get_int("power", 0), 2);
+
+        let full = definition();
+        let text = full.write(DefinitionFormat::Ron).unwrap();
+        assert_eq!(GameDefinition::parse(&text, DefinitionFormat::Ron).unwrap(), full);
+    }
+}
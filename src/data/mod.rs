@@ -0,0 +1,41 @@
+//! Data-driven game definitions.
+//!
+//! Instead of wiring `ZoneConfig`, `TemplateConfig`, card definitions and
+//! triggers up in Rust, a game can describe them in a file:
+//!
+//! - [`GameDefinition`]: Zones, templates, phases, event types, card types,
+//!   cards with attributes, and abilities as `Effect`/`TriggerCondition` trees
+//! - [`LoadedGame`]: The validated result: `GameConfig`, `CardRegistry`,
+//!   `TriggerRegistry`, plus name lookups
+//! - [`CardSet`]: A named card pool, also loadable from CSV (`csv` feature),
+//!   merged into a `CardRegistry` with conflict detection
+//! - [`DefinitionFormat`]: JSON, TOML (`toml` feature) or RON (`ron` feature)
+//!
+//! ## Example
+//!
+//! ```
+//! use rust_ccg::data::{DefinitionFormat, GameDefinition};
+//!
+//! let text = r#"{
+//!     "player_count": 2,
+//!     "zones": [{ "id": 0, "name": "Battlefield" }],
+//!     "card_types": [{ "id": 0, "name": "Creature" }],
+//!     "cards": [{ "id": 1, "name": "Goblin", "card_type": 0, "attributes": { "power": 2 } }]
+//! }"#;
+//!
+//! let game = GameDefinition::parse(text, DefinitionFormat::Json)
+//!     .unwrap()
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(game.card("Goblin").unwrap().get_int("power", 0), 2);
+//! ```
+
+pub mod card_set;
+pub mod error;
+pub mod format;
+pub mod game;
+
+pub use card_set::CardSet;
+pub use error::{DefinitionError, DefinitionResult, ValidationError};
+pub use format::DefinitionFormat;
+pub use game::{AbilityDef, CardDef, CardTypeDef, GameDefinition, LoadedGame, PhaseDef};
@@ -0,0 +1,274 @@
+//! Player decisions in the middle of resolution.
+//!
+//! `Effect::Choose` pauses resolution: the resolver computes the options
+//! from the effect's `TargetSpec` and stores a [`PendingChoice`] on the
+//! game state, together with the effects still waiting behind it.
+//! Resolution systems report `ResolutionStatus::WaitingForChoice` until
+//! the game resumes with [`EffectResolver::resolve_choice`].
+//!
+//! Rules engines with a `choice_template` list each legal selection as an
+//! action (the pointers are the chosen entities), so search can explore
+//! choices like any other action.
+//!
+//! [`EffectResolver::resolve_choice`]: super::EffectResolver::resolve_choice
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Action, EntityId, GameState, PendingDecision, PlayerId, TemplateId};
+
+use super::{Effect, EffectBatch, EffectOrigin};
+
+/// A decision a player must make before resolution continues.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct PendingChoice {
+    /// The deciding player.
+    pub player: PlayerId,
+    /// Entities that can be chosen, in order.
+    pub options: Vec<EntityId>,
+    /// Fewest options to choose.
+    pub min: usize,
+    /// Most options to choose.
+    pub max: usize,
+    /// Effect resolved on each chosen entity.
+    pub effect: Effect,
+    /// Origin of the choosing effect.
+    pub origin: EffectOrigin,
+    /// Effects waiting to resolve after the choice.
+    pub(crate) remaining: EffectBatch,
+}
+
+/// Why a selection was rejected by [`PendingChoice::check`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChoiceError {
+    /// There is no choice to make.
+    NoPendingChoice,
+
+    /// Too few or too many options were chosen.
+    WrongCount {
+        /// Fewest allowed.
+        min: usize,
+        /// Most allowed.
+        max: usize,
+        /// Number chosen.
+        found: usize,
+    },
+
+    /// The entity is not one of the options.
+    NotAnOption(EntityId),
+
+    /// The entity was chosen more than once.
+    Duplicate(EntityId),
+}
+
+impl std::fmt::Display for ChoiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChoiceError::NoPendingChoice => write!(f, "no choice is pending"),
+            ChoiceError::WrongCount { min, max, found } => {
+                write!(f, "choose between {} and {} option(s), got {}", min, max, found)
+            }
+            ChoiceError::NotAnOption(entity) => write!(f, "{} is not an option", entity),
+            ChoiceError::Duplicate(entity) => write!(f, "{} chosen more than once", entity),
+        }
+    }
+}
+
+impl std::error::Error for ChoiceError {}
+
+impl PendingDecision for PendingChoice {
+    const KIND: &'static str = "choice";
+}
+
+impl GameState {
+    /// Get the decision resolution is waiting on.
+    #[must_use]
+    pub fn pending_choice(&self) -> Option<&PendingChoice> {
+        self.pending().get()
+    }
+
+    /// Get the pending decision mutably.
+    pub fn pending_choice_mut(&mut self) -> Option<&mut PendingChoice> {
+        self.pending_mut().get_mut()
+    }
+
+    /// Set the decision resolution is waiting on.
+    pub fn set_pending_choice(&mut self, choice: PendingChoice) {
+        self.pending_mut().set(choice);
+    }
+
+    /// Take the pending decision, leaving none.
+    pub fn take_pending_choice(&mut self) -> Option<PendingChoice> {
+        self.pending_mut().take()
+    }
+}
+
+impl PendingChoice {
+    /// Effects waiting to resolve after the choice.
+    #[must_use]
+    pub fn remaining(&self) -> &EffectBatch {
+        &self.remaining
+    }
+
+    /// Check that a selection is legal.
+    pub fn check(&self, selection: &[EntityId]) -> Result<(), ChoiceError> {
+        check_selection(&self.options, self.min, self.max, selection)
+    }
+
+    /// All legal selections, each in option order.
+    ///
+    /// Order within a selection doesn't matter, so each set of options
+    /// appears once. The count grows combinatorially with the options.
+    #[must_use]
+    pub fn selections(&self) -> Vec<Vec<EntityId>> {
+        selections(&self.options, self.min, self.max)
+    }
+
+    /// The legal selections as actions of a choice template.
+    #[must_use]
+    pub fn actions(&self, template: TemplateId) -> Vec<Action> {
+        self.selections()
+            .iter()
+            .map(|selection| Action::with_pointers(template, selection))
+            .collect()
+    }
+}
+
+/// All selections of `min..=max` distinct entities from `options`, each
+/// in option order.
+pub(crate) fn selections(options: &[EntityId], min: usize, max: usize) -> Vec<Vec<EntityId>> {
+    fn collect(
+        options: &[EntityId],
+        (min, max): (usize, usize),
+        start: usize,
+        current: &mut Vec<EntityId>,
+        out: &mut Vec<Vec<EntityId>>,
+    ) {
+        if current.len() >= min {
+            out.push(current.clone());
+        }
+        if current.len() == max {
+            return;
+        }
+        for index in start..options.len() {
+            current.push(options[index]);
+            collect(options, (min, max), index + 1, current, out);
+            current.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    collect(options, (min, max), 0, &mut Vec::new(), &mut out);
+    out
+}
+
+/// Legal next entities for a selection from `options` built one at a
+/// time, in option order.
+///
+/// Returns the options after the last one chosen that still leave enough
+/// for `min`, and nothing once `max` have been chosen (or `prior` is not
+/// such a selection), so each selection is built exactly one way.
+pub(crate) fn next_options(options: &[EntityId], min: usize, max: usize, prior: &[EntityId]) -> Vec<EntityId> {
+    if prior.len() >= max {
+        return Vec::new();
+    }
+    let start = match prior.last() {
+        Some(last) => match options.iter().position(|option| option == last) {
+            Some(index) => index + 1,
+            None => return Vec::new(),
+        },
+        None => 0,
+    };
+    let needed_after = min.saturating_sub(prior.len() + 1);
+    let end = options.len().saturating_sub(needed_after);
+    options.get(start..end).unwrap_or_default().to_vec()
+}
+
+/// Check a selection of `min..=max` distinct entities from `options`.
+pub(crate) fn check_selection(
+    options: &[EntityId],
+    min: usize,
+    max: usize,
+    selection: &[EntityId],
+) -> Result<(), ChoiceError> {
+    if selection.len() < min || selection.len() > max {
+        return Err(ChoiceError::WrongCount {
+            min,
+            max,
+            found: selection.len(),
+        });
+    }
+    for (index, entity) in selection.iter().enumerate() {
+        if !options.contains(entity) {
+            return Err(ChoiceError::NotAnOption(*entity));
+        }
+        if selection[..index].contains(entity) {
+            return Err(ChoiceError::Duplicate(*entity));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choice(min: usize, max: usize) -> PendingChoice {
+        PendingChoice {
+            player: PlayerId::new(0),
+            options: vec![EntityId(10), EntityId(11), EntityId(12)],
+            min,
+            max,
+            effect: Effect::RemoveFromGame,
+            origin: EffectOrigin::default(),
+            remaining: EffectBatch::new(),
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let choice = choice(1, 2);
+        assert_eq!(choice.check(&[EntityId(11)]), Ok(()));
+        assert_eq!(choice.check(&[]), Err(ChoiceError::WrongCount { min: 1, max: 2, found: 0 }));
+        assert_eq!(choice.check(&[EntityId(99)]), Err(ChoiceError::NotAnOption(EntityId(99))));
+        assert_eq!(
+            choice.check(&[EntityId(10), EntityId(10)]),
+            Err(ChoiceError::Duplicate(EntityId(10)))
+        );
+    }
+
+    #[test]
+    fn test_selections() {
+        assert_eq!(choice(1, 1).selections().len(), 3);
+        // {}, 3 singles, 3 pairs
+        let up_to_two = choice(0, 2).selections();
+        assert_eq!(up_to_two.len(), 7);
+        assert!(up_to_two.contains(&vec![EntityId(10), EntityId(12)]));
+        assert!(!up_to_two.contains(&vec![EntityId(12), EntityId(10)]));
+        assert_eq!(choice(3, 3).selections(), vec![vec![EntityId(10), EntityId(11), EntityId(12)]]);
+
+        let actions = choice(1, 1).actions(TemplateId::new(5));
+        assert_eq!(actions[1], Action::with_pointers(TemplateId::new(5), &[EntityId(11)]));
+    }
+
+    #[test]
+    fn test_state_hash_covers_choice() {
+        use crate::core::GameState;
+
+        let hash = |choice: PendingChoice| {
+            let mut state = GameState::new(2, 42);
+            state.set_pending_choice(choice);
+            state.state_hash()
+        };
+        let base = hash(choice(1, 1));
+        assert_ne!(base, GameState::new(2, 42).state_hash());
+        assert_eq!(hash(choice(1, 1)), base);
+
+        let mut other = choice(1, 1);
+        other.effect = Effect::damage(1);
+        assert_ne!(hash(other), base);
+
+        let mut other = choice(1, 1);
+        other.remaining.add_player(Effect::damage(1), PlayerId::new(1));
+        assert_ne!(hash(other), base);
+    }
+}
@@ -6,9 +6,12 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::cards::CardId;
 use crate::core::{EntityId, PlayerId, ZoneId};
 use crate::zones::ZonePosition;
 
+use super::{PlayerRef, TargetSpec, ValueExpr};
+
 /// An atomic game effect.
 ///
 /// Effects are the fundamental building blocks of card abilities.
@@ -34,11 +37,25 @@ use crate::zones::ZonePosition;
 /// - `ModifyCardState`: Add/subtract from a card state value
 /// - `SetCardState`: Set a card state value directly
 ///
+/// ## Card Instance Effects
+///
+/// Bring card instances into the game or take them out:
+/// - `CreateCard`: Create a new instance (tokens, "add a card to your hand")
+/// - `CopyCard`: Create a copy of a card
+/// - `TransformCard`: Turn a card into a different card
+/// - `RemoveFromGame`: Remove a card instance entirely
+///
 /// ## Composite Effects
 ///
 /// - `Batch`: Execute multiple effects in sequence
 /// - `Conditional`: Execute an effect if a condition is met
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// - `Choose`: Have a player choose entities, then affect each
+///
+/// ## Amounts
+///
+/// Deltas, values and draw counts are [`ValueExpr`]s, evaluated when the
+/// effect resolves. Constructors accept plain integers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Effect {
     // === Player State ===
 
@@ -46,14 +63,14 @@ pub enum Effect {
     /// Target: player entity
     ModifyPlayerState {
         key: String,
-        delta: i64,
+        delta: ValueExpr,
     },
 
     /// Set a player state value directly.
     /// Target: player entity
     SetPlayerState {
         key: String,
-        value: i64,
+        value: ValueExpr,
     },
 
     // === Card Movement ===
@@ -69,7 +86,7 @@ pub enum Effect {
     /// Target: player entity
     /// Requires game to define deck and hand zone conventions.
     DrawCards {
-        count: usize,
+        count: ValueExpr,
         /// Zone to draw from (deck). If None, game must provide.
         from_zone: Option<ZoneId>,
         /// Zone to draw to (hand). If None, game must provide.
@@ -88,28 +105,65 @@ pub enum Effect {
     /// Target: card entity
     ModifyCardState {
         key: String,
-        delta: i64,
+        delta: ValueExpr,
     },
 
     /// Set a card state value directly.
     /// Target: card entity
     SetCardState {
         key: String,
-        value: i64,
+        value: ValueExpr,
+    },
+
+    // === Card Instances ===
+
+    /// Create a new card instance.
+    /// Target: any; `owner` is resolved relative to it. Also valid as a
+    /// zone effect when `owner` doesn't refer to the target.
+    CreateCard {
+        card_id: CardId,
+        /// Zone to create the card in. If None, the owner's hand.
+        zone: Option<ZoneId>,
+        /// Who owns (and controls) the new card.
+        owner: PlayerRef,
+        /// Where in an ordered zone. If None, the top.
+        position: Option<ZonePosition>,
+    },
+
+    /// Create a copy of a card: same card ID and card state.
+    /// Target: card entity (the card to copy)
+    CopyCard {
+        /// Zone to create the copy in. If None, the copied card's zone.
+        zone: Option<ZoneId>,
+        /// Who owns (and controls) the copy.
+        owner: PlayerRef,
+        /// Where in an ordered zone. If None, the top.
+        position: Option<ZonePosition>,
+    },
+
+    /// Turn a card into a different card.
+    /// The entity keeps its zone, owner and state.
+    /// Target: card entity
+    TransformCard {
+        into: CardId,
     },
 
+    /// Remove a card instance from the game entirely.
+    /// Target: card entity
+    RemoveFromGame,
+
     // === Turn/Game State ===
 
     /// Modify turn state value (add delta).
     ModifyTurnState {
         key: String,
-        delta: i64,
+        delta: ValueExpr,
     },
 
     /// Set turn state value directly.
     SetTurnState {
         key: String,
-        value: i64,
+        value: ValueExpr,
     },
 
     // === Composite ===
@@ -123,29 +177,39 @@ pub enum Effect {
         condition_key: String,
         effect: Box<Effect>,
     },
+
+    /// Have a player choose entities matching `spec`, then resolve
+    /// `effect` on each chosen entity.
+    /// Resolution pauses until the choice is made (see `PendingChoice`).
+    /// Target: any; `chooser` is resolved relative to it
+    Choose {
+        chooser: PlayerRef,
+        spec: TargetSpec,
+        effect: Box<Effect>,
+    },
 }
 
 impl Effect {
     /// Create a damage effect (modify player's "life" state).
-    pub fn damage(amount: i64) -> Self {
+    pub fn damage(amount: impl Into<ValueExpr>) -> Self {
         Self::ModifyPlayerState {
             key: "life".to_string(),
-            delta: -amount,
+            delta: -amount.into(),
         }
     }
 
     /// Create a heal effect (modify player's "life" state).
-    pub fn heal(amount: i64) -> Self {
+    pub fn heal(amount: impl Into<ValueExpr>) -> Self {
         Self::ModifyPlayerState {
             key: "life".to_string(),
-            delta: amount,
+            delta: amount.into(),
         }
     }
 
     /// Create a draw cards effect.
-    pub fn draw(count: usize) -> Self {
+    pub fn draw(count: impl Into<ValueExpr>) -> Self {
         Self::DrawCards {
-            count,
+            count: count.into(),
             from_zone: None,
             to_zone: None,
         }
@@ -176,34 +240,79 @@ impl Effect {
     }
 
     /// Create a modify player state effect.
-    pub fn modify_player(key: impl Into<String>, delta: i64) -> Self {
+    pub fn modify_player(key: impl Into<String>, delta: impl Into<ValueExpr>) -> Self {
         Self::ModifyPlayerState {
             key: key.into(),
-            delta,
+            delta: delta.into(),
         }
     }
 
     /// Create a set player state effect.
-    pub fn set_player(key: impl Into<String>, value: i64) -> Self {
+    pub fn set_player(key: impl Into<String>, value: impl Into<ValueExpr>) -> Self {
         Self::SetPlayerState {
             key: key.into(),
-            value,
+            value: value.into(),
         }
     }
 
     /// Create a modify card state effect.
-    pub fn modify_card(key: impl Into<String>, delta: i64) -> Self {
+    pub fn modify_card(key: impl Into<String>, delta: impl Into<ValueExpr>) -> Self {
         Self::ModifyCardState {
             key: key.into(),
-            delta,
+            delta: delta.into(),
         }
     }
 
     /// Create a set card state effect.
-    pub fn set_card(key: impl Into<String>, value: i64) -> Self {
+    pub fn set_card(key: impl Into<String>, value: impl Into<ValueExpr>) -> Self {
         Self::SetCardState {
             key: key.into(),
-            value,
+            value: value.into(),
+        }
+    }
+
+    /// Create a card in the target player's hand.
+    pub fn create_in_hand(card_id: CardId) -> Self {
+        Self::CreateCard {
+            card_id,
+            zone: None,
+            owner: PlayerRef::Target,
+            position: None,
+        }
+    }
+
+    /// Create a card in a zone, owned by the target player (or the target
+    /// card's controller).
+    pub fn create_card(card_id: CardId, zone: ZoneId) -> Self {
+        Self::CreateCard {
+            card_id,
+            zone: Some(zone),
+            owner: PlayerRef::Target,
+            position: None,
+        }
+    }
+
+    /// Create a copy of the target card in its zone, for its controller.
+    pub fn copy_card() -> Self {
+        Self::CopyCard {
+            zone: None,
+            owner: PlayerRef::Target,
+            position: None,
+        }
+    }
+
+    /// Create a transform effect.
+    pub fn transform_into(card_id: CardId) -> Self {
+        Self::TransformCard { into: card_id }
+    }
+
+    /// Create a choice made by the target player (or the target card's
+    /// controller).
+    pub fn choose(spec: TargetSpec, effect: Effect) -> Self {
+        Self::Choose {
+            chooser: PlayerRef::Target,
+            spec,
+            effect: Box::new(effect),
         }
     }
 
@@ -216,13 +325,13 @@ impl Effect {
 /// A batch of targeted effects to resolve.
 ///
 /// Pairs effects with their targets for resolution.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
 pub struct EffectBatch {
     entries: Vec<EffectEntry>,
 }
 
 /// A single entry in an effect batch.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
 pub struct EffectEntry {
     /// The effect to apply.
     pub effect: Effect,
@@ -262,6 +371,20 @@ impl EffectBatch {
         });
     }
 
+    /// Drop targets from every entry.
+    ///
+    /// Entries left without targets are dropped too, rather than becoming
+    /// zone effects.
+    pub fn remove_targets(&mut self, removed: &[EntityId]) {
+        self.entries.retain_mut(|entry| {
+            if entry.targets.is_empty() {
+                return true;
+            }
+            entry.targets.retain(|target| !removed.contains(target));
+            !entry.targets.is_empty()
+        });
+    }
+
     /// Iterate over entries.
     pub fn iter(&self) -> impl Iterator<Item = &EffectEntry> {
         self.entries.iter()
@@ -303,7 +426,7 @@ mod tests {
         match effect {
             Effect::ModifyPlayerState { key, delta } => {
                 assert_eq!(key, "life");
-                assert_eq!(delta, -3);
+                assert_eq!(delta, ValueExpr::from(-3));
             }
             _ => panic!("Expected ModifyPlayerState"),
         }
@@ -315,7 +438,7 @@ mod tests {
         match effect {
             Effect::ModifyPlayerState { key, delta } => {
                 assert_eq!(key, "life");
-                assert_eq!(delta, 5);
+                assert_eq!(delta, ValueExpr::from(5));
             }
             _ => panic!("Expected ModifyPlayerState"),
         }
@@ -326,7 +449,7 @@ mod tests {
         let effect = Effect::draw(2);
         match effect {
             Effect::DrawCards { count, from_zone, to_zone } => {
-                assert_eq!(count, 2);
+                assert_eq!(count, ValueExpr::from(2));
                 assert!(from_zone.is_none());
                 assert!(to_zone.is_none());
             }
@@ -347,6 +470,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_card_instance_effects() {
+        let effect = Effect::create_in_hand(CardId::new(3));
+        match effect {
+            Effect::CreateCard { card_id, zone, owner, position } => {
+                assert_eq!(card_id, CardId::new(3));
+                assert!(zone.is_none());
+                assert_eq!(owner, PlayerRef::Target);
+                assert!(position.is_none());
+            }
+            _ => panic!("Expected CreateCard"),
+        }
+
+        let json = serde_json::to_string(&Effect::RemoveFromGame).unwrap();
+        assert_eq!(json, r#""RemoveFromGame""#);
+        let effect: Effect = serde_json::from_str(r#"{ "TransformCard": { "into": 4 } }"#).unwrap();
+        assert_eq!(effect, Effect::transform_into(CardId::new(4)));
+    }
+
     #[test]
     fn test_batch_effect() {
         let effect = Effect::batch([
@@ -384,4 +526,18 @@ mod tests {
         let deserialized: Effect = serde_json::from_str(&json).unwrap();
         assert_eq!(effect, deserialized);
     }
+
+    #[test]
+    fn test_remove_targets() {
+        let mut batch = EffectBatch::new();
+        batch.add(Effect::damage(1), [EntityId(10), EntityId(11)]);
+        batch.add_single(Effect::damage(2), EntityId(11));
+        batch.add_zone(Effect::ShuffleZone { zone: ZoneId::new(0) });
+
+        batch.remove_targets(&[EntityId(11)]);
+        let entries: Vec<_> = batch.iter().collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].targets, vec![EntityId(10)]);
+        assert!(entries[1].targets.is_empty());
+    }
 }
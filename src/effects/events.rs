@@ -0,0 +1,272 @@
+//! Events emitted by the effect resolver.
+//!
+//! The resolver knows when it changes player state, moves, draws, creates,
+//! transforms or removes cards, changes card state or shuffles a zone, and
+//! the priority stack knows when an entry fizzles, but event types are
+//! game-defined.
+//! Games map each kind of change to one of their own `EventTypeId`s;
+//! unmapped kinds are not emitted.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cards::CardId;
+use crate::core::{EntityId, PlayerId, ZoneId};
+use crate::triggers::{EventTypeId, GameEvent};
+
+/// Mapping from resolver state changes to game event types.
+///
+/// ## Event Layout
+///
+/// - `player_state_changed`: `player` and `target` = the player,
+///   `values` = `[delta, new value]`, `tags` = `[key]`
+/// - `card_moved`: `target` = the card, `player` = its controller,
+///   `zones` = `[from, to]`
+/// - `card_drawn`: `target` = the card, `player` = the drawing player,
+///   `zones` = `[deck, hand]` (a draw also emits `card_moved`)
+/// - `card_state_changed`: `target` = the card, `player` = its controller,
+///   `values` = `[delta, new value]`, `tags` = `[key]`
+/// - `zone_shuffled`: `zones` = `[zone]`
+/// - `card_created`: `target` = the new card, `player` = its owner,
+///   `zones` = `[zone]`, `source` = the copied card (for copies)
+/// - `card_transformed`: `target` = the card, `player` = its controller,
+///   `values` = `[old card id, new card id]`
+/// - `card_removed`: `target` = the card, `player` = its controller,
+///   `zones` = `[zone it left]`
+/// - `entry_fizzled`: `player` = the stack entry's controller, `source` =
+///   the entity whose trigger it is, `values` = `[stack entry id]`
+///
+/// State changes that leave the value unchanged are not emitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolverEvents {
+    /// Fired when a player state value changes.
+    #[serde(default)]
+    pub player_state_changed: Option<EventTypeId>,
+    /// Fired when a card changes zones.
+    #[serde(default)]
+    pub card_moved: Option<EventTypeId>,
+    /// Fired for each card drawn.
+    #[serde(default)]
+    pub card_drawn: Option<EventTypeId>,
+    /// Fired when a card state value changes.
+    #[serde(default)]
+    pub card_state_changed: Option<EventTypeId>,
+    /// Fired when a zone is shuffled.
+    #[serde(default)]
+    pub zone_shuffled: Option<EventTypeId>,
+    /// Fired when a card instance is created (or copied).
+    #[serde(default)]
+    pub card_created: Option<EventTypeId>,
+    /// Fired when a card becomes a different card.
+    #[serde(default)]
+    pub card_transformed: Option<EventTypeId>,
+    /// Fired when a card instance is removed from the game.
+    #[serde(default)]
+    pub card_removed: Option<EventTypeId>,
+    /// Fired when a stack entry's targets are all illegal on resolution.
+    #[serde(default)]
+    pub entry_fizzled: Option<EventTypeId>,
+}
+
+impl ResolverEvents {
+    /// Create an empty mapping (no events emitted).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map player state changes to an event type (builder pattern).
+    #[must_use]
+    pub fn with_player_state_changed(mut self, event_type: EventTypeId) -> Self {
+        self.player_state_changed = Some(event_type);
+        self
+    }
+
+    /// Map zone changes to an event type (builder pattern).
+    #[must_use]
+    pub fn with_card_moved(mut self, event_type: EventTypeId) -> Self {
+        self.card_moved = Some(event_type);
+        self
+    }
+
+    /// Map card draws to an event type (builder pattern).
+    #[must_use]
+    pub fn with_card_drawn(mut self, event_type: EventTypeId) -> Self {
+        self.card_drawn = Some(event_type);
+        self
+    }
+
+    /// Map card state changes to an event type (builder pattern).
+    #[must_use]
+    pub fn with_card_state_changed(mut self, event_type: EventTypeId) -> Self {
+        self.card_state_changed = Some(event_type);
+        self
+    }
+
+    /// Map zone shuffles to an event type (builder pattern).
+    #[must_use]
+    pub fn with_zone_shuffled(mut self, event_type: EventTypeId) -> Self {
+        self.zone_shuffled = Some(event_type);
+        self
+    }
+
+    /// Map card creation to an event type (builder pattern).
+    #[must_use]
+    pub fn with_card_created(mut self, event_type: EventTypeId) -> Self {
+        self.card_created = Some(event_type);
+        self
+    }
+
+    /// Map card transformation to an event type (builder pattern).
+    #[must_use]
+    pub fn with_card_transformed(mut self, event_type: EventTypeId) -> Self {
+        self.card_transformed = Some(event_type);
+        self
+    }
+
+    /// Map card removal to an event type (builder pattern).
+    #[must_use]
+    pub fn with_card_removed(mut self, event_type: EventTypeId) -> Self {
+        self.card_removed = Some(event_type);
+        self
+    }
+
+    /// Map fizzled stack entries to an event type (builder pattern).
+    #[must_use]
+    pub fn with_entry_fizzled(mut self, event_type: EventTypeId) -> Self {
+        self.entry_fizzled = Some(event_type);
+        self
+    }
+
+    /// Check if no event types are mapped.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub(crate) fn player_state_changed(
+        &self,
+        player: PlayerId,
+        key: &str,
+        old: i64,
+        new: i64,
+    ) -> Option<GameEvent> {
+        let event_type = self.player_state_changed.filter(|_| old != new)?;
+        Some(
+            GameEvent::for_player(event_type, player)
+                .with_target(EntityId::player(player))
+                .with_value(new.saturating_sub(old))
+                .with_value(new)
+                .with_tag(key),
+        )
+    }
+
+    pub(crate) fn card_moved(
+        &self,
+        card: EntityId,
+        controller: Option<PlayerId>,
+        from: ZoneId,
+        to: ZoneId,
+    ) -> Option<GameEvent> {
+        let event = GameEvent::zone_change(self.card_moved?, card, from, to);
+        Some(match controller {
+            Some(controller) => event.with_player(controller),
+            None => event,
+        })
+    }
+
+    pub(crate) fn card_drawn(
+        &self,
+        card: EntityId,
+        player: PlayerId,
+        deck: ZoneId,
+        hand: ZoneId,
+    ) -> Option<GameEvent> {
+        Some(
+            GameEvent::zone_change(self.card_drawn?, card, deck, hand).with_player(player),
+        )
+    }
+
+    pub(crate) fn card_state_changed(
+        &self,
+        card: EntityId,
+        controller: Option<PlayerId>,
+        key: &str,
+        old: i64,
+        new: i64,
+    ) -> Option<GameEvent> {
+        let event_type = self.card_state_changed.filter(|_| old != new)?;
+        let event = GameEvent::new(event_type)
+            .with_target(card)
+            .with_value(new.saturating_sub(old))
+            .with_value(new)
+            .with_tag(key);
+        Some(match controller {
+            Some(controller) => event.with_player(controller),
+            None => event,
+        })
+    }
+
+    pub(crate) fn zone_shuffled(&self, zone: ZoneId) -> Option<GameEvent> {
+        Some(GameEvent::new(self.zone_shuffled?).with_zone(zone))
+    }
+
+    pub(crate) fn card_created(
+        &self,
+        card: EntityId,
+        owner: PlayerId,
+        zone: ZoneId,
+        copied_from: Option<EntityId>,
+    ) -> Option<GameEvent> {
+        let event = GameEvent::for_player(self.card_created?, owner)
+            .with_target(card)
+            .with_zone(zone);
+        Some(match copied_from {
+            Some(source) => event.with_source(source),
+            None => event,
+        })
+    }
+
+    pub(crate) fn card_transformed(
+        &self,
+        card: EntityId,
+        controller: Option<PlayerId>,
+        from: CardId,
+        into: CardId,
+    ) -> Option<GameEvent> {
+        let event_type = self.card_transformed.filter(|_| from != into)?;
+        let event = GameEvent::new(event_type)
+            .with_target(card)
+            .with_value(i64::from(from.0))
+            .with_value(i64::from(into.0));
+        Some(match controller {
+            Some(controller) => event.with_player(controller),
+            None => event,
+        })
+    }
+
+    pub(crate) fn card_removed(
+        &self,
+        card: EntityId,
+        controller: Option<PlayerId>,
+        zone: ZoneId,
+    ) -> Option<GameEvent> {
+        let event = GameEvent::new(self.card_removed?).with_target(card).with_zone(zone);
+        Some(match controller {
+            Some(controller) => event.with_player(controller),
+            None => event,
+        })
+    }
+
+    pub(crate) fn entry_fizzled(
+        &self,
+        entry_id: u32,
+        controller: PlayerId,
+        source: Option<EntityId>,
+    ) -> Option<GameEvent> {
+        let event = GameEvent::for_player(self.entry_fizzled?, controller).with_value(i64::from(entry_id));
+        Some(match source {
+            Some(source) => event.with_source(source),
+            None => event,
+        })
+    }
+}
@@ -0,0 +1,226 @@
+//! Card lifecycle effects: creating, copying, transforming and removing
+//! card instances.
+//!
+//! New cards get a fresh entity from the game state and count towards
+//! their owner's hand size when created there; removed cards stop
+//! counting.
+
+use crate::cards::{CardId, CardInstance};
+use crate::core::{EntityId, GameState, PlayerId, ZoneId};
+use crate::zones::ZonePosition;
+
+use super::resolver::Scope;
+use super::{EffectResolver, PlayerRef, ResolveResult, ResolverContext};
+
+impl EffectResolver {
+    /// Create a card instance, copying `copied_from`'s state if given.
+    pub(super) fn create_card(
+        state: &mut GameState,
+        card_id: CardId,
+        zone: Option<ZoneId>,
+        owner: Option<PlayerId>,
+        position: Option<ZonePosition>,
+        copied_from: Option<&CardInstance>,
+        context: &ResolverContext,
+    ) -> ResolveResult {
+        let Some(owner) = owner else {
+            return ResolveResult::Failed("Owner not found".to_string());
+        };
+        let hand = (context.get_hand_zone)(owner);
+        let zone = zone.unwrap_or(hand);
+
+        let entity = state.alloc_entity();
+        let mut card = CardInstance::new(entity, card_id, owner, zone);
+        if let Some(original) = copied_from {
+            card.state = original.state.clone();
+        }
+        state.add_card_at(card, position);
+        if zone == hand {
+            state.public.hand_sizes[owner] += 1;
+        }
+        context.emit(context.events.card_created(entity, owner, zone, copied_from.map(|card| card.entity_id)));
+        ResolveResult::Success
+    }
+
+    /// Copy a card, with its state, into `zone` (default: the card's zone).
+    pub(super) fn copy_card(
+        state: &mut GameState,
+        target: EntityId,
+        zone: Option<ZoneId>,
+        owner: &PlayerRef,
+        position: Option<ZonePosition>,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> ResolveResult {
+        if target.is_player(state.player_count()) {
+            return ResolveResult::Failed("Target is a player, not a card".to_string());
+        }
+        let Some(original) = state.get_card(target).cloned() else {
+            return ResolveResult::Failed("Card not found".to_string());
+        };
+        let zone = zone.or_else(|| state.zones.get_zone(target)).unwrap_or(original.zone);
+        let owner = Self::env(state, Some(target), context, scope).player(owner);
+        Self::create_card(state, original.card_id, Some(zone), owner, position, Some(&original), context)
+    }
+
+    /// Turn a card into another, keeping its entity, zone and state.
+    pub(super) fn transform_card(
+        state: &mut GameState,
+        target: EntityId,
+        into: CardId,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> ResolveResult {
+        if target.is_player(state.player_count()) {
+            return ResolveResult::Failed("Target is a player, not a card".to_string());
+        }
+        let Some(card) = state.get_card(target) else {
+            return ResolveResult::Failed("Card not found".to_string());
+        };
+        let event = context.events.card_transformed(target, card.controller, card.card_id, into);
+        if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+            return result;
+        }
+        if let Some(card) = state.get_card_mut(target) {
+            card.card_id = into;
+        }
+        context.emit(event);
+        ResolveResult::Success
+    }
+
+    /// Remove a card from the game.
+    pub(super) fn remove_card(
+        state: &mut GameState,
+        target: EntityId,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> ResolveResult {
+        if target.is_player(state.player_count()) {
+            return ResolveResult::Failed("Target is a player, not a card".to_string());
+        }
+        let Some(card) = state.get_card(target) else {
+            return ResolveResult::Failed("Card not found".to_string());
+        };
+        let zone = state.zones.get_zone(target).unwrap_or(card.zone);
+        let event = context.events.card_removed(target, card.controller, zone);
+        if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+            return result;
+        }
+        if let Some(card) = state.remove_card(target) {
+            if let Some(owner) = card.owner.filter(|&owner| zone == (context.get_hand_zone)(owner)) {
+                let size = &mut state.public.hand_sizes[owner];
+                *size = size.saturating_sub(1);
+            }
+        }
+        context.emit(event);
+        ResolveResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::resolver::tests::{setup_test_state, test_context};
+    use crate::effects::{Effect, EffectBatch, EffectOrigin, ResolverEvents};
+
+    #[test]
+    fn test_create_and_copy_cards() {
+        use crate::triggers::EventTypeId;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let created = EventTypeId::new(1);
+        let context = test_context(deck, hand).with_events(ResolverEvents::new().with_card_created(created));
+        let battlefield = ZoneId::new(5);
+
+        let result =
+            EffectResolver::resolve_single(&mut state, &Effect::create_in_hand(CardId::new(2)), EntityId::player_id(1), &context);
+        assert!(matches!(result, ResolveResult::Success));
+        assert_eq!(state.public.hand_sizes[PlayerId::new(1)], 1);
+        let events = context.take_events();
+        let token = events[0].target.unwrap();
+        assert_eq!(state.get_card(token).unwrap().card_id, CardId::new(2));
+        assert_eq!(state.get_card(token).unwrap().owner, Some(PlayerId::new(1)));
+        assert!(state.zones.is_in_zone(token, hand));
+
+        // Copies keep the card's state and default to its zone
+        state.zones.move_to_zone(EntityId(10), battlefield, None);
+        state.get_card_mut(EntityId(10)).unwrap().zone = battlefield;
+        state.get_card_mut(EntityId(10)).unwrap().set_state("power", 3);
+        EffectResolver::resolve_single(&mut state, &Effect::copy_card(), EntityId(10), &context);
+
+        let event = &context.take_events()[0];
+        let copy = state.get_card(event.target.unwrap()).unwrap();
+        assert_ne!(copy.entity_id, EntityId(10));
+        assert_eq!(copy.card_id, CardId::new(1));
+        assert_eq!(copy.get_state("power", 0), 3);
+        assert_eq!(copy.zone, battlefield);
+        assert_eq!(event.source, Some(EntityId(10)));
+        assert_eq!(event.zones, vec![battlefield]);
+        assert_eq!(state.zones.zone_size(battlefield), 2);
+    }
+
+    #[test]
+    fn test_create_card_on_bottom_as_zone_effect() {
+        use crate::effects::PlayerRef;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let context = test_context(deck, hand);
+        let mut batch = EffectBatch::new();
+        batch.add_zone(Effect::CreateCard {
+            card_id: CardId::new(9),
+            zone: Some(deck),
+            owner: PlayerRef::Controller,
+            position: Some(ZonePosition::Bottom),
+        });
+
+        let origin = EffectOrigin::for_player(PlayerId::new(0));
+        let results = EffectResolver::resolve_batch_from(&mut state, &batch, &origin, &context);
+        assert!(matches!(results[0], ResolveResult::Success));
+        let bottom = state.zones.bottom_card(deck).unwrap();
+        assert_eq!(state.get_card(bottom).unwrap().card_id, CardId::new(9));
+
+        // Without a controller there is no owner
+        let results = EffectResolver::resolve_batch(&mut state, &batch, &context);
+        assert!(matches!(results[0], ResolveResult::Failed(_)));
+    }
+
+    #[test]
+    fn test_transform_and_remove() {
+        use crate::triggers::EventTypeId;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let transformed = EventTypeId::new(1);
+        let removed = EventTypeId::new(2);
+        let context = test_context(deck, hand).with_events(
+            ResolverEvents::new()
+                .with_card_transformed(transformed)
+                .with_card_removed(removed),
+        );
+        state.get_card_mut(EntityId(10)).unwrap().set_state("damage", 1);
+
+        EffectResolver::resolve_single(&mut state, &Effect::transform_into(CardId::new(4)), EntityId(10), &context);
+        let card = state.get_card(EntityId(10)).unwrap();
+        assert_eq!(card.card_id, CardId::new(4));
+        assert_eq!(card.get_state("damage", 0), 1);
+        assert!(state.zones.is_in_zone(EntityId(10), deck));
+
+        // Cards removed from a hand shrink it
+        EffectResolver::resolve_single(&mut state, &Effect::draw(1), EntityId::player_id(0), &context);
+        let drawn = state.zones.cards_in_zone(hand).next().unwrap();
+        EffectResolver::resolve_single(&mut state, &Effect::RemoveFromGame, drawn, &context);
+        assert!(state.get_card(drawn).is_none());
+        assert_eq!(state.zones.zone_size(hand), 0);
+        assert_eq!(state.public.hand_sizes[PlayerId::new(0)], 0);
+
+        let events = context.take_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, transformed);
+        assert_eq!(events[0].values, vec![1, 4]);
+        assert_eq!(events[1].event_type, removed);
+        assert_eq!(events[1].target, Some(drawn));
+        assert_eq!(events[1].zones, vec![hand]);
+
+        let result = EffectResolver::resolve_single(&mut state, &Effect::RemoveFromGame, drawn, &context);
+        assert!(matches!(result, ResolveResult::Failed(_)));
+    }
+}
@@ -3,7 +3,12 @@
 //! Effects are the building blocks of card abilities:
 //! - `Effect`: Enumeration of game-agnostic effect types
 //! - `TargetSpec`: How to select targets for effects
+//! - `EntityPredicate`: Composable tests on players and cards
 //! - `EffectResolver`: Executes effects on game state
+//! - `ResolverEvents`: Maps resolver state changes to game event types
+//! - `PreventionShield`: State-backed damage prevention
+//! - `ValueExpr`: Effect amounts computed from game state
+//! - `PendingChoice`: Player decisions that pause resolution
 //!
 //! ## Design Philosophy
 //!
@@ -16,10 +21,22 @@
 //! Games define the meaning of these operations through their
 //! state key conventions and zone configurations.
 
+pub(crate) mod choice;
 mod effect;
+mod events;
+mod lifecycle;
+mod predicate;
+mod prevention;
+mod replacement;
 mod targeting;
 mod resolver;
+mod value;
 
+pub use choice::{ChoiceError, PendingChoice};
 pub use effect::{Effect, EffectBatch};
-pub use targeting::{TargetSpec, TargetFilter, TargetSelector};
-pub use resolver::{EffectResolver, ResolverContext};
+pub use events::ResolverEvents;
+pub use predicate::{EntityPredicate, PlayerMatch, PredicateContext};
+pub use prevention::PreventionShield;
+pub use targeting::{TargetSpec, TargetFilter, TargetSelector, TargetCount, TargetType, CustomFilters, TargetFilterFn};
+pub use resolver::{EffectResolver, ReplacementChoiceFn, ResolveResult, ResolverContext};
+pub use value::{CardRef, EffectOrigin, PlayerRef, ValueEnv, ValueExpr};
@@ -0,0 +1,359 @@
+//! Composable entity predicates.
+//!
+//! [`EntityPredicate`] is a small expression language over players and
+//! cards: leaf tests on zone, owner, controller, card type, attributes
+//! and state, combined with `All`, `Any` and `Not`. It is shared by
+//! targeting (target filters are evaluated as predicates, see
+//! `TargetFilter::predicate`) and trigger conditions
+//! (`TriggerCondition::SourceMatches` and friends), and serializes like
+//! the rest of the card data:
+//!
+//! ```
+//! use rust_ccg::cards::CardTypeId;
+//! use rust_ccg::effects::{EntityPredicate, PlayerMatch};
+//!
+//! // "Creature an opponent controls with power 3 or less, or any player"
+//! let json = r#"{ "Any": [
+//!     { "All": [
+//!         { "CardType": 0 },
+//!         { "ControlledBy": "Opponent" },
+//!         { "AttributeInRange": { "key": "power", "max": 3 } }
+//!     ] },
+//!     { "IsPlayer": "Any" }
+//! ] }"#;
+//! let predicate: EntityPredicate = serde_json::from_str(json).unwrap();
+//!
+//! let built = EntityPredicate::CardType(CardTypeId::new(0))
+//!     .and(EntityPredicate::ControlledBy(PlayerMatch::Opponent))
+//!     .and(EntityPredicate::attribute_in_range("power", None, Some(3)))
+//!     .or(EntityPredicate::IsPlayer(PlayerMatch::Any));
+//! assert_eq!(predicate, built);
+//! ```
+//!
+//! Players are matched relative to the acting player of a
+//! [`PredicateContext`]: the player choosing targets, or a trigger's
+//! controller.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cards::{CardDefinition, CardRegistry, CardTypeId};
+use crate::core::{EntityId, GameState, PlayerId, ZoneId};
+
+/// A player, relative to the acting player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlayerMatch {
+    /// Any player.
+    Any,
+    /// The acting player ("you").
+    You,
+    /// Any player other than the acting player.
+    Opponent,
+    /// A specific player.
+    Player(PlayerId),
+}
+
+impl PlayerMatch {
+    /// Check if `player` matches, with `you` as the acting player.
+    #[must_use]
+    pub fn matches(self, player: PlayerId, you: PlayerId) -> bool {
+        match self {
+            Self::Any => true,
+            Self::You => player == you,
+            Self::Opponent => player != you,
+            Self::Player(expected) => player == expected,
+        }
+    }
+}
+
+/// A test on a player or card entity.
+///
+/// Card type and attribute tests need a `CardRegistry` in the
+/// [`PredicateContext`]; without one they match nothing. Tests on cards
+/// never match players and vice versa, except `StateInRange`, which reads
+/// player state for players.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EntityPredicate {
+    // === Combinators ===
+
+    /// All predicates must match (true when empty).
+    All(Vec<EntityPredicate>),
+    /// At least one predicate must match (false when empty).
+    Any(Vec<EntityPredicate>),
+    /// The predicate must not match.
+    Not(Box<EntityPredicate>),
+
+    // === Players ===
+
+    /// The entity is a matching player.
+    IsPlayer(PlayerMatch),
+
+    // === Cards ===
+
+    /// The entity is a card.
+    IsCard,
+    /// The card is in the zone.
+    InZone(ZoneId),
+    /// The card's owner matches.
+    OwnedBy(PlayerMatch),
+    /// The card's controller matches.
+    ControlledBy(PlayerMatch),
+    /// The card's definition has the card type.
+    CardType(CardTypeId),
+    /// The card's definition has the attribute.
+    HasAttribute(String),
+    /// The card's definition has an integer attribute in range.
+    AttributeInRange {
+        key: String,
+        #[serde(default)]
+        min: Option<i64>,
+        #[serde(default)]
+        max: Option<i64>,
+    },
+    /// The card's (or player's) state value is in range.
+    ///
+    /// Missing keys read as 0.
+    StateInRange {
+        key: String,
+        #[serde(default)]
+        min: Option<i64>,
+        #[serde(default)]
+        max: Option<i64>,
+    },
+    /// The entity is the source of the effect or trigger.
+    IsSource,
+}
+
+impl EntityPredicate {
+    /// Create an AND predicate.
+    pub fn all(predicates: impl IntoIterator<Item = EntityPredicate>) -> Self {
+        Self::All(predicates.into_iter().collect())
+    }
+
+    /// Create an OR predicate.
+    pub fn any(predicates: impl IntoIterator<Item = EntityPredicate>) -> Self {
+        Self::Any(predicates.into_iter().collect())
+    }
+
+    /// Create an attribute range predicate.
+    pub fn attribute_in_range(key: impl Into<String>, min: Option<i64>, max: Option<i64>) -> Self {
+        Self::AttributeInRange { key: key.into(), min, max }
+    }
+
+    /// Create a state range predicate.
+    pub fn state_in_range(key: impl Into<String>, min: Option<i64>, max: Option<i64>) -> Self {
+        Self::StateInRange { key: key.into(), min, max }
+    }
+
+    /// Negate this predicate.
+    #[must_use]
+    pub fn negate(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Add another predicate with AND.
+    #[must_use]
+    pub fn and(self, other: EntityPredicate) -> Self {
+        match self {
+            Self::All(mut predicates) => {
+                predicates.push(other);
+                Self::All(predicates)
+            }
+            _ => Self::All(vec![self, other]),
+        }
+    }
+
+    /// Add another predicate with OR.
+    #[must_use]
+    pub fn or(self, other: EntityPredicate) -> Self {
+        match self {
+            Self::Any(mut predicates) => {
+                predicates.push(other);
+                Self::Any(predicates)
+            }
+            _ => Self::Any(vec![self, other]),
+        }
+    }
+
+    /// Check if the entity matches.
+    pub fn matches(&self, ctx: &PredicateContext, entity: EntityId) -> bool {
+        let player_count = ctx.state.player_count();
+        let player = entity.as_player_index(player_count).map(PlayerId::new);
+        match self {
+            Self::All(predicates) => predicates.iter().all(|p| p.matches(ctx, entity)),
+            Self::Any(predicates) => predicates.iter().any(|p| p.matches(ctx, entity)),
+            Self::Not(predicate) => !predicate.matches(ctx, entity),
+            Self::IsPlayer(expected) => player.is_some_and(|player| expected.matches(player, ctx.player)),
+            Self::IsCard => ctx.state.get_card(entity).is_some(),
+            Self::InZone(zone) => ctx.state.zones.is_in_zone(entity, *zone),
+            Self::OwnedBy(expected) => ctx
+                .state
+                .get_card(entity)
+                .and_then(|card| card.owner)
+                .is_some_and(|owner| expected.matches(owner, ctx.player)),
+            Self::ControlledBy(expected) => ctx
+                .state
+                .get_card(entity)
+                .and_then(|card| card.controller)
+                .is_some_and(|controller| expected.matches(controller, ctx.player)),
+            Self::CardType(card_type) => ctx
+                .definition(entity)
+                .is_some_and(|definition| definition.card_type == *card_type),
+            Self::HasAttribute(key) => ctx
+                .definition(entity)
+                .is_some_and(|definition| definition.get_attr(key).is_some()),
+            Self::AttributeInRange { key, min, max } => ctx
+                .definition(entity)
+                .and_then(|definition| definition.get_attr(key))
+                .and_then(|value| value.as_int())
+                .is_some_and(|value| in_range(value, *min, *max)),
+            Self::StateInRange { key, min, max } => {
+                let value = match player {
+                    Some(player) => Some(ctx.state.public.get_player_state(player, key, 0)),
+                    None => ctx.state.get_card(entity).map(|card| card.get_state(key, 0)),
+                };
+                value.is_some_and(|value| in_range(value, *min, *max))
+            }
+            Self::IsSource => ctx.source == Some(entity),
+        }
+    }
+}
+
+fn in_range(value: i64, min: Option<i64>, max: Option<i64>) -> bool {
+    min.is_none_or(|m| value >= m) && max.is_none_or(|m| value <= m)
+}
+
+/// What an [`EntityPredicate`] is evaluated against.
+#[derive(Clone, Copy)]
+pub struct PredicateContext<'a> {
+    /// Current game state.
+    pub state: &'a GameState,
+    /// The acting player, for relative [`PlayerMatch`]es.
+    pub player: PlayerId,
+    /// The source of the effect or trigger, for `IsSource`.
+    pub source: Option<EntityId>,
+    /// Card definitions, for card type and attribute tests.
+    pub cards: Option<&'a CardRegistry>,
+}
+
+impl<'a> PredicateContext<'a> {
+    /// Create a context with no source or card registry.
+    pub fn new(state: &'a GameState, player: PlayerId) -> Self {
+        Self {
+            state,
+            player,
+            source: None,
+            cards: None,
+        }
+    }
+
+    /// Set the source entity (builder pattern).
+    #[must_use]
+    pub fn with_source(mut self, source: Option<EntityId>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Read card types and attributes from this registry (builder pattern).
+    #[must_use]
+    pub fn with_cards(mut self, cards: Option<&'a CardRegistry>) -> Self {
+        self.cards = cards;
+        self
+    }
+
+    fn definition(&self, entity: EntityId) -> Option<&'a CardDefinition> {
+        let card = self.state.get_card(entity)?;
+        self.cards?.get(card.card_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{CardId, CardInstance};
+
+    const BATTLEFIELD: ZoneId = ZoneId::new(1);
+
+    fn setup() -> (GameState, CardRegistry) {
+        let creature = CardTypeId::new(0);
+        let mut cards = CardRegistry::new();
+        cards.register(CardDefinition::new(CardId::new(1), "Goblin", creature).with_attr("power", 2));
+        cards.register(CardDefinition::new(CardId::new(2), "Giant", creature).with_attr("power", 6));
+        cards.register(CardDefinition::new(CardId::new(3), "Bolt", CardTypeId::new(1)));
+
+        let mut state = GameState::new(2, 42);
+        state.add_card(CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(1), BATTLEFIELD));
+        state.add_card(CardInstance::new(EntityId(11), CardId::new(2), PlayerId::new(1), BATTLEFIELD));
+        state.add_card(CardInstance::new(EntityId(12), CardId::new(1), PlayerId::new(0), BATTLEFIELD));
+        state.add_card(CardInstance::new(EntityId(13), CardId::new(3), PlayerId::new(1), BATTLEFIELD));
+        (state, cards)
+    }
+
+    fn matching(predicate: &EntityPredicate, ctx: &PredicateContext) -> Vec<u32> {
+        (0..20)
+            .map(EntityId)
+            .filter(|&entity| predicate.matches(ctx, entity))
+            .map(|entity| entity.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_player_match() {
+        let you = PlayerId::new(0);
+        let them = PlayerId::new(1);
+        assert!(PlayerMatch::Any.matches(them, you));
+        assert!(PlayerMatch::You.matches(you, you));
+        assert!(!PlayerMatch::You.matches(them, you));
+        assert!(PlayerMatch::Opponent.matches(them, you));
+        assert!(!PlayerMatch::Opponent.matches(you, you));
+        assert!(PlayerMatch::Player(them).matches(them, you));
+    }
+
+    #[test]
+    fn test_opponent_creature_or_player() {
+        let (state, cards) = setup();
+        let ctx = PredicateContext::new(&state, PlayerId::new(0)).with_cards(Some(&cards));
+
+        let predicate = EntityPredicate::CardType(CardTypeId::new(0))
+            .and(EntityPredicate::ControlledBy(PlayerMatch::Opponent))
+            .and(EntityPredicate::attribute_in_range("power", None, Some(3)))
+            .or(EntityPredicate::IsPlayer(PlayerMatch::Any));
+
+        // Both players and the opponent's Goblin; not the Giant, our own
+        // Goblin or the Bolt
+        assert_eq!(matching(&predicate, &ctx), vec![0, 1, 10]);
+
+        // Without a registry card type tests match nothing
+        let bare = PredicateContext::new(&state, PlayerId::new(0));
+        assert!(!predicate.matches(&bare, EntityId(10)));
+    }
+
+    #[test]
+    fn test_combinators_and_leaves() {
+        let (mut state, cards) = setup();
+        state.get_card_mut(EntityId(11)).unwrap().set_state("tapped", 1);
+        let ctx = PredicateContext::new(&state, PlayerId::new(1))
+            .with_cards(Some(&cards))
+            .with_source(Some(EntityId(10)));
+
+        let untapped = EntityPredicate::state_in_range("tapped", None, Some(0));
+        let yours = EntityPredicate::all([EntityPredicate::IsCard, EntityPredicate::OwnedBy(PlayerMatch::You)]);
+        assert_eq!(matching(&yours.clone().and(untapped.clone()), &ctx), vec![10, 13]);
+        assert_eq!(matching(&yours.and(untapped.negate()), &ctx), vec![11]);
+        assert_eq!(matching(&EntityPredicate::IsSource, &ctx), vec![10]);
+        assert_eq!(matching(&EntityPredicate::HasAttribute("power".to_string()), &ctx), vec![10, 11, 12]);
+        assert_eq!(matching(&EntityPredicate::InZone(BATTLEFIELD), &ctx), vec![10, 11, 12, 13]);
+        assert!(matching(&EntityPredicate::any([]), &ctx).is_empty());
+        assert!(EntityPredicate::all([]).matches(&ctx, EntityId(10)));
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let predicate = EntityPredicate::any([
+            EntityPredicate::ControlledBy(PlayerMatch::Player(PlayerId::new(1))),
+            EntityPredicate::state_in_range("damage", Some(1), None).negate(),
+        ]);
+        let json = serde_json::to_string(&predicate).unwrap();
+        let parsed: EntityPredicate = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, predicate);
+    }
+}
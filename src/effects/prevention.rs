@@ -0,0 +1,99 @@
+//! Prevention shields.
+//!
+//! A shield is an ordinary state value ("prevent the next 3 damage" is
+//! `prevent_damage = 3`) that the resolver spends to absorb changes to a
+//! protected state key. Because shields live in player/card state they
+//! survive snapshots and are set up with normal effects.
+
+use serde::{Deserialize, Serialize};
+
+/// Absorbs changes to `key` using the target's `shield` state value.
+///
+/// By default a shield absorbs reductions (life going down). Games that
+/// track damage as an increasing counter use [`preventing_increases`].
+///
+/// [`preventing_increases`]: PreventionShield::preventing_increases
+///
+/// ## Example
+///
+/// ```
+/// use rust_ccg::core::{EntityId, GameState, PlayerId};
+/// use rust_ccg::effects::{Effect, EffectResolver, PreventionShield, ResolverContext};
+///
+/// let context = ResolverContext::simple(2)
+///     .with_prevention(PreventionShield::new("life", "prevent_damage"));
+///
+/// let mut state = GameState::new(2, 42);
+/// let player = PlayerId::new(0);
+/// state.public.set_player_state(player, "life", 20);
+/// state.public.set_player_state(player, "prevent_damage", 3);
+///
+/// EffectResolver::resolve_single(&mut state, &Effect::damage(5), EntityId::player(player), &context);
+/// assert_eq!(state.public.get_player_state(player, "life", 0), 18);
+/// assert_eq!(state.public.get_player_state(player, "prevent_damage", 0), 0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreventionShield {
+    /// The protected state key.
+    pub key: String,
+    /// The state key holding the remaining shield amount.
+    pub shield: String,
+    /// Absorb increases instead of reductions.
+    #[serde(default)]
+    pub increases: bool,
+}
+
+impl PreventionShield {
+    /// Create a shield that absorbs reductions of `key`.
+    pub fn new(key: impl Into<String>, shield: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            shield: shield.into(),
+            increases: false,
+        }
+    }
+
+    /// Absorb increases of `key` instead (builder pattern).
+    #[must_use]
+    pub fn preventing_increases(mut self) -> Self {
+        self.increases = true;
+        self
+    }
+
+    /// Split a delta into what gets through and what the shield absorbs.
+    ///
+    /// Returns `(remaining_delta, absorbed)`; `absorbed` never exceeds
+    /// `available` and is zero for changes in the unprotected direction.
+    #[must_use]
+    pub fn absorb(&self, delta: i64, available: i64) -> (i64, i64) {
+        let amount = if self.increases { delta } else { delta.saturating_neg() };
+        if amount <= 0 || available <= 0 {
+            return (delta, 0);
+        }
+        let absorbed = amount.min(available);
+        let remaining = amount - absorbed;
+        (if self.increases { remaining } else { -remaining }, absorbed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absorb_reductions() {
+        let shield = PreventionShield::new("life", "prevent_damage");
+        assert_eq!(shield.absorb(-5, 3), (-2, 3));
+        assert_eq!(shield.absorb(-2, 3), (0, 2));
+        assert_eq!(shield.absorb(4, 3), (4, 0));
+        assert_eq!(shield.absorb(-4, 0), (-4, 0));
+        assert_eq!(shield.absorb(i64::MIN, 3), (-(i64::MAX - 3), 3));
+    }
+
+    #[test]
+    fn test_absorb_increases() {
+        let shield = PreventionShield::new("damage", "prevent_damage").preventing_increases();
+        assert_eq!(shield.absorb(5, 3), (2, 3));
+        assert_eq!(shield.absorb(-5, 3), (-5, 0));
+    }
+}
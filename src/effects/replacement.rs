@@ -0,0 +1,712 @@
+//! `Before` triggers, prevention and replacement effects.
+//!
+//! These run on each change the [`EffectResolver`] is about to make,
+//! between working out the change and applying it.
+//!
+//! ## Before Triggers
+//!
+//! Before applying a change, the resolver builds the event it would emit
+//! and runs the matching `TriggerTiming::Before` triggers from the
+//! context's trigger registry, highest priority first. Each trigger's
+//! effects resolve right away (against its controller) and its
+//! [`EventModifier`]s change the pending event; the resolver then applies
+//! the modified event:
+//!
+//! - `ModifyPlayerState`/`ModifyCardState`: delta from `values[0]`
+//! - `SetPlayerState`/`SetCardState`: new value from `values[1]`
+//! - `MoveCard`/`DrawCards`: destination from `zones[1]`
+//! - `ShuffleZone`: zone from `zones[0]`
+//! - `TransformCard`: new card ID from `values[1]`
+//! - Any of them: target from `target`, or cancelled entirely
+//!
+//! A trigger's condition is re-checked against the event as modified by
+//! higher-priority triggers.
+//!
+//! ## Replacement Effects
+//!
+//! After `Before` triggers, the resolver looks up `TriggerTiming::Instead`
+//! triggers for the event. A matching trigger's effects resolve (against
+//! the original target) instead of the change; a trigger with no effects
+//! cancels it. Following MTG rules:
+//!
+//! - Each replacement applies at most once to an event: the effects it
+//!   substitutes are checked against the remaining replacements only
+//! - When several apply, the affected player chooses which goes first
+//!   (see [`ResolverContext::with_replacement_choice`]): the event's
+//!   player, or the player targeted, or the controller (else owner) of
+//!   the card targeted. Without one, the highest-priority replacement
+//!   applies.
+//!
+//! `Before` triggers and replacements only see changes whose event type is
+//! mapped in `ResolverEvents`, and not card creation (`CreateCard`,
+//! `CopyCard`). [`PreventionShield`]s are applied after
+//! `Before` triggers and before replacements.
+//!
+//! [`EventModifier`]: crate::triggers::EventModifier
+//! [`PreventionShield`]: super::PreventionShield
+
+use std::borrow::Cow;
+
+use crate::cards::CardId;
+use crate::core::{EntityId, GameState, PlayerId};
+use crate::triggers::{
+    ConditionEvaluator, CustomConditionFn, GameEvent, PendingEvent, TriggerTiming, TriggeredEffect,
+};
+
+use super::resolver::Scope;
+use super::{Effect, EffectResolver, ResolveResult, ResolverContext};
+
+impl EffectResolver {
+    /// Run `Before` triggers on the event an effect is about to cause.
+    ///
+    /// Returns the effect and target rewritten from the modified event, or
+    /// `None` if a trigger cancelled it.
+    pub(super) fn before<'e>(
+        state: &mut GameState,
+        effect: &'e Effect,
+        target: Option<EntityId>,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> Option<(Cow<'e, Effect>, Option<EntityId>)> {
+        let unchanged = Some((Cow::Borrowed(effect), target));
+        if context.triggers.is_none() {
+            return unchanged;
+        }
+        let Some(event) = Self::pending_event(state, effect, target, context) else {
+            return unchanged;
+        };
+        let modified = Self::run_before(state, event.clone(), context, scope)?;
+        if modified == event {
+            return unchanged;
+        }
+        Some((Cow::Owned(Self::rewrite(effect, &modified)), modified.target.or(target)))
+    }
+
+    /// Build the event a primitive effect would emit, if it is mapped.
+    pub(super) fn pending_event(
+        state: &GameState,
+        effect: &Effect,
+        target: Option<EntityId>,
+        context: &ResolverContext,
+    ) -> Option<GameEvent> {
+        let player_count = state.player_count();
+        let player = target
+            .and_then(|target| target.as_player_index(player_count))
+            .map(PlayerId::new);
+        let card = target
+            .filter(|target| !target.is_player(player_count))
+            .and_then(|target| state.get_card(target));
+
+        match effect {
+            Effect::ModifyPlayerState { key, delta } => {
+                let player = player?;
+                let old = state.public.get_player_state(player, key, 0);
+                context.events.player_state_changed(player, key, old, old.saturating_add(Self::amount(delta)))
+            }
+            Effect::SetPlayerState { key, value } => {
+                let player = player?;
+                let old = state.public.get_player_state(player, key, 0);
+                context.events.player_state_changed(player, key, old, Self::amount(value))
+            }
+            Effect::MoveCard { destination, .. } => {
+                let target = target.filter(|target| !target.is_player(player_count))?;
+                let from = state.zones.get_zone(target)?;
+                let controller = card.and_then(|card| card.controller);
+                context.events.card_moved(target, controller, from, *destination)
+            }
+            Effect::ModifyCardState { key, delta } => {
+                let card = card?;
+                let old = card.get_state(key, 0);
+                let new = old.saturating_add(Self::amount(delta));
+                context.events.card_state_changed(card.entity_id, card.controller, key, old, new)
+            }
+            Effect::SetCardState { key, value } => {
+                let card = card?;
+                let old = card.get_state(key, 0);
+                context.events.card_state_changed(card.entity_id, card.controller, key, old, Self::amount(value))
+            }
+            Effect::ShuffleZone { zone } => context.events.zone_shuffled(*zone),
+            Effect::TransformCard { into } => {
+                let card = card?;
+                context.events.card_transformed(card.entity_id, card.controller, card.card_id, *into)
+            }
+            Effect::RemoveFromGame => {
+                let card = card?;
+                let zone = state.zones.get_zone(card.entity_id).unwrap_or(card.zone);
+                context.events.card_removed(card.entity_id, card.controller, zone)
+            }
+            _ => None,
+        }
+    }
+
+    /// Rewrite a primitive effect from its (modified) pending event.
+    pub(super) fn rewrite(effect: &Effect, event: &GameEvent) -> Effect {
+        match effect {
+            Effect::ModifyPlayerState { key, delta } => Effect::ModifyPlayerState {
+                key: key.clone(),
+                delta: event.value(0, Self::amount(delta)).into(),
+            },
+            Effect::SetPlayerState { key, value } => Effect::SetPlayerState {
+                key: key.clone(),
+                value: event.value(1, Self::amount(value)).into(),
+            },
+            Effect::MoveCard { destination, position } => Effect::MoveCard {
+                destination: event.zone(1).unwrap_or(*destination),
+                position: *position,
+            },
+            Effect::ModifyCardState { key, delta } => Effect::ModifyCardState {
+                key: key.clone(),
+                delta: event.value(0, Self::amount(delta)).into(),
+            },
+            Effect::SetCardState { key, value } => Effect::SetCardState {
+                key: key.clone(),
+                value: event.value(1, Self::amount(value)).into(),
+            },
+            Effect::ShuffleZone { zone } => Effect::ShuffleZone {
+                zone: event.zone(0).unwrap_or(*zone),
+            },
+            Effect::TransformCard { into } => Effect::TransformCard {
+                into: u32::try_from(event.value(1, i64::from(into.0))).map_or(*into, CardId::new),
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Run the `Before` triggers matching a pending event, highest priority first.
+    ///
+    /// Returns the modified event, or `None` if it was cancelled.
+    pub(super) fn run_before(
+        state: &mut GameState,
+        event: GameEvent,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> Option<GameEvent> {
+        let Some(registry) = context.triggers else {
+            return Some(event);
+        };
+        let custom = |key: &str, _: &GameEvent, state: &GameState| (context.eval_condition)(key, state);
+        let custom: &CustomConditionFn<'_> = &custom;
+        let fired: Vec<TriggeredEffect> = registry
+            .find_triggers_in(&context.conditions(&event, state, custom), TriggerTiming::Before)
+            .into_iter()
+            .filter(|triggered| !scope.applied.contains(&triggered.trigger_id))
+            .collect();
+
+        let mut pending = PendingEvent::new(event);
+        for triggered in fired {
+            // Higher-priority triggers may have changed the event
+            let Some(trigger) = registry.get(triggered.trigger_id) else {
+                continue;
+            };
+            let still_matches = {
+                let ctx = context
+                    .conditions(&pending.event, state, custom)
+                    .for_trigger(trigger.controller, trigger.source);
+                ConditionEvaluator::evaluate(&trigger.condition, &ctx)
+            };
+            if !still_matches {
+                continue;
+            }
+            context.applied.borrow_mut().push(triggered.trigger_id);
+
+            for modifier in &triggered.modifiers {
+                modifier.apply(&mut pending, &triggered);
+            }
+            if let Some(hook) = &context.before_hook {
+                hook(&mut pending, &triggered, state);
+            }
+
+            let nested = scope.nested(&triggered);
+            for effect in &triggered.effects {
+                match triggered.controller {
+                    Some(controller) => {
+                        Self::resolve_targeted(state, effect, EntityId::player(controller), context, &nested);
+                    }
+                    None => {
+                        Self::resolve_zone_effect(state, effect, context, &nested);
+                    }
+                }
+            }
+
+            if pending.is_cancelled() {
+                return None;
+            }
+        }
+        Some(pending.event)
+    }
+
+    /// Apply the first applicable replacement to an event that is about to happen.
+    ///
+    /// Returns `None` if nothing replaces the event, otherwise the result of
+    /// resolving the replacement's effects against `target`.
+    pub(super) fn replace(
+        state: &mut GameState,
+        event: Option<&GameEvent>,
+        target: Option<EntityId>,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> Option<ResolveResult> {
+        let registry = context.triggers?;
+        let event = event?;
+
+        let custom = |key: &str, _: &GameEvent, state: &GameState| (context.eval_condition)(key, state);
+        let custom: &CustomConditionFn<'_> = &custom;
+        let mut candidates: Vec<TriggeredEffect> = registry
+            .find_triggers_in(&context.conditions(event, state, custom), TriggerTiming::Instead)
+            .into_iter()
+            .filter(|triggered| !scope.applied.contains(&triggered.trigger_id))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let affected = match candidates.len() {
+            1 => None,
+            _ => event.player.or_else(|| Self::affected_player(state, target?)),
+        };
+        let chosen = match affected {
+            Some(affected) => {
+                let index = (context.choose_replacement)(affected, event, &candidates);
+                candidates.swap_remove(index.min(candidates.len() - 1))
+            }
+            None => candidates.swap_remove(0),
+        };
+        context.applied.borrow_mut().push(chosen.trigger_id);
+
+        let nested = scope.nested(&chosen);
+        let mut result = ResolveResult::Skipped;
+        for effect in &chosen.effects {
+            let sub_result = match target {
+                Some(target) => Self::resolve_targeted(state, effect, target, context, &nested),
+                None => Self::resolve_zone_effect(state, effect, context, &nested),
+            };
+            match sub_result {
+                ResolveResult::Failed(_) => return Some(sub_result),
+                ResolveResult::Success => result = ResolveResult::Success,
+                // Nested scopes never pause for choices
+                ResolveResult::Skipped | ResolveResult::Pending => {}
+            }
+        }
+        Some(result)
+    }
+
+    /// The player affected by a change to `target`: the player itself, or
+    /// the controller (else owner) of a card.
+    fn affected_player(state: &GameState, target: EntityId) -> Option<PlayerId> {
+        match target.as_player_index(state.player_count()) {
+            Some(index) => Some(PlayerId::new(index)),
+            None => state.get_card(target).and_then(|card| card.controller.or(card.owner)),
+        }
+    }
+
+    /// Spend a player's prevention shield on a state change.
+    ///
+    /// Returns the part of `delta` that gets through.
+    pub(super) fn prevent_player(
+        state: &mut GameState,
+        player: PlayerId,
+        key: &str,
+        delta: i64,
+        context: &ResolverContext,
+    ) -> i64 {
+        let Some(shield) = context.prevention(key) else {
+            return delta;
+        };
+        let available = state.public.get_player_state(player, &shield.shield, 0);
+        let (delta, absorbed) = shield.absorb(delta, available);
+        if absorbed > 0 {
+            state.public.set_player_state(player, shield.shield.clone(), available - absorbed);
+            context.emit(context.events.player_state_changed(
+                player,
+                &shield.shield,
+                available,
+                available - absorbed,
+            ));
+        }
+        delta
+    }
+
+    /// Spend a card's prevention shield on a state change.
+    ///
+    /// Returns the part of `delta` that gets through.
+    pub(super) fn prevent_card(
+        state: &mut GameState,
+        target: EntityId,
+        key: &str,
+        delta: i64,
+        context: &ResolverContext,
+    ) -> i64 {
+        let Some(shield) = context.prevention(key) else {
+            return delta;
+        };
+        let Some(card) = state.get_card_mut(target) else {
+            return delta;
+        };
+        let available = card.get_state(&shield.shield, 0);
+        let (delta, absorbed) = shield.absorb(delta, available);
+        if absorbed > 0 {
+            card.set_state(shield.shield.clone(), available - absorbed);
+            let controller = card.controller;
+            context.emit(context.events.card_state_changed(
+                target,
+                controller,
+                &shield.shield,
+                available,
+                available - absorbed,
+            ));
+        }
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    use crate::core::ZoneId;
+    use crate::effects::resolver::tests::{setup_test_state, test_context};
+    use crate::effects::{PreventionShield, ResolverEvents, ValueExpr};
+
+    mod replacement {
+        use super::*;
+        use crate::triggers::{EventTypeId, Trigger, TriggerCondition, TriggerId, TriggerRegistry, TriggerTiming};
+
+        const STATE_CHANGED: EventTypeId = EventTypeId::new(1);
+        const CARD_MOVED: EventTypeId = EventTypeId::new(2);
+        const CARD_DRAWN: EventTypeId = EventTypeId::new(3);
+
+        fn events() -> ResolverEvents {
+            ResolverEvents::new()
+                .with_player_state_changed(STATE_CHANGED)
+                .with_card_moved(CARD_MOVED)
+                .with_card_drawn(CARD_DRAWN)
+        }
+
+        fn instead(id: u32, event_type: EventTypeId) -> Trigger {
+            Trigger::new(TriggerId::new(id), format!("Replacement {}", id), event_type)
+                .with_timing(TriggerTiming::Instead)
+        }
+
+        #[test]
+        fn test_replaces_each_draw() {
+            let (mut state, deck, hand) = setup_test_state();
+            let mut registry = TriggerRegistry::new();
+            registry.register_with_id(
+                instead(1, CARD_DRAWN)
+                    .with_condition(TriggerCondition::ForPlayer(PlayerId::new(0)))
+                    .with_effect(Effect::heal(2)),
+            );
+            let context = test_context(deck, hand).with_events(events()).with_triggers(&registry);
+
+            let result = EffectResolver::resolve_single(&mut state, &Effect::draw(2), EntityId::player_id(0), &context);
+
+            assert!(matches!(result, ResolveResult::Success));
+            assert_eq!(state.zones.zone_size(deck), 5);
+            assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), 4);
+            assert_eq!(context.take_applied_triggers(), vec![TriggerId::new(1); 2]);
+            // Only the replacement's own changes are emitted
+            let events = context.take_events();
+            assert_eq!(events.len(), 2);
+            assert!(events.iter().all(|event| event.event_type == STATE_CHANGED));
+        }
+
+        #[test]
+        fn test_cancel_move() {
+            let (mut state, deck, hand) = setup_test_state();
+            let mut registry = TriggerRegistry::new();
+            registry.register_with_id(
+                instead(1, CARD_MOVED).with_condition(TriggerCondition::TargetIs(EntityId(10))),
+            );
+            let context = test_context(deck, hand).with_events(events()).with_triggers(&registry);
+
+            let discard = ZoneId::new(2);
+            let result = EffectResolver::resolve_single(&mut state, &Effect::move_to_top(discard), EntityId(10), &context);
+            assert!(matches!(result, ResolveResult::Skipped));
+            assert!(state.zones.is_in_zone(EntityId(10), deck));
+
+            EffectResolver::resolve_single(&mut state, &Effect::move_to_top(discard), EntityId(11), &context);
+            assert!(state.zones.is_in_zone(EntityId(11), discard));
+        }
+
+        #[test]
+        fn test_replacement_reads_event_values() {
+            let mut state = GameState::new(2, 42);
+            let mut registry = TriggerRegistry::new();
+            // "If you would lose life, gain that much armor instead"
+            registry.register_with_id(
+                instead(1, STATE_CHANGED)
+                    .with_condition(TriggerCondition::HasTag("life".into()))
+                    .with_effect(Effect::modify_player("armor", -ValueExpr::EventValue(0))),
+            );
+            let context = ResolverContext::simple(2).with_events(events()).with_triggers(&registry);
+
+            EffectResolver::resolve_single(&mut state, &Effect::damage(3), EntityId::player_id(1), &context);
+
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 0);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "armor", 0), 3);
+        }
+
+        #[test]
+        fn test_applies_once_per_event() {
+            let (mut state, deck, hand) = setup_test_state();
+            let mut registry = TriggerRegistry::new();
+            // "If you would draw a card, draw two instead" must not recurse
+            registry.register_with_id(instead(1, CARD_DRAWN).with_effect(Effect::draw(2)));
+            let context = test_context(deck, hand).with_events(events()).with_triggers(&registry);
+
+            EffectResolver::resolve_single(&mut state, &Effect::draw(1), EntityId::player_id(0), &context);
+
+            assert_eq!(state.public.hand_sizes[PlayerId::new(0)], 2);
+            assert_eq!(context.take_applied_triggers().len(), 1);
+        }
+
+        #[test]
+        fn test_affected_player_orders_replacements() {
+            let mut state = GameState::new(2, 42);
+            let mut registry = TriggerRegistry::new();
+            let life_loss = TriggerCondition::HasTag("life".to_string());
+            registry.register_with_id(
+                instead(1, STATE_CHANGED)
+                    .with_priority(10)
+                    .with_condition(life_loss.clone())
+                    .with_effect(Effect::modify_player("armor", 1)),
+            );
+            registry.register_with_id(
+                instead(2, STATE_CHANGED)
+                    .with_condition(life_loss)
+                    .with_effect(Effect::modify_player("ward", 1)),
+            );
+            let target = EntityId::player_id(1);
+
+            // Default: highest priority first
+            let context = ResolverContext::simple(2).with_events(events()).with_triggers(&registry);
+            EffectResolver::resolve_single(&mut state, &Effect::damage(3), target, &context);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "armor", 0), 1);
+
+            let asked = RefCell::new(Vec::new());
+            let context = ResolverContext::simple(2)
+                .with_events(events())
+                .with_triggers(&registry)
+                .with_replacement_choice(|player, _, candidates| {
+                    asked.borrow_mut().push((player, candidates.len()));
+                    candidates.iter().position(|c| c.trigger_id == TriggerId::new(2)).unwrap()
+                });
+            EffectResolver::resolve_single(&mut state, &Effect::damage(3), target, &context);
+
+            assert_eq!(*asked.borrow(), vec![(PlayerId::new(1), 2)]);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "ward", 0), 1);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 0);
+        }
+
+        #[test]
+        fn test_card_owner_orders_replacements() {
+            let (mut state, deck, hand) = setup_test_state();
+            let mut registry = TriggerRegistry::new();
+            registry.register_with_id(instead(1, CARD_MOVED));
+            registry.register_with_id(instead(2, CARD_MOVED));
+            let card = state.get_card_mut(EntityId(10)).unwrap();
+            card.owner = Some(PlayerId::new(1));
+            card.controller = None;
+
+            // An uncontrolled card's move is its owner's to order
+            let asked = RefCell::new(Vec::new());
+            let context = test_context(deck, hand)
+                .with_events(events())
+                .with_triggers(&registry)
+                .with_replacement_choice(|player, _, _| {
+                    asked.borrow_mut().push(player);
+                    0
+                });
+            let result = EffectResolver::resolve_single(&mut state, &Effect::move_to(hand), EntityId(10), &context);
+
+            assert!(matches!(result, ResolveResult::Skipped));
+            assert_eq!(*asked.borrow(), vec![PlayerId::new(1)]);
+            assert!(state.zones.is_in_zone(EntityId(10), deck));
+        }
+
+        #[test]
+        fn test_prevention_shield() {
+            let (mut state, deck, hand) = setup_test_state();
+            let player = PlayerId::new(0);
+            state.public.set_player_state(player, "life", 20);
+            state.public.set_player_state(player, "prevent_damage", 3);
+            state.get_card_mut(EntityId(10)).unwrap().set_state("shield", 1);
+            let context = test_context(deck, hand)
+                .with_events(events())
+                .with_prevention(PreventionShield::new("life", "prevent_damage"))
+                .with_prevention(PreventionShield::new("damage", "shield").preventing_increases());
+            let target = EntityId::player(player);
+
+            let result = EffectResolver::resolve_single(&mut state, &Effect::damage(2), target, &context);
+            assert!(matches!(result, ResolveResult::Skipped));
+            EffectResolver::resolve_single(&mut state, &Effect::damage(4), target, &context);
+            // Healing passes through untouched
+            EffectResolver::resolve_single(&mut state, &Effect::heal(1), target, &context);
+
+            assert_eq!(state.public.get_player_state(player, "life", 0), 18);
+            assert_eq!(state.public.get_player_state(player, "prevent_damage", 0), 0);
+            let events = context.take_events();
+            assert_eq!(events.len(), 4);
+            assert!(events[0].has_tag("prevent_damage"));
+
+            EffectResolver::resolve_single(&mut state, &Effect::modify_card("damage", 3), EntityId(10), &context);
+            let card = state.get_card(EntityId(10)).unwrap();
+            assert_eq!(card.get_state("damage", 0), 2);
+            assert_eq!(card.get_state("shield", 0), 0);
+        }
+    }
+
+    mod before {
+        use super::*;
+        use crate::triggers::{
+            EventModifier, EventTypeId, Trigger, TriggerCondition, TriggerId, TriggerRegistry, TriggerTiming,
+        };
+
+        const STATE_CHANGED: EventTypeId = EventTypeId::new(1);
+        const CARD_DRAWN: EventTypeId = EventTypeId::new(2);
+
+        fn before(id: u32, event_type: EventTypeId) -> Trigger {
+            Trigger::new(TriggerId::new(id), format!("Before {}", id), event_type)
+                .with_timing(TriggerTiming::Before)
+        }
+
+        fn context(registry: &TriggerRegistry) -> ResolverContext<'_> {
+            ResolverContext::simple(2)
+                .with_events(ResolverEvents::new().with_player_state_changed(STATE_CHANGED).with_card_drawn(CARD_DRAWN))
+                .with_triggers(registry)
+        }
+
+        #[test]
+        fn test_modifiers_apply_in_priority_order() {
+            let mut registry = TriggerRegistry::new();
+            let life = TriggerCondition::HasTag("life".to_string());
+            // Registered first but lower priority: doubles the damage
+            registry.register_with_id(
+                before(1, STATE_CHANGED)
+                    .with_condition(life.clone())
+                    .with_modifier(EventModifier::MultiplyValue { index: 0, factor: 2 }),
+            );
+            // Applies first: reduces the damage by one
+            registry.register_with_id(
+                before(2, STATE_CHANGED)
+                    .with_priority(10)
+                    .with_condition(life)
+                    .with_modifier(EventModifier::AddValue { index: 0, delta: 1 }),
+            );
+            let mut state = GameState::new(2, 42);
+            state.public.set_player_state(PlayerId::new(1), "life", 20);
+            let context = context(&registry);
+
+            EffectResolver::resolve_single(&mut state, &Effect::damage(3), EntityId::player_id(1), &context);
+
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 16);
+            assert_eq!(context.take_applied_triggers(), vec![TriggerId::new(2), TriggerId::new(1)]);
+            let events = context.take_events();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].values, vec![-4, 16]);
+        }
+
+        #[test]
+        fn test_saturated_modifiers_resolve() {
+            let mut registry = TriggerRegistry::new();
+            // "Damage is multiplied without limit"
+            registry.register_with_id(
+                before(1, STATE_CHANGED)
+                    .with_condition(TriggerCondition::HasTag("life".to_string()))
+                    .with_modifier(EventModifier::MultiplyValue { index: 0, factor: i64::MAX }),
+            );
+            let mut state = GameState::new(2, 42);
+            state.public.set_player_state(PlayerId::new(1), "life", -20);
+            let context = context(&registry);
+
+            EffectResolver::resolve_single(&mut state, &Effect::damage(3), EntityId::player_id(1), &context);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), i64::MIN);
+            EffectResolver::resolve_single(&mut state, &Effect::heal(3), EntityId::player_id(1), &context);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), -1);
+
+            let events = context.take_events();
+            assert_eq!(events[0].values, vec![i64::MIN + 20, i64::MIN]);
+            assert_eq!(events[1].values, vec![i64::MAX, -1]);
+        }
+
+        #[test]
+        fn test_retarget_rechecks_conditions() {
+            let mut registry = TriggerRegistry::new();
+            // Player 0 takes player 1's damage and gains armor for it
+            registry.register_with_id(
+                before(1, STATE_CHANGED)
+                    .with_priority(10)
+                    .with_controller(PlayerId::new(0))
+                    .with_condition(TriggerCondition::ForPlayer(PlayerId::new(1)).and(TriggerCondition::HasTag("life".into())))
+                    .with_modifier(EventModifier::RetargetToController)
+                    .with_effect(Effect::modify_player("armor", 1)),
+            );
+            // No longer applies once the damage is redirected
+            registry.register_with_id(
+                before(2, STATE_CHANGED)
+                    .with_condition(TriggerCondition::ForPlayer(PlayerId::new(1)))
+                    .with_modifier(EventModifier::Cancel),
+            );
+            let mut state = GameState::new(2, 42);
+            let context = context(&registry);
+
+            let result = EffectResolver::resolve_single(&mut state, &Effect::damage(3), EntityId::player_id(1), &context);
+
+            assert!(matches!(result, ResolveResult::Success));
+            assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), -3);
+            assert_eq!(state.public.get_player_state(PlayerId::new(0), "armor", 0), 1);
+            assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 0);
+            assert_eq!(context.take_applied_triggers(), vec![TriggerId::new(1)]);
+        }
+
+        #[test]
+        fn test_cancel_and_redirect_draws() {
+            let (mut state, deck, hand) = setup_test_state();
+            let discard = ZoneId::new(2);
+            state.zones.init_ordered_zone(discard);
+            let mut registry = TriggerRegistry::new();
+            registry.register_with_id(
+                before(1, CARD_DRAWN)
+                    .with_condition(TriggerCondition::TargetIs(EntityId(13)))
+                    .with_modifier(EventModifier::Cancel),
+            );
+            registry.register_with_id(
+                before(2, CARD_DRAWN)
+                    .with_condition(TriggerCondition::TargetIs(EntityId(14)))
+                    .with_modifier(EventModifier::SetZone { index: 1, zone: discard }),
+            );
+            let context = ResolverContext::new(move |_| deck, move |_| hand)
+                .with_events(ResolverEvents::new().with_card_drawn(CARD_DRAWN))
+                .with_triggers(&registry);
+
+            // The top card (14) is milled; drawing 13 is cancelled and it stays on top
+            let result = EffectResolver::resolve_single(&mut state, &Effect::draw(2), EntityId::player_id(0), &context);
+
+            assert!(matches!(result, ResolveResult::Success));
+            assert!(state.zones.is_in_zone(EntityId(14), discard));
+            assert_eq!(state.get_card(EntityId(14)).unwrap().zone, discard);
+            assert_eq!(state.zones.top_card(deck), Some(EntityId(13)));
+            assert_eq!(state.public.hand_sizes[PlayerId::new(0)], 0);
+        }
+
+        #[test]
+        fn test_before_hook() {
+            let mut registry = TriggerRegistry::new();
+            registry.register_with_id(before(1, STATE_CHANGED).with_condition(TriggerCondition::HasTag("life".into())));
+            let mut state = GameState::new(2, 42);
+            state.public.set_player_state(PlayerId::new(0), "halving", 1);
+            // Halve damage (rounding down) while the player's "halving" flag is set
+            let context = context(&registry).with_before_hook(|pending, _, state| {
+                let player = pending.event.player.unwrap();
+                if state.public.get_player_state(player, "halving", 0) != 0 {
+                    pending.event.values[0] /= 2;
+                }
+            });
+
+            EffectResolver::resolve_single(&mut state, &Effect::damage(5), EntityId::player_id(0), &context);
+
+            assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), -2);
+        }
+    }
+}
@@ -3,10 +3,68 @@
 //! The `EffectResolver` applies effects to game state in a
 //! game-agnostic way. Games can provide callbacks for custom
 //! behavior (like determining deck/hand zones for draw effects).
+//!
+//! When the context maps [`ResolverEvents`], the resolver records a
+//! `GameEvent` for each state change it makes. Drain them with
+//! [`ResolverContext::take_events`] and feed them to a `TriggerRegistry`
+//! (see `stack::EventLoop`).
+//!
+//! Before applying a change, the resolver runs `Before` triggers,
+//! prevention shields and replacement effects on it (see the
+//! `replacement` module). Card creation, copying, transformation and
+//! removal live in the `lifecycle` module.
+//!
+//! ## Choices
+//!
+//! `Effect::Choose` stops resolution at a [`PendingChoice`] stored on the
+//! game state, along with the rest of the batch. [`resolve_choice`]
+//! resumes with the player's selection. A choice with one possible
+//! selection is made automatically. Inside `Before` and `Instead`
+//! triggers, which resolve in the middle of another change, choices can't
+//! wait: the first options are taken.
+//!
+//! [`resolve_choice`]: EffectResolver::resolve_choice
+//!
+//! ## Amounts
+//!
+//! [`ValueExpr`] amounts are evaluated against the state when each effect
+//! starts resolving, before `Before` triggers see its event. Effects
+//! resolved through [`resolve_batch_from`] read "you", the source and the
+//! triggering event from their [`EffectOrigin`]; trigger and replacement
+//! effects get the origin of their trigger.
+//!
+//! [`resolve_batch_from`]: EffectResolver::resolve_batch_from
+
+use std::borrow::Cow;
+use std::cell::RefCell;
 
-use crate::core::{EntityId, GameState, PlayerId, ZoneId};
+use crate::cards::CardRegistry;
+use crate::core::{EntityId, GameConfig, GameState, PlayerId, ZoneId};
+use crate::triggers::{
+    ConditionContext, CustomConditionFn, GameEvent, PendingEvent, TriggerId, TriggerRegistry, TriggeredEffect,
+};
 
-use super::{Effect, EffectBatch};
+use super::{
+    ChoiceError, CustomFilters, Effect, EffectBatch, EffectOrigin, PendingChoice, PlayerRef, PreventionShield,
+    ResolverEvents, TargetSelector, TargetSpec, ValueEnv, ValueExpr,
+};
+
+/// Game-provided evaluator for `Effect::Conditional` keys.
+pub type ConditionEvalFn<'a> = Box<dyn Fn(&str, &GameState) -> bool + 'a>;
+
+/// Game-provided choice between replacement effects for one event.
+///
+/// Called with the affected player, the event and the applicable
+/// replacements (highest trigger priority first). Returns the index of
+/// the replacement to apply.
+pub type ReplacementChoiceFn<'a> =
+    Box<dyn Fn(PlayerId, &GameEvent, &[TriggeredEffect]) -> usize + 'a>;
+
+/// Game-provided hook run for each fired `Before` trigger.
+///
+/// Called after the trigger's own modifiers, for changes that need game
+/// logic rather than a fixed `EventModifier`.
+pub type BeforeHookFn<'a> = Box<dyn Fn(&mut PendingEvent, &TriggeredEffect, &GameState) + 'a>;
 
 /// Context for resolving effects.
 ///
@@ -18,7 +76,28 @@ pub struct ResolverContext<'a> {
     /// Get the hand zone for a player.
     pub get_hand_zone: Box<dyn Fn(PlayerId) -> ZoneId + 'a>,
     /// Evaluate a custom condition.
-    pub eval_condition: Box<dyn Fn(&str, &GameState) -> bool + 'a>,
+    pub eval_condition: ConditionEvalFn<'a>,
+    /// Event types to emit for state changes.
+    pub events: ResolverEvents,
+    /// Registry holding `Before` and `Instead` (replacement) triggers.
+    pub triggers: Option<&'a TriggerRegistry>,
+    /// Choose which of several replacements applies first.
+    pub choose_replacement: ReplacementChoiceFn<'a>,
+    /// Extra handling for fired `Before` triggers.
+    pub before_hook: Option<BeforeHookFn<'a>>,
+    /// Prevention shields, by protected state key.
+    pub preventions: Vec<PreventionShield>,
+    /// Card definitions, for `ValueExpr::CardAttribute` and card type and
+    /// attribute target filters.
+    pub cards: Option<&'a CardRegistry>,
+    /// Game configuration, for targeting any card.
+    pub config: Option<&'a GameConfig>,
+    /// Callbacks for `TargetFilter::Custom`.
+    pub target_filters: CustomFilters<'a>,
+    /// Events emitted since the last `take_events`.
+    emitted: RefCell<Vec<GameEvent>>,
+    /// Triggers applied since the last `take_applied_triggers`.
+    pub(super) applied: RefCell<Vec<TriggerId>>,
 }
 
 impl<'a> ResolverContext<'a> {
@@ -31,6 +110,16 @@ impl<'a> ResolverContext<'a> {
             get_deck_zone: Box::new(get_deck_zone),
             get_hand_zone: Box::new(get_hand_zone),
             eval_condition: Box::new(|_, _| false),
+            events: ResolverEvents::default(),
+            triggers: None,
+            choose_replacement: Box::new(|_, _, _| 0),
+            before_hook: None,
+            preventions: Vec::new(),
+            cards: None,
+            config: None,
+            target_filters: CustomFilters::new(),
+            emitted: RefCell::new(Vec::new()),
+            applied: RefCell::new(Vec::new()),
         }
     }
 
@@ -39,14 +128,16 @@ impl<'a> ResolverContext<'a> {
     /// Zone layout: deck zones are 0..player_count, hand zones are player_count..2*player_count.
     /// Useful for testing.
     pub fn simple(player_count: usize) -> Self {
-        Self {
-            get_deck_zone: Box::new(move |p| ZoneId::new(p.0 as u16)),
-            get_hand_zone: Box::new(move |p| ZoneId::new((player_count as u16) + p.0 as u16)),
-            eval_condition: Box::new(|_, _| false),
-        }
+        Self::new(
+            move |p| ZoneId::new(p.0 as u16),
+            move |p| ZoneId::new((player_count as u16) + p.0 as u16),
+        )
     }
 
     /// Add a condition evaluator.
+    ///
+    /// Also evaluates `TriggerCondition::Custom` on `Before` and `Instead`
+    /// triggers.
     pub fn with_condition_eval(
         mut self,
         eval: impl Fn(&str, &GameState) -> bool + 'a,
@@ -54,6 +145,141 @@ impl<'a> ResolverContext<'a> {
         self.eval_condition = Box::new(eval);
         self
     }
+
+    /// Emit events for state changes (builder pattern).
+    #[must_use]
+    pub fn with_events(mut self, events: ResolverEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Run `Before` triggers and apply `Instead` triggers (replacement
+    /// effects) from this registry (builder pattern).
+    ///
+    /// `After` triggers in the registry are ignored here; see
+    /// `stack::EventLoop`. The resolver does not use up limited-use
+    /// triggers; take the applied IDs with [`take_applied_triggers`] and
+    /// call `Trigger::use_trigger` on them.
+    ///
+    /// [`take_applied_triggers`]: ResolverContext::take_applied_triggers
+    #[must_use]
+    pub fn with_triggers(mut self, registry: &'a TriggerRegistry) -> Self {
+        self.triggers = Some(registry);
+        self
+    }
+
+    /// Let the affected player order competing replacements (builder pattern).
+    ///
+    /// Without this, the highest-priority replacement applies first.
+    #[must_use]
+    pub fn with_replacement_choice(
+        mut self,
+        choose: impl Fn(PlayerId, &GameEvent, &[TriggeredEffect]) -> usize + 'a,
+    ) -> Self {
+        self.choose_replacement = Box::new(choose);
+        self
+    }
+
+    /// Handle fired `Before` triggers with game logic (builder pattern).
+    #[must_use]
+    pub fn with_before_hook(
+        mut self,
+        hook: impl Fn(&mut PendingEvent, &TriggeredEffect, &GameState) + 'a,
+    ) -> Self {
+        self.before_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Add a prevention shield (builder pattern).
+    #[must_use]
+    pub fn with_prevention(mut self, shield: PreventionShield) -> Self {
+        self.preventions.push(shield);
+        self
+    }
+
+    /// Read card attributes for effect amounts and target filters from
+    /// this registry (builder pattern).
+    #[must_use]
+    pub fn with_cards(mut self, cards: &'a CardRegistry) -> Self {
+        self.cards = Some(cards);
+        self
+    }
+
+    /// Find cards for `TargetType::Any` in this config's zones (builder pattern).
+    #[must_use]
+    pub fn with_config(mut self, config: &'a GameConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Add a named callback for `TargetFilter::Custom` (builder pattern).
+    ///
+    /// Called with the state, the candidate target and the acting player.
+    #[must_use]
+    pub fn with_target_filter(
+        mut self,
+        name: impl Into<String>,
+        filter: impl Fn(&GameState, EntityId, PlayerId) -> bool + 'a,
+    ) -> Self {
+        self.target_filters.insert(name, filter);
+        self
+    }
+
+    /// A target selector using this context's registry, config and
+    /// custom filters.
+    pub fn target_selector(&self, spec: TargetSpec, acting_player: PlayerId) -> TargetSelector<'_> {
+        let mut selector = TargetSelector::new(spec, acting_player).with_custom_filters(&self.target_filters);
+        if let Some(cards) = self.cards {
+            selector = selector.with_cards(cards);
+        }
+        if let Some(config) = self.config {
+            selector = selector.with_config(config);
+        }
+        selector
+    }
+
+    /// A condition context using this context's registry.
+    pub(super) fn conditions<'c>(
+        &'c self,
+        event: &'c GameEvent,
+        state: &'c GameState,
+        custom: &'c CustomConditionFn<'c>,
+    ) -> ConditionContext<'c> {
+        let ctx = ConditionContext::new(event, state).with_custom_eval(custom);
+        match self.cards {
+            Some(cards) => ctx.with_cards(cards),
+            None => ctx,
+        }
+    }
+
+    /// Take the events emitted since the last call, in resolution order.
+    pub fn take_events(&self) -> Vec<GameEvent> {
+        std::mem::take(&mut *self.emitted.borrow_mut())
+    }
+
+    /// Get the number of events waiting to be taken.
+    #[must_use]
+    pub fn pending_events(&self) -> usize {
+        self.emitted.borrow().len()
+    }
+
+    /// Take the `Before` and `Instead` triggers applied since the last
+    /// call, in order.
+    pub fn take_applied_triggers(&self) -> Vec<TriggerId> {
+        std::mem::take(&mut *self.applied.borrow_mut())
+    }
+
+    /// Record an emitted event.
+    pub(crate) fn emit(&self, event: Option<GameEvent>) {
+        if let Some(event) = event {
+            self.emitted.borrow_mut().push(event);
+        }
+    }
+
+    /// Find the prevention shield protecting a state key.
+    pub(super) fn prevention(&self, key: &str) -> Option<&PreventionShield> {
+        self.preventions.iter().find(|shield| shield.key == key)
+    }
 }
 
 /// Result of resolving an effect.
@@ -63,8 +289,42 @@ pub enum ResolveResult {
     Success,
     /// Effect failed (e.g., invalid target).
     Failed(String),
-    /// Effect was skipped (e.g., conditional not met).
+    /// Effect was skipped (e.g., conditional not met, fully prevented,
+    /// or cancelled by a replacement).
     Skipped,
+    /// Effect is waiting on a player's choice (see `PendingChoice`).
+    Pending,
+}
+
+/// Where an effect being resolved comes from.
+pub(super) struct Scope {
+    /// Controller, source and triggering event of the effect.
+    pub(super) origin: EffectOrigin,
+    /// `Before` and `Instead` triggers already applied on the way here.
+    pub(super) applied: Vec<TriggerId>,
+    /// Whether resolution may pause for a choice.
+    allow_choices: bool,
+}
+
+impl Scope {
+    fn new(origin: EffectOrigin) -> Self {
+        Self {
+            origin,
+            applied: Vec::new(),
+            allow_choices: true,
+        }
+    }
+
+    /// The scope of a fired trigger's effects.
+    pub(super) fn nested(&self, triggered: &TriggeredEffect) -> Self {
+        let mut applied = self.applied.clone();
+        applied.push(triggered.trigger_id);
+        Self {
+            origin: EffectOrigin::from_triggered(triggered),
+            applied,
+            allow_choices: false,
+        }
+    }
 }
 
 /// Resolves effects on game state.
@@ -77,38 +337,139 @@ impl EffectResolver {
         batch: &EffectBatch,
         context: &ResolverContext,
     ) -> Vec<ResolveResult> {
+        Self::resolve_batch_from(state, batch, &EffectOrigin::default(), context)
+    }
+
+    /// Resolve a batch of effects from a known origin.
+    pub fn resolve_batch_from(
+        state: &mut GameState,
+        batch: &EffectBatch,
+        origin: &EffectOrigin,
+        context: &ResolverContext,
+    ) -> Vec<ResolveResult> {
+        let scope = Scope::new(origin.clone());
         let mut results = Vec::new();
+        let entries: Vec<_> = batch.iter().collect();
 
-        for entry in batch.iter() {
-            for target in &entry.targets {
-                let result = Self::resolve_single(state, &entry.effect, *target, context);
+        for (index, entry) in entries.iter().enumerate() {
+            for (target_index, target) in entry.targets.iter().enumerate() {
+                let result = Self::resolve_targeted(state, &entry.effect, *target, context, &scope);
+                let pending = matches!(result, ResolveResult::Pending);
                 results.push(result);
+                if pending {
+                    let rest = &entry.targets[target_index + 1..];
+                    if !rest.is_empty() {
+                        Self::defer(state, &entry.effect, rest.to_vec());
+                    }
+                    for later in &entries[index + 1..] {
+                        Self::defer(state, &later.effect, later.targets.clone());
+                    }
+                    return results;
+                }
             }
 
             // Effects without targets (like ShuffleZone)
             if entry.targets.is_empty() {
-                let result = Self::resolve_zone_effect(state, &entry.effect, context);
+                let result = Self::resolve_zone_effect(state, &entry.effect, context, &scope);
+                let pending = matches!(result, ResolveResult::Pending);
                 results.push(result);
+                if pending {
+                    for later in &entries[index + 1..] {
+                        Self::defer(state, &later.effect, later.targets.clone());
+                    }
+                    return results;
+                }
             }
         }
 
         results
     }
 
+    /// Resume resolution with a player's selection for the pending choice.
+    ///
+    /// Resolves the choice's effect on each chosen entity, then the effects
+    /// that were waiting behind it, which may stop at another choice. The
+    /// state is unchanged on error.
+    pub fn resolve_choice(
+        state: &mut GameState,
+        selection: &[EntityId],
+        context: &ResolverContext,
+    ) -> Result<Vec<ResolveResult>, ChoiceError> {
+        let Some(choice) = state.take_pending_choice() else {
+            return Err(ChoiceError::NoPendingChoice);
+        };
+        if let Err(error) = choice.check(selection) {
+            state.set_pending_choice(choice);
+            return Err(error);
+        }
+
+        let scope = Scope::new(choice.origin);
+        let result = Self::resolve_chosen(state, &choice.effect, selection, context, &scope);
+        if matches!(result, ResolveResult::Pending) {
+            for entry in choice.remaining {
+                Self::defer(state, &entry.effect, entry.targets);
+            }
+            return Ok(vec![result]);
+        }
+        let mut results = vec![result];
+        results.extend(Self::resolve_batch_from(state, &choice.remaining, &scope.origin, context));
+        Ok(results)
+    }
+
     /// Resolve a single effect on a target.
     pub fn resolve_single(
         state: &mut GameState,
         effect: &Effect,
         target: EntityId,
         context: &ResolverContext,
+    ) -> ResolveResult {
+        Self::resolve_single_from(state, effect, target, &EffectOrigin::default(), context)
+    }
+
+    /// Resolve a single effect on a target from a known origin.
+    pub fn resolve_single_from(
+        state: &mut GameState,
+        effect: &Effect,
+        target: EntityId,
+        origin: &EffectOrigin,
+        context: &ResolverContext,
+    ) -> ResolveResult {
+        Self::resolve_targeted(state, effect, target, context, &Scope::new(origin.clone()))
+    }
+
+    /// Resolve an effect on a target, skipping the scope's applied triggers.
+    pub(super) fn resolve_targeted(
+        state: &mut GameState,
+        effect: &Effect,
+        target: EntityId,
+        context: &ResolverContext,
+        scope: &Scope,
     ) -> ResolveResult {
         let player_count = state.player_count();
 
+        let evaluated = Self::evaluate(state, effect, Some(target), context, scope);
+        let Some((effect, new_target)) = Self::before(state, &evaluated, Some(target), context, scope) else {
+            return ResolveResult::Skipped;
+        };
+        let effect: &Effect = &effect;
+        let target = new_target.unwrap_or(target);
+
         match effect {
             Effect::ModifyPlayerState { key, delta } => {
                 if let Some(idx) = target.as_player_index(player_count) {
                     let player = PlayerId::new(idx);
-                    state.public.modify_player_state(player, key, *delta);
+                    let delta = Self::amount(delta);
+                    let delta = match Self::prevent_player(state, player, key, delta, context) {
+                        0 if delta != 0 => return ResolveResult::Skipped,
+                        remaining => remaining,
+                    };
+                    let old = state.public.get_player_state(player, key, 0);
+                    let event = context.events.player_state_changed(player, key, old, old.saturating_add(delta));
+                    if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+                        return result;
+                    }
+                    state.public.modify_player_state(player, key, delta);
+                    context.emit(event);
                     ResolveResult::Success
                 } else {
                     ResolveResult::Failed("Target is not a player".to_string())
@@ -118,7 +479,14 @@ impl EffectResolver {
             Effect::SetPlayerState { key, value } => {
                 if let Some(idx) = target.as_player_index(player_count) {
                     let player = PlayerId::new(idx);
-                    state.public.set_player_state(player, key, *value);
+                    let old = state.public.get_player_state(player, key, 0);
+                    let value = Self::amount(value);
+                    let event = context.events.player_state_changed(player, key, old, value);
+                    if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+                        return result;
+                    }
+                    state.public.set_player_state(player, key, value);
+                    context.emit(event);
                     ResolveResult::Success
                 } else {
                     ResolveResult::Failed("Target is not a player".to_string())
@@ -127,10 +495,19 @@ impl EffectResolver {
 
             Effect::MoveCard { destination, position } => {
                 if !target.is_player(player_count) {
+                    let from = state.zones.get_zone(target);
+                    let controller = state.get_card(target).and_then(|card| card.controller);
+                    let event = from.and_then(|from| {
+                        context.events.card_moved(target, controller, from, *destination)
+                    });
+                    if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+                        return result;
+                    }
                     state.zones.move_to_zone(target, *destination, *position);
                     if let Some(card) = state.get_card_mut(target) {
                         card.zone = *destination;
                     }
+                    context.emit(event);
                     ResolveResult::Success
                 } else {
                     ResolveResult::Failed("Target is a player, not a card".to_string())
@@ -143,18 +520,54 @@ impl EffectResolver {
                     let deck = from_zone.unwrap_or_else(|| (context.get_deck_zone)(player));
                     let hand = to_zone.unwrap_or_else(|| (context.get_hand_zone)(player));
 
+                    // Each card is a separate draw, modified or replaced on its own
                     let mut drawn = 0;
-                    for _ in 0..*count {
-                        if let Some(entity_id) = state.zones.pop_top(deck) {
-                            state.zones.add_to_zone(entity_id, hand, None);
-                            if let Some(card) = state.get_card_mut(entity_id) {
-                                card.zone = hand;
+                    for _ in 0..Self::amount(count).max(0) {
+                        let Some(entity_id) = state.zones.top_card(deck) else {
+                            break;
+                        };
+                        let controller = state.get_card(entity_id).and_then(|card| card.controller);
+                        let pending = context.events.card_drawn(entity_id, player, deck, hand)
+                            .or_else(|| context.events.card_moved(entity_id, controller, deck, hand));
+                        let destination = match pending {
+                            Some(event) => match Self::run_before(state, event, context, scope) {
+                                Some(event) => event.zone(1).unwrap_or(hand),
+                                None => {
+                                    // Cancelled draws still count as handled
+                                    drawn += 1;
+                                    continue;
+                                }
+                            },
+                            None => hand,
+                        };
+                        if !state.zones.is_in_zone(entity_id, deck) {
+                            // A Before trigger's effects moved the card already
+                            continue;
+                        }
+                        let moved = context.events.card_moved(entity_id, controller, deck, destination);
+                        let draw = context.events.card_drawn(entity_id, player, deck, destination);
+                        let replaced = match Self::replace(state, draw.as_ref(), Some(target), context, scope) {
+                            Some(result) => Some(result),
+                            None => Self::replace(state, moved.as_ref(), Some(target), context, scope),
+                        };
+                        if let Some(result) = replaced {
+                            if matches!(result, ResolveResult::Failed(_)) {
+                                return result;
                             }
-                            state.public.hand_sizes[player] += 1;
                             drawn += 1;
-                        } else {
-                            break;
+                            continue;
+                        }
+
+                        state.zones.move_to_zone(entity_id, destination, None);
+                        if let Some(card) = state.get_card_mut(entity_id) {
+                            card.zone = destination;
+                        }
+                        if destination == hand {
+                            state.public.hand_sizes[player] += 1;
                         }
+                        drawn += 1;
+                        context.emit(moved);
+                        context.emit(draw);
                     }
 
                     if drawn > 0 {
@@ -169,12 +582,25 @@ impl EffectResolver {
 
             Effect::ModifyCardState { key, delta } => {
                 if !target.is_player(player_count) {
+                    let Some(card) = state.get_card(target) else {
+                        return ResolveResult::Failed("Card not found".to_string());
+                    };
+                    let controller = card.controller;
+                    let delta = Self::amount(delta);
+                    let delta = match Self::prevent_card(state, target, key, delta, context) {
+                        0 if delta != 0 => return ResolveResult::Skipped,
+                        remaining => remaining,
+                    };
+                    let old = state.get_card(target).map_or(0, |card| card.get_state(key, 0));
+                    let event = context.events.card_state_changed(target, controller, key, old, old.saturating_add(delta));
+                    if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+                        return result;
+                    }
                     if let Some(card) = state.get_card_mut(target) {
-                        card.modify_state(key, *delta);
-                        ResolveResult::Success
-                    } else {
-                        ResolveResult::Failed("Card not found".to_string())
+                        card.modify_state(key, delta);
                     }
+                    context.emit(event);
+                    ResolveResult::Success
                 } else {
                     ResolveResult::Failed("Target is a player, not a card".to_string())
                 }
@@ -182,47 +608,84 @@ impl EffectResolver {
 
             Effect::SetCardState { key, value } => {
                 if !target.is_player(player_count) {
+                    let Some(card) = state.get_card(target) else {
+                        return ResolveResult::Failed("Card not found".to_string());
+                    };
+                    let old = card.get_state(key, 0);
+                    let value = Self::amount(value);
+                    let event = context.events.card_state_changed(target, card.controller, key, old, value);
+                    if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+                        return result;
+                    }
                     if let Some(card) = state.get_card_mut(target) {
-                        card.set_state(key, *value);
-                        ResolveResult::Success
-                    } else {
-                        ResolveResult::Failed("Card not found".to_string())
+                        card.set_state(key, value);
                     }
+                    context.emit(event);
+                    ResolveResult::Success
                 } else {
                     ResolveResult::Failed("Target is a player, not a card".to_string())
                 }
             }
 
+            Effect::CreateCard { card_id, zone, owner, position } => {
+                let owner = Self::env(state, Some(target), context, scope).player(owner);
+                Self::create_card(state, *card_id, *zone, owner, *position, None, context)
+            }
+
+            Effect::CopyCard { zone, owner, position } => {
+                Self::copy_card(state, target, *zone, owner, *position, context, scope)
+            }
+
+            Effect::TransformCard { into } => Self::transform_card(state, target, *into, context, scope),
+
+            Effect::RemoveFromGame => Self::remove_card(state, target, context, scope),
+
             Effect::ModifyTurnState { key, delta } => {
                 let current = state.public.get_turn_state(key, 0);
-                state.public.set_turn_state(key, current + delta);
+                state.public.set_turn_state(key, current.saturating_add(Self::amount(delta)));
                 ResolveResult::Success
             }
 
             Effect::SetTurnState { key, value } => {
-                state.public.set_turn_state(key, *value);
+                state.public.set_turn_state(key, Self::amount(value));
                 ResolveResult::Success
             }
 
             Effect::ShuffleZone { zone } => {
                 // This is a zone effect, not a target effect
+                let event = context.events.zone_shuffled(*zone);
+                if let Some(result) = Self::replace(state, event.as_ref(), Some(target), context, scope) {
+                    return result;
+                }
                 state.zones.shuffle_zone(*zone, &mut state.rng);
+                context.emit(event);
                 ResolveResult::Success
             }
 
             Effect::Batch(effects) => {
-                for sub_effect in effects {
-                    let result = Self::resolve_single(state, sub_effect, target, context);
-                    if matches!(result, ResolveResult::Failed(_)) {
-                        return result;
+                for (index, sub_effect) in effects.iter().enumerate() {
+                    let result = Self::resolve_targeted(state, sub_effect, target, context, scope);
+                    match result {
+                        ResolveResult::Failed(_) => return result,
+                        ResolveResult::Pending => {
+                            for later in &effects[index + 1..] {
+                                Self::defer(state, later, vec![target]);
+                            }
+                            return result;
+                        }
+                        ResolveResult::Success | ResolveResult::Skipped => {}
                     }
                 }
                 ResolveResult::Success
             }
 
+            Effect::Choose { chooser, spec, effect } => {
+                Self::choose(state, chooser, spec, effect, Some(target), context, scope)
+            }
+
             Effect::Conditional { condition_key, effect } => {
                 if (context.eval_condition)(condition_key, state) {
-                    Self::resolve_single(state, effect, target, context)
+                    Self::resolve_targeted(state, effect, target, context, scope)
                 } else {
                     ResolveResult::Skipped
                 }
@@ -231,36 +694,184 @@ impl EffectResolver {
     }
 
     /// Resolve a zone-level effect (no target entity).
-    fn resolve_zone_effect(
+    pub(super) fn resolve_zone_effect(
         state: &mut GameState,
         effect: &Effect,
-        _context: &ResolverContext,
+        context: &ResolverContext,
+        scope: &Scope,
     ) -> ResolveResult {
-        match effect {
+        let evaluated = Self::evaluate(state, effect, None, context, scope);
+        let Some((effect, _)) = Self::before(state, &evaluated, None, context, scope) else {
+            return ResolveResult::Skipped;
+        };
+        match &*effect {
             Effect::ShuffleZone { zone } => {
+                let event = context.events.zone_shuffled(*zone);
+                if let Some(result) = Self::replace(state, event.as_ref(), None, context, scope) {
+                    return result;
+                }
                 state.zones.shuffle_zone(*zone, &mut state.rng);
+                context.emit(event);
                 ResolveResult::Success
             }
             Effect::ModifyTurnState { key, delta } => {
                 let current = state.public.get_turn_state(key, 0);
-                state.public.set_turn_state(key, current + delta);
+                state.public.set_turn_state(key, current.saturating_add(Self::amount(delta)));
                 ResolveResult::Success
             }
             Effect::SetTurnState { key, value } => {
-                state.public.set_turn_state(key, *value);
+                state.public.set_turn_state(key, Self::amount(value));
                 ResolveResult::Success
             }
+            Effect::CreateCard { card_id, zone, owner, position } => {
+                let owner = Self::env(state, None, context, scope).player(owner);
+                Self::create_card(state, *card_id, *zone, owner, *position, None, context)
+            }
+            Effect::Choose { chooser, spec, effect } => Self::choose(state, chooser, spec, effect, None, context, scope),
             _ => ResolveResult::Failed("Effect requires a target".to_string()),
         }
     }
+
+    /// Evaluate a primitive effect's amounts to literals.
+    ///
+    /// Composite effects are left alone; their parts are evaluated as they
+    /// resolve.
+    fn evaluate<'e>(
+        state: &GameState,
+        effect: &'e Effect,
+        target: Option<EntityId>,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> Cow<'e, Effect> {
+        let expr = match effect {
+            Effect::ModifyPlayerState { delta: expr, .. }
+            | Effect::ModifyCardState { delta: expr, .. }
+            | Effect::ModifyTurnState { delta: expr, .. }
+            | Effect::SetPlayerState { value: expr, .. }
+            | Effect::SetCardState { value: expr, .. }
+            | Effect::SetTurnState { value: expr, .. }
+            | Effect::DrawCards { count: expr, .. } => expr,
+            _ => return Cow::Borrowed(effect),
+        };
+        if expr.as_const().is_some() {
+            return Cow::Borrowed(effect);
+        }
+
+        let value = ValueExpr::Const(expr.eval(&Self::env(state, target, context, scope)));
+        let mut effect = effect.clone();
+        match &mut effect {
+            Effect::ModifyPlayerState { delta: expr, .. }
+            | Effect::ModifyCardState { delta: expr, .. }
+            | Effect::ModifyTurnState { delta: expr, .. }
+            | Effect::SetPlayerState { value: expr, .. }
+            | Effect::SetCardState { value: expr, .. }
+            | Effect::SetTurnState { value: expr, .. }
+            | Effect::DrawCards { count: expr, .. } => *expr = value,
+            _ => {}
+        }
+        Cow::Owned(effect)
+    }
+
+    /// The environment `ValueExpr`s and player references resolve in.
+    pub(super) fn env<'s>(
+        state: &'s GameState,
+        target: Option<EntityId>,
+        context: &'s ResolverContext,
+        scope: &'s Scope,
+    ) -> ValueEnv<'s> {
+        ValueEnv {
+            state,
+            target,
+            origin: &scope.origin,
+            cards: context.cards,
+            deck_zone: &*context.get_deck_zone,
+        }
+    }
+
+    /// Start a choice, or make it right away if there is only one way to.
+    fn choose(
+        state: &mut GameState,
+        chooser: &PlayerRef,
+        spec: &TargetSpec,
+        effect: &Effect,
+        target: Option<EntityId>,
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> ResolveResult {
+        let Some(player) = Self::env(state, target, context, scope).player(chooser) else {
+            return ResolveResult::Failed("Chooser not found".to_string());
+        };
+        let mut selector = context.target_selector(spec.clone(), player);
+        if let Some(source) = scope.origin.source {
+            selector = selector.with_source(source);
+        }
+        let options = selector.valid_targets(state);
+        let (min, max) = spec.bounds(options.len());
+
+        if min == options.len() || !scope.allow_choices {
+            let chosen = options[..min].to_vec();
+            return Self::resolve_chosen(state, effect, &chosen, context, scope);
+        }
+        state.set_pending_choice(PendingChoice {
+            player,
+            options,
+            min,
+            max,
+            effect: effect.clone(),
+            origin: scope.origin.clone(),
+            remaining: EffectBatch::new(),
+        });
+        ResolveResult::Pending
+    }
+
+    /// Resolve a choice's effect on each chosen entity.
+    fn resolve_chosen(
+        state: &mut GameState,
+        effect: &Effect,
+        chosen: &[EntityId],
+        context: &ResolverContext,
+        scope: &Scope,
+    ) -> ResolveResult {
+        let mut result = ResolveResult::Skipped;
+        for (index, &entity) in chosen.iter().enumerate() {
+            match Self::resolve_targeted(state, effect, entity, context, scope) {
+                ResolveResult::Pending => {
+                    let rest = &chosen[index + 1..];
+                    if !rest.is_empty() {
+                        Self::defer(state, effect, rest.to_vec());
+                    }
+                    return ResolveResult::Pending;
+                }
+                ResolveResult::Success => result = ResolveResult::Success,
+                // A chosen entity may be gone by the time its turn comes
+                ResolveResult::Failed(_) | ResolveResult::Skipped => {}
+            }
+        }
+        result
+    }
+
+    /// Queue an effect to resolve after the pending choice.
+    ///
+    /// No targets means a zone effect.
+    fn defer(state: &mut GameState, effect: &Effect, targets: Vec<EntityId>) {
+        if let Some(choice) = state.pending_choice_mut() {
+            choice.remaining.add(effect.clone(), targets);
+        }
+    }
+
+    /// The value of an evaluated amount.
+    pub(super) fn amount(expr: &ValueExpr) -> i64 {
+        // `evaluate` leaves only literals
+        expr.as_const().unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::cards::{CardId, CardInstance};
 
-    fn setup_test_state() -> (GameState, ZoneId, ZoneId) {
+    pub(crate) fn setup_test_state() -> (GameState, ZoneId, ZoneId) {
         let mut state = GameState::new(2, 42);
         let deck = ZoneId::new(0);
         let hand = ZoneId::new(1);
@@ -277,7 +888,7 @@ mod tests {
         (state, deck, hand)
     }
 
-    fn test_context(deck: ZoneId, hand: ZoneId) -> ResolverContext<'static> {
+    pub(crate) fn test_context(deck: ZoneId, hand: ZoneId) -> ResolverContext<'static> {
         ResolverContext::new(
             move |_| deck,
             move |_| hand,
@@ -457,4 +1068,233 @@ mod tests {
         assert!(matches!(result, ResolveResult::Skipped));
         assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), 20); // Unchanged
     }
+
+    #[test]
+    fn test_no_events_without_mapping() {
+        let (mut state, deck, hand) = setup_test_state();
+        let context = test_context(deck, hand);
+
+        EffectResolver::resolve_single(&mut state, &Effect::draw(2), EntityId::player_id(0), &context);
+
+        assert!(context.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_move_and_draw_events() {
+        use crate::triggers::EventTypeId;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let moved = EventTypeId::new(1);
+        let drawn = EventTypeId::new(2);
+        let context = test_context(deck, hand)
+            .with_events(ResolverEvents::new().with_card_moved(moved).with_card_drawn(drawn));
+
+        let discard = ZoneId::new(2);
+        EffectResolver::resolve_single(&mut state, &Effect::move_to_top(discard), EntityId(10), &context);
+        EffectResolver::resolve_single(&mut state, &Effect::draw(1), EntityId::player_id(0), &context);
+
+        let events = context.take_events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].event_type, moved);
+        assert_eq!(events[0].target, Some(EntityId(10)));
+        assert_eq!(events[0].zones, vec![deck, discard]);
+        assert_eq!(events[0].player, Some(PlayerId::new(0)));
+        // A draw is also a move
+        assert_eq!(events[1].event_type, moved);
+        assert_eq!(events[2].event_type, drawn);
+        assert_eq!(events[2].zones, vec![deck, hand]);
+        assert_eq!(context.pending_events(), 0);
+    }
+
+    #[test]
+    fn test_state_change_events() {
+        use crate::triggers::EventTypeId;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let player_changed = EventTypeId::new(1);
+        let card_changed = EventTypeId::new(2);
+        let shuffled = EventTypeId::new(3);
+        let context = test_context(deck, hand).with_events(
+            ResolverEvents::new()
+                .with_player_state_changed(player_changed)
+                .with_card_state_changed(card_changed)
+                .with_zone_shuffled(shuffled),
+        );
+        state.public.set_player_state(PlayerId::new(1), "life", 20);
+
+        EffectResolver::resolve_single(&mut state, &Effect::damage(3), EntityId::player_id(1), &context);
+        EffectResolver::resolve_single(&mut state, &Effect::modify_card("damage", 2), EntityId(10), &context);
+        // Setting a value to what it already is emits nothing
+        EffectResolver::resolve_single(&mut state, &Effect::set_card("damage", 2), EntityId(10), &context);
+        let mut batch = EffectBatch::new();
+        batch.add_zone(Effect::ShuffleZone { zone: deck });
+        EffectResolver::resolve_batch(&mut state, &batch, &context);
+
+        let events = context.take_events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].event_type, player_changed);
+        assert_eq!(events[0].player, Some(PlayerId::new(1)));
+        assert_eq!(events[0].values, vec![-3, 17]);
+        assert!(events[0].has_tag("life"));
+        assert_eq!(events[1].event_type, card_changed);
+        assert_eq!(events[1].target, Some(EntityId(10)));
+        assert_eq!(events[1].values, vec![2, 2]);
+        assert_eq!(events[2].event_type, shuffled);
+        assert_eq!(events[2].zones, vec![deck]);
+    }
+
+    #[test]
+    fn test_dynamic_amounts() {
+        use crate::effects::{CardRef, PlayerRef};
+
+        let (mut state, deck, hand) = setup_test_state();
+        let context = test_context(deck, hand);
+        state.public.hand_sizes[PlayerId::new(0)] = 3;
+        state.get_card_mut(EntityId(10)).unwrap().set_state("power", 4);
+        let origin = EffectOrigin::for_player(PlayerId::new(0)).with_source(EntityId(10));
+
+        // "Deal damage equal to the number of cards in your hand"
+        let damage = Effect::damage(ValueExpr::HandSize(PlayerRef::Controller));
+        EffectResolver::resolve_single_from(&mut state, &damage, EntityId::player_id(1), &origin, &context);
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), -3);
+
+        // "Draw cards equal to this card's power, minus one"
+        let power = ValueExpr::card_state(CardRef::Source, "power");
+        let draw = Effect::draw(power - ValueExpr::from(1));
+        EffectResolver::resolve_single_from(&mut state, &draw, EntityId::player_id(0), &origin, &context);
+        assert_eq!(state.public.hand_sizes[PlayerId::new(0)], 6);
+        assert_eq!(state.zones.zone_size(deck), 2);
+
+        // Without an origin there is no controller; the amount is 0
+        EffectResolver::resolve_single(&mut state, &damage, EntityId::player_id(1), &context);
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), -3);
+    }
+
+    #[test]
+    fn test_card_attribute_amounts() {
+        use crate::cards::{CardDefinition, CardRegistry, CardTypeId};
+        use crate::effects::CardRef;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let mut cards = CardRegistry::new();
+        cards.register(CardDefinition::new(CardId::new(1), "Ogre", CardTypeId::new(0)).with_attr("power", 5));
+        let context = test_context(deck, hand).with_cards(&cards);
+
+        let effect = Effect::set_card("damage", ValueExpr::card_attribute(CardRef::Target, "power"));
+        EffectResolver::resolve_single(&mut state, &effect, EntityId(11), &context);
+
+        assert_eq!(state.get_card(EntityId(11)).unwrap().get_state("damage", 0), 5);
+    }
+
+    #[test]
+    fn test_extreme_amounts_saturate() {
+        use crate::effects::ResolverEvents;
+        use crate::triggers::EventTypeId;
+
+        let (mut state, deck, hand) = setup_test_state();
+        let events = ResolverEvents::new()
+            .with_player_state_changed(EventTypeId::new(1))
+            .with_card_state_changed(EventTypeId::new(2));
+        let context = test_context(deck, hand).with_events(events);
+        let player = PlayerId::new(1);
+        state.public.set_player_state(player, "life", 20);
+        let huge = ValueExpr::from(i64::MAX) * ValueExpr::from(2);
+
+        let heal = Effect::modify_player("life", huge.clone());
+        EffectResolver::resolve_single(&mut state, &heal, EntityId::player(player), &context);
+        assert_eq!(state.public.get_player_state(player, "life", 0), i64::MAX);
+
+        let drain = Effect::modify_player("life", ValueExpr::from(i64::MIN) * ValueExpr::from(2));
+        for _ in 0..2 {
+            EffectResolver::resolve_single(&mut state, &drain, EntityId::player(player), &context);
+        }
+        assert_eq!(state.public.get_player_state(player, "life", 0), i64::MIN);
+        let set = Effect::set_player("life", i64::MAX);
+        EffectResolver::resolve_single(&mut state, &set, EntityId::player(player), &context);
+
+        state.get_card_mut(EntityId(10)).unwrap().set_state("counters", i64::MAX - 1);
+        EffectResolver::resolve_single(&mut state, &Effect::modify_card("counters", huge.clone()), EntityId(10), &context);
+        assert_eq!(state.get_card(EntityId(10)).unwrap().get_state("counters", 0), i64::MAX);
+
+        let turn = Effect::ModifyTurnState {
+            key: "storm".to_string(),
+            delta: huge,
+        };
+        for _ in 0..2 {
+            EffectResolver::resolve_single(&mut state, &turn, EntityId::player(player), &context);
+        }
+        assert_eq!(state.public.get_turn_state("storm", 0), i64::MAX);
+
+        // The events saturate their deltas too
+        let events = context.take_events();
+        assert_eq!(events[0].values, vec![i64::MAX - 20, i64::MAX]);
+        assert_eq!(events[3].values, vec![i64::MAX, i64::MAX]);
+    }
+
+    #[test]
+    fn test_choice_pauses_and_resumes() {
+        let (mut state, deck, hand) = setup_test_state();
+        let context = test_context(deck, hand);
+        let discard = ZoneId::new(2);
+        state.zones.init_ordered_zone(discard);
+        state.public.set_player_state(PlayerId::new(0), "life", 20);
+        state.public.set_player_state(PlayerId::new(1), "life", 20);
+        EffectResolver::resolve_single(&mut state, &Effect::draw(3), EntityId::player_id(0), &context);
+
+        // Discard a card, then lose 1 life; then player 1 takes 2
+        let discard_one = Effect::choose(TargetSpec::single_card([hand]), Effect::move_to_top(discard));
+        let mut batch = EffectBatch::new();
+        batch.add_player(Effect::batch([discard_one, Effect::damage(1)]), PlayerId::new(0));
+        batch.add_player(Effect::damage(2), PlayerId::new(1));
+
+        let results = EffectResolver::resolve_batch(&mut state, &batch, &context);
+        assert!(matches!(results[..], [ResolveResult::Pending]));
+        let choice = state.pending_choice().unwrap();
+        assert_eq!(choice.player, PlayerId::new(0));
+        assert_eq!((choice.min, choice.max, choice.options.len()), (1, 1, 3));
+        assert_eq!(choice.remaining().len(), 2);
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 20);
+
+        // Bad selections leave the choice in place
+        let discarded = choice.options[1];
+        assert_eq!(
+            EffectResolver::resolve_choice(&mut state, &[EntityId(99)], &context).unwrap_err(),
+            ChoiceError::NotAnOption(EntityId(99))
+        );
+        assert!(state.pending_choice().is_some());
+
+        let results = EffectResolver::resolve_choice(&mut state, &[discarded], &context).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(state.pending_choice().is_none());
+        assert!(state.zones.is_in_zone(discarded, discard));
+        assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), 19);
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 18);
+
+        assert_eq!(
+            EffectResolver::resolve_choice(&mut state, &[], &context).unwrap_err(),
+            ChoiceError::NoPendingChoice
+        );
+    }
+
+    #[test]
+    fn test_choices_without_alternatives() {
+        let (mut state, deck, hand) = setup_test_state();
+        let context = test_context(deck, hand);
+
+        // All five deck cards: nothing to decide
+        let all = Effect::choose(
+            TargetSpec::multiple_cards([deck], crate::effects::TargetCount::All),
+            Effect::modify_card("marked", 1),
+        );
+        let result = EffectResolver::resolve_single(&mut state, &all, EntityId::player_id(0), &context);
+        assert!(matches!(result, ResolveResult::Success));
+        assert!(state.pending_choice().is_none());
+        assert!((10..15).all(|i| state.get_card(EntityId(i)).unwrap().get_state("marked", 0) == 1));
+
+        // Nothing to choose from
+        let none = Effect::choose(TargetSpec::single_card([hand]), Effect::RemoveFromGame);
+        let result = EffectResolver::resolve_single(&mut state, &none, EntityId::player_id(0), &context);
+        assert!(matches!(result, ResolveResult::Skipped));
+        assert!(state.pending_choice().is_none());
+    }
 }
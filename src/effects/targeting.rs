@@ -4,16 +4,30 @@
 //! - `TargetSpec`: Specification for what can be targeted
 //! - `TargetFilter`: Filters for valid targets
 //! - `TargetSelector`: Algorithms for selecting targets
+//! - `CustomFilters`: Game callbacks for `TargetFilter::Custom`
+//!
+//! `TargetFilter::Matches` takes an [`EntityPredicate`] for targets that a
+//! flat list of filters can't describe, such as "a creature or a player".
+//! The other filters are shorthands for predicates too (see
+//! [`TargetFilter::predicate`]), and are evaluated as one.
+//!
+//! Card type and attribute filters need a `CardRegistry`, and
+//! `TargetType::Any` needs a `GameConfig` to find cards; give them to the
+//! selector with `with_cards` and `with_config`.
 
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
-use crate::cards::CardTypeId;
-use crate::core::{EntityId, GameState, PlayerId, ZoneId};
+use crate::cards::{CardRegistry, CardTypeId};
+use crate::core::{EntityId, GameConfig, GameState, PlayerId, ZoneId};
+
+use super::choice::{self, ChoiceError};
+use super::predicate::{EntityPredicate, PlayerMatch, PredicateContext};
 
 /// Specification for effect targeting.
 ///
 /// Describes what kind of entities can be targeted and how many.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TargetSpec {
     /// What kind of entities can be targeted.
     pub target_type: TargetType,
@@ -26,18 +40,21 @@ pub struct TargetSpec {
 }
 
 /// The type of entity that can be targeted.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TargetType {
     /// Target players.
     Player,
     /// Target cards in specific zones.
     Card { zones: Vec<ZoneId> },
     /// Target any entity (player or card).
+    ///
+    /// Cards are found in the zones of the selector's `GameConfig`; without
+    /// one only players are considered.
     Any,
 }
 
 /// Number of targets to select.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TargetCount {
     /// Exactly N targets.
     Exactly(usize),
@@ -54,7 +71,7 @@ pub enum TargetCount {
 /// Filters for valid targets.
 ///
 /// Game-agnostic filters that work across different card games.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TargetFilter {
     // === Player Filters ===
 
@@ -71,14 +88,25 @@ pub enum TargetFilter {
 
     /// Target card must be in specified zone.
     InZone(ZoneId),
+    /// Target card must be among the top N cards of its ordered zone.
+    TopCards(usize),
     /// Target card must have specified card type.
     ///
-    /// **Note**: This filter requires CardRegistry access to work properly.
-    /// Since TargetSelector doesn't have registry access, this filter
-    /// always returns `false` (no match). Games should use `Custom` filters
-    /// with registry access for card type filtering, or provide their own
-    /// selector implementation.
+    /// Requires a `CardRegistry` (see [`TargetSelector::with_cards`]);
+    /// without one nothing matches.
     HasCardType(CardTypeId),
+    /// Target card's definition must have the attribute.
+    ///
+    /// Requires a `CardRegistry`, like `HasCardType`.
+    HasAttribute(String),
+    /// Target card's definition must have an integer attribute in range.
+    ///
+    /// Requires a `CardRegistry`, like `HasCardType`.
+    AttributeInRange {
+        key: String,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
     /// Target card must be owned by specified player.
     OwnedBy(PlayerId),
     /// Target card must be controlled by specified player.
@@ -94,10 +122,42 @@ pub enum TargetFilter {
 
     /// Target must not be the source entity.
     NotSource,
-    /// Custom filter (evaluated by game-specific code).
+    /// Target must match the predicate.
+    ///
+    /// Players are relative to the acting player, and `IsSource` refers
+    /// to the selector's source.
+    Matches(EntityPredicate),
+    /// Custom filter, evaluated by the named callback in the selector's
+    /// [`CustomFilters`]. Unknown names match nothing.
     Custom(String),
 }
 
+impl TargetFilter {
+    /// The predicate this filter is shorthand for.
+    ///
+    /// `None` for `TopCards` and `Custom`, which need the zone order and
+    /// the selector's callbacks.
+    #[must_use]
+    pub fn predicate(&self) -> Option<EntityPredicate> {
+        Some(match self {
+            Self::Opponent => EntityPredicate::IsPlayer(PlayerMatch::Opponent),
+            Self::Self_ => EntityPredicate::IsPlayer(PlayerMatch::You),
+            Self::NotSelf => EntityPredicate::IsPlayer(PlayerMatch::You).negate(),
+            Self::SpecificPlayer(player) => EntityPredicate::IsPlayer(PlayerMatch::Player(*player)),
+            Self::InZone(zone) => EntityPredicate::InZone(*zone),
+            Self::HasCardType(card_type) => EntityPredicate::CardType(*card_type),
+            Self::HasAttribute(key) => EntityPredicate::HasAttribute(key.clone()),
+            Self::AttributeInRange { key, min, max } => EntityPredicate::attribute_in_range(key.clone(), *min, *max),
+            Self::OwnedBy(player) => EntityPredicate::OwnedBy(PlayerMatch::Player(*player)),
+            Self::ControlledBy(player) => EntityPredicate::ControlledBy(PlayerMatch::Player(*player)),
+            Self::StateInRange { key, min, max } => EntityPredicate::state_in_range(key.clone(), *min, *max),
+            Self::NotSource => EntityPredicate::IsSource.negate(),
+            Self::Matches(predicate) => predicate.clone(),
+            Self::TopCards(_) | Self::Custom(_) => return None,
+        })
+    }
+}
+
 impl TargetSpec {
     /// Create a spec for targeting a single player.
     pub fn single_player() -> Self {
@@ -154,23 +214,97 @@ impl TargetSpec {
         self.optional = true;
         self
     }
+
+    /// Fewest and most targets to select when `available` are valid.
+    ///
+    /// Requirements larger than what's available are capped, so a
+    /// selection is always possible.
+    #[must_use]
+    pub fn bounds(&self, available: usize) -> (usize, usize) {
+        let (min, max) = match self.count {
+            TargetCount::Exactly(n) => (n, n),
+            TargetCount::UpTo(n) => (0, n),
+            TargetCount::AtLeast(n) => (n, available),
+            TargetCount::Range { min, max } => (min, max),
+            TargetCount::All => (available, available),
+        };
+        let max = max.min(available);
+        let min = if self.optional { 0 } else { min.min(max) };
+        (min, max)
+    }
+}
+
+/// Game-provided evaluator for a `TargetFilter::Custom` filter.
+///
+/// Called with the state, the candidate target and the acting player.
+pub type TargetFilterFn<'a> = Box<dyn Fn(&GameState, EntityId, PlayerId) -> bool + 'a>;
+
+/// Named callbacks for `TargetFilter::Custom`.
+#[derive(Default)]
+pub struct CustomFilters<'a> {
+    filters: FxHashMap<String, TargetFilterFn<'a>>,
+}
+
+impl<'a> CustomFilters<'a> {
+    /// Create an empty set of filters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a named filter (builder pattern).
+    #[must_use]
+    pub fn with(mut self, name: impl Into<String>, filter: impl Fn(&GameState, EntityId, PlayerId) -> bool + 'a) -> Self {
+        self.insert(name, filter);
+        self
+    }
+
+    /// Add or replace a named filter.
+    pub fn insert(&mut self, name: impl Into<String>, filter: impl Fn(&GameState, EntityId, PlayerId) -> bool + 'a) {
+        self.filters.insert(name.into(), Box::new(filter));
+    }
+
+    /// Get a filter by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&TargetFilterFn<'a>> {
+        self.filters.get(name)
+    }
+
+    /// Check if no filters are defined.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+impl std::fmt::Debug for CustomFilters<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<_> = self.filters.keys().collect();
+        names.sort();
+        f.debug_tuple("CustomFilters").field(&names).finish()
+    }
 }
 
 /// Selector for choosing targets based on a spec.
 #[derive(Clone, Debug)]
-pub struct TargetSelector {
+pub struct TargetSelector<'a> {
     spec: TargetSpec,
     acting_player: PlayerId,
     source_entity: Option<EntityId>,
+    cards: Option<&'a CardRegistry>,
+    config: Option<&'a GameConfig>,
+    custom: Option<&'a CustomFilters<'a>>,
 }
 
-impl TargetSelector {
+impl<'a> TargetSelector<'a> {
     /// Create a new target selector.
     pub fn new(spec: TargetSpec, acting_player: PlayerId) -> Self {
         Self {
             spec,
             acting_player,
             source_entity: None,
+            cards: None,
+            config: None,
+            custom: None,
         }
     }
 
@@ -180,16 +314,50 @@ impl TargetSelector {
         self
     }
 
+    /// Read card types and attributes from this registry (builder pattern).
+    #[must_use]
+    pub fn with_cards(mut self, cards: &'a CardRegistry) -> Self {
+        self.cards = Some(cards);
+        self
+    }
+
+    /// Find cards for `TargetType::Any` in this config's zones (builder pattern).
+    #[must_use]
+    pub fn with_config(mut self, config: &'a GameConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Evaluate `TargetFilter::Custom` with these callbacks (builder pattern).
+    #[must_use]
+    pub fn with_custom_filters(mut self, filters: &'a CustomFilters<'a>) -> Self {
+        self.custom = Some(filters);
+        self
+    }
+
+    /// The spec targets are selected by.
+    #[must_use]
+    pub fn spec(&self) -> &TargetSpec {
+        &self.spec
+    }
+
+    /// The player choosing targets.
+    #[must_use]
+    pub fn acting_player(&self) -> PlayerId {
+        self.acting_player
+    }
+
     /// Get all valid targets from game state.
     pub fn valid_targets(&self, state: &GameState) -> Vec<EntityId> {
         let player_count = state.player_count();
+        let filter = self.filter(state);
         let mut targets = Vec::new();
 
         match &self.spec.target_type {
             TargetType::Player => {
                 for player in PlayerId::all(player_count) {
                     let entity = EntityId::player(player);
-                    if self.passes_filters(state, entity, player_count) {
+                    if filter.passes(entity) {
                         targets.push(entity);
                     }
                 }
@@ -197,7 +365,7 @@ impl TargetSelector {
             TargetType::Card { zones } => {
                 for zone in zones {
                     for entity in state.zones.cards_in_zone(*zone) {
-                        if self.passes_filters(state, entity, player_count) {
+                        if filter.passes(entity) {
                             targets.push(entity);
                         }
                     }
@@ -207,95 +375,41 @@ impl TargetSelector {
                 // Players
                 for player in PlayerId::all(player_count) {
                     let entity = EntityId::player(player);
-                    if self.passes_filters(state, entity, player_count) {
+                    if filter.passes(entity) {
                         targets.push(entity);
                     }
                 }
-                // All cards (would need zone enumeration from game config)
-                // For now, Any requires explicit zone list in practice
+                // Cards in every configured zone
+                for zone in self.config.iter().flat_map(|config| &config.zones) {
+                    for entity in state.zones.cards_in_zone(zone.id) {
+                        if filter.passes(entity) {
+                            targets.push(entity);
+                        }
+                    }
+                }
             }
         }
 
         targets
     }
 
-    /// Check if an entity passes all filters.
-    fn passes_filters(&self, state: &GameState, entity: EntityId, player_count: usize) -> bool {
+    /// The spec's filters, lowered to one predicate where possible.
+    fn filter<'s>(&'s self, state: &'s GameState) -> SpecFilter<'s> {
+        let mut predicates = Vec::new();
+        let mut rest = Vec::new();
         for filter in &self.spec.filters {
-            if !self.passes_filter(state, entity, filter, player_count) {
-                return false;
+            match filter.predicate() {
+                Some(predicate) => predicates.push(predicate),
+                None => rest.push(filter),
             }
         }
-        true
-    }
-
-    /// Check if an entity passes a single filter.
-    fn passes_filter(
-        &self,
-        state: &GameState,
-        entity: EntityId,
-        filter: &TargetFilter,
-        player_count: usize,
-    ) -> bool {
-        match filter {
-            TargetFilter::Opponent => {
-                entity.as_player_index(player_count).is_some_and(|idx| {
-                    PlayerId::new(idx) != self.acting_player
-                })
-            }
-            TargetFilter::Self_ => {
-                entity.as_player_index(player_count).is_some_and(|idx| {
-                    PlayerId::new(idx) == self.acting_player
-                })
-            }
-            TargetFilter::NotSelf => {
-                entity.as_player_index(player_count).is_none_or(|idx| {
-                    PlayerId::new(idx) != self.acting_player
-                })
-            }
-            TargetFilter::SpecificPlayer(player) => {
-                entity.as_player_index(player_count).is_some_and(|idx| {
-                    PlayerId::new(idx) == *player
-                })
-            }
-            TargetFilter::InZone(zone) => {
-                state.zones.is_in_zone(entity, *zone)
-            }
-            TargetFilter::HasCardType(_card_type) => {
-                // Requires CardRegistry access which TargetSelector doesn't have.
-                // Return false (conservative) - games should use Custom filters
-                // with registry access for card type filtering.
-                false
-            }
-            TargetFilter::OwnedBy(player) => {
-                state.get_card(entity).is_some_and(|card| {
-                    card.owner == Some(*player)
-                })
-            }
-            TargetFilter::ControlledBy(player) => {
-                state.get_card(entity).is_some_and(|card| {
-                    card.controller == Some(*player)
-                })
-            }
-            TargetFilter::StateInRange { key, min, max } => {
-                if entity.is_player(player_count) {
-                    let idx = entity.as_player_index(player_count).unwrap();
-                    let value = state.public.get_player_state(PlayerId::new(idx), key, 0);
-                    min.is_none_or(|m| value >= m) && max.is_none_or(|m| value <= m)
-                } else if let Some(card) = state.get_card(entity) {
-                    let value = card.get_state(key, 0);
-                    min.is_none_or(|m| value >= m) && max.is_none_or(|m| value <= m)
-                } else {
-                    false
-                }
-            }
-            TargetFilter::NotSource => {
-                self.source_entity != Some(entity)
-            }
-            TargetFilter::Custom(_) => {
-                // Custom filters must be handled by game-specific code
-                true
-            }
+        SpecFilter {
+            selector: self,
+            ctx: PredicateContext::new(state, self.acting_player)
+                .with_source(self.source_entity)
+                .with_cards(self.cards),
+            predicate: EntityPredicate::All(predicates),
+            rest,
         }
     }
 
@@ -317,6 +431,33 @@ impl TargetSelector {
         }
     }
 
+    /// Legal next targets given the ones already chosen.
+    ///
+    /// For building an action's pointers one at a time, as
+    /// `RulesEngine::legal_pointers` does. Targets are chosen in increasing
+    /// entity order, so each set of targets is built exactly one way, like
+    /// the selections of a [`PendingChoice`](super::PendingChoice): returns
+    /// the valid targets after the last one chosen that still leave enough
+    /// for the fewest targets required, and nothing once the most targets
+    /// allowed (capped at what's available) have been chosen.
+    pub fn legal_pointers(&self, state: &GameState, prior: &[EntityId]) -> Vec<EntityId> {
+        let mut valid = self.valid_targets(state);
+        valid.sort_unstable_by_key(|entity| entity.0);
+        let (min, max) = self.spec.bounds(valid.len());
+        choice::next_options(&valid, min, max, prior)
+    }
+
+    /// Check a selection of targets, explaining why it is illegal.
+    ///
+    /// Like [`validate_selection`](Self::validate_selection), but counts
+    /// are capped at what's available (see [`TargetSpec::bounds`]) and
+    /// duplicates are rejected.
+    pub fn check_selection(&self, state: &GameState, selected: &[EntityId]) -> Result<(), ChoiceError> {
+        let valid = self.valid_targets(state);
+        let (min, max) = self.spec.bounds(valid.len());
+        choice::check_selection(&valid, min, max, selected)
+    }
+
     /// Validate a selection of targets.
     pub fn validate_selection(&self, state: &GameState, selected: &[EntityId]) -> bool {
         // Check count
@@ -338,6 +479,36 @@ impl TargetSelector {
     }
 }
 
+/// A [`TargetSelector`]'s filters, ready to test entities.
+struct SpecFilter<'s> {
+    selector: &'s TargetSelector<'s>,
+    ctx: PredicateContext<'s>,
+    predicate: EntityPredicate,
+    /// Filters with no predicate equivalent.
+    rest: Vec<&'s TargetFilter>,
+}
+
+impl SpecFilter<'_> {
+    /// Check if an entity passes all filters.
+    fn passes(&self, entity: EntityId) -> bool {
+        let state = self.ctx.state;
+        self.predicate.matches(&self.ctx, entity)
+            && self.rest.iter().all(|filter| match filter {
+                TargetFilter::TopCards(depth) => state.zones.get_zone(entity).is_some_and(|zone| {
+                    let order = state.zones.cards_in_zone_ordered(zone);
+                    order.iter().rev().take(*depth).any(|&card| card == entity)
+                }),
+                TargetFilter::Custom(name) => self
+                    .selector
+                    .custom
+                    .and_then(|filters| filters.get(name))
+                    .is_some_and(|filter| filter(state, entity, self.selector.acting_player)),
+                // Lowered into the predicate
+                _ => true,
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,6 +585,36 @@ mod tests {
         assert!(!selector.validate_selection(&state, &[EntityId::player_id(1), EntityId::player_id(0)]));
     }
 
+    #[test]
+    fn test_top_cards_filter() {
+        use crate::cards::{CardId, CardInstance};
+
+        let mut state = GameState::new(2, 42);
+        let deck = ZoneId::new(0);
+        state.zones.init_ordered_zone(deck);
+        for i in 0..4 {
+            state.add_card(CardInstance::new(EntityId(10 + i), CardId::new(1), PlayerId::new(0), deck));
+        }
+
+        let spec = TargetSpec::multiple_cards([deck], TargetCount::UpTo(2)).with_filter(TargetFilter::TopCards(2));
+        let targets = TargetSelector::new(spec, PlayerId::new(0)).valid_targets(&state);
+
+        assert_eq!(targets.len(), 2);
+        assert!(targets.contains(&EntityId(12)));
+        assert!(targets.contains(&EntityId(13)));
+    }
+
+    #[test]
+    fn test_spec_bounds() {
+        assert_eq!(TargetSpec::single_player().bounds(3), (1, 1));
+        assert_eq!(TargetSpec::single_player().bounds(0), (0, 0));
+        assert_eq!(TargetSpec::single_player().optional().bounds(3), (0, 1));
+        let zone = ZoneId::new(0);
+        assert_eq!(TargetSpec::multiple_cards([zone], TargetCount::AtLeast(2)).bounds(5), (2, 5));
+        assert_eq!(TargetSpec::multiple_cards([zone], TargetCount::Range { min: 2, max: 4 }).bounds(3), (2, 3));
+        assert_eq!(TargetSpec::multiple_cards([zone], TargetCount::All).bounds(4), (4, 4));
+    }
+
     #[test]
     fn test_optional_targeting() {
         let state = GameState::new(1, 42);
@@ -428,4 +629,168 @@ mod tests {
         assert!(selector.validate_selection(&state, &[]));
         assert!(selector.has_enough_targets(&state));
     }
+
+    #[test]
+    fn test_legal_pointers_and_check_selection() {
+        let state = GameState::new(4, 42);
+        let spec = TargetSpec {
+            count: TargetCount::Exactly(2),
+            ..TargetSpec::single_opponent()
+        };
+        let selector = TargetSelector::new(spec, PlayerId::new(0));
+        let p = EntityId::player_id;
+
+        // Increasing order, leaving room for a second target
+        assert_eq!(selector.legal_pointers(&state, &[]), vec![p(1), p(2)]);
+        assert_eq!(selector.legal_pointers(&state, &[p(1)]), vec![p(2), p(3)]);
+        assert_eq!(selector.legal_pointers(&state, &[p(2)]), vec![p(3)]);
+        assert!(selector.legal_pointers(&state, &[p(1), p(2)]).is_empty());
+
+        let up_to = TargetSelector::new(
+            TargetSpec {
+                count: TargetCount::UpTo(2),
+                ..TargetSpec::single_opponent()
+            },
+            PlayerId::new(0),
+        );
+        assert_eq!(up_to.legal_pointers(&state, &[]), vec![p(1), p(2), p(3)]);
+        assert!(up_to.legal_pointers(&state, &[p(3)]).is_empty());
+
+        assert_eq!(selector.check_selection(&state, &[p(3), p(1)]), Ok(()));
+        assert_eq!(
+            selector.check_selection(&state, &[p(1)]),
+            Err(ChoiceError::WrongCount { min: 2, max: 2, found: 1 })
+        );
+        assert_eq!(
+            selector.check_selection(&state, &[p(1), p(0)]),
+            Err(ChoiceError::NotAnOption(p(0)))
+        );
+        assert_eq!(
+            selector.check_selection(&state, &[p(1), p(1)]),
+            Err(ChoiceError::Duplicate(p(1)))
+        );
+    }
+
+    fn registry_state() -> (GameState, CardRegistry, ZoneId) {
+        use crate::cards::{CardDefinition, CardId, CardInstance};
+
+        let creature = CardTypeId::new(0);
+        let spell = CardTypeId::new(1);
+        let mut cards = CardRegistry::new();
+        cards.register(CardDefinition::new(CardId::new(1), "Goblin", creature).with_attr("power", 2));
+        cards.register(CardDefinition::new(CardId::new(2), "Ogre", creature).with_attr("power", 5));
+        cards.register(CardDefinition::new(CardId::new(3), "Bolt", spell));
+
+        let mut state = GameState::new(2, 42);
+        let battlefield = ZoneId::new(4);
+        for id in 1..=3 {
+            let entity = EntityId(10 + id);
+            state.add_card(CardInstance::new(entity, CardId::new(id), PlayerId::new(1), battlefield));
+        }
+        (state, cards, battlefield)
+    }
+
+    #[test]
+    fn test_registry_filters() {
+        let (state, cards, battlefield) = registry_state();
+        let targets = |filter: TargetFilter| {
+            let spec = TargetSpec::single_card([battlefield]).with_filter(filter);
+            let mut found = TargetSelector::new(spec, PlayerId::new(0)).with_cards(&cards).valid_targets(&state);
+            found.sort_by_key(|entity| entity.0);
+            found
+        };
+
+        assert_eq!(targets(TargetFilter::HasCardType(CardTypeId::new(0))), vec![EntityId(11), EntityId(12)]);
+        assert_eq!(targets(TargetFilter::HasAttribute("power".to_string())), vec![EntityId(11), EntityId(12)]);
+        let small = TargetFilter::AttributeInRange {
+            key: "power".to_string(),
+            min: None,
+            max: Some(3),
+        };
+        assert_eq!(targets(small), vec![EntityId(11)]);
+
+        // Without a registry nothing matches
+        let spec = TargetSpec::single_card([battlefield]).with_filter(TargetFilter::HasCardType(CardTypeId::new(0)));
+        assert!(TargetSelector::new(spec, PlayerId::new(0)).valid_targets(&state).is_empty());
+    }
+
+    #[test]
+    fn test_filters_lower_to_predicates() {
+        let (state, cards, battlefield) = registry_state();
+        let filters = vec![
+            TargetFilter::OwnedBy(PlayerId::new(1)),
+            TargetFilter::AttributeInRange {
+                key: "power".to_string(),
+                min: Some(3),
+                max: None,
+            },
+            TargetFilter::NotSource,
+        ];
+        assert_eq!(
+            filters[0].predicate(),
+            Some(EntityPredicate::OwnedBy(PlayerMatch::Player(PlayerId::new(1))))
+        );
+        assert_eq!(TargetFilter::NotSelf.predicate(), Some(EntityPredicate::IsPlayer(PlayerMatch::You).negate()));
+        assert!(TargetFilter::TopCards(1).predicate().is_none());
+
+        // A filter list selects the same targets as the predicates it lowers to
+        let lowered = EntityPredicate::all(filters.iter().filter_map(TargetFilter::predicate));
+        let targets = |filters: Vec<TargetFilter>| {
+            let spec = TargetSpec {
+                filters,
+                ..TargetSpec::single_card([battlefield])
+            };
+            TargetSelector::new(spec, PlayerId::new(0))
+                .with_cards(&cards)
+                .with_source(EntityId(11))
+                .valid_targets(&state)
+        };
+        assert_eq!(targets(filters), vec![EntityId(12)]);
+        assert_eq!(targets(vec![TargetFilter::Matches(lowered)]), vec![EntityId(12)]);
+    }
+
+    #[test]
+    fn test_custom_filters() {
+        let (state, _, battlefield) = registry_state();
+        let filters = CustomFilters::new()
+            .with("odd", |_: &GameState, entity: EntityId, _: PlayerId| entity.0 % 2 == 1)
+            .with("mine", |state: &GameState, entity: EntityId, player: PlayerId| {
+                state.get_card(entity).is_some_and(|card| card.owner == Some(player))
+            });
+        let targets = |name: &str, player: u8| {
+            let spec = TargetSpec::single_card([battlefield]).with_filter(TargetFilter::Custom(name.to_string()));
+            let mut found = TargetSelector::new(spec, PlayerId::new(player))
+                .with_custom_filters(&filters)
+                .valid_targets(&state);
+            found.sort_by_key(|entity| entity.0);
+            found
+        };
+
+        assert_eq!(targets("odd", 0), vec![EntityId(11), EntityId(13)]);
+        assert!(targets("mine", 0).is_empty());
+        assert_eq!(targets("mine", 1).len(), 3);
+        assert!(targets("unknown", 0).is_empty());
+        assert_eq!(format!("{:?}", filters), r#"CustomFilters(["mine", "odd"])"#);
+    }
+
+    #[test]
+    fn test_any_target_uses_config_zones() {
+        use crate::core::ZoneConfig;
+
+        let (state, _, battlefield) = registry_state();
+        let spec = TargetSpec {
+            target_type: TargetType::Any,
+            filters: vec![TargetFilter::NotSelf],
+            count: TargetCount::Exactly(1),
+            optional: false,
+        };
+        let selector = TargetSelector::new(spec, PlayerId::new(0));
+        assert_eq!(selector.valid_targets(&state), vec![EntityId::player_id(1)]);
+
+        let config = GameConfig::new(2).with_zone(ZoneConfig::new(battlefield, "Battlefield"));
+        let targets = selector.with_config(&config).valid_targets(&state);
+        assert_eq!(targets.len(), 4);
+        assert_eq!(targets[0], EntityId::player_id(1));
+        assert!(targets.contains(&EntityId(12)));
+    }
 }
@@ -0,0 +1,477 @@
+//! Dynamic effect amounts.
+//!
+//! Effect amounts are [`ValueExpr`]s: usually a literal, but they can also
+//! read game state when the effect resolves ("deal damage equal to the
+//! cards in your hand"). References are relative to the effect's target
+//! and its [`EffectOrigin`] (who controls it, what caused it).
+
+use serde::{Deserialize, Serialize};
+
+use crate::cards::CardRegistry;
+use crate::core::{EntityId, GameState, PlayerId, ZoneId};
+use crate::triggers::{GameEvent, TriggeredEffect};
+
+/// Who an effect belongs to and what caused it.
+///
+/// Resolution systems fill this in from the queued action or trigger;
+/// [`ValueExpr`]s read it through [`PlayerRef::Controller`],
+/// [`CardRef::Source`] and the event references.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EffectOrigin {
+    /// The player controlling the effect.
+    pub controller: Option<PlayerId>,
+    /// The card (or other entity) the effect comes from.
+    pub source: Option<EntityId>,
+    /// The event that triggered the effect.
+    pub event: Option<GameEvent>,
+}
+
+impl EffectOrigin {
+    /// Create an empty origin.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an origin for a player's effect.
+    #[must_use]
+    pub fn for_player(controller: PlayerId) -> Self {
+        Self::new().with_controller(controller)
+    }
+
+    /// Create the origin of a fired trigger's effects.
+    #[must_use]
+    pub fn from_triggered(triggered: &TriggeredEffect) -> Self {
+        Self {
+            controller: triggered.controller,
+            source: triggered.source,
+            event: Some(triggered.triggering_event.clone()),
+        }
+    }
+
+    /// Set the controller (builder pattern).
+    #[must_use]
+    pub fn with_controller(mut self, controller: PlayerId) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Set the source entity (builder pattern).
+    #[must_use]
+    pub fn with_source(mut self, source: EntityId) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Set the triggering event (builder pattern).
+    #[must_use]
+    pub fn with_event(mut self, event: GameEvent) -> Self {
+        self.event = Some(event);
+        self
+    }
+}
+
+/// A player, relative to the resolving effect.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlayerRef {
+    /// The target player, or the controller of the target card.
+    Target,
+    /// The effect's controller ("you").
+    Controller,
+    /// The triggering event's player.
+    EventPlayer,
+    /// A specific player.
+    Player(PlayerId),
+}
+
+/// A card, relative to the resolving effect.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CardRef {
+    /// The target card.
+    Target,
+    /// The effect's source.
+    Source,
+    /// The triggering event's target.
+    EventTarget,
+    /// The triggering event's source.
+    EventSource,
+    /// A specific card entity.
+    Entity(EntityId),
+}
+
+/// An integer computed when an effect resolves.
+///
+/// Literals serialize as plain integers, so `"delta": 2` and
+/// `"delta": { "HandSize": "Controller" }` are both valid. References that
+/// can't be resolved (no such player, card or event value) evaluate to 0,
+/// as does division by zero.
+///
+/// ## Example
+///
+/// ```
+/// use rust_ccg::effects::{Effect, PlayerRef, ValueExpr};
+///
+/// // Deal damage equal to the number of cards in your hand, at most 5
+/// let amount = ValueExpr::HandSize(PlayerRef::Controller).min(ValueExpr::from(5));
+/// let effect = Effect::damage(amount);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValueExpr {
+    /// A player state value (0 if unset).
+    PlayerState { player: PlayerRef, key: String },
+
+    /// A turn state value (0 if unset).
+    TurnState(String),
+
+    /// A card state value (0 if unset).
+    CardState { card: CardRef, key: String },
+
+    /// An integer attribute of a card's definition.
+    ///
+    /// Needs a registry on the resolver context (`with_cards`).
+    CardAttribute { card: CardRef, key: String },
+
+    /// Number of cards in a zone.
+    ZoneSize(ZoneId),
+
+    /// Number of cards in a zone owned by a player.
+    CardsInZone { zone: ZoneId, owner: PlayerRef },
+
+    /// Number of cards in a player's hand.
+    HandSize(PlayerRef),
+
+    /// Number of cards in a player's deck (the resolver's deck zone).
+    DeckSize(PlayerRef),
+
+    /// A value of the triggering event.
+    EventValue(usize),
+
+    /// Sum of two values.
+    Add(Box<ValueExpr>, Box<ValueExpr>),
+
+    /// Difference of two values.
+    Sub(Box<ValueExpr>, Box<ValueExpr>),
+
+    /// Product of two values.
+    Mul(Box<ValueExpr>, Box<ValueExpr>),
+
+    /// Quotient of two values, rounded toward zero.
+    Div(Box<ValueExpr>, Box<ValueExpr>),
+
+    /// Negated value.
+    Neg(Box<ValueExpr>),
+
+    /// Smallest of the values (0 if empty).
+    Min(Vec<ValueExpr>),
+
+    /// Largest of the values (0 if empty).
+    Max(Vec<ValueExpr>),
+
+    /// A literal.
+    #[serde(untagged)]
+    Const(i64),
+}
+
+/// What a [`ValueExpr`] is evaluated against.
+pub struct ValueEnv<'a> {
+    /// Current game state.
+    pub state: &'a GameState,
+    /// The effect's target, if any.
+    pub target: Option<EntityId>,
+    /// The effect's origin.
+    pub origin: &'a EffectOrigin,
+    /// Card definitions, for `CardAttribute`.
+    pub cards: Option<&'a CardRegistry>,
+    /// A player's deck zone, for `DeckSize`.
+    pub deck_zone: &'a dyn Fn(PlayerId) -> ZoneId,
+}
+
+impl ValueExpr {
+    /// A player state value.
+    pub fn player_state(player: PlayerRef, key: impl Into<String>) -> Self {
+        Self::PlayerState {
+            player,
+            key: key.into(),
+        }
+    }
+
+    /// A card state value.
+    pub fn card_state(card: CardRef, key: impl Into<String>) -> Self {
+        Self::CardState {
+            card,
+            key: key.into(),
+        }
+    }
+
+    /// An integer attribute of a card's definition.
+    pub fn card_attribute(card: CardRef, key: impl Into<String>) -> Self {
+        Self::CardAttribute {
+            card,
+            key: key.into(),
+        }
+    }
+
+    /// The smaller of this and another value.
+    #[must_use]
+    pub fn min(self, other: ValueExpr) -> Self {
+        Self::Min(vec![self, other])
+    }
+
+    /// The larger of this and another value.
+    #[must_use]
+    pub fn max(self, other: ValueExpr) -> Self {
+        Self::Max(vec![self, other])
+    }
+
+    /// The literal value, if this is a literal.
+    #[must_use]
+    pub fn as_const(&self) -> Option<i64> {
+        match self {
+            Self::Const(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Evaluate against the current game state.
+    #[must_use]
+    pub fn eval(&self, env: &ValueEnv) -> i64 {
+        let state = env.state;
+        match self {
+            Self::Const(value) => *value,
+            Self::PlayerState { player, key } => env
+                .player(player)
+                .map_or(0, |player| state.public.get_player_state(player, key, 0)),
+            Self::TurnState(key) => state.public.get_turn_state(key, 0),
+            Self::CardState { card, key } => env
+                .card(card)
+                .and_then(|card| state.get_card(card))
+                .map_or(0, |card| card.get_state(key, 0)),
+            Self::CardAttribute { card, key } => {
+                let Some(cards) = env.cards else {
+                    return 0;
+                };
+                env.card(card)
+                    .and_then(|card| state.get_card(card))
+                    .and_then(|card| cards.get(card.card_id))
+                    .map_or(0, |definition| definition.get_int(key, 0))
+            }
+            Self::ZoneSize(zone) => state.zones.zone_size(*zone) as i64,
+            Self::CardsInZone { zone, owner } => {
+                let Some(owner) = env.player(owner) else {
+                    return 0;
+                };
+                state
+                    .zones
+                    .cards_in_zone(*zone)
+                    .filter(|&card| state.get_card(card).is_some_and(|card| card.owner == Some(owner)))
+                    .count() as i64
+            }
+            Self::HandSize(player) => env
+                .player(player)
+                .map_or(0, |player| i64::from(state.public.hand_sizes[player])),
+            Self::DeckSize(player) => env
+                .player(player)
+                .map_or(0, |player| state.zones.zone_size((env.deck_zone)(player)) as i64),
+            Self::EventValue(index) => env
+                .origin
+                .event
+                .as_ref()
+                .map_or(0, |event| event.value(*index, 0)),
+            Self::Add(a, b) => a.eval(env).saturating_add(b.eval(env)),
+            Self::Sub(a, b) => a.eval(env).saturating_sub(b.eval(env)),
+            Self::Mul(a, b) => a.eval(env).saturating_mul(b.eval(env)),
+            Self::Div(a, b) => a.eval(env).checked_div(b.eval(env)).unwrap_or(0),
+            Self::Neg(value) => value.eval(env).saturating_neg(),
+            Self::Min(values) => values.iter().map(|value| value.eval(env)).min().unwrap_or(0),
+            Self::Max(values) => values.iter().map(|value| value.eval(env)).max().unwrap_or(0),
+        }
+    }
+}
+
+impl ValueEnv<'_> {
+    /// Resolve a player reference.
+    ///
+    /// Returns `None` if the reference doesn't name a player in the game.
+    #[must_use]
+    pub fn player(&self, player: &PlayerRef) -> Option<PlayerId> {
+        let resolved = match player {
+            PlayerRef::Target => {
+                let target = self.target?;
+                match target.as_player_index(self.state.player_count()) {
+                    Some(index) => PlayerId::new(index),
+                    None => self.state.get_card(target)?.controller?,
+                }
+            }
+            PlayerRef::Controller => self.origin.controller?,
+            PlayerRef::EventPlayer => self.origin.event.as_ref()?.player?,
+            PlayerRef::Player(player) => *player,
+        };
+        (resolved.index() < self.state.player_count()).then_some(resolved)
+    }
+
+    /// Resolve a card reference.
+    #[must_use]
+    pub fn card(&self, card: &CardRef) -> Option<EntityId> {
+        match card {
+            CardRef::Target => self.target,
+            CardRef::Source => self.origin.source,
+            CardRef::EventTarget => self.origin.event.as_ref()?.target,
+            CardRef::EventSource => self.origin.event.as_ref()?.source,
+            CardRef::Entity(entity) => Some(*entity),
+        }
+    }
+}
+
+impl From<i64> for ValueExpr {
+    fn from(value: i64) -> Self {
+        Self::Const(value)
+    }
+}
+
+impl From<i32> for ValueExpr {
+    fn from(value: i32) -> Self {
+        Self::Const(i64::from(value))
+    }
+}
+
+impl From<u32> for ValueExpr {
+    fn from(value: u32) -> Self {
+        Self::Const(i64::from(value))
+    }
+}
+
+impl From<usize> for ValueExpr {
+    fn from(value: usize) -> Self {
+        Self::Const(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl std::ops::Neg for ValueExpr {
+    type Output = ValueExpr;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Self::Const(value) => Self::Const(value.saturating_neg()),
+            Self::Neg(value) => *value,
+            other => Self::Neg(Box::new(other)),
+        }
+    }
+}
+
+impl std::ops::Add for ValueExpr {
+    type Output = ValueExpr;
+
+    fn add(self, other: ValueExpr) -> Self::Output {
+        Self::Add(Box::new(self), Box::new(other))
+    }
+}
+
+impl std::ops::Sub for ValueExpr {
+    type Output = ValueExpr;
+
+    fn sub(self, other: ValueExpr) -> Self::Output {
+        Self::Sub(Box::new(self), Box::new(other))
+    }
+}
+
+impl std::ops::Mul for ValueExpr {
+    type Output = ValueExpr;
+
+    fn mul(self, other: ValueExpr) -> Self::Output {
+        Self::Mul(Box::new(self), Box::new(other))
+    }
+}
+
+impl std::ops::Div for ValueExpr {
+    type Output = ValueExpr;
+
+    fn div(self, other: ValueExpr) -> Self::Output {
+        Self::Div(Box::new(self), Box::new(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{CardDefinition, CardId, CardInstance, CardTypeId};
+    use crate::triggers::EventTypeId;
+
+    fn env<'a>(state: &'a GameState, target: Option<EntityId>, origin: &'a EffectOrigin) -> ValueEnv<'a> {
+        ValueEnv {
+            state,
+            target,
+            origin,
+            cards: None,
+            deck_zone: &|player| ZoneId::new(u16::from(player.0)),
+        }
+    }
+
+    #[test]
+    fn test_state_lookups() {
+        let mut state = GameState::new(2, 42);
+        state.public.set_player_state(PlayerId::new(0), "mana", 4);
+        state.public.set_turn_state("spells", 2);
+        state.public.hand_sizes[PlayerId::new(1)] = 3;
+        let card = CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(1), ZoneId::new(5));
+        state.add_card(card);
+        state.get_card_mut(EntityId(10)).unwrap().set_state("damage", 2);
+
+        let origin = EffectOrigin::for_player(PlayerId::new(0));
+        let env = env(&state, Some(EntityId(10)), &origin);
+
+        assert_eq!(ValueExpr::player_state(PlayerRef::Controller, "mana").eval(&env), 4);
+        assert_eq!(ValueExpr::TurnState("spells".into()).eval(&env), 2);
+        assert_eq!(ValueExpr::card_state(CardRef::Target, "damage").eval(&env), 2);
+        // The target card's controller
+        assert_eq!(ValueExpr::HandSize(PlayerRef::Target).eval(&env), 3);
+        assert_eq!(ValueExpr::ZoneSize(ZoneId::new(5)).eval(&env), 1);
+        let owned = ValueExpr::CardsInZone { zone: ZoneId::new(5), owner: PlayerRef::Controller };
+        assert_eq!(owned.eval(&env), 0);
+        assert_eq!(ValueExpr::HandSize(PlayerRef::Player(PlayerId::new(7))).eval(&env), 0);
+    }
+
+    #[test]
+    fn test_card_attribute_and_event() {
+        let mut state = GameState::new(2, 42);
+        state.add_card(CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(0), ZoneId::new(0)));
+        let mut cards = CardRegistry::new();
+        cards.register(CardDefinition::new(CardId::new(1), "Giant", CardTypeId::new(0)).with_attr("power", 6));
+
+        let event = GameEvent::new(EventTypeId::new(1)).with_source(EntityId(10)).with_value(3);
+        let origin = EffectOrigin::new().with_event(event);
+        let mut env = env(&state, None, &origin);
+        let power = ValueExpr::card_attribute(CardRef::EventSource, "power");
+
+        assert_eq!(power.eval(&env), 0);
+        env.cards = Some(&cards);
+        assert_eq!(power.eval(&env), 6);
+        assert_eq!(ValueExpr::EventValue(0).eval(&env), 3);
+        assert_eq!(ValueExpr::EventValue(4).eval(&env), 0);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let state = GameState::new(2, 42);
+        let origin = EffectOrigin::new();
+        let env = env(&state, None, &origin);
+
+        let value = (ValueExpr::from(7) - ValueExpr::from(1)) * ValueExpr::from(2) / ValueExpr::from(4);
+        assert_eq!(value.eval(&env), 3);
+        assert_eq!((ValueExpr::from(1) / ValueExpr::from(0)).eval(&env), 0);
+        assert_eq!(ValueExpr::from(9).min(ValueExpr::from(4)).eval(&env), 4);
+        assert_eq!(ValueExpr::Max(Vec::new()).eval(&env), 0);
+        assert_eq!(-ValueExpr::from(3), ValueExpr::Const(-3));
+        assert_eq!((-ValueExpr::TurnState("x".into())).eval(&env), 0);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let literal: ValueExpr = serde_json::from_str("2").unwrap();
+        assert_eq!(literal, ValueExpr::Const(2));
+
+        let expr = ValueExpr::HandSize(PlayerRef::Controller) + ValueExpr::from(1);
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!(json, r#"{"Add":[{"HandSize":"Controller"},1]}"#);
+        assert_eq!(serde_json::from_str::<ValueExpr>(&json).unwrap(), expr);
+    }
+}
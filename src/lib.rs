@@ -34,6 +34,9 @@
 //! - `stack`: Resolution systems (immediate and priority-based)
 //! - `mcts`: Monte Carlo Tree Search for AI
 //! - `nn`: Neural network traits and state encoding
+//! - `agents`: Common Agent trait with random, heuristic, search and network players
+//! - `runner`: Game loop driving agents, event stream and replay files
+//! - `data`: Declarative game definitions loaded from JSON/TOML/RON files
 //! - `training`: Self-play and training data collection
 
 pub mod core;
@@ -45,6 +48,9 @@ pub mod triggers;
 pub mod stack;
 pub mod mcts;
 pub mod nn;
+pub mod agents;
+pub mod runner;
+pub mod data;
 pub mod training;
 pub mod games;
 
@@ -66,11 +72,12 @@ pub use crate::zones::{ZoneManager, ZonePosition};
 pub use crate::cards::{
     CardId, CardTypeId, CardDefinition, CardInstance,
     CardRegistry, AttributeKey, AttributeValue, Attributes,
+    AttributeSchema, AttributeSpec, AttributeType, CardError,
 };
 
-pub use crate::rules::{RulesEngine, GameResult};
+pub use crate::rules::{RulesEngine, GameResult, ActionError};
 
-pub use crate::effects::{Effect, EffectBatch, TargetSpec, TargetFilter, TargetSelector, EffectResolver, ResolverContext};
+pub use crate::effects::{Effect, EffectBatch, TargetSpec, TargetFilter, TargetSelector, CustomFilters, EffectResolver, ResolverContext, ResolverEvents, PreventionShield, ValueExpr, PlayerRef, CardRef, EffectOrigin, PendingChoice, ChoiceError, EntityPredicate, PlayerMatch};
 
 pub use crate::triggers::{
     EventTypeId, GameEvent, EventTypeConfig,
@@ -79,16 +86,16 @@ pub use crate::triggers::{
 };
 
 pub use crate::stack::{
-    ResolutionStatus, ResolutionSystem,
+    ResolutionStatus, ResolutionSystem, EventLoop,
     ImmediateResolution,
-    PriorityStack, StackEntry, StackEntryId, StackSource,
+    PriorityStack, StackEntry, StackEntryId, StackSource, PendingTargets,
 };
 
 pub use crate::mcts::{
-    MCTSConfig, MCTSSearch, MCTSTree, MCTSNode, NodeId, Edge,
+    MCTSConfig, MCTSSearch, MCTSTree, MCTSNode, NodeId, Edge, AmafStats,
     SearchStats, TreeStats,
     SelectionPolicy, SimulationPolicy, OpponentPolicy,
-    UCB1, PUCT, RandomSimulation, UniformOpponent,
+    UCB1, PUCT, RAVE, RandomSimulation, UniformOpponent,
 };
 
 pub use crate::nn::{
@@ -98,6 +105,24 @@ pub use crate::nn::{
 };
 
 pub use crate::training::{
-    Step, Trajectory, TrainingSample, ExperienceBuffer,
+    Step, Trajectory, TrainingSample, ExperienceBuffer, ValueTarget,
     SelfPlayConfig, SelfPlayWorker,
+    ReanalyseConfig, ReanalyseSummary, Reanalyser,
+    ReplayConfig, ReplaySample, SampleId, Stratify,
+    StorageConfig, TrajectoryReader, TrajectoryWriter,
+    PermutationSymmetry, SeatRotation, Symmetry,
+    Arena, ArenaConfig, ArenaReport,
 };
+
+pub use crate::agents::{
+    Agent, SearchResult,
+    RandomAgent, GreedyAgent, ScriptedAgent, ReplayAgent,
+    MctsAgent, NetworkMctsAgent, NetworkPolicyAgent,
+};
+
+pub use crate::runner::{
+    EndReason, EventLog, GameObserver, GameOutcome, GameRunner, RunnerConfig, RunnerEvent,
+    Replay, ReplayRecorder, GameHistory, HistoryNodeId,
+};
+
+pub use crate::data::{CardSet, DefinitionError, DefinitionFormat, GameDefinition, LoadedGame};
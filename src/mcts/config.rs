@@ -32,6 +32,14 @@ pub struct MCTSConfig {
     /// Temperature for action selection (0 = greedy, higher = more exploration).
     /// Affects final action selection from root.
     pub temperature: f64,
+
+    /// Collect RAVE/AMAF statistics during backpropagation.
+    /// Required by the `RAVE` selection policy.
+    pub rave: bool,
+
+    /// RAVE equivalence parameter k (default: 1000).
+    /// Number of visits at which Q and AMAF values are weighted equally.
+    pub rave_equivalence: f64,
 }
 
 impl Default for MCTSConfig {
@@ -44,6 +52,8 @@ impl Default for MCTSConfig {
             seed: 42,
             gamma: 1.0,
             temperature: 0.0, // Greedy by default
+            rave: false,
+            rave_equivalence: 1000.0,
         }
     }
 }
@@ -72,6 +82,13 @@ impl MCTSConfig {
         self.temperature = temp;
         self
     }
+
+    /// Create a new config with RAVE enabled and the given equivalence parameter.
+    pub fn with_rave(mut self, equivalence: f64) -> Self {
+        self.rave = true;
+        self.rave_equivalence = equivalence;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -85,6 +102,7 @@ mod tests {
         assert_eq!(config.max_depth, 0);
         assert_eq!(config.seed, 42);
         assert_eq!(config.temperature, 0.0);
+        assert!(!config.rave);
     }
 
     #[test]
@@ -99,6 +117,14 @@ mod tests {
         assert_eq!(config.max_depth, 50);
     }
 
+    #[test]
+    fn test_with_rave() {
+        let config = MCTSConfig::default().with_rave(250.0);
+
+        assert!(config.rave);
+        assert_eq!(config.rave_equivalence, 250.0);
+    }
+
     #[test]
     fn test_serialization() {
         let config = MCTSConfig::default();
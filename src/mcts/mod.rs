@@ -8,7 +8,7 @@
 //! - **Public-State MCTS**: Nodes only expand on the searching player's turns
 //! - **Opponent Modeling**: Opponent actions sampled from configurable policies
 //! - **N-Player Support**: Works with any number of players
-//! - **Configurable Policies**: Selection (UCB1/PUCT), simulation, opponent
+//! - **Configurable Policies**: Selection (UCB1/PUCT/RAVE), simulation, opponent
 //! - **Serializable**: Tree and config can be saved/loaded
 //!
 //! ## Usage
@@ -47,6 +47,16 @@
 //! let search = MCTSSearch::new(engine, config)
 //!     .with_selection(PUCT);  // Use PUCT instead of UCB1
 //! ```
+//!
+//! RAVE blends Q with all-moves-as-first statistics, which helps when an
+//! action's value is largely independent of when it is played:
+//!
+//! ```rust,ignore
+//! use rust_ccg::mcts::{MCTSSearch, MCTSConfig, RAVE};
+//!
+//! let config = MCTSConfig::default().with_rave(1000.0);
+//! let search = MCTSSearch::new(engine, config).with_selection(RAVE);
+//! ```
 
 pub mod config;
 pub mod node;
@@ -57,10 +67,10 @@ pub mod tree;
 
 // Re-export main types
 pub use config::MCTSConfig;
-pub use node::{Edge, MCTSNode, NodeId};
+pub use node::{AmafStats, Edge, MCTSNode, NodeId};
 pub use policy::{
     OpponentPolicy, RandomSimulation, SelectionPolicy, SimulationPolicy,
-    UCB1, PUCT, UniformOpponent,
+    UCB1, PUCT, RAVE, UniformOpponent,
 };
 pub use search::MCTSSearch;
 pub use stats::SearchStats;
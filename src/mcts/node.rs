@@ -47,6 +47,47 @@ impl std::fmt::Display for NodeId {
     }
 }
 
+/// All-moves-as-first (AMAF) statistics for RAVE.
+///
+/// Counts every simulation in which the edge's action was played by the
+/// node's player at any later point, not only when it was played first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AmafStats {
+    /// Number of simulations in which the action appeared.
+    pub visits: u32,
+
+    /// Total reward accumulated over those simulations (per player).
+    pub total_reward: PlayerMap<f64>,
+}
+
+impl AmafStats {
+    /// Create empty AMAF statistics.
+    pub fn new(player_count: usize) -> Self {
+        Self {
+            visits: 0,
+            total_reward: PlayerMap::with_value(player_count, 0.0),
+        }
+    }
+
+    /// Record one simulation's rewards.
+    pub fn record(&mut self, rewards: &PlayerMap<f64>) {
+        self.visits += 1;
+        for (player, reward) in rewards.iter() {
+            self.total_reward[player] += reward;
+        }
+    }
+
+    /// Get the mean AMAF reward for a player.
+    #[must_use]
+    pub fn mean_reward(&self, player: PlayerId) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total_reward[player] / self.visits as f64
+        }
+    }
+}
+
 /// Edge representing an action from a parent node to a child.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Edge {
@@ -65,6 +106,10 @@ pub struct Edge {
     /// Prior probability from policy network (for PUCT).
     /// Default is 1.0 for uniform prior.
     pub prior: f32,
+
+    /// AMAF statistics (only populated when RAVE is enabled).
+    #[serde(default)]
+    pub amaf: Option<AmafStats>,
 }
 
 impl Edge {
@@ -76,6 +121,7 @@ impl Edge {
             visits: 0,
             total_reward: PlayerMap::with_value(player_count, 0.0),
             prior: 1.0,
+            amaf: None,
         }
     }
 
@@ -87,6 +133,7 @@ impl Edge {
             visits: 0,
             total_reward: PlayerMap::with_value(player_count, 0.0),
             prior,
+            amaf: None,
         }
     }
 
@@ -100,6 +147,26 @@ impl Edge {
         }
     }
 
+    /// Get the number of AMAF visits (0 if RAVE statistics are absent).
+    #[must_use]
+    pub fn amaf_visits(&self) -> u32 {
+        self.amaf.as_ref().map_or(0, |a| a.visits)
+    }
+
+    /// Get the mean AMAF reward for a player (0 if no AMAF visits).
+    #[must_use]
+    pub fn amaf_mean_reward(&self, player: PlayerId) -> f64 {
+        self.amaf.as_ref().map_or(0.0, |a| a.mean_reward(player))
+    }
+
+    /// Record a simulation in this edge's AMAF statistics.
+    pub fn record_amaf(&mut self, rewards: &PlayerMap<f64>) {
+        let player_count = rewards.player_count();
+        self.amaf
+            .get_or_insert_with(|| AmafStats::new(player_count))
+            .record(rewards);
+    }
+
     /// Check if this edge has been expanded (child exists).
     #[must_use]
     pub fn is_expanded(&self) -> bool {
@@ -236,6 +303,28 @@ mod tests {
         assert_eq!(edge.mean_reward(PlayerId::new(1)), 0.25);
     }
 
+    #[test]
+    fn test_edge_amaf() {
+        let action = Action::new(TemplateId::new(1));
+        let mut edge = Edge::new(action, 2);
+
+        assert!(edge.amaf.is_none());
+        assert_eq!(edge.amaf_visits(), 0);
+        assert_eq!(edge.amaf_mean_reward(PlayerId::new(0)), 0.0);
+
+        let mut rewards = PlayerMap::with_value(2, 0.0);
+        rewards[PlayerId::new(0)] = 1.0;
+        edge.record_amaf(&rewards);
+        edge.record_amaf(&PlayerMap::with_value(2, 0.5));
+
+        assert_eq!(edge.amaf_visits(), 2);
+        assert_eq!(edge.amaf_mean_reward(PlayerId::new(0)), 0.75);
+        assert_eq!(edge.amaf_mean_reward(PlayerId::new(1)), 0.25);
+
+        // Regular statistics are untouched
+        assert_eq!(edge.visits, 0);
+    }
+
     #[test]
     fn test_node_root() {
         let node = MCTSNode::root(PlayerId::new(0));
@@ -1,7 +1,7 @@
 //! MCTS policies for selection, simulation, and opponent modeling.
 //!
 //! Policies are trait-based to allow customization:
-//! - `SelectionPolicy`: How to choose which child to explore (UCB1, PUCT)
+//! - `SelectionPolicy`: How to choose which child to explore (UCB1, PUCT, RAVE)
 //! - `SimulationPolicy`: How to run rollouts (random, heuristic, neural)
 //! - `OpponentPolicy`: How to model opponent behavior
 
@@ -88,6 +88,60 @@ impl SelectionPolicy for PUCT {
     }
 }
 
+/// RAVE selection policy (Rapid Action Value Estimation).
+///
+/// Blends each edge's Q with its AMAF value using the schedule
+/// beta = sqrt(k / (3 * n(a) + k)), where k is `MCTSConfig::rave_equivalence`.
+/// Formula: (1 - beta) * Q(a) + beta * AMAF(a) + c * sqrt(ln(N) / n(a))
+///
+/// Requires `MCTSConfig::rave` so the search collects AMAF statistics;
+/// without them this behaves like UCB1.
+#[derive(Clone, Debug, Default)]
+pub struct RAVE;
+
+impl RAVE {
+    /// Weight given to the AMAF value for an edge with `visits` visits.
+    #[must_use]
+    pub fn beta(visits: u32, equivalence: f64) -> f64 {
+        if equivalence <= 0.0 {
+            return 0.0;
+        }
+        (equivalence / (3.0 * visits as f64 + equivalence)).sqrt()
+    }
+}
+
+impl SelectionPolicy for RAVE {
+    fn select(&self, node: &MCTSNode, player: PlayerId, config: &MCTSConfig) -> usize {
+        if node.edges.is_empty() {
+            return 0;
+        }
+
+        let ln_parent = (node.visits.max(1) as f64).ln();
+
+        node.edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge)| {
+                let q = edge.mean_reward(player);
+                let value = if edge.amaf_visits() == 0 {
+                    q
+                } else {
+                    let beta = Self::beta(edge.visits, config.rave_equivalence);
+                    (1.0 - beta) * q + beta * edge.amaf_mean_reward(player)
+                };
+                let exploration = if edge.visits == 0 {
+                    f64::INFINITY
+                } else {
+                    config.exploration_constant * (ln_parent / edge.visits as f64).sqrt()
+                };
+                (i, value + exploration)
+            })
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+}
+
 // =============================================================================
 // Simulation Policy
 // =============================================================================
@@ -104,6 +158,23 @@ pub trait SimulationPolicy<E: RulesEngine>: Send + Sync {
         rng: &mut GameRng,
         max_depth: u32,
     ) -> PlayerMap<f64>;
+
+    /// Run a simulation, appending each action played to `played`.
+    ///
+    /// Used by RAVE to collect the rollout's action sequence. The default
+    /// implementation records nothing, so only tree actions contribute to
+    /// AMAF statistics for policies that don't override it.
+    fn simulate_recorded(
+        &self,
+        engine: &mut E,
+        state: &mut GameState,
+        rng: &mut GameRng,
+        max_depth: u32,
+        played: &mut Vec<(PlayerId, Action)>,
+    ) -> PlayerMap<f64> {
+        let _ = played;
+        self.simulate(engine, state, rng, max_depth)
+    }
 }
 
 /// Random simulation policy.
@@ -112,13 +183,13 @@ pub trait SimulationPolicy<E: RulesEngine>: Send + Sync {
 #[derive(Clone, Debug, Default)]
 pub struct RandomSimulation;
 
-impl<E: RulesEngine> SimulationPolicy<E> for RandomSimulation {
-    fn simulate(
-        &self,
+impl RandomSimulation {
+    fn rollout<E: RulesEngine>(
         engine: &mut E,
         state: &mut GameState,
         rng: &mut GameRng,
         max_depth: u32,
+        mut played: Option<&mut Vec<(PlayerId, Action)>>,
     ) -> PlayerMap<f64> {
         let player_count = state.player_count();
         let mut depth = 0;
@@ -134,7 +205,7 @@ impl<E: RulesEngine> SimulationPolicy<E> for RandomSimulation {
                 return heuristic_eval(state, player_count);
             }
 
-            let active = state.public.active_player;
+            let active = engine.acting_player(state);
             let actions = engine.legal_actions(state, active);
 
             if actions.is_empty() {
@@ -146,11 +217,38 @@ impl<E: RulesEngine> SimulationPolicy<E> for RandomSimulation {
             let idx = rng.gen_range_usize(0..actions.len());
             engine.apply_action(state, active, &actions[idx]);
 
+            if let Some(played) = played.as_deref_mut() {
+                played.push((active, actions[idx].clone()));
+            }
+
             depth += 1;
         }
     }
 }
 
+impl<E: RulesEngine> SimulationPolicy<E> for RandomSimulation {
+    fn simulate(
+        &self,
+        engine: &mut E,
+        state: &mut GameState,
+        rng: &mut GameRng,
+        max_depth: u32,
+    ) -> PlayerMap<f64> {
+        Self::rollout(engine, state, rng, max_depth, None)
+    }
+
+    fn simulate_recorded(
+        &self,
+        engine: &mut E,
+        state: &mut GameState,
+        rng: &mut GameRng,
+        max_depth: u32,
+        played: &mut Vec<(PlayerId, Action)>,
+    ) -> PlayerMap<f64> {
+        Self::rollout(engine, state, rng, max_depth, Some(played))
+    }
+}
+
 // =============================================================================
 // Opponent Policy
 // =============================================================================
@@ -316,6 +414,54 @@ mod tests {
         assert_eq!(selected, 1);
     }
 
+    #[test]
+    fn test_rave_beta_schedule() {
+        assert_eq!(RAVE::beta(0, 1000.0), 1.0);
+        assert!(RAVE::beta(10, 1000.0) > RAVE::beta(1000, 1000.0));
+        assert!((RAVE::beta(1000, 1000.0) - 0.5).abs() < 0.001);
+        assert_eq!(RAVE::beta(10, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_rave_uses_amaf() {
+        let mut node = MCTSNode::root(PlayerId::new(0));
+
+        // Equal Q and visits, but edge 1 has much better AMAF statistics
+        let mut e0 = Edge::new(Action::new(TemplateId::new(1)), 2);
+        e0.visits = 10;
+        e0.total_reward[PlayerId::new(0)] = 5.0;
+        for _ in 0..50 {
+            e0.record_amaf(&PlayerMap::with_value(2, 0.2));
+        }
+
+        let mut e1 = Edge::new(Action::new(TemplateId::new(2)), 2);
+        e1.visits = 10;
+        e1.total_reward[PlayerId::new(0)] = 5.0;
+        for _ in 0..50 {
+            e1.record_amaf(&PlayerMap::with_value(2, 0.9));
+        }
+
+        node.edges.push(e0);
+        node.edges.push(e1);
+        node.visits = 20;
+
+        let config = MCTSConfig::default().with_rave(1000.0);
+        assert_eq!(RAVE.select(&node, PlayerId::new(0), &config), 1);
+    }
+
+    #[test]
+    fn test_rave_without_amaf_matches_ucb1() {
+        let mut node = make_test_node();
+        node.edges[2].visits = 5;
+        node.edges[2].total_reward[PlayerId::new(0)] = 2.0;
+
+        let config = MCTSConfig::default();
+        assert_eq!(
+            RAVE.select(&node, PlayerId::new(0), &config),
+            UCB1.select(&node, PlayerId::new(0), &config)
+        );
+    }
+
     #[test]
     fn test_result_to_rewards_winner() {
         let result = GameResult::Winner(PlayerId::new(1));
@@ -95,17 +95,47 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
         state: &mut GameState,
         player: PlayerId,
         iterations: u32,
+    ) -> Option<Action> {
+        self.run_search(state, player, iterations, None)
+    }
+
+    /// Run MCTS search with prior probabilities on the root edges.
+    ///
+    /// Priors are applied after the root is expanded and before any
+    /// iterations run, so a prior-aware selection policy (PUCT) sees them
+    /// from the first iteration. Root actions missing from `priors` keep
+    /// the default prior of 1.0.
+    pub fn search_with_priors(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        iterations: u32,
+        priors: &[(Action, f32)],
+    ) -> Option<Action> {
+        self.run_search(state, player, iterations, Some(priors))
+    }
+
+    fn run_search(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        iterations: u32,
+        priors: Option<&[(Action, f32)]>,
     ) -> Option<Action> {
         let start = Instant::now();
         self.stats.reset();
 
         // Initialize tree with root
-        self.tree.reset(state.public.active_player);
+        self.tree.reset(self.engine.acting_player(state));
 
         // Expand root node
         let root = self.tree.root();
         self.expand_node(root, state);
 
+        if let Some(priors) = priors {
+            self.set_root_priors(priors);
+        }
+
         // Check for terminal root
         if self.tree.get(root).is_terminal {
             return None;
@@ -147,7 +177,7 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
             // Terminal node
             if node.is_terminal {
                 if let Some(ref rewards) = node.terminal_reward {
-                    self.backpropagate(&path, rewards.clone());
+                    self.backpropagate(&path, rewards.clone(), &[]);
                 }
                 return;
             }
@@ -155,7 +185,7 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
             // Depth limit
             if self.config.max_depth > 0 && node.depth >= self.config.max_depth as u16 {
                 let rewards = super::policy::heuristic_eval(state, self.tree.player_count());
-                self.backpropagate(&path, rewards);
+                self.backpropagate(&path, rewards, &[]);
                 return;
             }
 
@@ -179,7 +209,7 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
                 } else {
                     // No legal moves - this is effectively terminal
                     let rewards = PlayerMap::with_value(self.tree.player_count(), 0.5);
-                    self.backpropagate(&path, rewards);
+                    self.backpropagate(&path, rewards, &[]);
                     return;
                 }
             }
@@ -203,9 +233,9 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
                 let _child = self.expand_child(current, edge_idx, state);
 
                 // Simulate from this state
-                let rewards = self.simulate(state);
+                let (rewards, played) = self.simulate(state);
                 self.stats.simulations += 1;
-                self.backpropagate(&path, rewards);
+                self.backpropagate(&path, rewards, &played);
                 return;
             }
 
@@ -223,9 +253,9 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
             if child.is_none() {
                 // Should not happen if is_fully_expanded, but handle gracefully
                 let _child = self.expand_child(current, edge_idx, state);
-                let rewards = self.simulate(state);
+                let (rewards, played) = self.simulate(state);
                 self.stats.simulations += 1;
-                self.backpropagate(&path, rewards);
+                self.backpropagate(&path, rewards, &played);
                 return;
             }
 
@@ -278,7 +308,7 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
     fn expand_child(&mut self, parent_id: NodeId, edge_idx: usize, state: &GameState) -> NodeId {
         let parent = self.tree.get(parent_id);
         let depth = parent.depth + 1;
-        let to_move = state.public.active_player;
+        let to_move = self.engine.acting_player(state);
 
         // Track max depth
         if depth > self.stats.max_depth {
@@ -328,21 +358,49 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
     }
 
     /// Run a simulation from the current state.
-    fn simulate(&mut self, state: &mut GameState) -> PlayerMap<f64> {
+    ///
+    /// Returns the rewards and, when RAVE is enabled, the rollout's actions.
+    fn simulate(&mut self, state: &mut GameState) -> (PlayerMap<f64>, Vec<(PlayerId, Action)>) {
         let mut sim_rng = self.rng.fork();
         let mut engine = self.engine.clone();
-        self.simulation.simulate(
-            &mut engine,
-            state,
-            &mut sim_rng,
-            self.config.max_depth,
-        )
+        let mut played = Vec::new();
+
+        let rewards = if self.config.rave {
+            self.simulation.simulate_recorded(
+                &mut engine,
+                state,
+                &mut sim_rng,
+                self.config.max_depth,
+                &mut played,
+            )
+        } else {
+            self.simulation.simulate(
+                &mut engine,
+                state,
+                &mut sim_rng,
+                self.config.max_depth,
+            )
+        };
+
+        (rewards, played)
     }
 
     /// Backpropagate rewards through the path.
-    fn backpropagate(&mut self, path: &[(NodeId, usize)], rewards: PlayerMap<f64>) {
+    ///
+    /// `rollout` holds the actions played after leaving the tree; together
+    /// with the path's own actions it drives AMAF updates when RAVE is enabled.
+    fn backpropagate(
+        &mut self,
+        path: &[(NodeId, usize)],
+        rewards: PlayerMap<f64>,
+        rollout: &[(PlayerId, Action)],
+    ) {
         let player_count = self.tree.player_count();
 
+        if self.config.rave {
+            self.update_amaf(path, &rewards, rollout);
+        }
+
         for &(node_id, edge_idx) in path.iter().rev() {
             let node = self.tree.get_mut(node_id);
             node.visits += 1;
@@ -359,6 +417,41 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
         self.tree.root_node_mut().visits += 1;
     }
 
+    /// Update AMAF statistics along the path.
+    ///
+    /// For each node on the path, every edge whose action was played by the
+    /// node's player at or after that node in the simulation is credited once.
+    fn update_amaf(
+        &mut self,
+        path: &[(NodeId, usize)],
+        rewards: &PlayerMap<f64>,
+        rollout: &[(PlayerId, Action)],
+    ) {
+        let tree_actions: Vec<(PlayerId, Action)> = path
+            .iter()
+            .map(|&(node_id, edge_idx)| {
+                let node = self.tree.get(node_id);
+                (node.to_move, node.edges[edge_idx].action.clone())
+            })
+            .collect();
+
+        for (depth, &(node_id, _)) in path.iter().enumerate() {
+            let node = self.tree.get_mut(node_id);
+            let to_move = node.to_move;
+            let subsequent = &tree_actions[depth..];
+
+            for edge in node.edges.iter_mut() {
+                let played = subsequent
+                    .iter()
+                    .chain(rollout.iter())
+                    .any(|(player, action)| *player == to_move && *action == edge.action);
+                if played {
+                    edge.record_amaf(rewards);
+                }
+            }
+        }
+    }
+
     /// Select the best action from the root.
     fn best_action(&self, _player: PlayerId) -> Option<Action> {
         let root = self.tree.root_node();
@@ -430,6 +523,24 @@ impl<E: RulesEngine + Clone> MCTSSearch<E> {
             .collect()
     }
 
+    /// Get the root value estimate (per player) from the last search.
+    ///
+    /// This is the visit-weighted mean reward over the root edges.
+    /// Returns `None` if no iterations reached the root's children
+    /// (terminal root, single legal action, or zero iterations).
+    pub fn root_value(&self) -> Option<PlayerMap<f64>> {
+        let root = self.tree.root_node();
+        let total: u32 = root.edges.iter().map(|e| e.visits).sum();
+
+        if total == 0 {
+            return None;
+        }
+
+        Some(PlayerMap::new(self.tree.player_count(), |player| {
+            root.edges.iter().map(|e| e.total_reward[player]).sum::<f64>() / total as f64
+        }))
+    }
+
     /// Get the engine reference.
     pub fn engine(&self) -> &E {
         &self.engine
@@ -722,6 +833,60 @@ mod tests {
         assert!(priors.is_empty());
     }
 
+    #[test]
+    fn test_search_with_priors() {
+        use crate::mcts::PUCT;
+
+        let engine = TestEngine::new(2).terminal_after(10);
+        let mut state = GameState::new(2, 42);
+        let config = MCTSConfig::default();
+
+        let priors = vec![
+            (Action::new(TemplateId::new(0)), 0.1),
+            (Action::new(TemplateId::new(1)), 0.1),
+            (Action::new(TemplateId::new(2)), 0.8),
+        ];
+
+        let mut search = MCTSSearch::new(engine, config).with_selection(PUCT);
+        search.search_with_priors(&mut state, PlayerId::new(0), 20, &priors);
+
+        // Priors survive the search instead of being reset with the tree
+        let root_priors = search.root_priors();
+        assert_eq!(root_priors.len(), 3);
+        for (action, prior) in &root_priors {
+            let expected = priors.iter().find(|(a, _)| a == action).map(|(_, p)| *p);
+            assert_eq!(Some(*prior), expected);
+        }
+    }
+
+    #[test]
+    fn test_root_value() {
+        let engine = AdversarialEngine::new();
+        let mut state = GameState::new(2, 42);
+        let config = MCTSConfig::default();
+
+        let mut search = MCTSSearch::new(engine, config);
+        search.search(&mut state, PlayerId::new(0), 100);
+
+        let value = search.root_value().unwrap();
+
+        // Player 0 wins whenever action 0 is explored, which dominates the visits
+        assert!(value[PlayerId::new(0)] > 0.5);
+        assert!((value[PlayerId::new(0)] + value[PlayerId::new(1)] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_root_value_without_iterations() {
+        let engine = TestEngine::new(2).terminal_after(0);
+        let mut state = GameState::new(2, 42);
+        let config = MCTSConfig::default();
+
+        let mut search = MCTSSearch::new(engine, config);
+        search.search(&mut state, PlayerId::new(0), 10);
+
+        assert!(search.root_value().is_none());
+    }
+
     #[test]
     fn test_action_visits() {
         let engine = TestEngine::new(2).terminal_after(10);
@@ -797,6 +962,56 @@ mod tests {
         assert!(action.is_some());
     }
 
+    #[test]
+    fn test_search_collects_amaf_with_rave() {
+        use crate::mcts::RAVE;
+
+        let engine = TestEngine::new(2).terminal_after(10);
+        let mut state = GameState::new(2, 42);
+        let config = MCTSConfig::default().with_rave(100.0);
+
+        let mut search = MCTSSearch::new(engine, config).with_selection(RAVE);
+        let action = search.search(&mut state, PlayerId::new(0), 100);
+        assert!(action.is_some());
+
+        // Every root action recurs later in rollouts, so AMAF counts exceed visits
+        let root = search.tree().root_node();
+        for edge in &root.edges {
+            assert!(edge.amaf_visits() >= edge.visits);
+        }
+        let amaf_total: u32 = root.edges.iter().map(|e| e.amaf_visits()).sum();
+        let visit_total: u32 = root.edges.iter().map(|e| e.visits).sum();
+        assert!(amaf_total > visit_total);
+    }
+
+    #[test]
+    fn test_search_without_rave_has_no_amaf() {
+        let engine = TestEngine::new(2).terminal_after(10);
+        let mut state = GameState::new(2, 42);
+        let config = MCTSConfig::default();
+
+        let mut search = MCTSSearch::new(engine, config);
+        search.search(&mut state, PlayerId::new(0), 50);
+
+        for (_, node) in search.tree().iter() {
+            assert!(node.edges.iter().all(|e| e.amaf.is_none()));
+        }
+    }
+
+    #[test]
+    fn test_rave_finds_winning_move() {
+        use crate::mcts::RAVE;
+
+        let engine = AdversarialEngine::new();
+        let mut state = GameState::new(2, 42);
+        let config = MCTSConfig::default().with_rave(100.0);
+
+        let mut search = MCTSSearch::new(engine, config).with_selection(RAVE);
+        let action = search.search(&mut state, PlayerId::new(0), 100);
+
+        assert_eq!(action, Some(Action::new(TemplateId::new(0))));
+    }
+
     #[test]
     fn test_search_four_player() {
         let engine = TestEngine::new(4).terminal_after(20);
@@ -77,7 +77,10 @@ impl SimpleGameEncoder {
     }
 
     /// Get the number of features per player.
-    fn features_per_player(&self) -> usize {
+    ///
+    /// Features are laid out as one block per seat, so the encoding works
+    /// with [`SeatRotation`](crate::training::SeatRotation).
+    pub fn features_per_player(&self) -> usize {
         5 // life, hand_size, deck_size, is_active, is_perspective
     }
 }
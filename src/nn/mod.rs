@@ -30,6 +30,6 @@ pub mod traits;
 // Re-export main types
 pub use encoder::{SimpleGameEncoder, StateEncoder, ZeroEncoder};
 pub use traits::{
-    EncodedState, PolicyNetwork, PolicyValueNetwork, UniformPolicy, UniformPolicyZeroValue,
-    ValueNetwork, ZeroValue,
+    policy_to_priors, EncodedState, PolicyNetwork, PolicyValueNetwork, UniformPolicy,
+    UniformPolicyZeroValue, ValueNetwork, ZeroValue,
 };
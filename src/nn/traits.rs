@@ -5,6 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::Action;
+
 /// Encoded game state as a flat tensor for neural network input.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EncodedState {
@@ -110,6 +112,32 @@ pub trait PolicyValueNetwork: Send + Sync {
     }
 }
 
+/// Map a network policy vector onto a list of legal actions.
+///
+/// Policy index `i` corresponds to `actions[i]`, matching the ordering used
+/// by `TrainingSample::policy`. Missing or negative entries count as zero,
+/// and the result is renormalized over the legal actions (uniform if the
+/// network assigns them no mass).
+pub fn policy_to_priors(actions: &[Action], policy: &[f32]) -> Vec<(Action, f32)> {
+    let weights: Vec<f32> = (0..actions.len())
+        .map(|i| policy.get(i).copied().unwrap_or(0.0).max(0.0))
+        .collect();
+    let total: f32 = weights.iter().sum();
+
+    actions
+        .iter()
+        .zip(weights)
+        .map(|(action, w)| {
+            let prior = if total > 0.0 {
+                w / total
+            } else {
+                1.0 / actions.len() as f32
+            };
+            (action.clone(), prior)
+        })
+        .collect()
+}
+
 /// Uniform random policy (baseline for testing).
 #[derive(Clone, Debug, Default)]
 pub struct UniformPolicy {
@@ -210,6 +238,23 @@ mod tests {
         assert_eq!(state.tensor, vec![0.0, 0.0, 5.0, 0.0]);
     }
 
+    #[test]
+    fn test_policy_to_priors() {
+        use crate::core::TemplateId;
+
+        let actions: Vec<Action> = (0..3).map(|i| Action::new(TemplateId::new(i))).collect();
+
+        // Extra policy entries beyond the legal actions are ignored
+        let priors = policy_to_priors(&actions, &[0.2, 0.2, 0.4, 0.2]);
+        assert_eq!(priors.len(), 3);
+        assert!((priors[0].1 - 0.25).abs() < 1e-6);
+        assert!((priors[2].1 - 0.5).abs() < 1e-6);
+
+        // Short or empty policy falls back to uniform
+        let priors = policy_to_priors(&actions, &[]);
+        assert!(priors.iter().all(|(_, p)| (p - 1.0 / 3.0).abs() < 1e-6));
+    }
+
     #[test]
     fn test_uniform_policy() {
         let policy = UniformPolicy::new(4);
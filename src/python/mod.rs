@@ -22,12 +22,14 @@
 
 use pyo3::prelude::*;
 
+mod py_agents;
 mod py_core;
 mod py_games;
 mod py_nn;
 mod py_self_play;
 mod py_training;
 
+pub use py_agents::*;
 pub use py_core::*;
 pub use py_games::*;
 pub use py_nn::*;
@@ -41,6 +43,7 @@ pub use py_training::*;
 /// - MCTS self-play infrastructure
 /// - Training data collection and buffering
 /// - Neural network integration via Python callbacks
+/// - Agents (random, MCTS, network, callback) for playing games
 #[pymodule]
 fn rust_ccg(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Core types
@@ -61,6 +64,9 @@ fn rust_ccg(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyExperienceBuffer>()?;
     m.add_class::<PyTrajectoryIterator>()?;
 
+    // Agents
+    m.add_class::<PyAgent>()?;
+
     // Self-play
     m.add_class::<PySelfPlayConfig>()?;
     m.add_class::<PySimpleGameWorker>()?;
@@ -0,0 +1,179 @@
+//! Agent bindings for Python.
+
+use std::sync::{Arc, Mutex};
+
+use pyo3::prelude::*;
+
+use crate::agents::{
+    Agent, MctsAgent, NetworkMctsAgent, NetworkPolicyAgent, RandomAgent, ScriptedAgent, SearchResult,
+};
+use crate::core::{Action, GameState, PlayerId, PlayerMap};
+use crate::games::simple::SimpleGame;
+use crate::nn::SimpleGameEncoder;
+
+use super::py_core::{PyAction, PyPlayerId};
+use super::py_nn::PyPolicyValueNetwork;
+
+/// A SimpleGame agent shared between Python handles.
+///
+/// The same agent may sit in several seats, so it is locked per call
+/// rather than borrowed for the whole game.
+#[derive(Clone)]
+pub(crate) struct SharedAgent {
+    name: String,
+    inner: Arc<Mutex<Box<dyn Agent<SimpleGame>>>>,
+}
+
+impl SharedAgent {
+    fn new(agent: impl Agent<SimpleGame> + 'static) -> Self {
+        Self {
+            name: agent.name().to_string(),
+            inner: Arc::new(Mutex::new(Box::new(agent))),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Box<dyn Agent<SimpleGame>>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Agent<SimpleGame> for SharedAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn new_game(&mut self) {
+        self.lock().new_game();
+    }
+
+    fn select_action(
+        &mut self,
+        engine: &SimpleGame,
+        state: &GameState,
+        player: PlayerId,
+        seed: u64,
+    ) -> Option<Action> {
+        self.lock().select_action(engine, state, player, seed)
+    }
+
+    fn observe_action(&mut self, state: &GameState, player: PlayerId, action: &Action) {
+        self.lock().observe_action(state, player, action);
+    }
+
+    fn game_over(&mut self, state: &GameState, rewards: &PlayerMap<f64>) {
+        self.lock().game_over(state, rewards);
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        self.lock().search_result()
+    }
+}
+
+/// An agent that can play SimpleGame seats.
+///
+/// Create one with a static constructor (`random`, `mcts`,
+/// `network_policy`, `network_mcts`, `callback`) and pass it to
+/// `SimpleGame.select_action` or `SimpleGameWorker.play_game_with_agents`.
+#[pyclass(name = "Agent")]
+#[derive(Clone)]
+pub struct PyAgent(pub(crate) SharedAgent);
+
+#[pymethods]
+impl PyAgent {
+    /// Agent that plays uniformly random legal actions.
+    #[staticmethod]
+    #[pyo3(signature = (name = "random".to_string()))]
+    fn random(name: String) -> Self {
+        Self(SharedAgent::new(RandomAgent::new(name)))
+    }
+
+    /// Agent that plays the most-visited action of a pure MCTS search.
+    #[staticmethod]
+    #[pyo3(signature = (iterations = 100, name = "mcts".to_string()))]
+    fn mcts(iterations: u32, name: String) -> Self {
+        Self(SharedAgent::new(MctsAgent::new(name, iterations)))
+    }
+
+    /// Agent that plays from a network's policy without search.
+    ///
+    /// Temperature 0 plays the highest-prior action; higher values sample.
+    #[staticmethod]
+    #[pyo3(signature = (network, temperature = 0.0, name = "policy".to_string()))]
+    fn network_policy(
+        py: Python<'_>,
+        network: &PyPolicyValueNetwork,
+        temperature: f64,
+        name: String,
+    ) -> Self {
+        let encoder = Box::new(SimpleGameEncoder::new(
+            network.player_count(),
+            network.action_space_size(),
+        ));
+        let agent = NetworkPolicyAgent::new(name, network.clone_ref(py), encoder)
+            .with_temperature(temperature);
+        Self(SharedAgent::new(agent))
+    }
+
+    /// Agent that runs PUCT search guided by a network's priors.
+    #[staticmethod]
+    #[pyo3(signature = (network, iterations = 100, name = "network_mcts".to_string()))]
+    fn network_mcts(
+        py: Python<'_>,
+        network: &PyPolicyValueNetwork,
+        iterations: u32,
+        name: String,
+    ) -> Self {
+        let encoder = Box::new(SimpleGameEncoder::new(
+            network.player_count(),
+            network.action_space_size(),
+        ));
+        let agent = NetworkMctsAgent::new(name, network.clone_ref(py), encoder, iterations);
+        Self(SharedAgent::new(agent))
+    }
+
+    /// Agent driven by a Python callable, e.g. a human front-end.
+    ///
+    /// The callable receives the acting PlayerId and the list of legal
+    /// Actions, and returns the index of the action to play (or None).
+    #[staticmethod]
+    #[pyo3(signature = (callback, name = "callback".to_string()))]
+    fn callback(callback: PyObject, name: String) -> Self {
+        let script = move |_: &GameState, player: PlayerId, actions: &[Action]| {
+            Python::with_gil(|py| {
+                let legal: Vec<PyAction> = actions.iter().cloned().map(PyAction).collect();
+                match callback
+                    .call1(py, (PyPlayerId(player), legal))
+                    .and_then(|index| index.extract::<Option<usize>>(py))
+                {
+                    Ok(index) => index,
+                    Err(e) => {
+                        eprintln!("Agent.callback: call failed: {}", e);
+                        None
+                    }
+                }
+            })
+        };
+        Self(SharedAgent::new(ScriptedAgent::new(name, script)))
+    }
+
+    /// Get the agent's name.
+    #[getter]
+    fn name(&self) -> String {
+        self.0.name.clone()
+    }
+
+    /// Get the action probabilities from the agent's last decision, if any.
+    fn last_action_probs(&self) -> Option<Vec<(PyAction, f64)>> {
+        self.0.search_result().map(|result| {
+            result
+                .action_probs
+                .into_iter()
+                .map(|(action, p)| (PyAction(action), p))
+                .collect()
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Agent(name={:?})", self.0.name)
+    }
+}
@@ -2,10 +2,12 @@
 
 use pyo3::prelude::*;
 
+use crate::agents::Agent;
 use crate::core::GameState;
 use crate::games::simple::{SimpleGame, SimpleGameBuilder};
 use crate::rules::{GameResult, RulesEngine};
 
+use super::py_agents::PyAgent;
 use super::py_core::{PyAction, PyPlayerId};
 
 /// Python wrapper for SimpleGame.
@@ -53,7 +55,7 @@ impl PySimpleGame {
 
     /// Get legal actions for the current player.
     fn legal_actions(&self) -> Vec<PyAction> {
-        let player = self.state.public.active_player;
+        let player = self.rules.acting_player(&self.state);
         self.rules
             .legal_actions(&self.state, player)
             .into_iter()
@@ -61,11 +63,38 @@ impl PySimpleGame {
             .collect()
     }
 
-    /// Apply an action.
-    fn apply_action(&mut self, action: &PyAction) {
-        let player = self.state.public.active_player;
+    /// Apply an action for the current player.
+    ///
+    /// Raises ValueError if the action is malformed or not legal; the game
+    /// is unchanged in that case.
+    fn apply_action(&mut self, action: &PyAction) -> PyResult<()> {
+        let player = self.rules.acting_player(&self.state);
+        self.rules
+            .try_apply_action(&mut self.state, player, &action.0)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))
+    }
+
+    /// Check an action for the current player without applying it.
+    ///
+    /// Returns None if it is legal, otherwise a description of the problem.
+    fn validate_action(&self, action: &PyAction) -> Option<String> {
+        let player = self.rules.acting_player(&self.state);
         self.rules
-            .apply_action(&mut self.state, player, &action.0);
+            .validate_action(&self.state, player, &action.0)
+            .err()
+            .map(|e| e.to_string())
+    }
+
+    /// Ask an agent to choose an action for the current player.
+    ///
+    /// The action is returned, not applied.
+    #[pyo3(signature = (agent, seed = 0))]
+    fn select_action(&mut self, agent: &PyAgent, seed: u64) -> Option<PyAction> {
+        let player = self.rules.acting_player(&self.state);
+        let mut agent = agent.0.clone();
+        agent
+            .select_action(&self.rules, &self.state, player, seed)
+            .map(PyAction)
     }
 
     /// Check if the game is terminal.
@@ -111,11 +140,14 @@ impl PySimpleGame {
         self.state.public.hand_sizes[player.0]
     }
 
-    /// Copy the game state for simulation.
-    fn copy(&mut self) -> Self {
+    /// Copy the game, including its RNG position.
+    ///
+    /// Applying the same actions to the copy and the original gives the
+    /// same game.
+    fn copy(&self) -> Self {
         Self {
             rules: self.rules.clone(),
-            state: self.state.clone_state(),
+            state: self.state.snapshot(),
         }
     }
 
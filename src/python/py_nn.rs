@@ -98,17 +98,26 @@ impl PyPolicyValueNetwork {
     }
 
     #[getter]
-    fn action_space_size(&self) -> usize {
+    pub(crate) fn action_space_size(&self) -> usize {
         self.action_space_size
     }
 
     #[getter]
-    fn player_count(&self) -> usize {
+    pub(crate) fn player_count(&self) -> usize {
         self.player_count
     }
 }
 
 impl PyPolicyValueNetwork {
+    /// Create another wrapper around the same Python callable.
+    pub(crate) fn clone_ref(&self, py: Python<'_>) -> Self {
+        Self {
+            callback: self.callback.clone_ref(py),
+            action_space_size: self.action_space_size,
+            player_count: self.player_count,
+        }
+    }
+
     /// Return a fallback prediction when Python callback fails.
     fn fallback_prediction(&self) -> (Vec<f32>, Vec<f32>) {
         let policy = vec![1.0 / self.action_space_size as f32; self.action_space_size];
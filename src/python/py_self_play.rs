@@ -6,6 +6,7 @@ use crate::games::simple::{SimpleGame, SimpleGameBuilder};
 use crate::nn::SimpleGameEncoder;
 use crate::training::{SelfPlayConfig, SelfPlayWorker};
 
+use super::py_agents::PyAgent;
 use super::py_nn::PyPolicyValueNetwork;
 use super::py_training::PyTrajectory;
 
@@ -172,6 +173,16 @@ impl PySimpleGameWorker {
         PyTrajectory(self.inner.play_game_with_network(&mut state, seed, network))
     }
 
+    /// Play a game with the given agents, one per seat.
+    ///
+    /// A single agent plays every seat. Steps record each agent's search
+    /// statistics, or a one-hot policy for agents without them.
+    fn play_game_with_agents(&self, seed: u64, agents: Vec<PyAgent>) -> PyTrajectory {
+        let mut state = self.create_game_state(seed);
+        let mut agents: Vec<_> = agents.into_iter().map(|agent| agent.0).collect();
+        PyTrajectory(self.inner.play_game_with_agents(&mut state, seed, &mut agents))
+    }
+
     /// Play multiple games in sequence using pure MCTS.
     fn play_games(&self, count: usize, base_seed: u64) -> Vec<PyTrajectory> {
         (0..count)
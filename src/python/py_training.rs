@@ -4,7 +4,10 @@ use numpy::{PyArray1, PyArray2, PyArrayMethods};
 use pyo3::prelude::*;
 
 use crate::core::PlayerId;
-use crate::training::{ExperienceBuffer, Step, TrainingSample, Trajectory};
+use crate::training::{
+    ExperienceBuffer, ReplayConfig, SampleId, SeatRotation, Step, Stratify, TrainingSample,
+    Trajectory,
+};
 
 use super::py_core::{PyAction, PyPlayerId};
 use super::py_nn::PyEncodedState;
@@ -156,6 +159,12 @@ impl PyTrainingSample {
         PyPlayerId(self.0.player)
     }
 
+    /// Get the value targets for every seat, indexed by player.
+    #[getter]
+    fn values(&self) -> Vec<f32> {
+        self.0.values.clone()
+    }
+
     /// Get state tensor as numpy array.
     fn state_numpy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
         PyArray1::from_slice_bound(py, &self.0.state.tensor)
@@ -236,6 +245,107 @@ impl PyExperienceBuffer {
             .collect()
     }
 
+    /// Configure prioritized, recency-weighted and stratified replay.
+    ///
+    /// # Arguments
+    /// - priority_exponent: Priority exponent alpha, 0 = ignore priorities (default: 0.6)
+    /// - importance_exponent: Importance-sampling exponent beta (default: 0.4)
+    /// - recency_decay: Weight multiplier per generation of age (default: 1.0)
+    /// - stratify: "none", "player", "phase" or "player_phase" (default: "none")
+    /// - phase_buckets: Number of game phases for phase stratification (default: 3)
+    #[pyo3(signature = (
+        priority_exponent = 0.6,
+        importance_exponent = 0.4,
+        recency_decay = 1.0,
+        stratify = "none",
+        phase_buckets = 3
+    ))]
+    fn set_replay(
+        &mut self,
+        priority_exponent: f64,
+        importance_exponent: f64,
+        recency_decay: f64,
+        stratify: &str,
+        phase_buckets: usize,
+    ) -> PyResult<()> {
+        let stratify = match stratify {
+            "none" => Stratify::None,
+            "player" => Stratify::Player,
+            "phase" => Stratify::Phase { buckets: phase_buckets },
+            "player_phase" => Stratify::PlayerPhase { buckets: phase_buckets },
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown stratify mode: {}",
+                    other
+                )))
+            }
+        };
+        self.inner.set_replay(
+            ReplayConfig::new()
+                .with_priorities(priority_exponent, importance_exponent)
+                .with_recency_decay(recency_decay)
+                .with_stratify(stratify),
+        );
+        Ok(())
+    }
+
+    /// Augment sampled batches with random seat rotations.
+    ///
+    /// Requires an encoding with one block of `features_per_player`
+    /// features per seat, such as SimpleGameEncoder.
+    #[pyo3(signature = (player_count, features_per_player = 5))]
+    fn enable_seat_rotation(&mut self, player_count: usize, features_per_player: usize) {
+        self.inner.set_symmetry(Some(std::sync::Arc::new(SeatRotation::new(
+            player_count,
+            features_per_player,
+        ))));
+    }
+
+    /// Current generation (network version) used to tag new trajectories.
+    #[getter]
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    #[setter]
+    fn set_generation(&mut self, generation: u64) {
+        self.inner.set_generation(generation);
+    }
+
+    /// Sample a batch with the replay config.
+    ///
+    /// Returns a list of ((trajectory_id, step), sample, importance_weight).
+    fn sample_replay(
+        &self,
+        batch_size: usize,
+        seed: u64,
+    ) -> Vec<((u64, usize), PyTrainingSample, f32)> {
+        self.inner
+            .sample_replay(batch_size, seed)
+            .into_iter()
+            .map(|r| ((r.id.trajectory, r.id.step), PyTrainingSample(r.sample), r.weight))
+            .collect()
+    }
+
+    /// Update priorities for sampled ids (e.g. with per-sample losses).
+    ///
+    /// Returns the number of priorities updated; evicted ids are ignored.
+    fn update_priorities(&mut self, ids: Vec<(u64, usize)>, priorities: Vec<f64>) -> PyResult<usize> {
+        if ids.len() != priorities.len() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Got {} ids but {} priorities",
+                ids.len(),
+                priorities.len()
+            )));
+        }
+        let updates: Vec<_> = ids
+            .into_iter()
+            .map(|(t, i)| SampleId::new(t, i))
+            .zip(priorities)
+            .collect();
+        Ok(self.inner.update_priorities(&updates))
+    }
+
     /// Get all samples as batched numpy arrays for efficient training.
     ///
     /// Returns (states, policies, values) as numpy arrays:
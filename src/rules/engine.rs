@@ -4,6 +4,8 @@
 //! - What actions are legal
 //! - How actions modify state
 //! - Win/loss conditions
+//! - How pending choices are offered (`choice_template`)
+//! - Who acts next (`acting_player`)
 
 use crate::core::action::Action;
 use crate::core::config::{GameConfig, TemplateId};
@@ -11,6 +13,8 @@ use crate::core::entity::EntityId;
 use crate::core::player::PlayerId;
 use crate::core::state::GameState;
 
+use super::error::ActionError;
+
 /// Result of a completed game.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GameResult {
@@ -45,6 +49,8 @@ impl GameResult {
 /// - `legal_pointers`: Called iteratively for multi-pointer actions
 /// - `apply_action`: Must be deterministic for MCTS
 /// - `is_terminal`: Return None if game continues
+/// - `choice_template`: Return a template to offer pending choices as actions
+/// - `acting_player`: Override if someone other than the active player can act
 pub trait RulesEngine {
     /// Get the game configuration.
     fn config(&self) -> &GameConfig;
@@ -80,12 +86,158 @@ pub trait RulesEngine {
     /// Returns `Some(result)` if the game has ended, `None` if it continues.
     fn is_terminal(&self, state: &GameState) -> Option<GameResult>;
 
+    /// Template for answering a [`PendingChoice`] or [`PendingTargets`].
+    ///
+    /// While a choice or targets are pending, [`legal_actions`](Self::legal_actions)
+    /// offers only this template to the deciding player, one action per
+    /// legal selection with the chosen entities as pointers. The template
+    /// should take variable pointers. `apply_action` is expected to pass
+    /// the pointers to `EffectResolver::resolve_choice`, or to
+    /// `ResolutionSystem::choose_targets` when `state.pending_targets()` is
+    /// set.
+    ///
+    /// Default is `None`: choices are not offered as actions.
+    ///
+    /// [`PendingChoice`]: crate::effects::PendingChoice
+    /// [`PendingTargets`]: crate::stack::PendingTargets
+    fn choice_template(&self) -> Option<TemplateId> {
+        None
+    }
+
+    /// The player who acts next.
+    ///
+    /// Runners and search ask this instead of reading `active_player`, so
+    /// decisions owned by another player are not mistaken for dead ends.
+    /// Default is the player deciding a pending choice or choosing pending
+    /// targets when they are offered through
+    /// [`choice_template`](Self::choice_template), otherwise the active
+    /// player.
+    fn acting_player(&self, state: &GameState) -> PlayerId {
+        if self.choice_template().is_some() {
+            if let Some(choice) = state.pending_choice() {
+                return choice.player;
+            }
+            if let Some(targets) = state.pending_targets() {
+                return targets.controller();
+            }
+        }
+        state.public.active_player
+    }
+
+    // === Validation ===
+
+    /// Check that an action is well-formed and legal for `player`.
+    ///
+    /// Checks, in order: the player exists, the template is defined in
+    /// [`GameConfig`] with a matching pointer count, the template is legal
+    /// now, and each pointer is among [`legal_pointers`](Self::legal_pointers)
+    /// given the ones before it. Pointers to entities that no longer exist
+    /// are reported as [`ActionError::StaleEntity`].
+    ///
+    /// While a choice or targets are pending and the game has a
+    /// [`choice_template`](Self::choice_template), only the deciding player
+    /// can act, with that template and a legal selection.
+    fn validate_action(&self, state: &GameState, player: PlayerId, action: &Action) -> Result<(), ActionError> {
+        if player.index() >= state.player_count() {
+            return Err(ActionError::UnknownPlayer(player));
+        }
+
+        let template = action.template;
+        let config = self
+            .config()
+            .get_template(template)
+            .ok_or(ActionError::UnknownTemplate(template))?;
+        let found = action.pointer_count();
+        let count_ok = if config.variable_pointers {
+            found >= config.pointer_count
+        } else {
+            found == config.pointer_count
+        };
+        if !count_ok {
+            return Err(ActionError::WrongPointerCount {
+                template,
+                expected: config.pointer_count,
+                found,
+            });
+        }
+
+        if let Some(choice_template) = self.choice_template() {
+            let pending = match (state.pending_choice(), state.pending_targets()) {
+                (Some(choice), _) => Some((choice.player, choice.check(&action.pointers))),
+                (None, Some(targets)) => Some((targets.controller(), targets.check(&action.pointers))),
+                (None, None) => None,
+            };
+            if let Some((decider, selection)) = pending {
+                if player != decider {
+                    return Err(ActionError::CannotAct(player));
+                }
+                if template != choice_template {
+                    return Err(ActionError::TemplateNotLegal(template));
+                }
+                return selection.map_err(ActionError::IllegalChoice);
+            }
+        }
+
+        let templates = self.legal_templates(state, player);
+        if templates.is_empty() {
+            return Err(ActionError::CannotAct(player));
+        }
+        if !templates.contains(&template) {
+            return Err(ActionError::TemplateNotLegal(template));
+        }
+
+        for (index, &entity) in action.pointers.iter().enumerate() {
+            let legal = self.legal_pointers(state, player, template, &action.pointers[..index]);
+            if legal.contains(&entity) {
+                continue;
+            }
+            let exists = entity.is_player(state.player_count())
+                || state.zones.contains(entity)
+                || state.get_card(entity).is_some();
+            return Err(if exists {
+                ActionError::IllegalPointer { index, entity }
+            } else {
+                ActionError::StaleEntity { index, entity }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Validate an action, then apply it.
+    ///
+    /// Front ends taking actions from people or other processes should use
+    /// this rather than [`apply_action`](Self::apply_action), which assumes
+    /// its input is legal. The state is unchanged on error.
+    fn try_apply_action(&mut self, state: &mut GameState, player: PlayerId, action: &Action) -> Result<(), ActionError> {
+        self.validate_action(state, player, action)?;
+        self.apply_action(state, player, action);
+        Ok(())
+    }
+
     // === Convenience Methods ===
 
     /// Enumerate all legal actions for a player.
     ///
-    /// Default implementation builds actions from templates and pointers.
+    /// Default implementation builds actions from templates and pointers,
+    /// or from the pending choice or targets if there are any (see
+    /// [`choice_template`](Self::choice_template)).
     fn legal_actions(&self, state: &GameState, player: PlayerId) -> Vec<Action> {
+        if let Some(template) = self.choice_template() {
+            if let Some(choice) = state.pending_choice() {
+                if player != choice.player {
+                    return Vec::new();
+                }
+                return choice.actions(template);
+            }
+            if let Some(targets) = state.pending_targets() {
+                if player != targets.controller() {
+                    return Vec::new();
+                }
+                return targets.actions(template);
+            }
+        }
+
         let mut actions = Vec::new();
 
         for template in self.legal_templates(state, player) {
@@ -123,6 +275,7 @@ pub trait RulesEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
 
     #[test]
     fn test_game_result_is_winner() {
@@ -138,4 +291,304 @@ mod tests {
         assert!(!team.is_winner(PlayerId::new(1)));
         assert!(team.is_winner(PlayerId::new(2)));
     }
+
+    fn simple_game() -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new().player_count(2).build(42)
+    }
+
+    #[test]
+    fn test_validate_legal_actions() {
+        let (game, state) = simple_game();
+        let player = state.public.active_player;
+        for action in game.legal_actions(&state, player) {
+            assert_eq!(game.validate_action(&state, player, &action), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_actions() {
+        let (game, state) = simple_game();
+        let player = state.public.active_player;
+        let other = PlayerId::new(1 - player.0);
+        let play = game.templates().play;
+        let pass = Action::new(game.templates().pass);
+
+        assert_eq!(
+            game.validate_action(&state, PlayerId::new(7), &pass),
+            Err(ActionError::UnknownPlayer(PlayerId::new(7)))
+        );
+        assert_eq!(
+            game.validate_action(&state, player, &Action::new(TemplateId::new(99))),
+            Err(ActionError::UnknownTemplate(TemplateId::new(99)))
+        );
+        assert_eq!(
+            game.validate_action(&state, player, &Action::new(play)),
+            Err(ActionError::WrongPointerCount {
+                template: play,
+                expected: 2,
+                found: 0,
+            })
+        );
+        assert_eq!(
+            game.validate_action(&state, other, &pass),
+            Err(ActionError::CannotAct(other))
+        );
+
+        // A card in hand targeting yourself is illegal; a made-up card is stale
+        let card = game.legal_pointers(&state, player, play, &[])[0];
+        let self_target = Action::with_pointers(play, &[card, EntityId::player(player)]);
+        assert_eq!(
+            game.validate_action(&state, player, &self_target),
+            Err(ActionError::IllegalPointer {
+                index: 1,
+                entity: EntityId::player(player),
+            })
+        );
+        let ghost = Action::with_pointers(play, &[EntityId(9999), EntityId::player(other)]);
+        assert_eq!(
+            game.validate_action(&state, player, &ghost),
+            Err(ActionError::StaleEntity {
+                index: 0,
+                entity: EntityId(9999),
+            })
+        );
+    }
+
+    /// Discards up to two of player 1's cards by their choice.
+    struct ChoiceGame {
+        config: GameConfig,
+    }
+
+    const PASS: TemplateId = TemplateId::new(0);
+    const CHOOSE: TemplateId = TemplateId::new(1);
+
+    impl ChoiceGame {
+        fn new() -> (Self, GameState) {
+            use crate::cards::{CardId, CardInstance};
+            use crate::core::config::TemplateConfig;
+            use crate::core::ZoneId;
+            use crate::effects::{Effect, EffectResolver, ResolverContext, TargetCount, TargetSpec};
+
+            let config = GameConfig::new(2)
+                .with_template(TemplateConfig::no_args(PASS, "pass"))
+                .with_template(TemplateConfig::new(CHOOSE, "choose", 0).with_variable_pointers());
+            let mut state = GameState::new(2, 42);
+            let hand = ZoneId::new(1);
+            for i in 0..3 {
+                state.add_card(CardInstance::new(EntityId(10 + i), CardId::new(1), PlayerId::new(1), hand));
+            }
+            let discard = Effect::choose(
+                TargetSpec::multiple_cards([hand], TargetCount::UpTo(2)),
+                Effect::RemoveFromGame,
+            );
+            EffectResolver::resolve_single(&mut state, &discard, EntityId::player_id(1), &ResolverContext::simple(2));
+            (Self { config }, state)
+        }
+    }
+
+    impl RulesEngine for ChoiceGame {
+        fn config(&self) -> &GameConfig {
+            &self.config
+        }
+
+        fn legal_templates(&self, state: &GameState, player: PlayerId) -> Vec<TemplateId> {
+            if player == state.public.active_player {
+                vec![PASS]
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn legal_pointers(&self, _: &GameState, _: PlayerId, _: TemplateId, _: &[EntityId]) -> Vec<EntityId> {
+            Vec::new()
+        }
+
+        fn apply_action(&mut self, state: &mut GameState, _: PlayerId, action: &Action) {
+            use crate::effects::{EffectResolver, ResolverContext};
+
+            if action.template == CHOOSE {
+                EffectResolver::resolve_choice(state, &action.pointers, &ResolverContext::simple(2)).unwrap();
+            }
+        }
+
+        fn is_terminal(&self, _: &GameState) -> Option<GameResult> {
+            None
+        }
+
+        fn choice_template(&self) -> Option<TemplateId> {
+            Some(CHOOSE)
+        }
+    }
+
+    #[test]
+    fn test_pending_choice_actions() {
+        use crate::effects::ChoiceError;
+
+        let (mut game, mut state) = ChoiceGame::new();
+        let chooser = PlayerId::new(1);
+        let active = state.public.active_player;
+        assert_ne!(active, chooser);
+
+        // Nothing, three singles or three pairs
+        let actions = game.legal_actions(&state, chooser);
+        assert_eq!(actions.len(), 7);
+        assert!(actions.iter().all(|action| action.template == CHOOSE));
+        assert!(game.legal_actions(&state, active).is_empty());
+        for action in &actions {
+            assert_eq!(game.validate_action(&state, chooser, action), Ok(()));
+        }
+
+        assert_eq!(
+            game.validate_action(&state, active, &Action::new(PASS)),
+            Err(ActionError::CannotAct(active))
+        );
+        assert_eq!(
+            game.validate_action(&state, chooser, &Action::new(PASS)),
+            Err(ActionError::TemplateNotLegal(PASS))
+        );
+        let all = Action::with_pointers(CHOOSE, &[EntityId(10), EntityId(11), EntityId(12)]);
+        assert_eq!(
+            game.validate_action(&state, chooser, &all),
+            Err(ActionError::IllegalChoice(ChoiceError::WrongCount { min: 0, max: 2, found: 3 }))
+        );
+
+        let pair = Action::with_pointers(CHOOSE, &[EntityId(10), EntityId(12)]);
+        game.try_apply_action(&mut state, chooser, &pair).unwrap();
+        assert!(state.pending_choice().is_none());
+        assert!(state.get_card(EntityId(10)).is_none());
+        assert!(state.get_card(EntityId(11)).is_some());
+        assert_eq!(game.legal_actions(&state, active), vec![Action::new(PASS)]);
+    }
+
+    /// Player 0's trigger deals 2 damage to an opponent of their choice, on
+    /// player 1's turn.
+    struct TargetsGame {
+        config: GameConfig,
+        resolution: crate::stack::ImmediateResolution,
+    }
+
+    impl TargetsGame {
+        fn new() -> (Self, GameState) {
+            use crate::core::config::TemplateConfig;
+            use crate::effects::{Effect, ResolverContext, TargetSpec};
+            use crate::stack::{ImmediateResolution, ResolutionStatus, ResolutionSystem};
+            use crate::triggers::{EventTypeId, GameEvent, TriggerId, TriggerTiming, TriggeredEffect};
+
+            let config = GameConfig::new(3)
+                .with_template(TemplateConfig::no_args(PASS, "pass"))
+                .with_template(TemplateConfig::new(CHOOSE, "choose", 0).with_variable_pointers());
+            let mut state = GameState::new(3, 42);
+            for player in 0..3 {
+                state.public.set_player_state(PlayerId::new(player), "life", 20);
+            }
+            state.public.set_active_player(PlayerId::new(1));
+            let mut resolution = ImmediateResolution::new();
+            resolution.queue_triggered(TriggeredEffect {
+                trigger_id: TriggerId::new(1),
+                controller: Some(PlayerId::new(0)),
+                source: None,
+                effects: vec![Effect::damage(2)],
+                target: Some(TargetSpec::single_opponent()),
+                modifiers: Vec::new(),
+                triggering_event: GameEvent::new(EventTypeId::new(1)),
+                timing: TriggerTiming::After,
+            });
+            let status = resolution.process(&mut state, &ResolverContext::simple(3));
+            assert_eq!(status, ResolutionStatus::WaitingForTargets(PlayerId::new(0)));
+            (Self { config, resolution }, state)
+        }
+    }
+
+    impl RulesEngine for TargetsGame {
+        fn config(&self) -> &GameConfig {
+            &self.config
+        }
+
+        fn legal_templates(&self, _: &GameState, _: PlayerId) -> Vec<TemplateId> {
+            vec![PASS]
+        }
+
+        fn legal_pointers(&self, _: &GameState, _: PlayerId, _: TemplateId, _: &[EntityId]) -> Vec<EntityId> {
+            Vec::new()
+        }
+
+        fn apply_action(&mut self, state: &mut GameState, _: PlayerId, action: &Action) {
+            use crate::effects::ResolverContext;
+            use crate::stack::ResolutionSystem;
+
+            if action.template == CHOOSE {
+                self.resolution.choose_targets(state, &action.pointers).unwrap();
+                self.resolution.process(state, &ResolverContext::simple(3));
+            }
+        }
+
+        fn is_terminal(&self, _: &GameState) -> Option<GameResult> {
+            None
+        }
+
+        fn choice_template(&self) -> Option<TemplateId> {
+            Some(CHOOSE)
+        }
+    }
+
+    #[test]
+    fn test_pending_targets_actions() {
+        use crate::effects::ChoiceError;
+        use crate::stack::ResolutionSystem;
+
+        let (mut game, mut state) = TargetsGame::new();
+        let controller = PlayerId::new(0);
+        let opponent = PlayerId::new(2);
+        assert_eq!(game.acting_player(&state), controller);
+
+        let actions = game.legal_actions(&state, controller);
+        assert_eq!(
+            actions,
+            vec![
+                Action::with_pointers(CHOOSE, &[EntityId::player(PlayerId::new(1))]),
+                Action::with_pointers(CHOOSE, &[EntityId::player(opponent)]),
+            ]
+        );
+        assert!(game.legal_actions(&state, opponent).is_empty());
+        for action in &actions {
+            assert_eq!(game.validate_action(&state, controller, action), Ok(()));
+        }
+
+        assert_eq!(
+            game.validate_action(&state, opponent, &actions[0]),
+            Err(ActionError::CannotAct(opponent))
+        );
+        assert_eq!(
+            game.validate_action(&state, controller, &Action::new(PASS)),
+            Err(ActionError::TemplateNotLegal(PASS))
+        );
+        let itself = Action::with_pointers(CHOOSE, &[EntityId::player(controller)]);
+        assert_eq!(
+            game.validate_action(&state, controller, &itself),
+            Err(ActionError::IllegalChoice(ChoiceError::NotAnOption(EntityId::player(controller))))
+        );
+
+        game.try_apply_action(&mut state, controller, &actions[1]).unwrap();
+        assert!(state.pending_targets().is_none());
+        assert!(game.resolution.is_complete());
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 20);
+        assert_eq!(state.public.get_player_state(opponent, "life", 0), 18);
+        assert_eq!(game.acting_player(&state), PlayerId::new(1));
+        assert_eq!(game.legal_actions(&state, controller), vec![Action::new(PASS)]);
+    }
+
+    #[test]
+    fn test_try_apply_action() {
+        let (mut game, mut state) = simple_game();
+        let player = state.public.active_player;
+        let before = state.state_hash();
+
+        let bogus = Action::new(TemplateId::new(99));
+        assert!(game.try_apply_action(&mut state, player, &bogus).is_err());
+        assert_eq!(state.state_hash(), before);
+
+        let legal = game.legal_actions(&state, player)[0].clone();
+        assert!(game.try_apply_action(&mut state, player, &legal).is_ok());
+        assert_ne!(state.state_hash(), before);
+    }
 }
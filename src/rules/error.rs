@@ -0,0 +1,81 @@
+//! Errors for rejected actions.
+
+use crate::core::{EntityId, PlayerId, TemplateId};
+use crate::effects::ChoiceError;
+
+/// Why an action was rejected by
+/// [`RulesEngine::validate_action`](super::RulesEngine::validate_action).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ActionError {
+    /// The player index is outside the game's player count.
+    UnknownPlayer(PlayerId),
+
+    /// The template is not defined in the game's `GameConfig`.
+    UnknownTemplate(TemplateId),
+
+    /// The action has the wrong number of pointers for its template.
+    WrongPointerCount {
+        /// The action's template.
+        template: TemplateId,
+        /// Pointers the template requires (a minimum for variable templates).
+        expected: usize,
+        /// Pointers the action has.
+        found: usize,
+    },
+
+    /// The player has no legal actions right now (e.g. not their turn).
+    CannotAct(PlayerId),
+
+    /// The template is defined but not legal in this state.
+    TemplateNotLegal(TemplateId),
+
+    /// A pointer refers to an entity that no longer exists.
+    StaleEntity {
+        /// Pointer position.
+        index: usize,
+        /// The missing entity.
+        entity: EntityId,
+    },
+
+    /// A pointer refers to an existing entity that is not a legal choice.
+    IllegalPointer {
+        /// Pointer position.
+        index: usize,
+        /// The rejected entity.
+        entity: EntityId,
+    },
+
+    /// The action's pointers are not a legal selection for the pending choice.
+    IllegalChoice(ChoiceError),
+}
+
+impl std::fmt::Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionError::UnknownPlayer(player) => write!(f, "unknown player {}", player),
+            ActionError::UnknownTemplate(template) => write!(f, "unknown action template {}", template.0),
+            ActionError::WrongPointerCount {
+                template,
+                expected,
+                found,
+            } => write!(
+                f,
+                "template {} takes {} pointer(s), action has {}",
+                template.0, expected, found
+            ),
+            ActionError::CannotAct(player) => write!(f, "{} cannot act now", player),
+            ActionError::TemplateNotLegal(template) => {
+                write!(f, "template {} is not legal now", template.0)
+            }
+            ActionError::StaleEntity { index, entity } => {
+                write!(f, "pointer {} refers to missing entity {}", index, entity.0)
+            }
+            ActionError::IllegalPointer { index, entity } => {
+                write!(f, "pointer {} ({}) is not a legal choice", index, entity.0)
+            }
+            ActionError::IllegalChoice(error) => write!(f, "illegal choice: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
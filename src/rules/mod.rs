@@ -9,5 +9,7 @@
 //! game-specific concepts directly.
 
 pub mod engine;
+pub mod error;
 
 pub use engine::{GameResult, RulesEngine};
+pub use error::ActionError;
@@ -0,0 +1,192 @@
+//! Typed events emitted while a game is driven by a [`GameRunner`].
+//!
+//! [`GameRunner`]: super::GameRunner
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::SearchResult;
+use crate::core::{Action, ActionRecord, GameState, PlayerId, PlayerMap};
+use crate::rules::GameResult;
+
+/// Why a run stopped.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EndReason {
+    /// The rules engine reported a result.
+    Terminal,
+
+    /// The configured turn limit was reached.
+    MaxTurns,
+
+    /// The configured move limit was reached.
+    MaxMoves,
+
+    /// The acting player's agent returned no action.
+    NoAction {
+        /// Player whose agent did not act.
+        player: PlayerId,
+    },
+
+    /// An agent chose an action that was not legal; the player forfeits.
+    IllegalAction {
+        /// Offending player.
+        player: PlayerId,
+        /// The rejected action.
+        action: Action,
+    },
+
+    /// An agent returned after the per-move time budget ran out; its move
+    /// is discarded and the player forfeits.
+    TimeExceeded {
+        /// Offending player.
+        player: PlayerId,
+        /// Time the agent took.
+        elapsed: Duration,
+    },
+}
+
+impl EndReason {
+    /// Whether the game ended by a forfeit.
+    pub fn is_forfeit(&self) -> bool {
+        matches!(self, EndReason::IllegalAction { .. } | EndReason::TimeExceeded { .. })
+    }
+}
+
+/// An event in a running game.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RunnerEvent {
+    /// The game is about to start.
+    GameStarted {
+        /// Game seed.
+        seed: u64,
+        /// Number of seats.
+        player_count: usize,
+        /// Name of the agent in each seat.
+        agents: Vec<String>,
+    },
+
+    /// An action was applied.
+    MoveMade {
+        /// The recorded action.
+        record: ActionRecord,
+        /// Time the agent took to choose it.
+        elapsed: Duration,
+        /// The agent's search statistics for the move, if it reports them.
+        search: Option<SearchResult>,
+    },
+
+    /// The turn number changed.
+    TurnAdvanced {
+        /// New turn number.
+        turn: u32,
+        /// Player to act.
+        active_player: PlayerId,
+    },
+
+    /// The game is over.
+    GameEnded {
+        /// Why the run stopped.
+        reason: EndReason,
+        /// Result, if the game was decided.
+        result: Option<GameResult>,
+        /// Reward per player.
+        rewards: PlayerMap<f64>,
+    },
+}
+
+/// Subscriber to a runner's event stream.
+///
+/// Observers see each event together with the state after it happened.
+/// Any `FnMut(&RunnerEvent, &GameState) + Send` closure is an observer.
+pub trait GameObserver: Send {
+    /// Handle an event.
+    fn on_event(&mut self, event: &RunnerEvent, state: &GameState);
+}
+
+impl<F> GameObserver for F
+where
+    F: FnMut(&RunnerEvent, &GameState) + Send,
+{
+    fn on_event(&mut self, event: &RunnerEvent, state: &GameState) {
+        self(event, state);
+    }
+}
+
+/// Observer that collects every event.
+///
+/// Clones share the same log, so keep one handle and subscribe another.
+#[derive(Clone, Debug, Default)]
+pub struct EventLog {
+    events: Arc<Mutex<Vec<RunnerEvent>>>,
+}
+
+impl EventLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a copy of the events recorded so far.
+    pub fn events(&self) -> Vec<RunnerEvent> {
+        self.lock().clone()
+    }
+
+    /// Remove and return the events recorded so far.
+    pub fn take(&self) -> Vec<RunnerEvent> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Get the number of events recorded.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no events have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<RunnerEvent>> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl GameObserver for EventLog {
+    fn on_event(&mut self, event: &RunnerEvent, _state: &GameState) {
+        self.lock().push(event.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_log_shares_events() {
+        let log = EventLog::new();
+        let mut handle = log.clone();
+        let state = GameState::new(2, 0);
+
+        let started = RunnerEvent::GameStarted {
+            seed: 1,
+            player_count: 2,
+            agents: vec!["a".into(), "b".into()],
+        };
+        handle.on_event(&started, &state);
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.take(), vec![started]);
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn test_end_reason_forfeit() {
+        assert!(!EndReason::Terminal.is_forfeit());
+        assert!(EndReason::TimeExceeded {
+            player: PlayerId::new(0),
+            elapsed: Duration::from_millis(5),
+        }
+        .is_forfeit());
+    }
+}
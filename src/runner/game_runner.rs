@@ -0,0 +1,592 @@
+//! The game loop: ask agents for actions, enforce limits, notify observers.
+
+use std::time::{Duration, Instant};
+
+use crate::agents::Agent;
+use crate::core::{ActionRecord, GameState, PlayerId, PlayerMap};
+use crate::rules::{GameResult, RulesEngine};
+
+use super::events::{EndReason, GameObserver, RunnerEvent};
+
+/// Limits applied while running a game.
+#[derive(Clone, Debug)]
+pub struct RunnerConfig {
+    /// Maximum number of actions before the game is stopped.
+    pub max_moves: usize,
+
+    /// Maximum number of turns to play (turn advances), if limited.
+    pub max_turns: Option<u32>,
+
+    /// Time an agent may take per move before forfeiting, if limited.
+    ///
+    /// The budget is checked after the agent returns: a slow agent is not
+    /// interrupted, its move is discarded and the player forfeits. Agents
+    /// that must stay within it should bound their own work (e.g. search
+    /// iterations).
+    pub move_time_budget: Option<Duration>,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            max_moves: 1000,
+            max_turns: None,
+            move_time_budget: None,
+        }
+    }
+}
+
+impl RunnerConfig {
+    /// Create a config with default limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the move limit.
+    pub fn with_max_moves(mut self, max_moves: usize) -> Self {
+        self.max_moves = max_moves;
+        self
+    }
+
+    /// Set the turn limit.
+    pub fn with_max_turns(mut self, max_turns: u32) -> Self {
+        self.max_turns = Some(max_turns);
+        self
+    }
+
+    /// Set the per-move time budget (checked after each move, see
+    /// [`move_time_budget`](Self::move_time_budget)).
+    pub fn with_move_time_budget(mut self, budget: Duration) -> Self {
+        self.move_time_budget = Some(budget);
+        self
+    }
+}
+
+/// Summary of a finished run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameOutcome {
+    /// Why the run stopped.
+    pub reason: EndReason,
+
+    /// Result, if the game was decided (including by forfeit).
+    pub result: Option<GameResult>,
+
+    /// Reward per player (see [`outcome_rewards`]).
+    pub rewards: PlayerMap<f64>,
+
+    /// Every action applied, in order.
+    pub history: Vec<ActionRecord>,
+
+    /// Number of turns that advanced during the run.
+    pub turns: u32,
+}
+
+impl GameOutcome {
+    /// Number of actions applied.
+    pub fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Whether the game reached a decided result.
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+/// Drives a game between agents.
+///
+/// The agent for seat `p` is `agents[p % agents.len()]`, so a single agent
+/// can play every seat. Each step asks the active player's agent for an
+/// action, checks it is legal and was chosen within the time budget,
+/// applies it and notifies every agent and observer. Time is measured
+/// around the agent's call, so overrunning the budget is a forfeit after
+/// the fact rather than an interruption.
+pub struct GameRunner<E: RulesEngine> {
+    engine: E,
+    config: RunnerConfig,
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+impl<E: RulesEngine> GameRunner<E> {
+    /// Create a runner for an engine.
+    pub fn new(engine: E, config: RunnerConfig) -> Self {
+        Self {
+            engine,
+            config,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Subscribe an observer (builder pattern).
+    pub fn with_observer(mut self, observer: impl GameObserver + 'static) -> Self {
+        self.subscribe(observer);
+        self
+    }
+
+    /// Subscribe an observer to the event stream.
+    pub fn subscribe(&mut self, observer: impl GameObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Get the rules engine.
+    pub fn engine(&self) -> &E {
+        &self.engine
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &RunnerConfig {
+        &self.config
+    }
+
+    /// Per-move seed passed to agents.
+    pub fn move_seed(seed: u64, move_number: usize) -> u64 {
+        seed.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            .wrapping_add(move_number as u64)
+    }
+
+    /// Play `state` to the end (or a limit) with the given agents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `agents` is empty.
+    pub fn run<A: Agent<E>>(&mut self, state: &mut GameState, seed: u64, agents: &mut [A]) -> GameOutcome {
+        assert!(!agents.is_empty(), "GameRunner needs at least one agent");
+        let player_count = state.player_count();
+        let start_turn = state.public.turn_number;
+
+        for agent in agents.iter_mut() {
+            agent.new_game();
+        }
+        let seated = PlayerId::all(player_count)
+            .map(|p| agents[p.index() % agents.len()].name().to_string())
+            .collect();
+        let event = RunnerEvent::GameStarted {
+            seed,
+            player_count,
+            agents: seated,
+        };
+        self.emit(&event, state);
+
+        let mut history = Vec::new();
+        let mut sequence = 0;
+        let reason = loop {
+            if self.engine.is_terminal(state).is_some() {
+                break EndReason::Terminal;
+            }
+            if history.len() >= self.config.max_moves {
+                break EndReason::MaxMoves;
+            }
+            let turns = state.public.turn_number.saturating_sub(start_turn);
+            if self.config.max_turns.is_some_and(|max| turns >= max) {
+                break EndReason::MaxTurns;
+            }
+
+            let player = self.engine.acting_player(state);
+            let agent = &mut agents[player.index() % agents.len()];
+            let move_seed = Self::move_seed(seed, history.len());
+
+            let started = Instant::now();
+            let chosen = agent.select_action(&self.engine, state, player, move_seed);
+            let elapsed = started.elapsed();
+
+            let Some(action) = chosen else {
+                break EndReason::NoAction { player };
+            };
+            if self.config.move_time_budget.is_some_and(|budget| elapsed > budget) {
+                break EndReason::TimeExceeded { player, elapsed };
+            }
+            if !self.engine.legal_actions(state, player).contains(&action) {
+                break EndReason::IllegalAction { player, action };
+            }
+
+            let search = agent.search_result();
+            let turn = state.public.turn_number;
+            self.engine.apply_action(state, player, &action);
+            for agent in agents.iter_mut() {
+                agent.observe_action(state, player, &action);
+            }
+
+            let record = ActionRecord::new(player, action, turn, sequence);
+            sequence += 1;
+            history.push(record.clone());
+            self.emit(&RunnerEvent::MoveMade { record, elapsed, search }, state);
+
+            if state.public.turn_number != turn {
+                sequence = 0;
+                let event = RunnerEvent::TurnAdvanced {
+                    turn: state.public.turn_number,
+                    active_player: state.public.active_player,
+                };
+                self.emit(&event, state);
+            }
+        };
+
+        let result = match &reason {
+            EndReason::IllegalAction { player, .. } | EndReason::TimeExceeded { player, .. } => {
+                Some(forfeit_result(*player, player_count))
+            }
+            _ => self.engine.is_terminal(state),
+        };
+        let rewards = outcome_rewards(result.as_ref(), player_count);
+        for agent in agents.iter_mut() {
+            agent.game_over(state, &rewards);
+        }
+
+        let outcome = GameOutcome {
+            reason,
+            result,
+            rewards,
+            history,
+            turns: state.public.turn_number.saturating_sub(start_turn),
+        };
+        let event = RunnerEvent::GameEnded {
+            reason: outcome.reason.clone(),
+            result: outcome.result.clone(),
+            rewards: outcome.rewards.clone(),
+        };
+        self.emit(&event, state);
+        outcome
+    }
+
+    fn emit(&mut self, event: &RunnerEvent, state: &GameState) {
+        for observer in &mut self.observers {
+            observer.on_event(event, state);
+        }
+    }
+}
+
+/// Result when `loser` forfeits: every other seat wins.
+fn forfeit_result(loser: PlayerId, player_count: usize) -> GameResult {
+    let winners: Vec<PlayerId> = PlayerId::all(player_count).filter(|&p| p != loser).collect();
+    match winners.as_slice() {
+        [] => GameResult::Draw,
+        [winner] => GameResult::Winner(*winner),
+        _ => GameResult::Winners(winners),
+    }
+}
+
+/// Convert a game result into per-player rewards.
+///
+/// Winners share 1.0, a draw splits 1.0 evenly, and an unfinished game
+/// (e.g. one that hit the move limit) gives every player 0.5.
+pub fn outcome_rewards(result: Option<&GameResult>, player_count: usize) -> PlayerMap<f64> {
+    let mut outcome = PlayerMap::with_value(player_count, 0.0);
+
+    match result {
+        Some(GameResult::Winner(winner)) => {
+            outcome[*winner] = 1.0;
+        }
+        Some(GameResult::Winners(winners)) => {
+            // Multiple winners share the reward
+            let share = 1.0 / winners.len() as f64;
+            for &player in winners {
+                outcome[player] = share;
+            }
+        }
+        Some(GameResult::Draw) => {
+            // Equal reward for all
+            let share = 1.0 / player_count as f64;
+            for player in PlayerId::all(player_count) {
+                outcome[player] = share;
+            }
+        }
+        None => {
+            // Game didn't complete (hit max moves)
+            // Use heuristic evaluation
+            let share = 0.5;
+            for player in PlayerId::all(player_count) {
+                outcome[player] = share;
+            }
+        }
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{MctsAgent, RandomAgent, ScriptedAgent};
+    use crate::cards::{CardId, CardInstance};
+    use crate::core::config::TemplateConfig;
+    use crate::core::{Action, EntityId, GameConfig, TemplateId, ZoneId};
+    use crate::effects::{Effect, EffectResolver, ResolverContext, TargetSpec};
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+    use crate::runner::EventLog;
+
+    fn game() -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(42)
+    }
+
+    #[test]
+    fn test_run_to_completion() {
+        let (engine, mut state) = game();
+        let log = EventLog::new();
+        let mut runner = GameRunner::new(engine, RunnerConfig::new()).with_observer(log.clone());
+
+        let mut agents = [RandomAgent::new("a"), RandomAgent::new("b")];
+        let outcome = runner.run(&mut state, 7, &mut agents);
+
+        assert_eq!(outcome.reason, EndReason::Terminal);
+        assert!(outcome.is_finished());
+        assert!(outcome.moves() > 0);
+
+        let events = log.events();
+        assert!(matches!(events.first(), Some(RunnerEvent::GameStarted { seed: 7, .. })));
+        assert!(matches!(events.last(), Some(RunnerEvent::GameEnded { reason: EndReason::Terminal, .. })));
+        let moves = events
+            .iter()
+            .filter(|e| matches!(e, RunnerEvent::MoveMade { .. }))
+            .count();
+        assert_eq!(moves, outcome.moves());
+    }
+
+    #[test]
+    fn test_run_is_deterministic() {
+        let play = || {
+            let (engine, mut state) = game();
+            let mut agents = [RandomAgent::new("a"), RandomAgent::new("b")];
+            GameRunner::new(engine, RunnerConfig::new()).run(&mut state, 3, &mut agents)
+        };
+        assert_eq!(play(), play());
+    }
+
+    /// SimpleGame where every action ends the turn.
+    struct TurnPerMove(SimpleGame);
+
+    impl RulesEngine for TurnPerMove {
+        fn config(&self) -> &GameConfig {
+            self.0.config()
+        }
+
+        fn legal_templates(&self, state: &GameState, player: PlayerId) -> Vec<TemplateId> {
+            self.0.legal_templates(state, player)
+        }
+
+        fn legal_pointers(
+            &self,
+            state: &GameState,
+            player: PlayerId,
+            template: TemplateId,
+            prior_pointers: &[EntityId],
+        ) -> Vec<EntityId> {
+            self.0.legal_pointers(state, player, template, prior_pointers)
+        }
+
+        fn apply_action(&mut self, state: &mut GameState, player: PlayerId, action: &Action) {
+            self.0.apply_action(state, player, action);
+            state.public.advance_turn_preserve_state();
+        }
+
+        fn is_terminal(&self, state: &GameState) -> Option<GameResult> {
+            self.0.is_terminal(state)
+        }
+
+        fn choice_template(&self) -> Option<TemplateId> {
+            self.0.choice_template()
+        }
+    }
+
+    #[test]
+    fn test_turn_limit_and_events() {
+        let (engine, mut state) = game();
+        let log = EventLog::new();
+        let mut runner = GameRunner::new(TurnPerMove(engine), RunnerConfig::new().with_max_turns(3))
+            .with_observer(log.clone());
+
+        let mut agents = [ScriptedAgent::new("pass", |_: &GameState, _: PlayerId, _: &[Action]| Some(0))];
+        let outcome = runner.run(&mut state, 1, &mut agents);
+
+        assert_eq!(outcome.reason, EndReason::MaxTurns);
+        assert_eq!(outcome.turns, 3);
+        assert!(!outcome.is_finished());
+
+        let turns: Vec<u32> = log
+            .events()
+            .into_iter()
+            .filter_map(|e| match e {
+                RunnerEvent::TurnAdvanced { turn, .. } => Some(turn),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(turns, vec![2, 3, 4]);
+
+        // Sequence numbers restart each turn
+        assert!(outcome.history.iter().all(|r| r.sequence == 0));
+    }
+
+    #[test]
+    fn test_max_moves() {
+        let (engine, mut state) = game();
+        let mut runner = GameRunner::new(engine, RunnerConfig::new().with_max_moves(2));
+        let mut agents = [RandomAgent::new("solo")];
+
+        let outcome = runner.run(&mut state, 1, &mut agents);
+        assert_eq!(outcome.reason, EndReason::MaxMoves);
+        assert_eq!(outcome.moves(), 2);
+        assert_eq!(outcome.rewards[PlayerId::new(0)], 0.5);
+    }
+
+    /// Agent that always plays an action outside the game's templates.
+    struct Cheater;
+
+    impl Agent<SimpleGame> for Cheater {
+        fn name(&self) -> &str {
+            "cheater"
+        }
+
+        fn select_action(
+            &mut self,
+            _engine: &SimpleGame,
+            _state: &GameState,
+            _player: PlayerId,
+            _seed: u64,
+        ) -> Option<Action> {
+            Some(Action::new(TemplateId::new(999)))
+        }
+    }
+
+    #[test]
+    fn test_illegal_action_forfeits() {
+        let (engine, mut state) = game();
+        let first = state.public.active_player;
+        let mut runner = GameRunner::new(engine, RunnerConfig::new());
+
+        let outcome = runner.run(&mut state, 0, &mut [Cheater]);
+        assert_eq!(
+            outcome.reason,
+            EndReason::IllegalAction {
+                player: first,
+                action: Action::new(TemplateId::new(999)),
+            }
+        );
+        assert_eq!(outcome.moves(), 0);
+        assert!(outcome.result.as_ref().is_some_and(|r| !r.is_winner(first)));
+    }
+
+    #[test]
+    fn test_agent_without_action_stops() {
+        let (engine, mut state) = game();
+        let first = state.public.active_player;
+        let mut runner = GameRunner::new(engine, RunnerConfig::new());
+
+        let mut agents = [ScriptedAgent::new("idle", |_: &GameState, _: PlayerId, _: &[Action]| None)];
+        let outcome = runner.run(&mut state, 0, &mut agents);
+        assert_eq!(outcome.reason, EndReason::NoAction { player: first });
+        assert!(outcome.result.is_none());
+    }
+
+    #[test]
+    fn test_time_budget_forfeits() {
+        let (engine, mut state) = game();
+        let first = state.public.active_player;
+        let mut runner = GameRunner::new(
+            engine,
+            RunnerConfig::new().with_move_time_budget(Duration::from_millis(1)),
+        );
+
+        let mut agents = [ScriptedAgent::new("slow", |_: &GameState, _: PlayerId, _: &[Action]| {
+            std::thread::sleep(Duration::from_millis(20));
+            Some(0)
+        })];
+        let outcome = runner.run(&mut state, 0, &mut agents);
+
+        assert!(matches!(outcome.reason, EndReason::TimeExceeded { player, .. } if player == first));
+        assert!(outcome.reason.is_forfeit());
+        assert_eq!(outcome.rewards[first], 0.0);
+        assert_eq!(outcome.moves(), 0);
+    }
+
+    /// Player 0 plays a discard, then player 1 picks which of their two
+    /// cards goes.
+    #[derive(Clone)]
+    struct DiscardGame {
+        config: GameConfig,
+    }
+
+    const PLAY: TemplateId = TemplateId::new(0);
+    const CHOOSE: TemplateId = TemplateId::new(1);
+    const HAND: ZoneId = ZoneId::new(1);
+
+    impl DiscardGame {
+        fn new() -> (Self, GameState) {
+            let config = GameConfig::new(2)
+                .with_template(TemplateConfig::no_args(PLAY, "play"))
+                .with_template(TemplateConfig::new(CHOOSE, "choose", 0).with_variable_pointers());
+            let mut state = GameState::new(2, 42);
+            for i in 0..2 {
+                state.add_card(CardInstance::new(EntityId(10 + i), CardId::new(1), PlayerId::new(1), HAND));
+            }
+            (Self { config }, state)
+        }
+    }
+
+    impl RulesEngine for DiscardGame {
+        fn config(&self) -> &GameConfig {
+            &self.config
+        }
+
+        fn legal_templates(&self, state: &GameState, player: PlayerId) -> Vec<TemplateId> {
+            if player == state.public.active_player && state.zones.zone_size(HAND) == 2 {
+                vec![PLAY]
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn legal_pointers(&self, _: &GameState, _: PlayerId, _: TemplateId, _: &[EntityId]) -> Vec<EntityId> {
+            Vec::new()
+        }
+
+        fn apply_action(&mut self, state: &mut GameState, _: PlayerId, action: &Action) {
+            let context = ResolverContext::simple(2);
+            if action.template == PLAY {
+                let discard = Effect::choose(TargetSpec::single_card([HAND]), Effect::RemoveFromGame);
+                EffectResolver::resolve_single(state, &discard, EntityId::player_id(1), &context);
+            } else {
+                EffectResolver::resolve_choice(state, &action.pointers, &context).unwrap();
+            }
+        }
+
+        fn is_terminal(&self, state: &GameState) -> Option<GameResult> {
+            (state.zones.zone_size(HAND) < 2).then_some(GameResult::Winner(PlayerId::new(0)))
+        }
+
+        fn choice_template(&self) -> Option<TemplateId> {
+            Some(CHOOSE)
+        }
+    }
+
+    #[test]
+    fn test_non_active_player_resolves_choice() {
+        let (engine, mut state) = DiscardGame::new();
+        let mut runner = GameRunner::new(engine, RunnerConfig::new());
+        let mut agents = [RandomAgent::new("a"), RandomAgent::new("b")];
+        let outcome = runner.run(&mut state, 5, &mut agents);
+
+        assert_eq!(outcome.reason, EndReason::Terminal);
+        let moves: Vec<_> = outcome.history.iter().map(|r| (r.player, r.action.template)).collect();
+        assert_eq!(moves, vec![(PlayerId::new(0), PLAY), (PlayerId::new(1), CHOOSE)]);
+        assert_eq!(state.public.active_player, PlayerId::new(0));
+
+        // Search plans through the other player's choice too
+        let (engine, mut state) = DiscardGame::new();
+        let mut runner = GameRunner::new(engine, RunnerConfig::new());
+        let mut agents = [MctsAgent::new("a", 20), MctsAgent::new("b", 20)];
+        let outcome = runner.run(&mut state, 5, &mut agents);
+        assert_eq!(outcome.reason, EndReason::Terminal);
+        assert_eq!(outcome.moves(), 2);
+    }
+
+    #[test]
+    fn test_forfeit_result() {
+        assert_eq!(forfeit_result(PlayerId::new(0), 2), GameResult::Winner(PlayerId::new(1)));
+        assert_eq!(
+            forfeit_result(PlayerId::new(1), 3),
+            GameResult::Winners(vec![PlayerId::new(0), PlayerId::new(2)])
+        );
+    }
+}
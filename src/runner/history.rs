@@ -0,0 +1,326 @@
+//! Undo/redo and branching history for analysis tools.
+//!
+//! [`GameHistory`] keeps a tree of exact state snapshots, one per applied
+//! action. Moving around the tree is free, so a UI or debugger can step
+//! backward and forward, jump to any earlier position, and branch off an
+//! alternative line without losing the original. Snapshots share most of
+//! their structure through `im`'s persistent collections.
+
+use crate::core::{Action, GameState, PlayerId};
+use crate::rules::RulesEngine;
+
+/// Index of a position in a [`GameHistory`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HistoryNodeId(pub usize);
+
+impl HistoryNodeId {
+    /// The initial position.
+    pub const ROOT: HistoryNodeId = HistoryNodeId(0);
+}
+
+struct HistoryNode {
+    state: GameState,
+    parent: Option<HistoryNodeId>,
+    /// The move that led here from the parent.
+    action: Option<(PlayerId, Action)>,
+    children: Vec<HistoryNodeId>,
+    /// Child that `redo` follows (the most recently visited).
+    redo: Option<HistoryNodeId>,
+}
+
+/// Tree of game positions with a cursor.
+///
+/// Applying an action at the cursor reuses an existing child for the same
+/// move, or starts a new branch; either way the cursor moves to it.
+/// [`undo`](Self::undo) returns to the parent and [`redo`](Self::redo)
+/// follows the most recently visited child.
+pub struct GameHistory {
+    nodes: Vec<HistoryNode>,
+    cursor: HistoryNodeId,
+}
+
+impl GameHistory {
+    /// Start a history at `initial`.
+    pub fn new(initial: GameState) -> Self {
+        Self {
+            nodes: vec![HistoryNode {
+                state: initial,
+                parent: None,
+                action: None,
+                children: Vec::new(),
+                redo: None,
+            }],
+            cursor: HistoryNodeId::ROOT,
+        }
+    }
+
+    /// Get the state at the cursor.
+    pub fn current(&self) -> &GameState {
+        &self.node(self.cursor).state
+    }
+
+    /// Get the state at a position.
+    pub fn state(&self, id: HistoryNodeId) -> Option<&GameState> {
+        self.nodes.get(id.0).map(|node| &node.state)
+    }
+
+    /// Get an exact copy of the state at the cursor, e.g. to run a search
+    /// without disturbing the history.
+    pub fn snapshot(&self) -> GameState {
+        self.current().snapshot()
+    }
+
+    /// Get the cursor position.
+    pub fn position(&self) -> HistoryNodeId {
+        self.cursor
+    }
+
+    /// Number of moves from the initial position to the cursor.
+    pub fn depth(&self) -> usize {
+        self.path().len()
+    }
+
+    /// Total number of positions stored, across all branches.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check if only the initial position is stored.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
+    }
+
+    /// Apply an action at the cursor and move to the resulting position.
+    pub fn apply<E: RulesEngine>(&mut self, engine: &mut E, player: PlayerId, action: Action) -> HistoryNodeId {
+        let parent = self.cursor;
+        let existing = self
+            .node(parent)
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.node(child).action.as_ref() == Some(&(player, action.clone())));
+
+        let child = match existing {
+            Some(child) => child,
+            None => {
+                let mut state = self.current().snapshot();
+                engine.apply_action(&mut state, player, &action);
+
+                let child = HistoryNodeId(self.nodes.len());
+                self.nodes.push(HistoryNode {
+                    state,
+                    parent: Some(parent),
+                    action: Some((player, action)),
+                    children: Vec::new(),
+                    redo: None,
+                });
+                self.nodes[parent.0].children.push(child);
+                child
+            }
+        };
+
+        self.nodes[parent.0].redo = Some(child);
+        self.cursor = child;
+        child
+    }
+
+    /// Check if there is a position before the cursor.
+    pub fn can_undo(&self) -> bool {
+        self.node(self.cursor).parent.is_some()
+    }
+
+    /// Check if there is a position to redo.
+    pub fn can_redo(&self) -> bool {
+        self.node(self.cursor).redo.is_some()
+    }
+
+    /// Step back one move. Returns false at the initial position.
+    pub fn undo(&mut self) -> bool {
+        match self.node(self.cursor).parent {
+            Some(parent) => {
+                self.cursor = parent;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Step forward along the most recently visited branch. Returns false
+    /// if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.node(self.cursor).redo {
+            Some(child) => {
+                self.cursor = child;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the cursor to any stored position. Returns false if `id` is
+    /// unknown.
+    ///
+    /// The path to `id` becomes the redo line, so undoing and redoing
+    /// from there retraces it.
+    pub fn goto(&mut self, id: HistoryNodeId) -> bool {
+        if id.0 >= self.nodes.len() {
+            return false;
+        }
+
+        let mut node = id;
+        while let Some(parent) = self.node(node).parent {
+            self.nodes[parent.0].redo = Some(node);
+            node = parent;
+        }
+        self.cursor = id;
+        true
+    }
+
+    /// Moves from the initial position to the cursor.
+    pub fn path(&self) -> Vec<(PlayerId, Action)> {
+        let mut path = Vec::new();
+        let mut node = self.cursor;
+        while let Some(parent) = self.node(node).parent {
+            if let Some(action) = &self.node(node).action {
+                path.push(action.clone());
+            }
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Branches from a position: each child with the move that leads to it.
+    pub fn children(&self, id: HistoryNodeId) -> Vec<(HistoryNodeId, &(PlayerId, Action))> {
+        let Some(node) = self.nodes.get(id.0) else {
+            return Vec::new();
+        };
+        node.children
+            .iter()
+            .filter_map(|&child| self.node(child).action.as_ref().map(|action| (child, action)))
+            .collect()
+    }
+
+    fn node(&self, id: HistoryNodeId) -> &HistoryNode {
+        &self.nodes[id.0]
+    }
+}
+
+impl std::fmt::Debug for GameHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameHistory")
+            .field("positions", &self.nodes.len())
+            .field("cursor", &self.cursor)
+            .field("depth", &self.depth())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{Agent, MctsAgent};
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+
+    fn game() -> (SimpleGame, GameHistory) {
+        let (engine, state) = SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(42);
+        (engine, GameHistory::new(state))
+    }
+
+    fn play_first(engine: &mut SimpleGame, history: &mut GameHistory) -> HistoryNodeId {
+        let player = history.current().public.active_player;
+        let action = engine.legal_actions(history.current(), player)[0].clone();
+        history.apply(engine, player, action)
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let (mut engine, mut history) = game();
+        let initial = history.current().state_hash();
+
+        play_first(&mut engine, &mut history);
+        let after_one = history.current().state_hash();
+        play_first(&mut engine, &mut history);
+        assert_eq!(history.depth(), 2);
+
+        assert!(history.undo());
+        assert_eq!(history.current().state_hash(), after_one);
+        assert!(history.undo());
+        assert_eq!(history.current().state_hash(), initial);
+        assert!(!history.undo());
+
+        assert!(history.redo());
+        assert!(history.redo());
+        assert!(!history.can_redo());
+        assert_eq!(history.depth(), 2);
+    }
+
+    #[test]
+    fn test_history_matches_direct_play() {
+        let (mut engine, mut history) = game();
+        let (mut direct_engine, mut direct) = SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(42);
+
+        for _ in 0..6 {
+            let player = direct.public.active_player;
+            let action = direct_engine.legal_actions(&direct, player)[0].clone();
+            direct_engine.apply_action(&mut direct, player, &action);
+            history.apply(&mut engine, player, action);
+        }
+        assert_eq!(history.current().state_hash(), direct.state_hash());
+    }
+
+    #[test]
+    fn test_branching() {
+        let (mut engine, mut history) = game();
+        let player = history.current().public.active_player;
+        let actions = engine.legal_actions(history.current(), player);
+        assert!(actions.len() > 1);
+
+        let main = history.apply(&mut engine, player, actions[0].clone());
+        history.undo();
+        let alt = history.apply(&mut engine, player, actions[1].clone());
+        assert_ne!(main, alt);
+        assert_eq!(history.children(HistoryNodeId::ROOT).len(), 2);
+
+        // Redo follows the latest branch; goto switches back
+        history.undo();
+        history.redo();
+        assert_eq!(history.position(), alt);
+        assert!(history.goto(main));
+        history.undo();
+        history.redo();
+        assert_eq!(history.position(), main);
+
+        // Re-applying a known move reuses its position
+        history.undo();
+        assert_eq!(history.apply(&mut engine, player, actions[0].clone()), main);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.path(), vec![(player, actions[0].clone())]);
+    }
+
+    #[test]
+    fn test_search_from_position() {
+        let (mut engine, mut history) = game();
+        play_first(&mut engine, &mut history);
+        let before = history.current().state_hash();
+
+        let scratch = history.snapshot();
+        let player = scratch.public.active_player;
+        let mut agent = MctsAgent::new("mcts", 20);
+        let suggestion = agent
+            .select_action(&engine, &scratch, player, 0)
+            .unwrap();
+
+        assert_eq!(history.current().state_hash(), before);
+        history.undo();
+        history.redo();
+        history.apply(&mut engine, player, suggestion);
+        assert_eq!(history.depth(), 2);
+    }
+}
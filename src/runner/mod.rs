@@ -0,0 +1,43 @@
+//! Game runner: the standard match loop.
+//!
+//! [`GameRunner`] drives a [`RulesEngine`](crate::rules::RulesEngine)
+//! with one [`Agent`](crate::agents::Agent) per seat:
+//! - Enforces legality (an illegal action forfeits)
+//! - Enforces move and turn limits and an optional per-move time budget
+//! - Records an [`ActionRecord`](crate::core::ActionRecord) per move
+//! - Emits a typed [`RunnerEvent`] stream to subscribed [`GameObserver`]s
+//!
+//! A [`ReplayRecorder`] observer turns the stream into a [`Replay`] file
+//! that can be saved and later re-executed with [`Replay::verify`], and
+//! [`GameHistory`] gives analysis tools undo/redo and branching over exact
+//! state snapshots.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use rust_ccg::agents::RandomAgent;
+//! use rust_ccg::games::simple::SimpleGameBuilder;
+//! use rust_ccg::runner::{EventLog, GameRunner, RunnerConfig};
+//!
+//! let (engine, mut state) = SimpleGameBuilder::new().player_count(2).build(42);
+//! let log = EventLog::new();
+//! let mut runner = GameRunner::new(engine, RunnerConfig::new().with_max_turns(50))
+//!     .with_observer(log.clone());
+//!
+//! let mut agents = [RandomAgent::new("a"), RandomAgent::new("b")];
+//! let outcome = runner.run(&mut state, 42, &mut agents);
+//! assert_eq!(log.len(), outcome.moves() + outcome.turns as usize + 2);
+//! ```
+
+pub mod events;
+pub mod game_runner;
+pub mod history;
+pub mod replay;
+
+pub use events::{EndReason, EventLog, GameObserver, RunnerEvent};
+pub use game_runner::{outcome_rewards, GameOutcome, GameRunner, RunnerConfig};
+pub use history::{GameHistory, HistoryNodeId};
+pub use replay::{
+    Replay, ReplayError, ReplayHeader, ReplayMove, ReplayRecorder, ReplayResult,
+    REPLAY_FORMAT_VERSION, REPLAY_MAGIC,
+};
@@ -0,0 +1,556 @@
+//! Replay files: record a game and play it back deterministically.
+//!
+//! A game is fully determined by its seed and its action sequence, so a
+//! [`Replay`] stores just those plus enough fingerprints to notice when a
+//! playback has diverged:
+//! - **Header**: engine id, [`GameConfig::fingerprint`], seed, the agent in
+//!   each seat and the hash of the initial state
+//! - **Body**: one [`ReplayMove`] per action, with the state hash at
+//!   checkpoints and, optionally, the agent's search summary
+//! - **Footer**: why the game ended and the final state hash
+//!
+//! Record with a [`ReplayRecorder`] subscribed to a [`GameRunner`], save
+//! with [`Replay::save`], and check with [`Replay::verify`].
+//!
+//! ## File Layout
+//!
+//! ```text
+//! [magic "CCGREPL\0"][version u32 LE][bincode Replay]
+//! ```
+//!
+//! [`GameConfig::fingerprint`]: crate::core::GameConfig::fingerprint
+//! [`GameRunner`]: super::GameRunner
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::SearchResult;
+use crate::core::{ActionRecord, GameState};
+use crate::rules::RulesEngine;
+
+use super::events::{EndReason, GameObserver, RunnerEvent};
+
+/// Magic bytes at the start of every replay file.
+pub const REPLAY_MAGIC: [u8; 8] = *b"CCGREPL\0";
+
+/// Current replay format version.
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+/// Identifier recorded for an engine type.
+pub fn engine_id<E: RulesEngine>() -> String {
+    std::any::type_name::<E>().to_string()
+}
+
+/// Errors from saving, loading or verifying a replay.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// Underlying I/O failure.
+    Io(io::Error),
+
+    /// Serialization failure.
+    Encode(bincode::Error),
+
+    /// Deserialization failure.
+    Decode(bincode::Error),
+
+    /// The data does not start with [`REPLAY_MAGIC`].
+    BadMagic,
+
+    /// The file was written with an unknown format version.
+    UnsupportedFormat(u32),
+
+    /// The replay was recorded with a different engine.
+    EngineMismatch {
+        /// Engine id in the replay.
+        expected: String,
+        /// Engine id being verified against.
+        found: String,
+    },
+
+    /// The replay was recorded with a different game configuration.
+    ConfigMismatch {
+        /// Config fingerprint in the replay.
+        expected: u64,
+        /// Fingerprint of the engine's config.
+        found: u64,
+    },
+
+    /// A recorded action is not legal when played back.
+    IllegalAction {
+        /// Index of the move.
+        index: usize,
+    },
+
+    /// The state hash differs from the recording.
+    ///
+    /// `index` is the move after which the check failed, or `None` for the
+    /// initial state.
+    Diverged {
+        /// Move after which the hashes differ.
+        index: Option<usize>,
+        /// Recorded hash.
+        expected: u64,
+        /// Hash during playback.
+        found: u64,
+    },
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "I/O error: {}", e),
+            ReplayError::Encode(e) => write!(f, "failed to encode replay: {}", e),
+            ReplayError::Decode(e) => write!(f, "failed to decode replay: {}", e),
+            ReplayError::BadMagic => write!(f, "not a replay file"),
+            ReplayError::UnsupportedFormat(version) => {
+                write!(f, "unsupported replay format (version {})", version)
+            }
+            ReplayError::EngineMismatch { expected, found } => write!(
+                f,
+                "replay was recorded with engine {} but is played with {}",
+                expected, found
+            ),
+            ReplayError::ConfigMismatch { expected, found } => write!(
+                f,
+                "game config fingerprint {:#018x} does not match recorded {:#018x}",
+                found, expected
+            ),
+            ReplayError::IllegalAction { index } => {
+                write!(f, "recorded move {} is illegal on playback", index)
+            }
+            ReplayError::Diverged {
+                index: Some(index),
+                expected,
+                found,
+            } => write!(
+                f,
+                "state diverged after move {}: hash {:#018x}, recorded {:#018x}",
+                index, found, expected
+            ),
+            ReplayError::Diverged {
+                index: None,
+                expected,
+                found,
+            } => write!(
+                f,
+                "initial state differs: hash {:#018x}, recorded {:#018x}",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Io(e) => Some(e),
+            ReplayError::Encode(e) | ReplayError::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+/// Result alias for replay operations.
+pub type ReplayResult<T> = Result<T, ReplayError>;
+
+/// Identifies the game a replay belongs to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    /// Engine type the game was played with (see [`engine_id`]).
+    pub engine_id: String,
+
+    /// Fingerprint of the engine's [`GameConfig`](crate::core::GameConfig).
+    pub config_hash: u64,
+
+    /// Seed the game was built and run with.
+    pub seed: u64,
+
+    /// Name of the agent in each seat.
+    pub agents: Vec<String>,
+
+    /// Hash of the state before the first move.
+    pub initial_hash: u64,
+}
+
+/// One recorded move.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayMove {
+    /// The action and who took it.
+    pub record: ActionRecord,
+
+    /// State hash after the move, at checkpoints.
+    pub state_hash: Option<u64>,
+
+    /// The agent's search statistics, if recorded.
+    pub search: Option<SearchResult>,
+}
+
+/// A recorded game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Game identification.
+    pub header: ReplayHeader,
+
+    /// Moves in order.
+    pub moves: Vec<ReplayMove>,
+
+    /// Why the game ended.
+    pub end: Option<EndReason>,
+
+    /// Hash of the final state.
+    pub final_hash: u64,
+}
+
+impl Replay {
+    /// Number of recorded moves.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Check if no moves were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Iterate over the recorded actions.
+    pub fn actions(&self) -> impl Iterator<Item = &ActionRecord> {
+        self.moves.iter().map(|m| &m.record)
+    }
+
+    /// Re-execute the replay and check it matches the recording.
+    ///
+    /// `state` must be the game's initial state (built from
+    /// `header.seed`); it is left at the final position. Checks the engine
+    /// id and config fingerprint, the initial hash, that every action is
+    /// legal, every checkpoint hash and the final hash.
+    pub fn verify<E: RulesEngine>(&self, engine: &mut E, state: &mut GameState) -> ReplayResult<()> {
+        let found = engine_id::<E>();
+        if found != self.header.engine_id {
+            return Err(ReplayError::EngineMismatch {
+                expected: self.header.engine_id.clone(),
+                found,
+            });
+        }
+        let found = engine.config().fingerprint();
+        if found != self.header.config_hash {
+            return Err(ReplayError::ConfigMismatch {
+                expected: self.header.config_hash,
+                found,
+            });
+        }
+        check_hash(None, self.header.initial_hash, state)?;
+
+        for (index, m) in self.moves.iter().enumerate() {
+            let ActionRecord { player, action, .. } = &m.record;
+            if !engine.legal_actions(state, *player).contains(action) {
+                return Err(ReplayError::IllegalAction { index });
+            }
+            engine.apply_action(state, *player, action);
+
+            if let Some(expected) = m.state_hash {
+                check_hash(Some(index), expected, state)?;
+            }
+        }
+
+        let last = self.moves.len().checked_sub(1);
+        check_hash(last, self.final_hash, state)
+    }
+
+    /// Write the replay in the versioned binary format.
+    pub fn write_to(&self, mut writer: impl Write) -> ReplayResult<()> {
+        writer.write_all(&REPLAY_MAGIC)?;
+        writer.write_all(&REPLAY_FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self).map_err(ReplayError::Encode)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a replay written by [`write_to`](Self::write_to).
+    pub fn read_from(mut reader: impl Read) -> ReplayResult<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedFormat(version));
+        }
+
+        bincode::deserialize_from(reader).map_err(ReplayError::Decode)
+    }
+
+    /// Save to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> ReplayResult<()> {
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+
+    /// Load from a file.
+    pub fn load(path: impl AsRef<Path>) -> ReplayResult<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+fn check_hash(index: Option<usize>, expected: u64, state: &GameState) -> ReplayResult<()> {
+    let found = state.state_hash();
+    if found != expected {
+        return Err(ReplayError::Diverged {
+            index,
+            expected,
+            found,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+struct RecorderState {
+    header: Option<ReplayHeader>,
+    moves: Vec<ReplayMove>,
+    finished: Option<Replay>,
+}
+
+/// Observer that records a [`Replay`] from a runner's event stream.
+///
+/// Clones share the same recording, so keep one handle and subscribe
+/// another. The replay is available from [`replay`](Self::replay) once the
+/// game has ended; a new game started on the same recorder replaces it.
+#[derive(Clone, Debug)]
+pub struct ReplayRecorder {
+    engine_id: String,
+    config_hash: u64,
+    checkpoint_interval: usize,
+    record_search: bool,
+    state: Arc<Mutex<RecorderState>>,
+}
+
+impl ReplayRecorder {
+    /// Create a recorder for games played with `engine`.
+    ///
+    /// By default every move is checkpointed and search summaries are kept.
+    pub fn new<E: RulesEngine>(engine: &E) -> Self {
+        Self {
+            engine_id: engine_id::<E>(),
+            config_hash: engine.config().fingerprint(),
+            checkpoint_interval: 1,
+            record_search: true,
+            state: Arc::new(Mutex::new(RecorderState {
+                header: None,
+                moves: Vec::new(),
+                finished: None,
+            })),
+        }
+    }
+
+    /// Store a state hash every `interval` moves (0 = final hash only).
+    pub fn with_checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Set whether per-move search summaries are stored.
+    pub fn with_search_summaries(mut self, record: bool) -> Self {
+        self.record_search = record;
+        self
+    }
+
+    /// Get the replay of the last finished game.
+    pub fn replay(&self) -> Option<Replay> {
+        self.lock().finished.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl GameObserver for ReplayRecorder {
+    fn on_event(&mut self, event: &RunnerEvent, state: &GameState) {
+        let mut recording = self.lock();
+        match event {
+            RunnerEvent::GameStarted { seed, agents, .. } => {
+                recording.header = Some(ReplayHeader {
+                    engine_id: self.engine_id.clone(),
+                    config_hash: self.config_hash,
+                    seed: *seed,
+                    agents: agents.clone(),
+                    initial_hash: state.state_hash(),
+                });
+                recording.moves.clear();
+            }
+            RunnerEvent::MoveMade { record, search, .. } => {
+                let number = recording.moves.len() + 1;
+                let checkpoint = self.checkpoint_interval > 0 && number.is_multiple_of(self.checkpoint_interval);
+                let search = if self.record_search { search.clone() } else { None };
+                recording.moves.push(ReplayMove {
+                    record: record.clone(),
+                    state_hash: checkpoint.then(|| state.state_hash()),
+                    search,
+                });
+            }
+            RunnerEvent::TurnAdvanced { .. } => {}
+            RunnerEvent::GameEnded { reason, .. } => {
+                if let Some(header) = recording.header.take() {
+                    let moves = std::mem::take(&mut recording.moves);
+                    recording.finished = Some(Replay {
+                        header,
+                        moves,
+                        end: Some(reason.clone()),
+                        final_hash: state.state_hash(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{MctsAgent, RandomAgent};
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+    use crate::runner::{GameRunner, RunnerConfig};
+
+    fn build(seed: u64) -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(seed)
+    }
+
+    fn record(seed: u64, interval: usize) -> Replay {
+        let (engine, mut state) = build(seed);
+        let recorder = ReplayRecorder::new(&engine).with_checkpoint_interval(interval);
+        let mut runner = GameRunner::new(engine, RunnerConfig::new()).with_observer(recorder.clone());
+
+        let mut agents = [RandomAgent::new("random"), RandomAgent::new("other")];
+        runner.run(&mut state, seed, &mut agents);
+        recorder.replay().expect("game finished")
+    }
+
+    #[test]
+    fn test_record_and_verify() {
+        let replay = record(5, 1);
+        assert!(!replay.is_empty());
+        assert_eq!(replay.header.seed, 5);
+        assert_eq!(replay.header.agents, vec!["random", "other"]);
+        assert_eq!(replay.end, Some(EndReason::Terminal));
+        assert!(replay.moves.iter().all(|m| m.state_hash.is_some()));
+
+        let (mut engine, mut state) = build(5);
+        replay.verify(&mut engine, &mut state).unwrap();
+        assert_eq!(state.state_hash(), replay.final_hash);
+    }
+
+    #[test]
+    fn test_checkpoint_interval() {
+        let replay = record(5, 3);
+        for (i, m) in replay.moves.iter().enumerate() {
+            assert_eq!(m.state_hash.is_some(), (i + 1) % 3 == 0);
+        }
+
+        let (mut engine, mut state) = build(5);
+        assert!(replay.verify(&mut engine, &mut state).is_ok());
+    }
+
+    #[test]
+    fn test_verify_detects_wrong_seed() {
+        let replay = record(5, 1);
+        let (mut engine, mut state) = build(6);
+        assert!(matches!(
+            replay.verify(&mut engine, &mut state),
+            Err(ReplayError::Diverged { index: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let mut replay = record(5, 1);
+        replay.moves[0].state_hash = Some(replay.moves[0].state_hash.unwrap() ^ 1);
+
+        let (mut engine, mut state) = build(5);
+        assert!(matches!(
+            replay.verify(&mut engine, &mut state),
+            Err(ReplayError::Diverged { index: Some(0), .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_detects_config_mismatch() {
+        let replay = record(5, 1);
+        let (mut engine, mut state) = SimpleGameBuilder::new()
+            .player_count(3)
+            .starting_life(5)
+            .build(5);
+        assert!(matches!(
+            replay.verify(&mut engine, &mut state),
+            Err(ReplayError::ConfigMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_search_summaries_recorded() {
+        let (engine, mut state) = build(2);
+        let recorder = ReplayRecorder::new(&engine);
+        let mut runner = GameRunner::new(engine, RunnerConfig::new().with_max_moves(4))
+            .with_observer(recorder.clone());
+
+        let mut agents = [MctsAgent::new("mcts", 10)];
+        runner.run(&mut state, 2, &mut agents);
+        let replay = recorder.replay().unwrap();
+
+        assert_eq!(replay.end, Some(EndReason::MaxMoves));
+        assert!(replay.moves.iter().all(|m| m.search.is_some()));
+
+        // Searching forks the RNG, but playback without search still matches
+        let (mut engine, mut state) = build(2);
+        replay.verify(&mut engine, &mut state).unwrap();
+    }
+
+    #[test]
+    fn test_roundtrip_bytes() {
+        let replay = record(9, 2);
+        let mut bytes = Vec::new();
+        replay.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..8], &REPLAY_MAGIC);
+        assert_eq!(Replay::read_from(bytes.as_slice()).unwrap(), replay);
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert!(matches!(Replay::read_from(bad.as_slice()), Err(ReplayError::BadMagic)));
+
+        let mut future = bytes;
+        future[8..12].copy_from_slice(&99u32.to_le_bytes());
+        assert!(matches!(
+            Replay::read_from(future.as_slice()),
+            Err(ReplayError::UnsupportedFormat(99))
+        ));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let replay = record(3, 4);
+        let path = std::env::temp_dir().join(format!("rust-ccg-replay-{}.ccgr", std::process::id()));
+
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, replay);
+        let (mut engine, mut state) = build(3);
+        loaded.verify(&mut engine, &mut state).unwrap();
+    }
+}
@@ -0,0 +1,307 @@
+//! Event loop connecting effect resolution to triggers.
+//!
+//! Resolving effects emits `GameEvent`s (see `ResolverEvents`). The event
+//! loop takes those events, finds matching triggers, and queues them into
+//! the active resolution system, repeating until nothing new fires.
+
+use crate::cards::CardRegistry;
+use crate::core::GameState;
+use crate::effects::ResolverContext;
+use crate::triggers::{ConditionContext, CustomConditionFn, GameEvent, TriggerRegistry, TriggerTiming};
+
+use super::{ResolutionStatus, ResolutionSystem};
+
+/// Default limit on process/dispatch rounds per `run`.
+const DEFAULT_MAX_ROUNDS: usize = 1000;
+
+/// Drives a [`ResolutionSystem`] and feeds emitted events to a
+/// [`TriggerRegistry`].
+///
+/// ## Behavior
+///
+/// - `dispatch()`: Finds `After` triggers for events and queues them
+/// - `run()`: Processes the resolution system, dispatching the events each
+///   step emits, until it completes or waits for priority
+///
+/// Each queued trigger uses up one of its remaining uses.
+///
+/// ## Example
+///
+/// ```
+/// use rust_ccg::core::{Action, GameState, PlayerId, TemplateId};
+/// use rust_ccg::effects::{Effect, EffectBatch, ResolverContext, ResolverEvents};
+/// use rust_ccg::stack::{EventLoop, ImmediateResolution, ResolutionStatus, ResolutionSystem};
+/// use rust_ccg::triggers::{EventTypeId, Trigger, TriggerCondition, TriggerId, TriggerRegistry};
+///
+/// const LIFE_CHANGED: EventTypeId = EventTypeId::new(1);
+///
+/// // Whenever anyone's life changes, player 1 gains a point of "fury"
+/// let mut triggers = TriggerRegistry::new();
+/// triggers.register_with_id(
+///     Trigger::new(TriggerId::new(1), "Fury", LIFE_CHANGED)
+///         .with_controller(PlayerId::new(1))
+///         .with_condition(TriggerCondition::HasTag("life".into()))
+///         .with_effect(Effect::modify_player("fury", 1)),
+/// );
+///
+/// let context = ResolverContext::simple(2)
+///     .with_events(ResolverEvents::new().with_player_state_changed(LIFE_CHANGED));
+///
+/// let mut state = GameState::new(2, 42);
+/// let mut resolution = ImmediateResolution::new();
+/// let mut batch = EffectBatch::new();
+/// batch.add_player(Effect::damage(3), PlayerId::new(1));
+/// resolution.queue_action(Action::new(TemplateId::new(0)), batch, PlayerId::new(0));
+///
+/// let status = EventLoop::new().run(&mut resolution, &mut state, &context, &mut triggers);
+/// assert_eq!(status, ResolutionStatus::Complete);
+/// assert_eq!(state.public.get_player_state(PlayerId::new(1), "fury", 0), 1);
+/// ```
+pub struct EventLoop<'a> {
+    /// Evaluator for `TriggerCondition::Custom`.
+    custom_eval: Option<&'a CustomConditionFn<'a>>,
+    /// Card definitions for predicate conditions.
+    cards: Option<&'a CardRegistry>,
+    /// Maximum process/dispatch rounds per `run`.
+    max_rounds: usize,
+}
+
+impl Default for EventLoop<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> EventLoop<'a> {
+    /// Create an event loop with no custom condition evaluator.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            custom_eval: None,
+            cards: None,
+            max_rounds: DEFAULT_MAX_ROUNDS,
+        }
+    }
+
+    /// Evaluate custom trigger conditions with this function (builder pattern).
+    #[must_use]
+    pub fn with_custom_eval(mut self, eval: &'a CustomConditionFn<'a>) -> Self {
+        self.custom_eval = Some(eval);
+        self
+    }
+
+    /// Read card types and attributes for trigger conditions from this
+    /// registry (builder pattern).
+    #[must_use]
+    pub fn with_cards(mut self, cards: &'a CardRegistry) -> Self {
+        self.cards = Some(cards);
+        self
+    }
+
+    /// Limit the rounds per `run` (builder pattern).
+    ///
+    /// Guards against triggers that keep triggering each other.
+    #[must_use]
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Queue the `After` triggers matching each event, in event order.
+    ///
+    /// Returns the number of triggered effects queued.
+    pub fn dispatch<R: ResolutionSystem + ?Sized>(
+        &self,
+        events: &[GameEvent],
+        state: &GameState,
+        triggers: &mut TriggerRegistry,
+        resolution: &mut R,
+    ) -> usize {
+        let mut queued = 0;
+        for event in events {
+            let mut ctx = ConditionContext::new(event, state);
+            ctx.eval_custom = self.custom_eval;
+            ctx.cards = self.cards;
+            let fired = triggers.find_triggers_in(&ctx, TriggerTiming::After);
+            for triggered in fired {
+                if let Some(trigger) = triggers.get_mut(triggered.trigger_id) {
+                    // An earlier event may have used up the last use
+                    if !trigger.can_fire() {
+                        continue;
+                    }
+                    trigger.use_trigger();
+                }
+                resolution.queue_triggered(triggered);
+                queued += 1;
+            }
+        }
+        queued
+    }
+
+    /// Process until resolution completes or a player has priority.
+    ///
+    /// Events emitted by each processing step are dispatched before the
+    /// next step. Events left in `context` beforehand are dispatched
+    /// first. Returns `Processing` if `max_rounds` was reached with work
+    /// remaining; calling `run` again continues.
+    pub fn run<R: ResolutionSystem + ?Sized>(
+        &self,
+        resolution: &mut R,
+        state: &mut GameState,
+        context: &ResolverContext,
+        triggers: &mut TriggerRegistry,
+    ) -> ResolutionStatus {
+        self.dispatch(&context.take_events(), state, triggers, resolution);
+
+        for _ in 0..self.max_rounds {
+            let status = resolution.process(state, context);
+            let queued = self.dispatch(&context.take_events(), state, triggers, resolution);
+            match status {
+                ResolutionStatus::Processing => {}
+                ResolutionStatus::Complete if queued > 0 => {}
+                _ => return status,
+            }
+        }
+        ResolutionStatus::Processing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{CardId, CardInstance};
+    use crate::core::{Action, EntityId, PlayerId, TemplateId, ZoneId};
+    use crate::effects::{Effect, EffectBatch, ResolverEvents};
+    use crate::stack::{ImmediateResolution, PriorityStack};
+    use crate::triggers::{EventTypeId, Trigger, TriggerCondition, TriggerId};
+
+    const CARD_MOVED: EventTypeId = EventTypeId::new(1);
+    const CARD_DRAWN: EventTypeId = EventTypeId::new(2);
+    const LIFE_CHANGED: EventTypeId = EventTypeId::new(3);
+
+    fn context() -> ResolverContext<'static> {
+        ResolverContext::simple(2).with_events(
+            ResolverEvents::new()
+                .with_card_moved(CARD_MOVED)
+                .with_card_drawn(CARD_DRAWN)
+                .with_player_state_changed(LIFE_CHANGED),
+        )
+    }
+
+    fn state_with_deck() -> GameState {
+        let mut state = GameState::new(2, 42);
+        let deck = ZoneId::new(0);
+        state.zones.init_ordered_zone(deck);
+        for i in 0..3 {
+            let entity = EntityId(10 + i);
+            state.add_card(CardInstance::new(entity, CardId::new(1), PlayerId::new(0), deck));
+        }
+        state
+    }
+
+    fn queue(resolution: &mut impl ResolutionSystem, effect: Effect, player: PlayerId) {
+        let mut batch = EffectBatch::new();
+        batch.add_player(effect, player);
+        resolution.queue_action(Action::new(TemplateId::new(0)), batch, player);
+    }
+
+    #[test]
+    fn test_draw_triggers_resolve() {
+        let mut triggers = TriggerRegistry::new();
+        // "Whenever you draw a card, lose 1 life"
+        triggers.register_with_id(
+            Trigger::new(TriggerId::new(1), "Cursed", CARD_DRAWN)
+                .with_controller(PlayerId::new(0))
+                .with_condition(TriggerCondition::ForPlayer(PlayerId::new(0)))
+                .with_effect(Effect::damage(1)),
+        );
+
+        let mut state = state_with_deck();
+        state.public.set_player_state(PlayerId::new(0), "life", 20);
+        let context = context();
+        let mut resolution = ImmediateResolution::new();
+        queue(&mut resolution, Effect::draw(2), PlayerId::new(0));
+
+        let status = EventLoop::new().run(&mut resolution, &mut state, &context, &mut triggers);
+
+        assert_eq!(status, ResolutionStatus::Complete);
+        assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), 18);
+        // The life changes were dispatched too (no listeners)
+        assert_eq!(context.pending_events(), 0);
+    }
+
+    #[test]
+    fn test_limited_uses_consumed() {
+        let mut triggers = TriggerRegistry::new();
+        triggers.register_with_id(
+            Trigger::new(TriggerId::new(1), "Once", CARD_MOVED)
+                .with_controller(PlayerId::new(1))
+                .with_uses(1)
+                .with_effect(Effect::modify_player("seen", 1)),
+        );
+
+        let mut state = state_with_deck();
+        let context = context();
+        let mut resolution = ImmediateResolution::new();
+        queue(&mut resolution, Effect::draw(3), PlayerId::new(0));
+
+        EventLoop::new().run(&mut resolution, &mut state, &context, &mut triggers);
+
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "seen", 0), 1);
+        assert!(!triggers.get(TriggerId::new(1)).unwrap().can_fire());
+    }
+
+    #[test]
+    fn test_runaway_triggers_stop() {
+        let mut triggers = TriggerRegistry::new();
+        // Every life change causes another one
+        triggers.register_with_id(
+            Trigger::new(TriggerId::new(1), "Echo", LIFE_CHANGED)
+                .with_controller(PlayerId::new(0))
+                .with_effect(Effect::damage(1)),
+        );
+
+        let mut state = GameState::new(2, 42);
+        let context = context();
+        let mut resolution = ImmediateResolution::new();
+        queue(&mut resolution, Effect::damage(1), PlayerId::new(0));
+
+        let status = EventLoop::new()
+            .with_max_rounds(5)
+            .run(&mut resolution, &mut state, &context, &mut triggers);
+
+        assert_eq!(status, ResolutionStatus::Processing);
+        assert!(!resolution.is_complete());
+        assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), -5);
+    }
+
+    #[test]
+    fn test_priority_stack_waits_with_triggers() {
+        let mut triggers = TriggerRegistry::new();
+        triggers.register_with_id(
+            Trigger::new(TriggerId::new(1), "Watcher", CARD_DRAWN)
+                .with_controller(PlayerId::new(1))
+                .with_effect(Effect::modify_player("draws_seen", 1)),
+        );
+
+        let mut state = state_with_deck();
+        let context = context();
+        let mut stack = PriorityStack::new(2);
+        queue(&mut stack, Effect::draw(1), PlayerId::new(0));
+        stack.pass(PlayerId::new(0));
+        stack.pass(PlayerId::new(1));
+
+        let event_loop = EventLoop::new();
+        let status = event_loop.run(&mut stack, &mut state, &context, &mut triggers);
+
+        // The draw resolved; the trigger is on the stack awaiting priority
+        assert_eq!(status, ResolutionStatus::WaitingForPriority(PlayerId::new(0)));
+        assert_eq!(stack.stack_size(), 1);
+
+        stack.pass(PlayerId::new(0));
+        stack.pass(PlayerId::new(1));
+        let status = event_loop.run(&mut stack, &mut state, &context, &mut triggers);
+        assert_eq!(status, ResolutionStatus::Complete);
+        assert_eq!(state.public.get_player_state(PlayerId::new(1), "draws_seen", 0), 1);
+    }
+}
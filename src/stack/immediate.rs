@@ -5,11 +5,12 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Action, GameState, PlayerId};
-use crate::effects::{EffectBatch, EffectResolver, ResolverContext};
+use crate::core::{Action, EntityId, GameState, PlayerId};
+use crate::effects::{ChoiceError, EffectBatch, EffectOrigin, EffectResolver, ResolverContext, TargetSpec};
 use crate::triggers::TriggeredEffect;
 
-use super::{ResolutionStatus, ResolutionSystem};
+use super::targets::recheck_targets;
+use super::{PendingTargets, ResolutionStatus, ResolutionSystem};
 
 /// A pending effect waiting to be resolved.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -18,6 +19,35 @@ struct PendingEffect {
     effects: EffectBatch,
     /// Who controls this effect (for targeting decisions).
     controller: PlayerId,
+    /// Controller, source and triggering event, for effect amounts.
+    origin: EffectOrigin,
+    /// A triggered effect whose targets are chosen when it comes up.
+    /// `effects` is empty until then.
+    targeting: Option<TriggeredEffect>,
+    /// Targets chosen when the effect was queued.
+    targets: Vec<EntityId>,
+    /// Spec the targets must still meet when the effect comes up.
+    target_spec: Option<TargetSpec>,
+}
+
+impl PendingEffect {
+    fn new(effects: EffectBatch, controller: PlayerId, origin: EffectOrigin) -> Self {
+        Self {
+            effects,
+            controller,
+            origin,
+            targeting: None,
+            targets: Vec::new(),
+            target_spec: None,
+        }
+    }
+
+    fn triggered(triggered: &TriggeredEffect, effects: EffectBatch) -> Self {
+        // Use controller if available, otherwise default to player 0
+        // (consistent with PriorityStack behavior)
+        let controller = triggered.controller.unwrap_or(PlayerId::new(0));
+        Self::new(effects, controller, EffectOrigin::from_triggered(triggered))
+    }
 }
 
 /// Immediate resolution system.
@@ -31,11 +61,14 @@ struct PendingEffect {
 /// - `queue_action()`: Adds effects to pending queue
 /// - `queue_triggered()`: Adds triggered effects to pending queue
 /// - `process()`: Resolves all pending effects immediately, returns `Complete`
+///   (or waits for a choice or for a triggered effect's targets)
 /// - `priority_player()`: Always returns `None` (no priority in immediate mode)
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ImmediateResolution {
     /// Pending effects to resolve.
     pending: Vec<PendingEffect>,
+    /// Whether a triggered effect is waiting on the game state for targets.
+    awaiting_targets: bool,
 }
 
 impl ImmediateResolution {
@@ -59,45 +92,94 @@ impl ResolutionSystem for ImmediateResolution {
         controller: PlayerId,
     ) {
         if !effects.is_empty() {
-            self.pending.push(PendingEffect { effects, controller });
+            let origin = EffectOrigin::for_player(controller);
+            self.pending.push(PendingEffect::new(effects, controller, origin));
+        }
+    }
+
+    fn queue_targeted(
+        &mut self,
+        _source_action: Action,
+        effects: EffectBatch,
+        controller: PlayerId,
+        spec: TargetSpec,
+        targets: Vec<EntityId>,
+    ) {
+        if !effects.is_empty() {
+            let mut pending = PendingEffect::new(effects, controller, EffectOrigin::for_player(controller));
+            pending.targets = targets;
+            pending.target_spec = Some(spec);
+            self.pending.push(pending);
         }
     }
 
     fn queue_triggered(&mut self, triggered: TriggeredEffect) {
-        if !triggered.effects.is_empty() {
-            let mut batch = EffectBatch::new();
-            for effect in triggered.effects {
-                // For triggered effects, apply to controller by default
-                // Games can override targeting via the effect itself
-                if let Some(controller) = triggered.controller {
-                    batch.add_player(effect, controller);
-                } else {
-                    // No controller, add as zone effect (no target)
-                    batch.add_zone(effect);
-                }
+        if triggered.effects.is_empty() {
+            return;
+        }
+        if triggered.target.is_some() {
+            let mut pending = PendingEffect::triggered(&triggered, EffectBatch::new());
+            pending.targeting = Some(triggered);
+            self.pending.push(pending);
+            return;
+        }
+
+        let mut batch = EffectBatch::new();
+        for effect in &triggered.effects {
+            // For triggered effects, apply to controller by default
+            // Games can override targeting via the effect itself
+            if let Some(controller) = triggered.controller {
+                batch.add_player(effect.clone(), controller);
+            } else {
+                // No controller, add as zone effect (no target)
+                batch.add_zone(effect.clone());
             }
-            // Use controller if available, otherwise default to player 0
-            // (consistent with PriorityStack behavior)
-            let controller = triggered.controller.unwrap_or(PlayerId::new(0));
-            self.pending.push(PendingEffect {
-                effects: batch,
-                controller,
-            });
         }
+        self.pending.push(PendingEffect::triggered(&triggered, batch));
     }
 
     fn process(&mut self, state: &mut GameState, context: &ResolverContext) -> ResolutionStatus {
-        // Resolve all pending effects immediately
-        while let Some(pending) = self.pending.pop() {
-            let _results = EffectResolver::resolve_batch(state, &pending.effects, context);
-            // In immediate mode, we don't stop for anything - just keep resolving
+        // Resolve all pending effects immediately, stopping only for choices
+        if let Some(choice) = state.pending_choice() {
+            return ResolutionStatus::WaitingForChoice(choice.player);
+        }
+        if let Some(targeting) = state.pending_targets() {
+            return ResolutionStatus::WaitingForTargets(targeting.controller());
+        }
+        while let Some(mut pending) = self.pending.pop() {
+            if let Some(targeting) = pending
+                .targeting
+                .take()
+                .and_then(|triggered| PendingTargets::new(triggered, state, context))
+            {
+                match targeting.forced_targets() {
+                    Some(targets) => pending.effects = targeting.into_parts(&targets).1,
+                    None => {
+                        let controller = targeting.controller();
+                        state.set_pending_targets(targeting);
+                        self.awaiting_targets = true;
+                        return ResolutionStatus::WaitingForTargets(controller);
+                    }
+                }
+            }
+            if let Some(spec) = &pending.target_spec {
+                let source = pending.origin.source;
+                let (targets, effects) = (&mut pending.targets, &mut pending.effects);
+                if !recheck_targets(spec, pending.controller, source, targets, effects, state, context) {
+                    continue;
+                }
+            }
+            let _results = EffectResolver::resolve_batch_from(state, &pending.effects, &pending.origin, context);
+            if let Some(choice) = state.pending_choice() {
+                return ResolutionStatus::WaitingForChoice(choice.player);
+            }
         }
 
         ResolutionStatus::Complete
     }
 
     fn is_complete(&self) -> bool {
-        self.pending.is_empty()
+        self.pending.is_empty() && !self.awaiting_targets
     }
 
     fn priority_player(&self) -> Option<PlayerId> {
@@ -105,14 +187,28 @@ impl ResolutionSystem for ImmediateResolution {
         None
     }
 
+    fn choose_targets(&mut self, state: &mut GameState, targets: &[EntityId]) -> Result<(), ChoiceError> {
+        let targeting = state.pending_targets().ok_or(ChoiceError::NoPendingChoice)?;
+        targeting.check(targets)?;
+        if let Some(targeting) = state.take_pending_targets() {
+            let (triggered, batch) = targeting.into_parts(targets);
+            self.pending.push(PendingEffect::triggered(&triggered, batch));
+        }
+        self.awaiting_targets = false;
+        Ok(())
+    }
+
     fn clear(&mut self) {
         self.pending.clear();
+        self.awaiting_targets = false;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cards::CardId;
+    use crate::core::EntityId;
     use crate::effects::Effect;
 
     #[test]
@@ -173,6 +269,105 @@ mod tests {
         assert_eq!(resolver.pending_count(), 0);
     }
 
+    #[test]
+    fn test_process_waits_for_choice() {
+        use crate::core::ZoneId;
+        use crate::effects::{EffectResolver, TargetSpec};
+
+        let mut state = GameState::new(2, 42);
+        let context = ResolverContext::simple(2);
+        let hand = ZoneId::new(3);
+        EffectResolver::resolve_single(&mut state, &Effect::create_in_hand(CardId::new(1)), EntityId::player_id(1), &context);
+        EffectResolver::resolve_single(&mut state, &Effect::create_in_hand(CardId::new(2)), EntityId::player_id(1), &context);
+
+        let mut resolver = ImmediateResolution::new();
+        let mut batch = EffectBatch::new();
+        batch.add_player(
+            Effect::choose(TargetSpec::single_card([hand]), Effect::RemoveFromGame),
+            PlayerId::new(1),
+        );
+        resolver.queue_action(Action::new(crate::core::TemplateId::new(1)), batch, PlayerId::new(0));
+
+        let status = resolver.process(&mut state, &context);
+        assert_eq!(status, ResolutionStatus::WaitingForChoice(PlayerId::new(1)));
+        assert_eq!(resolver.process(&mut state, &context), status);
+
+        let card = state.pending_choice().unwrap().options[0];
+        EffectResolver::resolve_choice(&mut state, &[card], &context).unwrap();
+        assert_eq!(resolver.process(&mut state, &context), ResolutionStatus::Complete);
+        assert_eq!(state.zones.zone_size(hand), 1);
+    }
+
+    #[test]
+    fn test_triggered_targets() {
+        use crate::effects::TargetSpec;
+        use crate::triggers::{EventTypeId, GameEvent, TriggerId, TriggerTiming};
+
+        let mut state = GameState::new(3, 42);
+        state.public.set_player_state(PlayerId::new(2), "life", 20);
+        let context = ResolverContext::simple(3);
+        let mut resolver = ImmediateResolution::new();
+        resolver.queue_triggered(TriggeredEffect {
+            trigger_id: TriggerId::new(1),
+            controller: Some(PlayerId::new(0)),
+            source: None,
+            effects: vec![Effect::damage(2)],
+            target: Some(TargetSpec::single_opponent()),
+            modifiers: Vec::new(),
+            triggering_event: GameEvent::new(EventTypeId::new(1)),
+            timing: TriggerTiming::After,
+        });
+
+        let status = resolver.process(&mut state, &context);
+        assert_eq!(status, ResolutionStatus::WaitingForTargets(PlayerId::new(0)));
+        assert_eq!(resolver.process(&mut state, &context), status);
+        assert!(!resolver.is_complete());
+        assert_eq!(
+            resolver.choose_targets(&mut state, &[]),
+            Err(ChoiceError::WrongCount { min: 1, max: 1, found: 0 })
+        );
+
+        resolver.choose_targets(&mut state, &[EntityId::player_id(2)]).unwrap();
+        assert!(state.pending_targets().is_none());
+        assert_eq!(resolver.process(&mut state, &context), ResolutionStatus::Complete);
+        assert_eq!(state.public.get_player_state(PlayerId::new(2), "life", 0), 18);
+    }
+
+    #[test]
+    fn test_queue_targeted_drops_illegal_targets() {
+        use crate::cards::CardInstance;
+        use crate::core::{TemplateId, ZoneId};
+        use crate::effects::{TargetCount, TargetSpec};
+
+        let battlefield = ZoneId::new(4);
+        let graveyard = ZoneId::new(5);
+        let mut state = GameState::new(2, 42);
+        for id in [10, 11] {
+            state.add_card(CardInstance::new(EntityId(id), CardId::new(1), PlayerId::new(1), battlefield));
+        }
+        let context = ResolverContext::simple(2);
+        let mut resolver = ImmediateResolution::new();
+
+        // Mark both creatures; one leaves the battlefield first
+        let targets = vec![EntityId(10), EntityId(11)];
+        let mut batch = EffectBatch::new();
+        batch.add(Effect::modify_card("marked", 1), targets.clone());
+        resolver.queue_targeted(
+            Action::new(TemplateId::new(1)),
+            batch,
+            PlayerId::new(0),
+            TargetSpec::multiple_cards([battlefield], TargetCount::UpTo(2)),
+            targets,
+        );
+        let mut response = EffectBatch::new();
+        response.add_single(Effect::move_to(graveyard), EntityId(10));
+        resolver.queue_action(Action::new(TemplateId::new(2)), response, PlayerId::new(1));
+
+        assert_eq!(resolver.process(&mut state, &context), ResolutionStatus::Complete);
+        assert_eq!(state.get_card(EntityId(10)).unwrap().get_state("marked", 0), 0);
+        assert_eq!(state.get_card(EntityId(11)).unwrap().get_state("marked", 0), 1);
+    }
+
     #[test]
     fn test_clear() {
         let mut resolver = ImmediateResolution::new();
@@ -10,6 +10,11 @@
 //! `ResolutionSystem` implementation. The engine provides the infrastructure;
 //! games decide when and how effects resolve.
 //!
+//! An [`EventLoop`] drives either system and queues the triggers fired by
+//! the events that resolution emits. Triggers with a target spec wait on
+//! the game state as [`PendingTargets`] for their controller to choose
+//! targets.
+//!
 //! ## Example Usage
 //!
 //! ```
@@ -31,16 +36,20 @@
 //! assert!(matches!(status, ResolutionStatus::Complete));
 //! ```
 
+mod event_loop;
 mod immediate;
 mod priority;
+mod targets;
 
+pub use event_loop::EventLoop;
 pub use immediate::ImmediateResolution;
 pub use priority::{PriorityStack, StackEntry, StackEntryId, StackSource};
+pub use targets::PendingTargets;
 
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Action, GameState, PlayerId};
-use crate::effects::{EffectBatch, ResolverContext};
+use crate::core::{Action, EntityId, GameState, PlayerId};
+use crate::effects::{ChoiceError, EffectBatch, ResolverContext, TargetSpec};
 use crate::triggers::TriggeredEffect;
 
 /// Status returned by resolution processing.
@@ -54,6 +63,16 @@ pub enum ResolutionStatus {
 
     /// Still processing (more effects to resolve).
     Processing,
+
+    /// Waiting for a player to make a pending choice
+    /// (see [`EffectResolver::resolve_choice`]).
+    ///
+    /// [`EffectResolver::resolve_choice`]: crate::effects::EffectResolver::resolve_choice
+    WaitingForChoice(PlayerId),
+
+    /// Waiting for a player to choose targets for a triggered effect
+    /// (see [`ResolutionSystem::choose_targets`]).
+    WaitingForTargets(PlayerId),
 }
 
 /// Trait for resolution systems.
@@ -74,6 +93,22 @@ pub trait ResolutionSystem {
         controller: PlayerId,
     );
 
+    /// Queue an action's effects with the targets chosen for them.
+    ///
+    /// Like [`queue_action`](Self::queue_action), but the effects remember
+    /// `spec`, and targets that no longer meet it when they resolve are
+    /// dropped. Default ignores the spec.
+    fn queue_targeted(
+        &mut self,
+        source_action: Action,
+        effects: EffectBatch,
+        controller: PlayerId,
+        _spec: TargetSpec,
+        _targets: Vec<EntityId>,
+    ) {
+        self.queue_action(source_action, effects, controller);
+    }
+
     /// Queue a triggered effect for resolution.
     ///
     /// Triggered effects come from the trigger registry when events fire.
@@ -85,6 +120,8 @@ pub trait ResolutionSystem {
     /// - `Complete`: All effects resolved, nothing left to do
     /// - `WaitingForPriority(player)`: Player must pass or respond
     /// - `Processing`: More effects to resolve (call again)
+    /// - `WaitingForChoice(player)`: Player must resolve the pending choice
+    /// - `WaitingForTargets(player)`: Player must choose targets
     fn process(&mut self, state: &mut GameState, context: &ResolverContext) -> ResolutionStatus;
 
     /// Check if resolution is complete (stack empty, no pending effects).
@@ -95,6 +132,16 @@ pub trait ResolutionSystem {
     /// Returns `None` for immediate resolution mode or when no one has priority.
     fn priority_player(&self) -> Option<PlayerId>;
 
+    /// Choose targets for the triggered effect waiting for them.
+    ///
+    /// Takes the [`PendingTargets`] off the game state and queues the
+    /// effects against the chosen targets; call [`process`](Self::process)
+    /// to continue. Nothing changes on error. Default is an error for
+    /// systems that don't support targeted triggers.
+    fn choose_targets(&mut self, _state: &mut GameState, _targets: &[EntityId]) -> Result<(), ChoiceError> {
+        Err(ChoiceError::NoPendingChoice)
+    }
+
     /// Clear all pending effects and reset state.
     fn clear(&mut self);
 }
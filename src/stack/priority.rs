@@ -3,14 +3,19 @@
 //! Effects are pushed onto a stack and resolve in LIFO order.
 //! Players pass priority in turn order; when all pass, the top
 //! of the stack resolves.
+//!
+//! Entries with a target spec re-check their targets when they resolve.
+//! Targets that are no longer legal are dropped; an entry with none left
+//! fizzles and does nothing.
 
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Action, GameState, PlayerId};
-use crate::effects::{EffectBatch, EffectResolver, ResolverContext};
+use crate::core::{Action, EntityId, GameState, PlayerId};
+use crate::effects::{ChoiceError, EffectBatch, EffectOrigin, EffectResolver, ResolverContext, TargetSpec};
 use crate::triggers::{GameEvent, TriggeredEffect, TriggerId};
 
-use super::{ResolutionStatus, ResolutionSystem};
+use super::targets::recheck_targets;
+use super::{PendingTargets, ResolutionStatus, ResolutionSystem};
 
 /// Unique identifier for a stack entry.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -46,6 +51,9 @@ pub enum StackSource {
     Triggered {
         trigger_id: TriggerId,
         event: GameEvent,
+        /// The entity whose trigger this is.
+        #[serde(default)]
+        source: Option<EntityId>,
     },
 
     /// A response/instant effect.
@@ -68,6 +76,49 @@ pub struct StackEntry {
 
     /// What caused this entry.
     pub source: StackSource,
+
+    /// Targets chosen when the entry was queued.
+    #[serde(default)]
+    pub targets: Vec<EntityId>,
+
+    /// Spec the targets must still meet on resolution.
+    #[serde(default)]
+    pub target_spec: Option<TargetSpec>,
+}
+
+impl StackEntry {
+    /// The origin the entry's effects resolve with.
+    #[must_use]
+    pub fn origin(&self) -> EffectOrigin {
+        let origin = EffectOrigin::for_player(self.controller);
+        match &self.source {
+            StackSource::Triggered { event, source, .. } => {
+                let origin = origin.with_event(event.clone());
+                match source {
+                    Some(source) => origin.with_source(*source),
+                    None => origin,
+                }
+            }
+            _ => origin,
+        }
+    }
+
+    /// Drop targets that no longer meet the entry's target spec.
+    ///
+    /// The dropped targets are removed from the effects as well. Returns
+    /// `false` if the entry had targets and none are left (it fizzles).
+    /// Entries without a spec or targets are left alone. Targets are
+    /// checked with the context's registry, config and custom filters.
+    pub fn recheck_targets(&mut self, state: &GameState, context: &ResolverContext) -> bool {
+        let Some(spec) = &self.target_spec else {
+            return true;
+        };
+        let source = match &self.source {
+            StackSource::Triggered { source, .. } => *source,
+            _ => None,
+        };
+        recheck_targets(spec, self.controller, source, &mut self.targets, &mut self.effects, state, context)
+    }
 }
 
 /// Priority-based stack resolution system.
@@ -92,6 +143,9 @@ pub struct PriorityStack {
     /// Triggered effects waiting to be added to stack.
     pending_triggers: Vec<TriggeredEffect>,
 
+    /// Whether a triggered effect is waiting on the game state for targets.
+    awaiting_targets: bool,
+
     /// Player who currently has priority.
     current_priority: PlayerId,
 
@@ -114,6 +168,7 @@ impl PriorityStack {
         Self {
             entries: Vec::new(),
             pending_triggers: Vec::new(),
+            awaiting_targets: false,
             current_priority: PlayerId::new(0),
             consecutive_passes: 0,
             player_count,
@@ -126,6 +181,7 @@ impl PriorityStack {
         Self {
             entries: Vec::new(),
             pending_triggers: Vec::new(),
+            awaiting_targets: false,
             current_priority: starting_priority,
             consecutive_passes: 0,
             player_count,
@@ -180,7 +236,36 @@ impl PriorityStack {
     /// Player responds by adding effects to the stack.
     ///
     /// This resets the pass counter and gives the responding player priority.
+    /// The response has no target spec and never fizzles; use
+    /// [`respond_targeted`](Self::respond_targeted) for one that should.
     pub fn respond(&mut self, effects: EffectBatch, controller: PlayerId, description: String) {
+        self.push_response(effects, controller, description, Vec::new(), None);
+    }
+
+    /// Player responds with effects on targets chosen for them.
+    ///
+    /// Like [`respond`](Self::respond), but the targets are re-checked
+    /// against `spec` when the response resolves, as for
+    /// [`queue_targeted`](ResolutionSystem::queue_targeted).
+    pub fn respond_targeted(
+        &mut self,
+        effects: EffectBatch,
+        controller: PlayerId,
+        description: String,
+        spec: TargetSpec,
+        targets: Vec<EntityId>,
+    ) {
+        self.push_response(effects, controller, description, targets, Some(spec));
+    }
+
+    fn push_response(
+        &mut self,
+        effects: EffectBatch,
+        controller: PlayerId,
+        description: String,
+        targets: Vec<EntityId>,
+        target_spec: Option<TargetSpec>,
+    ) {
         let id = StackEntryId::new(self.next_id);
         self.next_id += 1;
 
@@ -189,6 +274,8 @@ impl PriorityStack {
             effects,
             controller,
             source: StackSource::Response { description },
+            targets,
+            target_spec,
         });
 
         // Reset passes and give priority to the responding player
@@ -200,6 +287,10 @@ impl PriorityStack {
     ///
     /// Triggers are sorted by priority (higher first) before being added.
     /// Call this after resolving a stack entry to add any triggered effects.
+    ///
+    /// Stops at the first trigger with a target spec: its targets are
+    /// chosen by [`process`](ResolutionSystem::process), which flushes the
+    /// rest afterwards.
     pub fn flush_triggers(&mut self) {
         if self.pending_triggers.is_empty() {
             return;
@@ -213,42 +304,94 @@ impl PriorityStack {
         });
 
         // Add to stack in order (first trigger = bottom of new batch)
-        for triggered in self.pending_triggers.drain(..) {
-            let id = StackEntryId::new(self.next_id);
-            self.next_id += 1;
-
+        let targeted = self
+            .pending_triggers
+            .iter()
+            .position(|triggered| triggered.target.is_some())
+            .unwrap_or(self.pending_triggers.len());
+        let untargeted: Vec<_> = self.pending_triggers.drain(..targeted).collect();
+        for triggered in untargeted {
             let mut batch = EffectBatch::new();
-            for effect in triggered.effects {
+            for effect in triggered.effects.iter().cloned() {
                 if let Some(controller) = triggered.controller {
                     batch.add_player(effect, controller);
                 } else {
                     batch.add_zone(effect);
                 }
             }
-
-            let controller = triggered.controller.unwrap_or(PlayerId::new(0));
-
-            self.entries.push(StackEntry {
-                id,
-                effects: batch,
-                controller,
-                source: StackSource::Triggered {
-                    trigger_id: triggered.trigger_id,
-                    event: triggered.triggering_event,
-                },
-            });
+            self.push_triggered(triggered, batch, Vec::new());
         }
 
         // Reset passes after adding triggers
         self.consecutive_passes = 0;
     }
 
+    /// Flush pending triggers, choosing targets where there's no choice.
+    ///
+    /// Returns the player who must choose targets, if any.
+    fn flush_with_targets(&mut self, state: &mut GameState, context: &ResolverContext) -> Option<PlayerId> {
+        loop {
+            self.flush_triggers();
+            if self.pending_triggers.is_empty() {
+                return None;
+            }
+            let triggered = self.pending_triggers.remove(0);
+            let pending = PendingTargets::new(triggered, state, context)?;
+            match pending.forced_targets() {
+                Some(targets) => self.push_targeted(pending, &targets),
+                None => {
+                    let controller = pending.controller();
+                    state.set_pending_targets(pending);
+                    self.awaiting_targets = true;
+                    return Some(controller);
+                }
+            }
+        }
+    }
+
+    /// Push a targeted trigger's entry. Without targets it does nothing.
+    fn push_targeted(&mut self, pending: PendingTargets, targets: &[EntityId]) {
+        let (triggered, batch) = pending.into_parts(targets);
+        if !batch.is_empty() {
+            self.push_triggered(triggered, batch, targets.to_vec());
+        }
+        self.consecutive_passes = 0;
+    }
+
+    /// Push an entry for a triggered effect.
+    fn push_triggered(&mut self, triggered: TriggeredEffect, effects: EffectBatch, targets: Vec<EntityId>) {
+        let id = StackEntryId::new(self.next_id);
+        self.next_id += 1;
+
+        let controller = triggered.controller.unwrap_or(PlayerId::new(0));
+
+        self.entries.push(StackEntry {
+            id,
+            effects,
+            controller,
+            source: StackSource::Triggered {
+                trigger_id: triggered.trigger_id,
+                event: triggered.triggering_event,
+                source: triggered.source,
+            },
+            targets,
+            target_spec: triggered.target,
+        });
+    }
+
     /// Resolve the top of the stack.
     ///
+    /// An entry whose targets are all illegal fizzles instead (see
+    /// [`StackEntry::recheck_targets`]).
     /// Returns the resolved entry, or `None` if stack is empty.
     fn resolve_top(&mut self, state: &mut GameState, context: &ResolverContext) -> Option<StackEntry> {
-        let entry = self.entries.pop()?;
-        let _results = EffectResolver::resolve_batch(state, &entry.effects, context);
+        let mut entry = self.entries.pop()?;
+        if entry.recheck_targets(state, context) {
+            let _results = EffectResolver::resolve_batch_from(state, &entry.effects, &entry.origin(), context);
+        } else {
+            let source = entry.origin().source;
+            context.emit(context.events.entry_fizzled(entry.id.raw(), entry.controller, source));
+        }
 
         // Reset passes and give priority to active player (or entry controller)
         self.consecutive_passes = 0;
@@ -291,6 +434,8 @@ impl ResolutionSystem for PriorityStack {
             effects,
             controller,
             source: StackSource::Action(source_action),
+            targets: Vec::new(),
+            target_spec: None,
         });
 
         // Reset passes and give priority to controller
@@ -298,6 +443,24 @@ impl ResolutionSystem for PriorityStack {
         self.current_priority = controller;
     }
 
+    fn queue_targeted(
+        &mut self,
+        source_action: Action,
+        effects: EffectBatch,
+        controller: PlayerId,
+        spec: TargetSpec,
+        targets: Vec<EntityId>,
+    ) {
+        if effects.is_empty() {
+            return;
+        }
+        self.queue_action(source_action, effects, controller);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.targets = targets;
+            entry.target_spec = Some(spec);
+        }
+    }
+
     fn queue_triggered(&mut self, triggered: TriggeredEffect) {
         if !triggered.effects.is_empty() {
             self.pending_triggers.push(triggered);
@@ -305,9 +468,18 @@ impl ResolutionSystem for PriorityStack {
     }
 
     fn process(&mut self, state: &mut GameState, context: &ResolverContext) -> ResolutionStatus {
+        // A choice made during resolution comes before anything else
+        if let Some(choice) = state.pending_choice() {
+            return ResolutionStatus::WaitingForChoice(choice.player);
+        }
+
+        if let Some(pending) = state.pending_targets() {
+            return ResolutionStatus::WaitingForTargets(pending.controller());
+        }
+
         // First, flush any pending triggers to the stack
-        if !self.pending_triggers.is_empty() {
-            self.flush_triggers();
+        if let Some(player) = self.flush_with_targets(state, context) {
+            return ResolutionStatus::WaitingForTargets(player);
         }
 
         // If stack is empty, we're done
@@ -319,6 +491,9 @@ impl ResolutionSystem for PriorityStack {
         if self.consecutive_passes >= self.player_count {
             // Resolve top of stack
             self.resolve_top(state, context);
+            if let Some(choice) = state.pending_choice() {
+                return ResolutionStatus::WaitingForChoice(choice.player);
+            }
 
             // Check if more entries remain
             if self.entries.is_empty() && self.pending_triggers.is_empty() {
@@ -334,20 +509,31 @@ impl ResolutionSystem for PriorityStack {
     }
 
     fn is_complete(&self) -> bool {
-        self.entries.is_empty() && self.pending_triggers.is_empty()
+        self.entries.is_empty() && self.pending_triggers.is_empty() && !self.awaiting_targets
     }
 
     fn priority_player(&self) -> Option<PlayerId> {
-        if self.entries.is_empty() && self.pending_triggers.is_empty() {
+        if self.is_complete() {
             None
         } else {
             Some(self.current_priority)
         }
     }
 
+    fn choose_targets(&mut self, state: &mut GameState, targets: &[EntityId]) -> Result<(), ChoiceError> {
+        let pending = state.pending_targets().ok_or(ChoiceError::NoPendingChoice)?;
+        pending.check(targets)?;
+        if let Some(pending) = state.take_pending_targets() {
+            self.push_targeted(pending, targets);
+        }
+        self.awaiting_targets = false;
+        Ok(())
+    }
+
     fn clear(&mut self) {
         self.entries.clear();
         self.pending_triggers.clear();
+        self.awaiting_targets = false;
         self.consecutive_passes = 0;
     }
 }
@@ -544,4 +730,131 @@ mod tests {
         assert!(stack.is_complete());
         assert_eq!(stack.stack_size(), 0);
     }
+
+    /// Player 0's trigger dealing 2 damage to an opponent of their choice.
+    fn targeted_trigger() -> TriggeredEffect {
+        use crate::effects::TargetSpec;
+        use crate::triggers::{EventTypeId, TriggerTiming};
+
+        TriggeredEffect {
+            trigger_id: TriggerId::new(1),
+            controller: Some(PlayerId::new(0)),
+            source: None,
+            effects: vec![Effect::damage(2)],
+            target: Some(TargetSpec::single_opponent()),
+            modifiers: Vec::new(),
+            triggering_event: GameEvent::new(EventTypeId::new(1)),
+            timing: TriggerTiming::After,
+        }
+    }
+
+    #[test]
+    fn test_triggered_targets() {
+        let context = ResolverContext::simple(3);
+        let mut state = GameState::new(3, 42);
+        let mut stack = PriorityStack::new(3);
+        stack.queue_triggered(targeted_trigger());
+
+        let status = stack.process(&mut state, &context);
+        assert_eq!(status, ResolutionStatus::WaitingForTargets(PlayerId::new(0)));
+        assert!(!stack.is_complete());
+        assert_eq!(stack.stack_size(), 0);
+
+        let opponent = EntityId::player_id(2);
+        let pending = state.pending_targets().unwrap();
+        assert_eq!(pending.legal_pointers(&[]), vec![EntityId::player_id(1), opponent]);
+        assert!(stack.choose_targets(&mut state, &[EntityId::player_id(0)]).is_err());
+        stack.choose_targets(&mut state, &[opponent]).unwrap();
+        assert!(state.pending_targets().is_none());
+        assert!(!stack.is_complete());
+
+        let entry = stack.peek_top().unwrap();
+        assert_eq!(entry.targets, vec![opponent]);
+        assert_eq!(entry.effects.iter().next().unwrap().targets, vec![opponent]);
+
+        // With one opponent there is nothing to choose
+        let mut state = GameState::new(2, 42);
+        let mut stack = PriorityStack::new(2);
+        stack.queue_triggered(targeted_trigger());
+        assert_eq!(stack.process(&mut state, &context), ResolutionStatus::WaitingForPriority(PlayerId::new(0)));
+        assert_eq!(stack.peek_top().unwrap().targets, vec![EntityId::player_id(1)]);
+    }
+
+    #[test]
+    fn test_targets_rechecked_on_resolution() {
+        use crate::cards::{CardId, CardInstance};
+        use crate::core::ZoneId;
+        use crate::effects::{ResolverEvents, TargetCount, TargetSpec};
+        use crate::triggers::EventTypeId;
+
+        let fizzled = EventTypeId::new(9);
+        let context = ResolverContext::simple(2).with_events(ResolverEvents::new().with_entry_fizzled(fizzled));
+        let battlefield = ZoneId::new(4);
+        let graveyard = ZoneId::new(5);
+        let mut state = GameState::new(2, 42);
+        for i in 0..2 {
+            state.add_card(CardInstance::new(EntityId(10 + i), CardId::new(1), PlayerId::new(1), battlefield));
+        }
+
+        // Mark two creatures; one leaves the battlefield first
+        let spec = TargetSpec::multiple_cards([battlefield], TargetCount::Exactly(2));
+        let targets = vec![EntityId(10), EntityId(11)];
+        let mut stack = PriorityStack::new(2);
+        let mut batch = EffectBatch::new();
+        batch.add(Effect::modify_card("marked", 1), targets.clone());
+        stack.queue_targeted(Action::new(TemplateId::new(1)), batch.clone(), PlayerId::new(0), spec.clone(), targets.clone());
+        state.zones.move_to_zone(EntityId(10), graveyard, None);
+
+        stack.pass(PlayerId::new(0));
+        stack.pass(PlayerId::new(1));
+        stack.process(&mut state, &context);
+        assert_eq!(state.get_card(EntityId(10)).unwrap().get_state("marked", 0), 0);
+        assert_eq!(state.get_card(EntityId(11)).unwrap().get_state("marked", 0), 1);
+        assert!(context.take_events().is_empty());
+
+        // With both gone the entry fizzles
+        state.zones.move_to_zone(EntityId(11), graveyard, None);
+        stack.queue_targeted(Action::new(TemplateId::new(1)), batch, PlayerId::new(0), spec, targets);
+        stack.pass(PlayerId::new(0));
+        stack.pass(PlayerId::new(1));
+        assert_eq!(stack.process(&mut state, &context), ResolutionStatus::Complete);
+        assert_eq!(state.get_card(EntityId(11)).unwrap().get_state("marked", 0), 1);
+
+        let events = context.take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, fizzled);
+        assert_eq!(events[0].player, Some(PlayerId::new(0)));
+        assert_eq!(events[0].values, vec![1]);
+    }
+
+    #[test]
+    fn test_targeted_response_fizzles() {
+        use crate::cards::{CardId, CardInstance};
+        use crate::core::ZoneId;
+        use crate::effects::TargetSpec;
+
+        let context = ResolverContext::simple(2);
+        let battlefield = ZoneId::new(4);
+        let graveyard = ZoneId::new(5);
+        let mut state = GameState::new(2, 42);
+        state.add_card(CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(0), battlefield));
+
+        // Player 1 marks the creature; player 0 buries it in response
+        let mut stack = PriorityStack::new(2);
+        let mut mark = EffectBatch::new();
+        mark.add_single(Effect::modify_card("marked", 1), EntityId(10));
+        let spec = TargetSpec::single_card([battlefield]);
+        stack.respond_targeted(mark, PlayerId::new(1), "Mark".to_string(), spec, vec![EntityId(10)]);
+        let mut bury = EffectBatch::new();
+        bury.add_single(Effect::move_to(graveyard), EntityId(10));
+        stack.respond(bury, PlayerId::new(0), "Bury".to_string());
+
+        while !stack.is_complete() {
+            let player = stack.priority_player().unwrap();
+            stack.pass(player);
+            stack.process(&mut state, &context);
+        }
+        assert_eq!(state.zones.get_zone(EntityId(10)), Some(graveyard));
+        assert_eq!(state.get_card(EntityId(10)).unwrap().get_state("marked", 0), 0);
+    }
 }
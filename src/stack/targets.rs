@@ -0,0 +1,183 @@
+//! Target selection for triggered effects.
+//!
+//! A trigger with a [`TargetSpec`] has its controller choose targets
+//! before its effects are queued. Resolution systems store the triggered
+//! effect on the game state as [`PendingTargets`] (next to a
+//! `PendingChoice`) and report `ResolutionStatus::WaitingForTargets` until
+//! [`ResolutionSystem::choose_targets`] is called. A choice with one
+//! possible selection is made automatically.
+//!
+//! Like a `PendingChoice`, the valid targets are worked out when the
+//! trigger comes up, so rules engines can offer them without a resolver
+//! context: with a `choice_template`, `RulesEngine::legal_actions` lists
+//! each legal selection as an action for the controller, and
+//! `validate_action` checks it. Engines building pointers one at a time
+//! can use [`PendingTargets::legal_pointers`].
+//!
+//! [`TargetSpec`]: crate::effects::TargetSpec
+//! [`ResolutionSystem::choose_targets`]: super::ResolutionSystem::choose_targets
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Action, EntityId, GameState, PendingDecision, PlayerId, TemplateId};
+use crate::effects::{choice, ChoiceError, EffectBatch, ResolverContext, TargetSpec};
+use crate::triggers::TriggeredEffect;
+
+/// A triggered effect waiting for its controller to choose targets.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct PendingTargets {
+    triggered: TriggeredEffect,
+    controller: PlayerId,
+    /// Valid targets, in entity order.
+    options: Vec<EntityId>,
+    min: usize,
+    max: usize,
+}
+
+impl PendingDecision for PendingTargets {
+    const KIND: &'static str = "targets";
+}
+
+impl GameState {
+    /// Get the triggered effect waiting for targets.
+    #[must_use]
+    pub fn pending_targets(&self) -> Option<&PendingTargets> {
+        self.pending().get()
+    }
+
+    /// Set the triggered effect waiting for targets.
+    pub fn set_pending_targets(&mut self, targets: PendingTargets) {
+        self.pending_mut().set(targets);
+    }
+
+    /// Take the triggered effect waiting for targets, leaving none.
+    pub fn take_pending_targets(&mut self) -> Option<PendingTargets> {
+        self.pending_mut().take()
+    }
+}
+
+impl PendingTargets {
+    /// Wrap a triggered effect with its valid targets, if it has a target
+    /// spec.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the triggered effect has a target spec but no controller.
+    pub(crate) fn new(triggered: TriggeredEffect, state: &GameState, context: &ResolverContext) -> Option<Self> {
+        let spec = triggered.target.clone()?;
+        let controller = triggered
+            .controller
+            .unwrap_or_else(|| panic!("{} has a target spec but no controller to choose it", triggered.trigger_id));
+        let mut selector = context.target_selector(spec, controller);
+        if let Some(source) = triggered.source {
+            selector = selector.with_source(source);
+        }
+        let mut options = selector.valid_targets(state);
+        options.sort_unstable_by_key(|entity| entity.0);
+        let (min, max) = selector.spec().bounds(options.len());
+        Some(Self {
+            triggered,
+            controller,
+            options,
+            min,
+            max,
+        })
+    }
+
+    /// The triggered effect.
+    #[must_use]
+    pub fn triggered(&self) -> &TriggeredEffect {
+        &self.triggered
+    }
+
+    /// The player choosing targets: the trigger's controller.
+    #[must_use]
+    pub fn controller(&self) -> PlayerId {
+        self.controller
+    }
+
+    /// The valid targets, in entity order.
+    #[must_use]
+    pub fn options(&self) -> &[EntityId] {
+        &self.options
+    }
+
+    /// Fewest and most targets to choose.
+    #[must_use]
+    pub fn bounds(&self) -> (usize, usize) {
+        (self.min, self.max)
+    }
+
+    /// Legal next targets given the ones already chosen.
+    ///
+    /// Targets are chosen in entity order, as for
+    /// [`TargetSelector::legal_pointers`](crate::effects::TargetSelector::legal_pointers).
+    #[must_use]
+    pub fn legal_pointers(&self, prior: &[EntityId]) -> Vec<EntityId> {
+        choice::next_options(&self.options, self.min, self.max, prior)
+    }
+
+    /// Check a selection of targets.
+    pub fn check(&self, targets: &[EntityId]) -> Result<(), ChoiceError> {
+        choice::check_selection(&self.options, self.min, self.max, targets)
+    }
+
+    /// The legal selections as actions of a choice template.
+    ///
+    /// Each set of targets appears once, in entity order.
+    #[must_use]
+    pub fn actions(&self, template: TemplateId) -> Vec<Action> {
+        choice::selections(&self.options, self.min, self.max)
+            .iter()
+            .map(|selection| Action::with_pointers(template, selection))
+            .collect()
+    }
+
+    /// The targets if there is only one legal selection.
+    ///
+    /// With no valid targets this is the empty selection.
+    pub(crate) fn forced_targets(&self) -> Option<Vec<EntityId>> {
+        (self.min == self.options.len()).then(|| self.options.clone())
+    }
+
+    /// The trigger's effects applied to each target.
+    pub(crate) fn into_parts(self, targets: &[EntityId]) -> (TriggeredEffect, EffectBatch) {
+        let mut batch = EffectBatch::new();
+        if !targets.is_empty() {
+            for effect in &self.triggered.effects {
+                batch.add(effect.clone(), targets.to_vec());
+            }
+        }
+        (self.triggered, batch)
+    }
+}
+
+/// Drop `targets` that no longer meet `spec`, removing them from `effects`
+/// as well.
+///
+/// Returns `false` if there were targets and none are left. Targets are
+/// checked with the context's registry, config and custom filters.
+pub(crate) fn recheck_targets(
+    spec: &TargetSpec,
+    controller: PlayerId,
+    source: Option<EntityId>,
+    targets: &mut Vec<EntityId>,
+    effects: &mut EffectBatch,
+    state: &GameState,
+    context: &ResolverContext,
+) -> bool {
+    if targets.is_empty() {
+        return true;
+    }
+    let mut selector = context.target_selector(spec.clone(), controller);
+    if let Some(source) = source {
+        selector = selector.with_source(source);
+    }
+    let valid = selector.valid_targets(state);
+    let illegal: Vec<_> = targets.iter().copied().filter(|target| !valid.contains(target)).collect();
+    if !illegal.is_empty() {
+        targets.retain(|target| !illegal.contains(target));
+        effects.remove_targets(&illegal);
+    }
+    !targets.is_empty()
+}
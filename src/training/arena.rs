@@ -0,0 +1,986 @@
+//! Arena for evaluating agents against each other.
+//!
+//! The arena plays seeded games between a set of [`Agent`]s across every
+//! seat assignment, so no agent benefits from always moving first, and
+//! reports:
+//! - Pairwise win/draw/loss tables
+//! - Elo estimates (Bradley-Terry fit) with 95% confidence intervals
+//! - TrueSkill ratings
+//! - An optional SPRT decision for gating a candidate against a baseline,
+//!   which can stop the match early
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! use rust_ccg::agents::{MctsAgent, RandomAgent};
+//! use rust_ccg::training::arena::{Arena, ArenaConfig, SprtConfig};
+//!
+//! let mut arena = Arena::new(ArenaConfig::new().with_rounds(100).with_sprt(SprtConfig::new(0, 1)))
+//!     .with_agent(MctsAgent::new("mcts", 200))
+//!     .with_agent(RandomAgent::new("random"));
+//!
+//! let report = arena.run(|seed| SimpleGameBuilder::new().build(seed));
+//! println!("{}", report);
+//! ```
+
+use crate::agents::Agent;
+use crate::core::GameState;
+use crate::rules::RulesEngine;
+use crate::runner::{GameRunner, RunnerConfig};
+
+/// Sequential probability ratio test settings for gating.
+///
+/// Tests H0: the candidate is `elo0` stronger than the baseline, against
+/// H1: it is `elo1` stronger, using the normal approximation to the
+/// per-game score distribution.
+#[derive(Clone, Debug)]
+pub struct SprtConfig {
+    /// Agent index of the candidate.
+    pub candidate: usize,
+
+    /// Agent index of the baseline.
+    pub baseline: usize,
+
+    /// Elo difference under H0.
+    pub elo0: f64,
+
+    /// Elo difference under H1.
+    pub elo1: f64,
+
+    /// False positive rate (accepting H1 when H0 holds).
+    pub alpha: f64,
+
+    /// False negative rate (accepting H0 when H1 holds).
+    pub beta: f64,
+}
+
+impl SprtConfig {
+    /// SPRT of `candidate` against `baseline` with H0: +0 Elo, H1: +35 Elo,
+    /// and 5% error rates.
+    pub fn new(candidate: usize, baseline: usize) -> Self {
+        Self {
+            candidate,
+            baseline,
+            elo0: 0.0,
+            elo1: 35.0,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// Set the H0 and H1 Elo differences.
+    pub fn with_elo_bounds(mut self, elo0: f64, elo1: f64) -> Self {
+        self.elo0 = elo0;
+        self.elo1 = elo1;
+        self
+    }
+
+    /// Set the error rates.
+    pub fn with_error_rates(mut self, alpha: f64, beta: f64) -> Self {
+        self.alpha = alpha.clamp(1e-6, 0.5);
+        self.beta = beta.clamp(1e-6, 0.5);
+        self
+    }
+
+    /// Log-likelihood ratio bounds `(lower, upper)`.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Log-likelihood ratio of H1 over H0 for a pairwise record.
+    ///
+    /// Half a pseudo-game is added to each of wins, draws and losses so
+    /// one-sided records still have a non-zero score variance.
+    pub fn llr(&self, record: &PairRecord) -> f64 {
+        if record.games() == 0 {
+            return 0.0;
+        }
+        let (w, d, l) = (
+            record.wins as f64 + 0.5,
+            record.draws as f64 + 0.5,
+            record.losses as f64 + 0.5,
+        );
+        let n = w + d + l;
+        let score = (w + 0.5 * d) / n;
+        let variance =
+            (w * (1.0 - score).powi(2) + d * (0.5 - score).powi(2) + l * score.powi(2)) / n;
+        let s0 = elo_to_score(self.elo0);
+        let s1 = elo_to_score(self.elo1);
+        n * (s1 - s0) * (2.0 * score - s0 - s1) / (2.0 * variance)
+    }
+
+    /// Decide from a pairwise record.
+    pub fn decide(&self, record: &PairRecord) -> SprtResult {
+        let llr = self.llr(record);
+        let (lower, upper) = self.bounds();
+        let decision = if llr >= upper {
+            SprtDecision::AcceptH1
+        } else if llr <= lower {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        };
+        SprtResult {
+            llr,
+            lower,
+            upper,
+            decision,
+        }
+    }
+}
+
+/// Outcome of an SPRT check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SprtDecision {
+    /// Candidate is not stronger by `elo1` (reject it).
+    AcceptH0,
+    /// Candidate is stronger by at least `elo0` (accept it).
+    AcceptH1,
+    /// Not enough evidence yet.
+    Continue,
+}
+
+/// SPRT statistics at the end of an arena run.
+#[derive(Clone, Debug)]
+pub struct SprtResult {
+    /// Log-likelihood ratio of H1 over H0.
+    pub llr: f64,
+    /// Lower decision bound.
+    pub lower: f64,
+    /// Upper decision bound.
+    pub upper: f64,
+    /// Decision.
+    pub decision: SprtDecision,
+}
+
+/// Configuration for an arena run.
+#[derive(Clone, Debug)]
+pub struct ArenaConfig {
+    /// Number of rounds; each round plays one game per seat assignment.
+    pub rounds: usize,
+
+    /// Maximum moves per game before it is scored as unfinished.
+    pub max_moves: usize,
+
+    /// Base seed.
+    ///
+    /// Games are paired: within a round, every seat order of the same set
+    /// of agents plays the same seed, so seat advantage and deal luck
+    /// cancel out of the comparison.
+    pub seed: u64,
+
+    /// Optional SPRT, checked after every round.
+    pub sprt: Option<SprtConfig>,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self {
+            rounds: 50,
+            max_moves: 500,
+            seed: 0,
+            sprt: None,
+        }
+    }
+}
+
+impl ArenaConfig {
+    /// Create a new arena config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of rounds.
+    pub fn with_rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Set maximum moves per game.
+    pub fn with_max_moves(mut self, max: usize) -> Self {
+        self.max_moves = max;
+        self
+    }
+
+    /// Set the base seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Enable SPRT early stopping.
+    pub fn with_sprt(mut self, sprt: SprtConfig) -> Self {
+        self.sprt = Some(sprt);
+        self
+    }
+}
+
+/// Head-to-head record of one agent against another.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PairRecord {
+    /// Games where this agent scored higher.
+    pub wins: u32,
+    /// Games where both scored the same.
+    pub draws: u32,
+    /// Games where this agent scored lower.
+    pub losses: u32,
+}
+
+impl PairRecord {
+    /// Total games played.
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Score fraction, counting draws as half.
+    pub fn score(&self) -> f64 {
+        let games = self.games();
+        if games == 0 {
+            return 0.5;
+        }
+        (self.wins as f64 + 0.5 * self.draws as f64) / games as f64
+    }
+
+    /// Elo difference implied by the score, with a 95% confidence interval
+    /// `(elo, lower, upper)`.
+    pub fn elo_difference(&self) -> (f64, f64, f64) {
+        let n = self.games() as f64;
+        if n == 0.0 {
+            return (0.0, f64::NEG_INFINITY, f64::INFINITY);
+        }
+        let score = self.score();
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / n;
+        let margin = 1.96 * (variance / n).sqrt();
+        (
+            score_to_elo(score),
+            score_to_elo(score - margin),
+            score_to_elo(score + margin),
+        )
+    }
+
+    fn flipped(self) -> Self {
+        Self {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+        }
+    }
+}
+
+/// Elo estimate for one agent, relative to a field mean of 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EloEstimate {
+    /// Point estimate.
+    pub elo: f64,
+    /// Lower bound of the 95% confidence interval.
+    pub lower: f64,
+    /// Upper bound of the 95% confidence interval.
+    pub upper: f64,
+}
+
+/// TrueSkill rating (Gaussian skill belief).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrueSkillRating {
+    /// Mean skill.
+    pub mu: f64,
+    /// Skill uncertainty (standard deviation).
+    pub sigma: f64,
+}
+
+impl Default for TrueSkillRating {
+    fn default() -> Self {
+        Self {
+            mu: 25.0,
+            sigma: 25.0 / 3.0,
+        }
+    }
+}
+
+impl TrueSkillRating {
+    /// Conservative skill estimate `mu - 3 sigma`.
+    pub fn conservative(&self) -> f64 {
+        self.mu - 3.0 * self.sigma
+    }
+}
+
+/// Parameters of the TrueSkill model.
+const TRUESKILL_BETA: f64 = 25.0 / 6.0;
+const TRUESKILL_TAU: f64 = 25.0 / 300.0;
+const TRUESKILL_DRAW_PROBABILITY: f64 = 0.1;
+
+/// Record of one arena game.
+#[derive(Clone, Debug)]
+pub struct ArenaGame {
+    /// Seed the game was built with.
+    pub seed: u64,
+    /// Agent index in each seat.
+    pub seats: Vec<usize>,
+    /// Reward per seat.
+    pub rewards: Vec<f64>,
+    /// Number of moves played.
+    pub moves: usize,
+    /// Whether the game reached a terminal state.
+    pub finished: bool,
+}
+
+/// Results of an arena run.
+#[derive(Clone, Debug)]
+pub struct ArenaReport {
+    /// Agent names, by agent index.
+    pub agents: Vec<String>,
+    /// All games played.
+    pub games: Vec<ArenaGame>,
+    /// `records[i][j]`: agent `i`'s record against agent `j`.
+    pub records: Vec<Vec<PairRecord>>,
+    /// Elo estimates, by agent index.
+    pub elo: Vec<EloEstimate>,
+    /// TrueSkill ratings, by agent index.
+    pub trueskill: Vec<TrueSkillRating>,
+    /// SPRT result, if configured.
+    pub sprt: Option<SprtResult>,
+}
+
+impl ArenaReport {
+    /// Record of agent `a` against agent `b`.
+    pub fn record(&self, a: usize, b: usize) -> PairRecord {
+        self.records[a][b]
+    }
+
+    /// Total record of agent `a` against all other agents.
+    pub fn total_record(&self, a: usize) -> PairRecord {
+        self.records[a].iter().fold(PairRecord::default(), |acc, r| PairRecord {
+            wins: acc.wins + r.wins,
+            draws: acc.draws + r.draws,
+            losses: acc.losses + r.losses,
+        })
+    }
+}
+
+impl std::fmt::Display for ArenaReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Arena: {} games", self.games.len())?;
+        for (i, name) in self.agents.iter().enumerate() {
+            let total = self.total_record(i);
+            let elo = self.elo[i];
+            let ts = self.trueskill[i];
+            writeln!(
+                f,
+                "  {:<16} W/D/L {}/{}/{}  Elo {:+.0} [{:+.0}, {:+.0}]  TrueSkill {:.1} ± {:.1}",
+                name,
+                total.wins,
+                total.draws,
+                total.losses,
+                elo.elo,
+                elo.lower,
+                elo.upper,
+                ts.mu,
+                ts.sigma
+            )?;
+        }
+        if let Some(sprt) = &self.sprt {
+            writeln!(
+                f,
+                "  SPRT: LLR {:.2} [{:.2}, {:.2}] {:?}",
+                sprt.llr, sprt.lower, sprt.upper, sprt.decision
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays agents against each other.
+pub struct Arena<E: RulesEngine> {
+    agents: Vec<Box<dyn Agent<E>>>,
+    config: ArenaConfig,
+}
+
+impl<E: RulesEngine> Arena<E> {
+    /// Create an empty arena.
+    pub fn new(config: ArenaConfig) -> Self {
+        Self {
+            agents: Vec::new(),
+            config,
+        }
+    }
+
+    /// Add an agent (builder pattern).
+    pub fn with_agent(mut self, agent: impl Agent<E> + 'static) -> Self {
+        self.agents.push(Box::new(agent));
+        self
+    }
+
+    /// Add an agent, returning its index.
+    pub fn add_agent(&mut self, agent: Box<dyn Agent<E>>) -> usize {
+        self.agents.push(agent);
+        self.agents.len() - 1
+    }
+
+    /// Get the number of agents.
+    pub fn agent_count(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &ArenaConfig {
+        &self.config
+    }
+
+    /// Play the configured rounds and report results.
+    ///
+    /// Every ordered assignment of distinct agents to seats is played once
+    /// per round, so the number of agents must be at least the game's
+    /// player count. With SPRT configured, the run stops after the first
+    /// round that reaches a decision.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer agents than seats.
+    pub fn run(&mut self, game_builder: impl Fn(u64) -> (E, GameState)) -> ArenaReport {
+        let agent_count = self.agents.len();
+        let player_count = game_builder(self.config.seed).1.player_count();
+        assert!(
+            agent_count >= player_count,
+            "arena needs at least {} agents, got {}",
+            player_count,
+            agent_count
+        );
+
+        let assignments = seat_assignments(agent_count, player_count);
+        let (pairings, pairing_count) = pairings(&assignments);
+        let mut games = Vec::new();
+        let mut records = vec![vec![PairRecord::default(); agent_count]; agent_count];
+        let mut trueskill = vec![TrueSkillRating::default(); agent_count];
+        let mut sprt = None;
+
+        'rounds: for round in 0..self.config.rounds {
+            for (seats, &pairing) in assignments.iter().zip(&pairings) {
+                let game_index = (round * pairing_count + pairing) as u64;
+                let seed = self.config.seed.wrapping_add(game_index);
+                let game = self.play_game(&game_builder, seats, seed);
+                record_game(&game, &mut records, &mut trueskill);
+                games.push(game);
+            }
+
+            if let Some(config) = &self.config.sprt {
+                let result = config.decide(&records[config.candidate][config.baseline]);
+                let done = result.decision != SprtDecision::Continue;
+                sprt = Some(result);
+                if done {
+                    break 'rounds;
+                }
+            }
+        }
+
+        ArenaReport {
+            agents: self.agents.iter().map(|a| a.name().to_string()).collect(),
+            elo: fit_elo(&records),
+            games,
+            records,
+            trueskill,
+            sprt,
+        }
+    }
+
+    fn play_game(
+        &mut self,
+        game_builder: &impl Fn(u64) -> (E, GameState),
+        seats: &[usize],
+        seed: u64,
+    ) -> ArenaGame {
+        let (engine, mut state) = game_builder(seed);
+
+        // Borrow the seated agents in seat order
+        let mut available: Vec<Option<&mut Box<dyn Agent<E>>>> =
+            self.agents.iter_mut().map(Some).collect();
+        let mut seated: Vec<&mut Box<dyn Agent<E>>> = seats
+            .iter()
+            .map(|&agent| available[agent].take().expect("seats hold distinct agents"))
+            .collect();
+
+        let config = RunnerConfig::new().with_max_moves(self.config.max_moves);
+        let outcome = GameRunner::new(engine, config).run(&mut state, seed, &mut seated);
+
+        ArenaGame {
+            seed,
+            seats: seats.to_vec(),
+            rewards: outcome.rewards.iter().map(|(_, r)| *r).collect(),
+            moves: outcome.moves(),
+            finished: outcome.is_finished(),
+        }
+    }
+}
+
+/// The pairing of each seat assignment, and the number of pairings.
+///
+/// Assignments seating the same set of agents share a pairing.
+fn pairings(assignments: &[Vec<usize>]) -> (Vec<usize>, usize) {
+    let mut sets: Vec<Vec<usize>> = Vec::new();
+    let pairings = assignments
+        .iter()
+        .map(|seats| {
+            let mut set = seats.clone();
+            set.sort_unstable();
+            sets.iter().position(|s| *s == set).unwrap_or_else(|| {
+                sets.push(set);
+                sets.len() - 1
+            })
+        })
+        .collect();
+    (pairings, sets.len())
+}
+
+/// All ordered selections of `seats` distinct agents out of `agents`.
+fn seat_assignments(agents: usize, seats: usize) -> Vec<Vec<usize>> {
+    fn extend(agents: usize, seats: usize, current: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        if current.len() == seats {
+            out.push(current.clone());
+            return;
+        }
+        for agent in 0..agents {
+            if !current.contains(&agent) {
+                current.push(agent);
+                extend(agents, seats, current, out);
+                current.pop();
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    extend(agents, seats, &mut Vec::with_capacity(seats), &mut out);
+    out
+}
+
+/// Update pairwise records and TrueSkill ratings with one game.
+fn record_game(game: &ArenaGame, records: &mut [Vec<PairRecord>], trueskill: &mut [TrueSkillRating]) {
+    for a in 0..game.seats.len() {
+        for b in (a + 1)..game.seats.len() {
+            let (i, j) = (game.seats[a], game.seats[b]);
+            let diff = game.rewards[a] - game.rewards[b];
+            let outcome = if diff > 1e-9 {
+                1
+            } else if diff < -1e-9 {
+                -1
+            } else {
+                0
+            };
+            let result = PairRecord {
+                wins: u32::from(outcome > 0),
+                draws: u32::from(outcome == 0),
+                losses: u32::from(outcome < 0),
+            };
+
+            for (x, y, r) in [(i, j, result), (j, i, result.flipped())] {
+                let rec = &mut records[x][y];
+                rec.wins += r.wins;
+                rec.draws += r.draws;
+                rec.losses += r.losses;
+            }
+
+            let (ri, rj) = trueskill_update(trueskill[i], trueskill[j], outcome);
+            trueskill[i] = ri;
+            trueskill[j] = rj;
+        }
+    }
+}
+
+/// Fit Bradley-Terry strengths (draws as half wins) and convert to Elo.
+///
+/// Each pair that met gets one virtual draw as a prior so unbeaten agents
+/// have finite ratings. Confidence intervals use the Fisher information.
+fn fit_elo(records: &[Vec<PairRecord>]) -> Vec<EloEstimate> {
+    let k = records.len();
+    let games = |i: usize, j: usize| {
+        let n = records[i][j].games() as f64;
+        if n > 0.0 {
+            n + 1.0
+        } else {
+            0.0
+        }
+    };
+    let score = |i: usize, j: usize| {
+        let r = records[i][j];
+        if r.games() > 0 {
+            r.wins as f64 + 0.5 * r.draws as f64 + 0.5
+        } else {
+            0.0
+        }
+    };
+
+    // Minorization-maximization iterations on gamma = 10^(elo / 400)
+    let mut gamma = vec![1.0; k];
+    for _ in 0..500 {
+        let mut next = gamma.clone();
+        for i in 0..k {
+            let wins: f64 = (0..k).map(|j| score(i, j)).sum();
+            let denom: f64 = (0..k)
+                .filter(|&j| j != i)
+                .map(|j| games(i, j) / (gamma[i] + gamma[j]))
+                .sum();
+            if wins > 0.0 && denom > 0.0 {
+                next[i] = wins / denom;
+            }
+        }
+        // Normalize to geometric mean 1
+        let log_mean = next.iter().map(|g: &f64| g.ln()).sum::<f64>() / k as f64;
+        for g in &mut next {
+            *g /= log_mean.exp();
+        }
+        let converged = next
+            .iter()
+            .zip(&gamma)
+            .all(|(a, b)| (a.ln() - b.ln()).abs() < 1e-9);
+        gamma = next;
+        if converged {
+            break;
+        }
+    }
+
+    let scale = 400.0 / std::f64::consts::LN_10;
+    (0..k)
+        .map(|i| {
+            let elo = scale * gamma[i].ln();
+            let information: f64 = (0..k)
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let p = gamma[i] / (gamma[i] + gamma[j]);
+                    games(i, j) * p * (1.0 - p)
+                })
+                .sum();
+            let margin = if information > 0.0 {
+                1.96 * scale / information.sqrt()
+            } else {
+                f64::INFINITY
+            };
+            EloEstimate {
+                elo,
+                lower: elo - margin,
+                upper: elo + margin,
+            }
+        })
+        .collect()
+}
+
+/// Expected score for an Elo difference.
+fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Elo difference for an expected score (clamped away from 0 and 1).
+fn score_to_elo(score: f64) -> f64 {
+    let score = score.clamp(1e-6, 1.0 - 1e-6);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Two-player TrueSkill update; `result` is 1 if `a` won, -1 if `b` won, 0 for a draw.
+fn trueskill_update(
+    a: TrueSkillRating,
+    b: TrueSkillRating,
+    result: i32,
+) -> (TrueSkillRating, TrueSkillRating) {
+    let var_a = a.sigma.powi(2) + TRUESKILL_TAU.powi(2);
+    let var_b = b.sigma.powi(2) + TRUESKILL_TAU.powi(2);
+    let c = (2.0 * TRUESKILL_BETA.powi(2) + var_a + var_b).sqrt();
+    let epsilon = inverse_normal_cdf((TRUESKILL_DRAW_PROBABILITY + 1.0) / 2.0)
+        * 2f64.sqrt()
+        * TRUESKILL_BETA
+        / c;
+
+    // Orient so `t` is the winner's advantage (or a's, for draws)
+    let (t, sign) = match result {
+        r if r < 0 => ((b.mu - a.mu) / c, -1.0),
+        _ => ((a.mu - b.mu) / c, 1.0),
+    };
+
+    let (v, w) = if result == 0 {
+        let denom = normal_cdf(epsilon - t) - normal_cdf(-epsilon - t);
+        if denom < 1e-12 {
+            (0.0, 1.0)
+        } else {
+            let v = (normal_pdf(-epsilon - t) - normal_pdf(epsilon - t)) / denom;
+            let w = v * v
+                + ((epsilon - t) * normal_pdf(epsilon - t) + (epsilon + t) * normal_pdf(epsilon + t))
+                    / denom;
+            (v, w)
+        }
+    } else {
+        let x = t - epsilon;
+        let denom = normal_cdf(x);
+        if denom < 1e-12 {
+            (-x, 1.0)
+        } else {
+            let v = normal_pdf(x) / denom;
+            (v, v * (v + x))
+        }
+    };
+
+    let update = |var: f64, mu: f64, direction: f64| TrueSkillRating {
+        mu: mu + direction * var / c * v,
+        sigma: (var * (1.0 - var / (c * c) * w).max(1e-6)).sqrt(),
+    };
+    (update(var_a, a.mu, sign), update(var_b, b.mu, -sign))
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Error function (Abramowitz and Stegun 7.1.26, max error 1.5e-7).
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    if x >= 0.0 {
+        y
+    } else {
+        -y
+    }
+}
+
+/// Inverse of the standard normal CDF by bisection.
+fn inverse_normal_cdf(p: f64) -> f64 {
+    let (mut lo, mut hi) = (-10.0, 10.0);
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if normal_cdf(mid) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{MctsAgent, RandomAgent, ScriptedAgent};
+    use crate::core::{Action, PlayerId, PlayerMap};
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+    use std::sync::{Arc, Mutex};
+
+    fn build(seed: u64) -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(seed)
+    }
+
+    #[test]
+    fn test_seat_assignments() {
+        assert_eq!(seat_assignments(2, 2), vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(seat_assignments(3, 2).len(), 6);
+        assert_eq!(seat_assignments(3, 3).len(), 6);
+
+        let (pairings, count) = pairings(&seat_assignments(3, 2));
+        assert_eq!(count, 3);
+        assert_eq!(pairings, vec![0, 1, 0, 2, 1, 2]);
+    }
+
+    #[test]
+    fn test_pair_record() {
+        let record = PairRecord {
+            wins: 6,
+            draws: 2,
+            losses: 2,
+        };
+        assert_eq!(record.games(), 10);
+        assert!((record.score() - 0.7).abs() < 1e-12);
+
+        let (elo, lower, upper) = record.elo_difference();
+        assert!(elo > 100.0 && elo < 200.0);
+        assert!(lower < elo && elo < upper);
+        assert_eq!(record.flipped().losses, 6);
+    }
+
+    #[test]
+    fn test_fit_elo_two_agents() {
+        let mut records = vec![vec![PairRecord::default(); 2]; 2];
+        records[0][1] = PairRecord {
+            wins: 75,
+            draws: 0,
+            losses: 25,
+        };
+        records[1][0] = records[0][1].flipped();
+
+        let elo = fit_elo(&records);
+        let diff = elo[0].elo - elo[1].elo;
+        // 75% score is about +190 Elo (slightly shrunk by the prior)
+        assert!(diff > 170.0 && diff < 195.0, "diff = {}", diff);
+        assert!((elo[0].elo + elo[1].elo).abs() < 1e-6);
+        assert!(elo[0].lower < elo[0].elo && elo[0].upper > elo[0].elo);
+    }
+
+    #[test]
+    fn test_trueskill_update() {
+        let a = TrueSkillRating::default();
+        let b = TrueSkillRating::default();
+
+        let (winner, loser) = trueskill_update(a, b, 1);
+        assert!(winner.mu > 25.0 && loser.mu < 25.0);
+        assert!(winner.sigma < a.sigma);
+
+        let (x, y) = trueskill_update(a, b, -1);
+        assert!(x.mu < 25.0 && y.mu > 25.0);
+
+        // A draw between unequal players pulls them together
+        let strong = TrueSkillRating { mu: 30.0, sigma: 5.0 };
+        let (s, w) = trueskill_update(strong, b, 0);
+        assert!(s.mu < 30.0 && w.mu > 25.0);
+    }
+
+    #[test]
+    fn test_sprt_decisions() {
+        let config = SprtConfig::new(0, 1);
+        let (lower, upper) = config.bounds();
+        assert!(lower < 0.0 && upper > 0.0);
+
+        let strong = PairRecord {
+            wins: 300,
+            draws: 50,
+            losses: 150,
+        };
+        assert_eq!(config.decide(&strong).decision, SprtDecision::AcceptH1);
+        assert_eq!(config.decide(&strong.flipped()).decision, SprtDecision::AcceptH0);
+
+        let early = PairRecord {
+            wins: 3,
+            draws: 1,
+            losses: 2,
+        };
+        assert_eq!(config.decide(&early).decision, SprtDecision::Continue);
+    }
+
+    #[test]
+    fn test_arena_mcts_beats_random() {
+        let mut arena = Arena::new(ArenaConfig::new().with_rounds(5).with_seed(3))
+            .with_agent(MctsAgent::new("mcts", 100))
+            .with_agent(RandomAgent::new("random"));
+
+        let report = arena.run(build);
+
+        assert_eq!(report.games.len(), 10);
+        assert_eq!(report.agents, vec!["mcts", "random"]);
+        let record = report.record(0, 1);
+        assert_eq!(record.games(), 10);
+        assert_eq!(record, report.record(1, 0).flipped());
+        assert!(record.score() > 0.5);
+        assert!(report.elo[0].elo > report.elo[1].elo);
+        assert!(report.trueskill[0].mu > report.trueskill[1].mu);
+
+        // Both seat orders were played
+        assert!(report.games.iter().any(|g| g.seats == vec![0, 1]));
+        assert!(report.games.iter().any(|g| g.seats == vec![1, 0]));
+        assert!(report.to_string().contains("mcts"));
+    }
+
+    #[test]
+    fn test_seat_orders_share_a_seed() {
+        let mut arena = Arena::new(ArenaConfig::new().with_rounds(2).with_seed(10))
+            .with_agent(RandomAgent::new("a"))
+            .with_agent(RandomAgent::new("b"))
+            .with_agent(RandomAgent::new("c"));
+        let report = arena.run(build);
+
+        // Per round: three pairings, each played in both seat orders
+        assert_eq!(report.games.len(), 12);
+        for game in &report.games {
+            let flipped: Vec<usize> = game.seats.iter().rev().copied().collect();
+            assert!(report.games.iter().any(|g| g.seats == flipped && g.seed == game.seed));
+        }
+        let mut seeds: Vec<u64> = report.games.iter().map(|g| g.seed).collect();
+        seeds.sort_unstable();
+        seeds.dedup();
+        assert_eq!(seeds.len(), 6);
+    }
+
+    #[test]
+    fn test_arena_is_deterministic() {
+        let run = || {
+            let mut arena = Arena::new(ArenaConfig::new().with_rounds(2))
+                .with_agent(RandomAgent::new("a"))
+                .with_agent(RandomAgent::new("b"));
+            arena
+                .run(build)
+                .games
+                .iter()
+                .map(|g| (g.moves, g.rewards.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_arena_sprt_stops_early() {
+        let config = ArenaConfig::new()
+            .with_rounds(200)
+            .with_sprt(SprtConfig::new(0, 1).with_elo_bounds(0.0, 200.0));
+        let mut arena = Arena::new(config)
+            .with_agent(MctsAgent::new("mcts", 100))
+            .with_agent(ScriptedAgent::new("first", |_: &GameState, _: PlayerId, _: &[Action]| {
+                Some(0)
+            }));
+
+        let report = arena.run(build);
+        let sprt = report.sprt.expect("SPRT configured");
+        assert_ne!(sprt.decision, SprtDecision::Continue);
+        assert!(report.games.len() < 400);
+    }
+
+    /// Random agent that counts the notifications it receives.
+    struct CountingAgent {
+        inner: RandomAgent,
+        observed: Arc<Mutex<(usize, usize)>>,
+    }
+
+    impl Agent<SimpleGame> for CountingAgent {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn select_action(
+            &mut self,
+            engine: &SimpleGame,
+            state: &GameState,
+            player: PlayerId,
+            seed: u64,
+        ) -> Option<Action> {
+            self.inner.select_action(engine, state, player, seed)
+        }
+
+        fn observe_action(&mut self, _state: &GameState, _player: PlayerId, _action: &Action) {
+            self.observed.lock().unwrap().0 += 1;
+        }
+
+        fn game_over(&mut self, _state: &GameState, _rewards: &PlayerMap<f64>) {
+            self.observed.lock().unwrap().1 += 1;
+        }
+    }
+
+    #[test]
+    fn test_arena_notifies_agents() {
+        let observed = Arc::new(Mutex::new((0, 0)));
+        let mut arena = Arena::new(ArenaConfig::new().with_rounds(2))
+            .with_agent(CountingAgent {
+                inner: RandomAgent::new("a"),
+                observed: Arc::clone(&observed),
+            })
+            .with_agent(RandomAgent::new("b"));
+        let report = arena.run(build);
+
+        // Every move of every game is observed, and every game ends
+        let moves: usize = report.games.iter().map(|g| g.moves).sum();
+        assert_eq!(*observed.lock().unwrap(), (moves, report.games.len()));
+    }
+}
@@ -7,7 +7,14 @@
 //!
 //! - **Trajectory**: Records a complete game with states, policies, and outcome
 //! - **ExperienceBuffer**: Collects and samples from trajectories
+//! - **ReplayConfig**: Prioritized, recency-weighted and stratified sampling
+//! - **Symmetry**: Seat rotation and permutation augmentation of samples
+//! - **ValueTarget**: Outcome, n-step, TD(λ) or blended value targets
+//! - **TrajectoryWriter/TrajectoryReader**: Sharded on-disk storage for
+//!   running self-play and training as separate processes
 //! - **SelfPlayWorker**: Runs games using MCTS to generate trajectories
+//! - **Arena**: Evaluates agents against each other with Elo/TrueSkill and SPRT
+//! - **Reanalyser**: Refreshes stored trajectories' targets with a newer network
 //!
 //! ## Usage
 //!
@@ -34,9 +41,21 @@
 //! let samples = buffer.sample_batch(32, rng_seed);
 //! ```
 
+pub mod arena;
+pub mod reanalyse;
+pub mod replay;
 pub mod self_play;
+pub mod storage;
+pub mod symmetry;
 pub mod trajectory;
 
 // Re-export main types
+pub use arena::{Arena, ArenaConfig, ArenaReport, SprtConfig, SprtDecision};
+pub use reanalyse::{ReanalyseConfig, ReanalyseError, ReanalyseSummary, Reanalyser};
+pub use replay::{ReplayConfig, ReplaySample, SampleId, Stratify};
 pub use self_play::{SelfPlayConfig, SelfPlayWorker};
-pub use trajectory::{ExperienceBuffer, Step, Trajectory, TrainingSample};
+pub use storage::{
+    Compression, SampleBatches, StorageConfig, StorageError, TrajectoryReader, TrajectoryWriter,
+};
+pub use symmetry::{PermutationSymmetry, SeatRotation, Symmetry};
+pub use trajectory::{ExperienceBuffer, Step, Trajectory, TrainingSample, ValueTarget};
@@ -0,0 +1,530 @@
+//! Reanalyse: refresh stored trajectories with a newer network.
+//!
+//! Policy targets recorded during self-play reflect the network that was
+//! current when the game was played. Reanalyse replays a stored
+//! `Trajectory` from its seed and action list, re-runs MCTS at selected
+//! positions using the current network's policy as root priors, and
+//! rewrites each selected step's `action_probs` and `root_value` in place.
+
+use crate::core::{GameRng, GameState};
+use crate::mcts::{MCTSConfig, MCTSSearch, PUCT};
+use crate::nn::{policy_to_priors, PolicyValueNetwork};
+use crate::rules::RulesEngine;
+
+use super::trajectory::{ExperienceBuffer, Trajectory};
+
+/// Configuration for reanalyse.
+#[derive(Clone, Debug)]
+pub struct ReanalyseConfig {
+    /// Number of MCTS iterations per re-searched position.
+    pub mcts_iterations: u32,
+
+    /// MCTS exploration constant.
+    pub exploration_constant: f64,
+
+    /// Fraction of positions to re-search (1.0 = every step).
+    pub fraction: f64,
+
+    /// Seed offset for position selection and search RNG.
+    pub seed_offset: u64,
+}
+
+impl Default for ReanalyseConfig {
+    fn default() -> Self {
+        Self {
+            mcts_iterations: 800,
+            exploration_constant: 1.414,
+            fraction: 1.0,
+            seed_offset: 0,
+        }
+    }
+}
+
+impl ReanalyseConfig {
+    /// Create a new reanalyse config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set MCTS iterations per position.
+    pub fn with_mcts_iterations(mut self, iterations: u32) -> Self {
+        self.mcts_iterations = iterations;
+        self
+    }
+
+    /// Set exploration constant.
+    pub fn with_exploration(mut self, c: f64) -> Self {
+        self.exploration_constant = c;
+        self
+    }
+
+    /// Set the fraction of positions to re-search.
+    pub fn with_fraction(mut self, fraction: f64) -> Self {
+        self.fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set seed offset.
+    pub fn with_seed_offset(mut self, offset: u64) -> Self {
+        self.seed_offset = offset;
+        self
+    }
+}
+
+/// Error raised when a stored trajectory can't be replayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReanalyseError {
+    /// The game ended before all recorded steps were replayed.
+    UnexpectedTerminal {
+        /// Step at which the game was already over.
+        move_number: usize,
+    },
+    /// A recorded action is not legal in the reconstructed state.
+    IllegalAction {
+        /// Step whose action could not be applied.
+        move_number: usize,
+    },
+    /// A step index passed to `reanalyse_steps` is out of range.
+    StepOutOfRange {
+        /// The offending index.
+        index: usize,
+    },
+}
+
+impl std::fmt::Display for ReanalyseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedTerminal { move_number } => {
+                write!(f, "game ended before step {}", move_number)
+            }
+            Self::IllegalAction { move_number } => {
+                write!(f, "recorded action at step {} is illegal on replay", move_number)
+            }
+            Self::StepOutOfRange { index } => {
+                write!(f, "step index {} is out of range", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReanalyseError {}
+
+/// Outcome of reanalysing an experience buffer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReanalyseSummary {
+    /// Steps rewritten across all trajectories.
+    pub steps: usize,
+    /// Trajectories that failed to replay, by position in the buffer.
+    pub failures: Vec<(usize, ReanalyseError)>,
+}
+
+/// Re-searches stored trajectories with a newer network.
+///
+/// The game is reconstructed with a builder of the same shape as
+/// `SelfPlayWorker::play_games`: given the trajectory's seed it must
+/// return the engine and initial state the game was originally played from.
+#[derive(Clone, Debug, Default)]
+pub struct Reanalyser {
+    config: ReanalyseConfig,
+}
+
+impl Reanalyser {
+    /// Create a new reanalyser.
+    pub fn new(config: ReanalyseConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &ReanalyseConfig {
+        &self.config
+    }
+
+    /// Select which steps of a trajectory to re-search.
+    ///
+    /// Deterministic given the trajectory seed and `seed_offset`.
+    pub fn select_steps(&self, trajectory: &Trajectory) -> Vec<usize> {
+        if self.config.fraction >= 1.0 {
+            return (0..trajectory.len()).collect();
+        }
+
+        let mut rng = GameRng::new(trajectory.seed ^ self.config.seed_offset);
+        (0..trajectory.len())
+            .filter(|_| rng.gen_bool(self.config.fraction))
+            .collect()
+    }
+
+    /// Reanalyse a trajectory at the configured fraction of positions.
+    ///
+    /// Returns the number of steps rewritten.
+    pub fn reanalyse<E, N>(
+        &self,
+        trajectory: &mut Trajectory,
+        game_builder: impl Fn(u64) -> (E, GameState),
+        network: &N,
+    ) -> Result<usize, ReanalyseError>
+    where
+        E: RulesEngine + Clone,
+        N: PolicyValueNetwork,
+    {
+        let steps = self.select_steps(trajectory);
+        self.reanalyse_steps(trajectory, &steps, game_builder, network)
+    }
+
+    /// Reanalyse specific steps of a trajectory.
+    ///
+    /// The whole recorded action list is replayed (and validated) even if
+    /// only a few steps are selected. On error the trajectory is left
+    /// unchanged. Returns the number of steps rewritten.
+    pub fn reanalyse_steps<E, N>(
+        &self,
+        trajectory: &mut Trajectory,
+        steps: &[usize],
+        game_builder: impl Fn(u64) -> (E, GameState),
+        network: &N,
+    ) -> Result<usize, ReanalyseError>
+    where
+        E: RulesEngine + Clone,
+        N: PolicyValueNetwork,
+    {
+        if let Some(&index) = steps.iter().find(|&&i| i >= trajectory.len()) {
+            return Err(ReanalyseError::StepOutOfRange { index });
+        }
+
+        let (mut engine, mut state) = game_builder(trajectory.seed);
+        let mut updates = Vec::with_capacity(steps.len());
+
+        for (index, step) in trajectory.steps.iter().enumerate() {
+            if engine.is_terminal(&state).is_some() {
+                return Err(ReanalyseError::UnexpectedTerminal {
+                    move_number: step.move_number,
+                });
+            }
+
+            let actions = engine.legal_actions(&state, step.player);
+            if !actions.contains(&step.action_taken) {
+                return Err(ReanalyseError::IllegalAction {
+                    move_number: step.move_number,
+                });
+            }
+
+            if steps.contains(&index) {
+                let (policy, _value) = network.predict(&step.encoded_state);
+                let priors = policy_to_priors(&actions, &policy);
+
+                let mcts_config = MCTSConfig::default()
+                    .with_exploration(self.config.exploration_constant)
+                    .with_seed(
+                        trajectory
+                            .seed
+                            .wrapping_add(self.config.seed_offset)
+                            .wrapping_add(step.move_number as u64),
+                    );
+
+                // Search a snapshot so the replayed state's RNG is untouched
+                let mut search =
+                    MCTSSearch::new(engine.clone(), mcts_config).with_selection(PUCT);
+                search.search_with_priors(
+                    &mut state.snapshot(),
+                    step.player,
+                    self.config.mcts_iterations,
+                    &priors,
+                );
+
+                updates.push((index, search.action_probabilities(), search.root_value()));
+            }
+
+            engine.apply_action(&mut state, step.player, &step.action_taken);
+        }
+
+        let updated = updates.len();
+        for (index, action_probs, root_value) in updates {
+            let step = &mut trajectory.steps[index];
+            step.action_probs = action_probs;
+            step.root_value = root_value;
+        }
+
+        Ok(updated)
+    }
+
+    /// Reanalyse every trajectory in an experience buffer.
+    ///
+    /// Trajectories that fail to replay are left unchanged and reported in
+    /// the summary with their error.
+    pub fn reanalyse_buffer<E, N>(
+        &self,
+        buffer: &mut ExperienceBuffer,
+        game_builder: impl Fn(u64) -> (E, GameState),
+        network: &N,
+    ) -> ReanalyseSummary
+    where
+        E: RulesEngine + Clone,
+        N: PolicyValueNetwork,
+    {
+        let mut summary = ReanalyseSummary::default();
+        for (index, trajectory) in buffer.iter_mut().enumerate() {
+            match self.reanalyse(trajectory, &game_builder, network) {
+                Ok(steps) => summary.steps += steps,
+                Err(error) => summary.failures.push((index, error)),
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Action, TemplateId};
+    use crate::games::simple::{SimpleGame, SimpleGameBuilder};
+    use crate::nn::{EncodedState, SimpleGameEncoder, UniformPolicyZeroValue};
+    use crate::training::{SelfPlayConfig, SelfPlayWorker};
+
+    fn build(seed: u64) -> (SimpleGame, GameState) {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(seed)
+    }
+
+    fn self_play_trajectory(seed: u64) -> Trajectory {
+        let (engine, mut state) = build(seed);
+        let encoder = Box::new(SimpleGameEncoder::new(2, 10));
+        let config = SelfPlayConfig::default()
+            .with_mcts_iterations(10)
+            .with_max_moves(30);
+
+        let worker = SelfPlayWorker::new(engine, encoder, config);
+        worker.play_game(&mut state, seed)
+    }
+
+    /// Network that puts all its mass on the first legal action.
+    struct FirstActionNetwork;
+
+    impl PolicyValueNetwork for FirstActionNetwork {
+        fn predict(&self, _encoded: &EncodedState) -> (Vec<f32>, Vec<f32>) {
+            (vec![1.0], vec![0.5, 0.5])
+        }
+    }
+
+    #[test]
+    fn test_config_builders() {
+        let config = ReanalyseConfig::new()
+            .with_mcts_iterations(50)
+            .with_exploration(2.0)
+            .with_fraction(1.5)
+            .with_seed_offset(7);
+
+        assert_eq!(config.mcts_iterations, 50);
+        assert_eq!(config.exploration_constant, 2.0);
+        assert_eq!(config.fraction, 1.0); // Clamped
+        assert_eq!(config.seed_offset, 7);
+    }
+
+    #[test]
+    fn test_reanalyse_rewrites_all_steps() {
+        let mut trajectory = self_play_trajectory(42);
+        assert!(!trajectory.is_empty());
+        for step in &mut trajectory.steps {
+            step.root_value = None;
+        }
+
+        let reanalyser = Reanalyser::new(ReanalyseConfig::new().with_mcts_iterations(20));
+        let network = UniformPolicyZeroValue::new(10, 2);
+        let updated = reanalyser.reanalyse(&mut trajectory, build, &network).unwrap();
+
+        assert_eq!(updated, trajectory.len());
+        for step in &trajectory.steps {
+            let sum: f64 = step.action_probs.iter().map(|(_, p)| p).sum();
+            assert!((sum - 1.0).abs() < 0.01);
+        }
+        // Positions with a real choice get a root value from the new search
+        assert!(trajectory.steps.iter().any(|s| s.root_value.is_some()));
+    }
+
+    #[test]
+    fn test_reanalyse_uses_network_priors() {
+        let mut trajectory = self_play_trajectory(7);
+
+        let reanalyser = Reanalyser::new(
+            ReanalyseConfig::new()
+                .with_mcts_iterations(50)
+                .with_exploration(5.0),
+        );
+        reanalyser
+            .reanalyse(&mut trajectory, build, &FirstActionNetwork)
+            .unwrap();
+
+        // With a strong prior on the first legal action, it dominates visits
+        let first_favoured = trajectory
+            .steps
+            .iter()
+            .filter(|s| s.action_probs.len() > 1)
+            .filter(|s| {
+                let first = s.action_probs[0].1;
+                s.action_probs.iter().all(|(_, p)| first >= *p)
+            })
+            .count();
+        let multi_choice = trajectory
+            .steps
+            .iter()
+            .filter(|s| s.action_probs.len() > 1)
+            .count();
+        assert!(first_favoured * 2 > multi_choice);
+    }
+
+    #[test]
+    fn test_reanalyse_selected_steps_only() {
+        let original = self_play_trajectory(42);
+        let mut trajectory = original.clone();
+
+        let reanalyser = Reanalyser::new(ReanalyseConfig::new().with_mcts_iterations(20));
+        let network = UniformPolicyZeroValue::new(10, 2);
+        let updated = reanalyser
+            .reanalyse_steps(&mut trajectory, &[0], build, &network)
+            .unwrap();
+
+        assert_eq!(updated, 1);
+        for (before, after) in original.steps.iter().zip(&trajectory.steps).skip(1) {
+            assert_eq!(before.action_probs, after.action_probs);
+            assert_eq!(before.root_value, after.root_value);
+        }
+    }
+
+    #[test]
+    fn test_select_steps_fraction() {
+        let trajectory = self_play_trajectory(42);
+
+        let all = Reanalyser::new(ReanalyseConfig::new()).select_steps(&trajectory);
+        assert_eq!(all.len(), trajectory.len());
+
+        let none = Reanalyser::new(ReanalyseConfig::new().with_fraction(0.0))
+            .select_steps(&trajectory);
+        assert!(none.is_empty());
+
+        let half = Reanalyser::new(ReanalyseConfig::new().with_fraction(0.5));
+        assert_eq!(half.select_steps(&trajectory), half.select_steps(&trajectory));
+    }
+
+    #[test]
+    fn test_reanalyse_detects_mismatched_trajectory() {
+        let original = self_play_trajectory(42);
+        let mut trajectory = original.clone();
+
+        // Corrupt the first action with one that never appears in SimpleGame
+        trajectory.steps[0].action_taken = Action::new(TemplateId::new(99));
+
+        let reanalyser = Reanalyser::new(ReanalyseConfig::new().with_mcts_iterations(5));
+        let network = UniformPolicyZeroValue::new(10, 2);
+        let result = reanalyser.reanalyse(&mut trajectory, build, &network);
+
+        assert_eq!(result, Err(ReanalyseError::IllegalAction { move_number: 0 }));
+        // Trajectory untouched on failure
+        assert_eq!(trajectory.steps[1].action_probs, original.steps[1].action_probs);
+    }
+
+    #[test]
+    fn test_reanalyse_step_out_of_range() {
+        let mut trajectory = self_play_trajectory(42);
+        let len = trajectory.len();
+
+        let reanalyser = Reanalyser::default();
+        let network = UniformPolicyZeroValue::new(10, 2);
+        let result = reanalyser.reanalyse_steps(&mut trajectory, &[len], build, &network);
+
+        assert_eq!(result, Err(ReanalyseError::StepOutOfRange { index: len }));
+    }
+
+    /// Simple game that rolls a fork of the state RNG on every move. Only
+    /// the engine the builder returns records its rolls; search clones
+    /// don't.
+    struct RollingGame {
+        inner: SimpleGame,
+        rolls: Option<std::rc::Rc<std::cell::RefCell<Vec<i32>>>>,
+    }
+
+    impl Clone for RollingGame {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+                rolls: None,
+            }
+        }
+    }
+
+    impl RulesEngine for RollingGame {
+        fn config(&self) -> &crate::core::GameConfig {
+            self.inner.config()
+        }
+
+        fn legal_templates(&self, state: &GameState, player: crate::core::PlayerId) -> Vec<TemplateId> {
+            self.inner.legal_templates(state, player)
+        }
+
+        fn legal_pointers(
+            &self,
+            state: &GameState,
+            player: crate::core::PlayerId,
+            template: TemplateId,
+            prior: &[crate::core::EntityId],
+        ) -> Vec<crate::core::EntityId> {
+            self.inner.legal_pointers(state, player, template, prior)
+        }
+
+        fn apply_action(&mut self, state: &mut GameState, player: crate::core::PlayerId, action: &Action) {
+            let roll = state.rng.fork().gen_range(0..1_000_000);
+            if let Some(rolls) = &self.rolls {
+                rolls.borrow_mut().push(roll);
+            }
+            self.inner.apply_action(state, player, action);
+        }
+
+        fn is_terminal(&self, state: &GameState) -> Option<crate::rules::GameResult> {
+            self.inner.is_terminal(state)
+        }
+    }
+
+    #[test]
+    fn test_search_leaves_replay_state_alone() {
+        let mut trajectory = self_play_trajectory(7);
+        let reanalyser = Reanalyser::new(ReanalyseConfig::new().with_mcts_iterations(10));
+        let network = UniformPolicyZeroValue::new(10, 2);
+
+        // The replay rolls the same numbers whether or not it searches
+        let replay_rolls = |steps: &[usize], trajectory: &mut Trajectory| {
+            let rolls = std::rc::Rc::default();
+            let builder = |seed| {
+                let (inner, state) = build(seed);
+                let rolls = Some(std::rc::Rc::clone(&rolls));
+                (RollingGame { inner, rolls }, state)
+            };
+            reanalyser.reanalyse_steps(trajectory, steps, builder, &network).unwrap();
+            rolls.take()
+        };
+        let all: Vec<usize> = (0..trajectory.len()).collect();
+        let searched = replay_rolls(&all, &mut trajectory);
+        assert_eq!(searched.len(), trajectory.len());
+        assert_eq!(searched, replay_rolls(&[], &mut trajectory));
+    }
+
+    #[test]
+    fn test_reanalyse_buffer() {
+        let mut buffer = ExperienceBuffer::new(10);
+        buffer.push(self_play_trajectory(1));
+        buffer.push(self_play_trajectory(2));
+
+        let reanalyser = Reanalyser::new(ReanalyseConfig::new().with_mcts_iterations(10));
+        let network = UniformPolicyZeroValue::new(10, 2);
+        let summary = reanalyser.reanalyse_buffer(&mut buffer, build, &network);
+
+        assert_eq!(summary.steps, buffer.total_steps());
+        assert!(summary.failures.is_empty());
+
+        // A trajectory that no longer replays is reported, not counted
+        let mut broken = self_play_trajectory(3);
+        broken.steps[0].action_taken = Action::new(TemplateId::new(99));
+        let move_number = broken.steps[0].move_number;
+        buffer.push(broken);
+        let summary = reanalyser.reanalyse_buffer(&mut buffer, build, &network);
+        assert_eq!(summary.failures, vec![(2, ReanalyseError::IllegalAction { move_number })]);
+    }
+}
@@ -0,0 +1,287 @@
+//! Non-uniform replay for the experience buffer.
+//!
+//! [`ReplayConfig`] controls how [`ExperienceBuffer::sample_replay`] draws
+//! steps:
+//! - **Prioritized**: each step carries a priority (updated by the trainer
+//!   through its [`SampleId`]); steps are drawn proportional to
+//!   `priority^priority_exponent`, with importance weights to correct bias.
+//! - **Recency**: trajectories are tagged with the generation (network
+//!   version) that produced them; older generations are down-weighted by
+//!   `recency_decay^age`.
+//! - **Stratified**: the batch is split evenly across players and/or game
+//!   phases before weighted sampling within each stratum.
+//!
+//! All sampling is deterministic given the seed.
+//!
+//! [`ExperienceBuffer::sample_replay`]: super::ExperienceBuffer::sample_replay
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::GameRng;
+
+use super::trajectory::TrainingSample;
+
+/// Smallest priority stored for a step, so every step stays reachable.
+pub const MIN_PRIORITY: f64 = 1e-6;
+
+/// Stable identifier for a step in an [`ExperienceBuffer`].
+///
+/// Remains valid until the owning trajectory is evicted; updates to
+/// evicted ids are ignored.
+///
+/// [`ExperienceBuffer`]: super::ExperienceBuffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SampleId {
+    /// Buffer-assigned trajectory id (monotonically increasing).
+    pub trajectory: u64,
+
+    /// Step index within the trajectory.
+    pub step: usize,
+}
+
+impl SampleId {
+    /// Create a new sample id.
+    pub fn new(trajectory: u64, step: usize) -> Self {
+        Self { trajectory, step }
+    }
+}
+
+/// How a replay batch is split before sampling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stratify {
+    /// Sample from all steps together.
+    #[default]
+    None,
+
+    /// Equal share of the batch per acting player.
+    Player,
+
+    /// Equal share per game phase; a step's phase is its position in the
+    /// trajectory split into `buckets` equal parts.
+    Phase {
+        /// Number of phases (at least 1).
+        buckets: usize,
+    },
+
+    /// Equal share per (player, phase) pair.
+    PlayerPhase {
+        /// Number of phases (at least 1).
+        buckets: usize,
+    },
+}
+
+impl Stratify {
+    /// Stratum key of step `index` (by `player` index) in a trajectory of `len` steps.
+    pub(crate) fn key(self, player: u8, index: usize, len: usize) -> (u8, usize) {
+        let phase = |buckets: usize| {
+            let buckets = buckets.max(1);
+            (index * buckets / len.max(1)).min(buckets - 1)
+        };
+        match self {
+            Stratify::None => (0, 0),
+            Stratify::Player => (player, 0),
+            Stratify::Phase { buckets } => (0, phase(buckets)),
+            Stratify::PlayerPhase { buckets } => (player, phase(buckets)),
+        }
+    }
+}
+
+/// Configuration for non-uniform replay sampling.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayConfig {
+    /// Priority exponent α (0 = ignore priorities).
+    pub priority_exponent: f64,
+
+    /// Importance-sampling exponent β (0 = no correction, 1 = full).
+    pub importance_exponent: f64,
+
+    /// Weight multiplier per generation of age (1 = no recency weighting).
+    pub recency_decay: f64,
+
+    /// How the batch is split across strata.
+    pub stratify: Stratify,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            priority_exponent: 0.0,
+            importance_exponent: 0.0,
+            recency_decay: 1.0,
+            stratify: Stratify::None,
+        }
+    }
+}
+
+impl ReplayConfig {
+    /// Create a uniform replay config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable prioritized replay with exponents α and β.
+    pub fn with_priorities(mut self, priority_exponent: f64, importance_exponent: f64) -> Self {
+        self.priority_exponent = priority_exponent.max(0.0);
+        self.importance_exponent = importance_exponent.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the per-generation recency decay (clamped to `[0, 1]`).
+    pub fn with_recency_decay(mut self, decay: f64) -> Self {
+        self.recency_decay = decay.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the stratification mode.
+    pub fn with_stratify(mut self, stratify: Stratify) -> Self {
+        self.stratify = stratify;
+        self
+    }
+
+    /// Whether this config samples uniformly without stratification.
+    pub fn is_uniform(&self) -> bool {
+        self.priority_exponent == 0.0 && self.recency_decay == 1.0 && self.stratify == Stratify::None
+    }
+
+    /// Sampling weight for a step with `priority` from a trajectory `age` generations old.
+    pub(crate) fn weight(&self, priority: f64, age: u64) -> f64 {
+        let priority = priority.max(MIN_PRIORITY).powf(self.priority_exponent);
+        let age = i32::try_from(age).unwrap_or(i32::MAX);
+        priority * self.recency_decay.powi(age)
+    }
+}
+
+/// A training sample drawn by replay, with its id and importance weight.
+#[derive(Clone, Debug)]
+pub struct ReplaySample {
+    /// Id for feeding back a new priority.
+    pub id: SampleId,
+
+    /// The training sample.
+    pub sample: TrainingSample,
+
+    /// Importance-sampling weight, normalized so the batch maximum is 1.
+    pub weight: f32,
+}
+
+/// Draw `batch_size` candidates with replacement.
+///
+/// Each candidate is `(stratum, weight)`. The batch is split evenly across
+/// non-empty strata (remainder assigned at random), then drawn proportional
+/// to weight within each stratum; a stratum with zero total weight is
+/// sampled uniformly. Returns `(candidate index, probability)` pairs, where
+/// the probability is that of drawing the candidate in one batch slot.
+pub(crate) fn draw(
+    candidates: &[((u8, usize), f64)],
+    batch_size: usize,
+    rng: &mut GameRng,
+) -> Vec<(usize, f64)> {
+    if candidates.is_empty() || batch_size == 0 {
+        return vec![];
+    }
+
+    let mut strata: BTreeMap<(u8, usize), Vec<usize>> = BTreeMap::new();
+    for (i, (key, _)) in candidates.iter().enumerate() {
+        strata.entry(*key).or_default().push(i);
+    }
+    let strata: Vec<Vec<usize>> = strata.into_values().collect();
+
+    // Even split, with the remainder going to randomly chosen strata
+    let mut counts = vec![batch_size / strata.len(); strata.len()];
+    let mut order: Vec<usize> = (0..strata.len()).collect();
+    rng.shuffle(&mut order);
+    for &s in order.iter().take(batch_size % strata.len()) {
+        counts[s] += 1;
+    }
+
+    let mut drawn = Vec::with_capacity(batch_size);
+    for (members, count) in strata.iter().zip(counts) {
+        let share = count as f64 / batch_size as f64;
+        let total: f64 = members.iter().map(|&i| candidates[i].1).sum();
+
+        for _ in 0..count {
+            if total <= 0.0 {
+                let i = members[rng.gen_range_usize(0..members.len())];
+                drawn.push((i, share / members.len() as f64));
+                continue;
+            }
+
+            let mut threshold = rng.gen_f64() * total;
+            let mut chosen = *members.last().expect("strata are non-empty");
+            for &i in members {
+                threshold -= candidates[i].1;
+                if threshold < 0.0 {
+                    chosen = i;
+                    break;
+                }
+            }
+            drawn.push((chosen, share * candidates[chosen].1 / total));
+        }
+    }
+    drawn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_config_builder() {
+        let config = ReplayConfig::new()
+            .with_priorities(0.6, 2.0)
+            .with_recency_decay(0.9)
+            .with_stratify(Stratify::Player);
+
+        assert_eq!(config.priority_exponent, 0.6);
+        assert_eq!(config.importance_exponent, 1.0); // Clamped
+        assert_eq!(config.recency_decay, 0.9);
+        assert!(!config.is_uniform());
+        assert!(ReplayConfig::default().is_uniform());
+    }
+
+    #[test]
+    fn test_replay_weight() {
+        let config = ReplayConfig::new().with_priorities(1.0, 0.0).with_recency_decay(0.5);
+        assert!((config.weight(2.0, 0) - 2.0).abs() < 1e-12);
+        assert!((config.weight(2.0, 2) - 0.5).abs() < 1e-12);
+
+        // Uniform config ignores priority and age
+        assert_eq!(ReplayConfig::default().weight(7.0, 3), 1.0);
+    }
+
+    #[test]
+    fn test_stratify_key() {
+        assert_eq!(Stratify::None.key(1, 5, 10), (0, 0));
+        assert_eq!(Stratify::Player.key(1, 5, 10), (1, 0));
+        assert_eq!(Stratify::Phase { buckets: 3 }.key(1, 0, 10), (0, 0));
+        assert_eq!(Stratify::Phase { buckets: 3 }.key(1, 9, 10), (0, 2));
+        assert_eq!(Stratify::PlayerPhase { buckets: 2 }.key(1, 5, 10), (1, 1));
+    }
+
+    #[test]
+    fn test_draw_is_deterministic_and_weighted() {
+        let candidates = vec![((0, 0), 1.0), ((0, 0), 0.0), ((0, 0), 3.0)];
+
+        let a = draw(&candidates, 200, &mut GameRng::new(5));
+        let b = draw(&candidates, 200, &mut GameRng::new(5));
+        assert_eq!(a, b);
+
+        assert!(a.iter().all(|(i, _)| *i != 1));
+        let heavy = a.iter().filter(|(i, _)| *i == 2).count();
+        assert!(heavy > 120);
+        assert!((a[0].1 - candidates[a[0].0].1 / 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_draw_stratified_split() {
+        // Stratum 0 has most candidates, stratum 1 a single one
+        let mut candidates = vec![((0, 0), 1.0); 9];
+        candidates.push(((1, 0), 1.0));
+
+        let drawn = draw(&candidates, 10, &mut GameRng::new(1));
+        assert_eq!(drawn.len(), 10);
+        assert_eq!(drawn.iter().filter(|(i, _)| *i == 9).count(), 5);
+    }
+}
@@ -1,12 +1,16 @@
 //! Self-play loop for generating training data.
 //!
 //! Runs games using MCTS to generate trajectories for training
-//! neural networks in an AlphaZero-style loop.
+//! neural networks in an AlphaZero-style loop. Games are played by a
+//! [`GameRunner`], with a wrapper recording each agent decision as a
+//! training [`Step`].
 
-use crate::core::{GameState, PlayerId, PlayerMap};
-use crate::mcts::{MCTSConfig, MCTSSearch};
-use crate::nn::{PolicyValueNetwork, StateEncoder};
-use crate::rules::{GameResult, RulesEngine};
+use crate::agents::{Agent, SearchResult};
+use crate::core::{Action, GameState, PlayerId, PlayerMap};
+use crate::mcts::{MCTSConfig, MCTSSearch, PUCT};
+use crate::nn::{policy_to_priors, PolicyValueNetwork, StateEncoder};
+use crate::rules::RulesEngine;
+use crate::runner::{GameRunner, RunnerConfig};
 
 use super::trajectory::{Step, Trajectory};
 
@@ -127,120 +131,57 @@ impl<E: RulesEngine + Clone> SelfPlayWorker<E> {
     ///
     /// Uses random rollouts for evaluation.
     pub fn play_game(&self, state: &mut GameState, seed: u64) -> Trajectory {
-        let player_count = state.player_count();
-        let mut trajectory = Trajectory::new(seed, player_count);
-
-        for move_number in 0..self.config.max_moves {
-            // Check for terminal state
-            if self.engine.is_terminal(state).is_some() {
-                break;
-            }
-
-            let active_player = state.public.active_player;
-
-            // Run MCTS search
-            let mcts_config = MCTSConfig::default()
-                .with_exploration(self.config.exploration_constant)
-                .with_temperature(self.config.effective_temperature(move_number))
-                .with_seed(seed.wrapping_add(move_number as u64));
-
-            let mut search = MCTSSearch::new(self.engine.clone(), mcts_config);
-            let action = search.search(state, active_player, self.config.mcts_iterations);
-
-            let action = match action {
-                Some(a) => a,
-                None => break, // No legal actions
-            };
-
-            // Record step
-            let encoded_state = self.encoder.encode(state, active_player);
-            let action_probs = search.action_probabilities();
-
-            let step = Step::new(
-                encoded_state,
-                action_probs,
-                action.clone(),
-                active_player,
-                move_number,
-            );
-            trajectory.push(step);
-
-            // Apply action
-            let mut engine = self.engine.clone();
-            engine.apply_action(state, active_player, &action);
-        }
-
-        // Set final outcome
-        let outcome = self.compute_outcome(state, player_count);
-        trajectory.set_outcome(outcome);
-
-        trajectory
+        let mut agent = SelfPlayAgent::new(&self.config, None, self.encoder.as_ref());
+        self.play_game_with_agents(state, seed, std::slice::from_mut(&mut agent))
     }
 
     /// Play a game with a neural network for policy/value guidance.
     ///
-    /// The network provides prior probabilities for MCTS edges and
-    /// value estimates for leaf evaluation.
+    /// The network's policy gives the prior probabilities of the root
+    /// edges, as in [`NetworkMctsAgent`](crate::agents::NetworkMctsAgent).
     pub fn play_game_with_network<N: PolicyValueNetwork>(
         &self,
         state: &mut GameState,
         seed: u64,
         network: &N,
     ) -> Trajectory {
-        let player_count = state.player_count();
-        let mut trajectory = Trajectory::new(seed, player_count);
-
-        for move_number in 0..self.config.max_moves {
-            // Check for terminal state
-            if self.engine.is_terminal(state).is_some() {
-                break;
-            }
-
-            let active_player = state.public.active_player;
-
-            // Encode state and get network predictions
-            let encoded = self.encoder.encode(state, active_player);
-            let (policy_probs, _value) = network.predict(&encoded);
-
-            // Create MCTS with network priors
-            let mcts_config = MCTSConfig::default()
-                .with_exploration(self.config.exploration_constant)
-                .with_temperature(self.config.effective_temperature(move_number))
-                .with_seed(seed.wrapping_add(move_number as u64));
-
-            let mut search = MCTSSearch::new(self.engine.clone(), mcts_config);
-
-            // Run search (note: priors would be set via set_root_priors if implemented)
-            let action = search.search(state, active_player, self.config.mcts_iterations);
-
-            // For now, we use policy_probs in the trajectory but don't integrate
-            // them into MCTS selection. Full integration requires extending MCTSSearch.
-            let _ = policy_probs; // Silence unused warning
+        let mut agent = SelfPlayAgent::new(&self.config, Some(network), self.encoder.as_ref());
+        self.play_game_with_agents(state, seed, std::slice::from_mut(&mut agent))
+    }
 
-            let action = match action {
-                Some(a) => a,
-                None => break,
-            };
+    /// Play a game with arbitrary agents.
+    ///
+    /// The game is driven by a [`GameRunner`] limited to the config's
+    /// `max_moves`. The agent for seat `p` is `agents[p % agents.len()]`,
+    /// so a single agent plays every seat. Steps use the agent's
+    /// [`search_result`](Agent::search_result) as the policy target, or a
+    /// one-hot policy on the chosen action for agents that do not report one.
+    pub fn play_game_with_agents<A: Agent<E>>(
+        &self,
+        state: &mut GameState,
+        seed: u64,
+        agents: &mut [A],
+    ) -> Trajectory {
+        let mut trajectory = Trajectory::new(seed, state.player_count());
+        if agents.is_empty() {
+            return trajectory;
+        }
 
-            // Record step
-            let action_probs = search.action_probabilities();
-            let step = Step::new(
-                encoded,
-                action_probs,
-                action.clone(),
-                active_player,
-                move_number,
-            );
+        let mut recorders: Vec<Recorder<'_, &mut A>> = agents
+            .iter_mut()
+            .map(|agent| Recorder::new(agent, self.encoder.as_ref()))
+            .collect();
+        let config = RunnerConfig::new().with_max_moves(self.config.max_moves);
+        let outcome = GameRunner::new(self.engine.clone(), config).run(state, seed, &mut recorders);
+
+        // A decision the runner rejected is not part of the game
+        let mut steps: Vec<Step> = recorders.into_iter().flat_map(|recorder| recorder.steps).collect();
+        steps.sort_by_key(|step| step.move_number);
+        steps.truncate(outcome.moves());
+        for step in steps {
             trajectory.push(step);
-
-            // Apply action
-            let mut engine = self.engine.clone();
-            engine.apply_action(state, active_player, &action);
         }
-
-        // Set final outcome
-        let outcome = self.compute_outcome(state, player_count);
-        trajectory.set_outcome(outcome);
+        trajectory.set_outcome(outcome.rewards);
 
         trajectory
     }
@@ -256,41 +197,6 @@ impl<E: RulesEngine + Clone> SelfPlayWorker<E> {
             .collect()
     }
 
-    /// Compute the outcome rewards for each player.
-    fn compute_outcome(&self, state: &GameState, player_count: usize) -> PlayerMap<f64> {
-        let mut outcome = PlayerMap::with_value(player_count, 0.0);
-
-        match self.engine.is_terminal(state) {
-            Some(GameResult::Winner(winner)) => {
-                outcome[winner] = 1.0;
-            }
-            Some(GameResult::Winners(ref winners)) => {
-                // Multiple winners share the reward
-                let share = 1.0 / winners.len() as f64;
-                for &player in winners {
-                    outcome[player] = share;
-                }
-            }
-            Some(GameResult::Draw) => {
-                // Equal reward for all
-                let share = 1.0 / player_count as f64;
-                for player in PlayerId::all(player_count) {
-                    outcome[player] = share;
-                }
-            }
-            None => {
-                // Game didn't complete (hit max moves)
-                // Use heuristic evaluation
-                let share = 0.5;
-                for player in PlayerId::all(player_count) {
-                    outcome[player] = share;
-                }
-            }
-        }
-
-        outcome
-    }
-
     /// Get the encoder.
     pub fn encoder(&self) -> &dyn StateEncoder {
         self.encoder.as_ref()
@@ -302,11 +208,140 @@ impl<E: RulesEngine + Clone> SelfPlayWorker<E> {
     }
 }
 
+/// MCTS agent following the self-play temperature schedule.
+///
+/// With a network, the network's policy sets the root priors.
+struct SelfPlayAgent<'a> {
+    config: &'a SelfPlayConfig,
+    network: Option<&'a dyn PolicyValueNetwork>,
+    encoder: &'a dyn StateEncoder,
+    /// Actions applied so far this game.
+    moves: usize,
+    last: Option<SearchResult>,
+}
+
+impl<'a> SelfPlayAgent<'a> {
+    fn new(
+        config: &'a SelfPlayConfig,
+        network: Option<&'a dyn PolicyValueNetwork>,
+        encoder: &'a dyn StateEncoder,
+    ) -> Self {
+        Self {
+            config,
+            network,
+            encoder,
+            moves: 0,
+            last: None,
+        }
+    }
+}
+
+impl<E: RulesEngine + Clone> Agent<E> for SelfPlayAgent<'_> {
+    fn name(&self) -> &str {
+        "self-play"
+    }
+
+    fn new_game(&mut self) {
+        self.moves = 0;
+    }
+
+    fn select_action(&mut self, engine: &E, state: &GameState, player: PlayerId, seed: u64) -> Option<Action> {
+        let mcts_config = MCTSConfig::default()
+            .with_exploration(self.config.exploration_constant)
+            .with_temperature(self.config.effective_temperature(self.moves))
+            .with_seed(seed);
+        let mut root = state.snapshot();
+        let mut search = MCTSSearch::new(engine.clone(), mcts_config);
+        let action = match self.network {
+            Some(network) => {
+                let actions = engine.legal_actions(state, player);
+                let (policy, _value) = network.predict(&self.encoder.encode(state, player));
+                let priors = policy_to_priors(&actions, &policy);
+                search = search.with_selection(PUCT);
+                search.search_with_priors(&mut root, player, self.config.mcts_iterations, &priors)
+            }
+            None => search.search(&mut root, player, self.config.mcts_iterations),
+        };
+        self.last = Some(SearchResult {
+            action_probs: search.action_probabilities(),
+            root_value: search.root_value(),
+        });
+        action
+    }
+
+    fn observe_action(&mut self, _state: &GameState, _player: PlayerId, _action: &Action) {
+        self.moves += 1;
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        self.last.clone()
+    }
+}
+
+/// Wraps an agent to record a training step for each of its decisions.
+struct Recorder<'a, A> {
+    agent: A,
+    encoder: &'a dyn StateEncoder,
+    /// Actions applied so far this game.
+    moves: usize,
+    steps: Vec<Step>,
+}
+
+impl<'a, A> Recorder<'a, A> {
+    fn new(agent: A, encoder: &'a dyn StateEncoder) -> Self {
+        Self {
+            agent,
+            encoder,
+            moves: 0,
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl<E: RulesEngine, A: Agent<E>> Agent<E> for Recorder<'_, A> {
+    fn name(&self) -> &str {
+        self.agent.name()
+    }
+
+    fn new_game(&mut self) {
+        self.moves = 0;
+        self.steps.clear();
+        self.agent.new_game();
+    }
+
+    fn select_action(&mut self, engine: &E, state: &GameState, player: PlayerId, seed: u64) -> Option<Action> {
+        let encoded_state = self.encoder.encode(state, player);
+        let action = self.agent.select_action(engine, state, player, seed)?;
+        let (action_probs, root_value) = match self.agent.search_result() {
+            Some(result) => (result.action_probs, result.root_value),
+            None => (vec![(action.clone(), 1.0)], None),
+        };
+        let step = Step::new(encoded_state, action_probs, action.clone(), player, self.moves)
+            .with_root_value(root_value);
+        self.steps.push(step);
+        Some(action)
+    }
+
+    fn observe_action(&mut self, state: &GameState, player: PlayerId, action: &Action) {
+        self.moves += 1;
+        self.agent.observe_action(state, player, action);
+    }
+
+    fn game_over(&mut self, state: &GameState, rewards: &PlayerMap<f64>) {
+        self.agent.game_over(state, rewards);
+    }
+
+    fn search_result(&self) -> Option<SearchResult> {
+        self.agent.search_result()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::games::simple::SimpleGameBuilder;
     use crate::nn::{SimpleGameEncoder, UniformPolicyZeroValue};
+    use crate::training::ValueTarget;
 
     #[test]
     fn test_self_play_config_default() {
@@ -362,8 +397,43 @@ mod tests {
         let p1_outcome = trajectory.player_outcome(PlayerId::new(1));
 
         // Outcomes should be valid
-        assert!(p0_outcome >= 0.0 && p0_outcome <= 1.0);
-        assert!(p1_outcome >= 0.0 && p1_outcome <= 1.0);
+        assert!((0.0..=1.0).contains(&p0_outcome));
+        assert!((0.0..=1.0).contains(&p1_outcome));
+    }
+
+    #[test]
+    fn test_play_game_with_agents() {
+        use crate::agents::{MctsAgent, RandomAgent};
+        use crate::games::simple::SimpleGame;
+
+        let (engine, mut state) = SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(42);
+
+        let encoder = Box::new(SimpleGameEncoder::new(2, 10));
+        let config = SelfPlayConfig::default().with_max_moves(50);
+        let worker = SelfPlayWorker::new(engine, encoder, config);
+
+        let mut agents: Vec<Box<dyn Agent<SimpleGame>>> = vec![
+            Box::new(MctsAgent::new("mcts", 10)),
+            Box::new(RandomAgent::new("random")),
+        ];
+        let trajectory = worker.play_game_with_agents(&mut state, 42, &mut agents);
+        assert!(!trajectory.is_empty());
+
+        for step in &trajectory.steps {
+            if step.player == PlayerId::new(0) {
+                // Search statistics from the MCTS agent
+                assert!(step.root_value.is_some());
+            } else {
+                // One-hot policy for the random agent
+                assert_eq!(step.action_probs, vec![(step.action_taken.clone(), 1.0)]);
+            }
+        }
+
+        let total: f64 = trajectory.outcome.iter().map(|(_, r)| r).sum();
+        assert!((total - 1.0).abs() < 1e-9);
     }
 
     #[test]
@@ -497,6 +567,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_self_play_records_root_values() {
+        let (engine, mut state) = SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(3)
+            .build(42);
+
+        let encoder = Box::new(SimpleGameEncoder::new(2, 10));
+        let config = SelfPlayConfig::default()
+            .with_mcts_iterations(10)
+            .with_max_moves(20);
+
+        let worker = SelfPlayWorker::new(engine, encoder, config);
+        let trajectory = worker.play_game(&mut state, 42);
+
+        assert!(trajectory.steps.iter().all(|s| s.root_value.is_some()));
+
+        let samples = trajectory.to_training_samples_with(ValueTarget::td_lambda(0.5));
+        assert_eq!(samples.len(), trajectory.len());
+        for sample in &samples {
+            assert!((0.0..=1.0).contains(&sample.value));
+        }
+    }
+
     #[test]
     fn test_self_play_config_all_builders() {
         let config = SelfPlayConfig::new()
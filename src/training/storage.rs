@@ -0,0 +1,917 @@
+//! On-disk sharded trajectory storage.
+//!
+//! Lets self-play and training run as separate processes on one machine:
+//! a [`TrajectoryWriter`] appends trajectories to shard files in a
+//! directory, and a [`TrajectoryReader`] indexes them for random access
+//! and streams [`TrainingSample`] batches, picking up new trajectories via
+//! [`TrajectoryReader::refresh`].
+//!
+//! ## Layout
+//!
+//! Each shard is a pair of append-only files:
+//! - `shard-NNNNN.traj`: a 16-byte header (magic, format version, flags)
+//!   followed by records of `[len: u32][crc32: u32][payload]`, where the
+//!   payload is a bincode-encoded [`Trajectory`], optionally LZ4-compressed
+//!   (requires the `compression` feature).
+//! - `shard-NNNNN.idx`: `[offset: u64][len: u32]` per record, written after
+//!   the record itself so readers never see an index entry for a partial
+//!   record.
+//!
+//! All integers are little-endian. A writer never reopens an existing
+//! shard; use one writer per directory.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! // Self-play process
+//! let mut writer = TrajectoryWriter::create("data/selfplay", StorageConfig::default())?;
+//! writer.write(&trajectory)?;
+//!
+//! // Training process
+//! let mut reader = TrajectoryReader::open("data/selfplay")?;
+//! for batch in reader.shuffled_batches(256, ValueTarget::Outcome, seed) {
+//!     train_on(batch?);
+//! }
+//! reader.refresh()?;
+//! ```
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::GameRng;
+
+use super::trajectory::{TrainingSample, Trajectory, ValueTarget};
+
+/// Magic bytes at the start of every shard file.
+pub const SHARD_MAGIC: [u8; 8] = *b"CCGTRAJ\0";
+
+/// Current shard format version.
+pub const FORMAT_VERSION: u32 = 1;
+
+const HEADER_LEN: u64 = 16;
+const RECORD_HEADER_LEN: u64 = 8;
+const INDEX_ENTRY_LEN: usize = 12;
+
+/// Payload compression for a shard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// Plain bincode.
+    #[default]
+    None,
+
+    /// LZ4 block compression (requires the `compression` feature).
+    Lz4,
+}
+
+impl Compression {
+    /// Whether this build can read and write this compression.
+    pub fn is_available(self) -> bool {
+        match self {
+            Compression::None => true,
+            Compression::Lz4 => cfg!(feature = "compression"),
+        }
+    }
+
+    fn flags(self) -> u32 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+        }
+    }
+
+    fn from_flags(flags: u32) -> Option<Self> {
+        match flags & 0xff {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Lz4),
+            _ => None,
+        }
+    }
+}
+
+/// Configuration for a [`TrajectoryWriter`].
+#[derive(Clone, Debug)]
+pub struct StorageConfig {
+    /// Start a new shard after this many trajectories.
+    pub max_trajectories_per_shard: usize,
+
+    /// Start a new shard before exceeding this many bytes
+    /// (a single oversized trajectory still gets its own shard).
+    pub max_shard_bytes: u64,
+
+    /// Payload compression.
+    pub compression: Compression,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            max_trajectories_per_shard: 1000,
+            max_shard_bytes: 256 * 1024 * 1024,
+            compression: Compression::None,
+        }
+    }
+}
+
+impl StorageConfig {
+    /// Create a new storage config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum trajectories per shard (at least 1).
+    pub fn with_max_trajectories_per_shard(mut self, max: usize) -> Self {
+        self.max_trajectories_per_shard = max.max(1);
+        self
+    }
+
+    /// Set the maximum shard size in bytes.
+    pub fn with_max_shard_bytes(mut self, max: u64) -> Self {
+        self.max_shard_bytes = max;
+        self
+    }
+
+    /// Set payload compression.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+/// Errors from trajectory storage.
+#[derive(Debug)]
+pub enum StorageError {
+    /// Underlying I/O failure.
+    Io(io::Error),
+
+    /// A trajectory could not be encoded.
+    Encode(String),
+
+    /// A record could not be decoded.
+    Decode(String),
+
+    /// A shard file does not start with [`SHARD_MAGIC`].
+    BadMagic {
+        /// Offending shard file.
+        path: PathBuf,
+    },
+
+    /// A shard was written with an unknown format version or flags.
+    UnsupportedFormat {
+        /// Offending shard file.
+        path: PathBuf,
+        /// Format version found in the header.
+        version: u32,
+    },
+
+    /// A record's checksum or length does not match its index entry.
+    Corrupt {
+        /// Shard number.
+        shard: u32,
+        /// Byte offset of the record.
+        offset: u64,
+    },
+
+    /// The compression is not available in this build.
+    CompressionUnavailable(Compression),
+
+    /// Trajectory index out of range.
+    OutOfRange {
+        /// Requested index.
+        index: usize,
+    },
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "I/O error: {}", e),
+            StorageError::Encode(e) => write!(f, "failed to encode trajectory: {}", e),
+            StorageError::Decode(e) => write!(f, "failed to decode trajectory: {}", e),
+            StorageError::BadMagic { path } => {
+                write!(f, "{} is not a trajectory shard", path.display())
+            }
+            StorageError::UnsupportedFormat { path, version } => write!(
+                f,
+                "{} has unsupported format (version {})",
+                path.display(),
+                version
+            ),
+            StorageError::Corrupt { shard, offset } => {
+                write!(f, "corrupt record in shard {} at offset {}", shard, offset)
+            }
+            StorageError::CompressionUnavailable(c) => write!(
+                f,
+                "{:?} compression requires the `compression` feature",
+                c
+            ),
+            StorageError::OutOfRange { index } => {
+                write!(f, "trajectory index {} out of range", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+/// Result type for storage operations.
+pub type StorageResult<T> = Result<T, StorageError>;
+
+fn shard_path(dir: &Path, shard: u32) -> PathBuf {
+    dir.join(format!("shard-{:05}.traj", shard))
+}
+
+fn index_path(dir: &Path, shard: u32) -> PathBuf {
+    dir.join(format!("shard-{:05}.idx", shard))
+}
+
+/// Shard numbers present in `dir`, ascending.
+fn list_shards(dir: &Path) -> io::Result<Vec<u32>> {
+    let mut shards: Vec<u32> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let name = name.to_str()?;
+            name.strip_prefix("shard-")?
+                .strip_suffix(".traj")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    shards.sort_unstable();
+    Ok(shards)
+}
+
+fn encode(trajectory: &Trajectory, compression: Compression) -> StorageResult<Vec<u8>> {
+    let bytes = bincode::serialize(trajectory).map_err(|e| StorageError::Encode(e.to_string()))?;
+    match compression {
+        Compression::None => Ok(bytes),
+        #[cfg(feature = "compression")]
+        Compression::Lz4 => Ok(lz4_flex::compress_prepend_size(&bytes)),
+        #[cfg(not(feature = "compression"))]
+        Compression::Lz4 => Err(StorageError::CompressionUnavailable(compression)),
+    }
+}
+
+fn decode(payload: &[u8], compression: Compression) -> StorageResult<Trajectory> {
+    let deserialize =
+        |bytes: &[u8]| bincode::deserialize(bytes).map_err(|e| StorageError::Decode(e.to_string()));
+    match compression {
+        Compression::None => deserialize(payload),
+        #[cfg(feature = "compression")]
+        Compression::Lz4 => {
+            let bytes = lz4_flex::decompress_size_prepended(payload)
+                .map_err(|e| StorageError::Decode(e.to_string()))?;
+            deserialize(&bytes)
+        }
+        #[cfg(not(feature = "compression"))]
+        Compression::Lz4 => Err(StorageError::CompressionUnavailable(compression)),
+    }
+}
+
+/// Read a shard header, or `None` if the file is still shorter than a header.
+fn read_header(path: &Path) -> StorageResult<Option<Compression>> {
+    let mut header = [0u8; HEADER_LEN as usize];
+    let mut file = File::open(path)?;
+    let mut read = 0;
+    while read < header.len() {
+        match file.read(&mut header[read..])? {
+            0 => return Ok(None),
+            n => read += n,
+        }
+    }
+
+    if header[..8] != SHARD_MAGIC {
+        return Err(StorageError::BadMagic {
+            path: path.to_path_buf(),
+        });
+    }
+    let version = u32::from_le_bytes(header[8..12].try_into().expect("4 bytes"));
+    let flags = u32::from_le_bytes(header[12..16].try_into().expect("4 bytes"));
+    match Compression::from_flags(flags) {
+        Some(compression) if version == FORMAT_VERSION => Ok(Some(compression)),
+        _ => Err(StorageError::UnsupportedFormat {
+            path: path.to_path_buf(),
+            version,
+        }),
+    }
+}
+
+/// Appends trajectories to sharded files in a directory.
+pub struct TrajectoryWriter {
+    dir: PathBuf,
+    config: StorageConfig,
+    shard: u32,
+    data: BufWriter<File>,
+    index: BufWriter<File>,
+    shard_trajectories: usize,
+    shard_bytes: u64,
+    written: usize,
+}
+
+impl TrajectoryWriter {
+    /// Create a writer in `dir`, creating the directory if needed.
+    ///
+    /// Existing shards are left untouched; writing starts in a new shard
+    /// after the highest-numbered one.
+    pub fn create(dir: impl AsRef<Path>, config: StorageConfig) -> StorageResult<Self> {
+        if !config.compression.is_available() {
+            return Err(StorageError::CompressionUnavailable(config.compression));
+        }
+
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let shard = list_shards(&dir)?.last().map_or(0, |s| s + 1);
+        let (data, index) = Self::open_shard(&dir, shard, config.compression)?;
+
+        Ok(Self {
+            dir,
+            config,
+            shard,
+            data,
+            index,
+            shard_trajectories: 0,
+            shard_bytes: HEADER_LEN,
+            written: 0,
+        })
+    }
+
+    fn open_shard(
+        dir: &Path,
+        shard: u32,
+        compression: Compression,
+    ) -> StorageResult<(BufWriter<File>, BufWriter<File>)> {
+        let create = |path| OpenOptions::new().write(true).create_new(true).open(path);
+
+        let mut data = BufWriter::new(create(shard_path(dir, shard))?);
+        data.write_all(&SHARD_MAGIC)?;
+        data.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        data.write_all(&compression.flags().to_le_bytes())?;
+        data.flush()?;
+
+        let index = BufWriter::new(create(index_path(dir, shard))?);
+        Ok((data, index))
+    }
+
+    /// Append a trajectory, rotating to a new shard when limits are reached.
+    ///
+    /// The record is flushed before its index entry, so a concurrent reader
+    /// only ever sees complete records.
+    pub fn write(&mut self, trajectory: &Trajectory) -> StorageResult<()> {
+        let payload = encode(trajectory, self.config.compression)?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| StorageError::Encode("trajectory exceeds 4 GiB".to_string()))?;
+        let record_bytes = RECORD_HEADER_LEN + payload.len() as u64;
+
+        let full = self.shard_trajectories >= self.config.max_trajectories_per_shard;
+        let oversized = self.shard_trajectories > 0
+            && self.shard_bytes + record_bytes > self.config.max_shard_bytes;
+        if full || oversized {
+            self.rotate()?;
+        }
+
+        let offset = self.shard_bytes;
+        self.data.write_all(&len.to_le_bytes())?;
+        self.data.write_all(&crc32fast::hash(&payload).to_le_bytes())?;
+        self.data.write_all(&payload)?;
+        self.data.flush()?;
+
+        self.index.write_all(&offset.to_le_bytes())?;
+        self.index.write_all(&len.to_le_bytes())?;
+        self.index.flush()?;
+
+        self.shard_trajectories += 1;
+        self.shard_bytes += record_bytes;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Append several trajectories.
+    pub fn write_all<'a>(
+        &mut self,
+        trajectories: impl IntoIterator<Item = &'a Trajectory>,
+    ) -> StorageResult<()> {
+        for trajectory in trajectories {
+            self.write(trajectory)?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> StorageResult<()> {
+        self.flush()?;
+        self.shard += 1;
+        let (data, index) = Self::open_shard(&self.dir, self.shard, self.config.compression)?;
+        self.data = data;
+        self.index = index;
+        self.shard_trajectories = 0;
+        self.shard_bytes = HEADER_LEN;
+        Ok(())
+    }
+
+    /// Flush buffered data to disk.
+    pub fn flush(&mut self) -> StorageResult<()> {
+        self.data.flush()?;
+        self.index.flush()?;
+        Ok(())
+    }
+
+    /// Get the storage directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get the shard currently being written.
+    pub fn shard(&self) -> u32 {
+        self.shard
+    }
+
+    /// Get the number of trajectories written by this writer.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+}
+
+/// Location of one stored trajectory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct IndexEntry {
+    shard: u32,
+    offset: u64,
+    len: u32,
+}
+
+/// A shard known to the reader.
+#[derive(Clone, Debug)]
+struct ShardInfo {
+    number: u32,
+    compression: Compression,
+    /// Number of index entries already loaded.
+    loaded: usize,
+}
+
+/// Random-access reader over a trajectory storage directory.
+pub struct TrajectoryReader {
+    dir: PathBuf,
+    shards: Vec<ShardInfo>,
+    entries: Vec<IndexEntry>,
+}
+
+impl TrajectoryReader {
+    /// Open a storage directory and load its index.
+    pub fn open(dir: impl AsRef<Path>) -> StorageResult<Self> {
+        let mut reader = Self {
+            dir: dir.as_ref().to_path_buf(),
+            shards: Vec::new(),
+            entries: Vec::new(),
+        };
+        reader.refresh()?;
+        Ok(reader)
+    }
+
+    /// Pick up trajectories written since the last refresh.
+    ///
+    /// Returns the number of newly indexed trajectories.
+    pub fn refresh(&mut self) -> StorageResult<usize> {
+        let before = self.entries.len();
+
+        for number in list_shards(&self.dir)? {
+            if !self.shards.iter().any(|s| s.number == number) {
+                // A shard whose header is not fully written yet is picked up later
+                let Some(compression) = read_header(&shard_path(&self.dir, number))? else {
+                    break;
+                };
+                self.shards.push(ShardInfo {
+                    number,
+                    compression,
+                    loaded: 0,
+                });
+            }
+        }
+
+        for shard in &mut self.shards {
+            let new = Self::load_index(&self.dir, shard)?;
+            self.entries.extend(new);
+        }
+
+        Ok(self.entries.len() - before)
+    }
+
+    /// Load index entries of `shard` beyond those already loaded.
+    ///
+    /// Falls back to scanning the shard when its index file is missing.
+    fn load_index(dir: &Path, shard: &mut ShardInfo) -> StorageResult<Vec<IndexEntry>> {
+        let entries = match fs::read(index_path(dir, shard.number)) {
+            Ok(bytes) => bytes
+                .chunks_exact(INDEX_ENTRY_LEN)
+                .map(|chunk| IndexEntry {
+                    shard: shard.number,
+                    offset: u64::from_le_bytes(chunk[..8].try_into().expect("8 bytes")),
+                    len: u32::from_le_bytes(chunk[8..].try_into().expect("4 bytes")),
+                })
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Self::scan_shard(dir, shard.number)?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let new: Vec<IndexEntry> = entries.into_iter().skip(shard.loaded).collect();
+        shard.loaded += new.len();
+        Ok(new)
+    }
+
+    /// Rebuild index entries by walking a shard's record headers.
+    fn scan_shard(dir: &Path, shard: u32) -> StorageResult<Vec<IndexEntry>> {
+        let mut file = BufReader::new(File::open(shard_path(dir, shard))?);
+        let file_len = file.get_ref().metadata()?.len();
+        let mut entries = Vec::new();
+        let mut offset = HEADER_LEN;
+
+        while offset + RECORD_HEADER_LEN <= file_len {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut len = [0u8; 4];
+            file.read_exact(&mut len)?;
+            let len = u32::from_le_bytes(len);
+            let end = offset + RECORD_HEADER_LEN + u64::from(len);
+            if end > file_len {
+                break; // Partially written record
+            }
+            entries.push(IndexEntry { shard, offset, len });
+            offset = end;
+        }
+        Ok(entries)
+    }
+
+    /// Get the number of indexed trajectories.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no trajectories are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the number of shards found.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Read the trajectory at `index`, verifying its checksum.
+    pub fn get(&self, index: usize) -> StorageResult<Trajectory> {
+        let entry = *self
+            .entries
+            .get(index)
+            .ok_or(StorageError::OutOfRange { index })?;
+        let compression = self
+            .shards
+            .iter()
+            .find(|s| s.number == entry.shard)
+            .map(|s| s.compression)
+            .expect("entries belong to known shards");
+        let corrupt = || StorageError::Corrupt {
+            shard: entry.shard,
+            offset: entry.offset,
+        };
+
+        let mut file = File::open(shard_path(&self.dir, entry.shard))?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+        let mut header = [0u8; RECORD_HEADER_LEN as usize];
+        file.read_exact(&mut header).map_err(|_| corrupt())?;
+        let len = u32::from_le_bytes(header[..4].try_into().expect("4 bytes"));
+        let checksum = u32::from_le_bytes(header[4..].try_into().expect("4 bytes"));
+        if len != entry.len {
+            return Err(corrupt());
+        }
+
+        let mut payload = vec![0u8; len as usize];
+        file.read_exact(&mut payload).map_err(|_| corrupt())?;
+        if crc32fast::hash(&payload) != checksum {
+            return Err(corrupt());
+        }
+        decode(&payload, compression)
+    }
+
+    /// Iterate over all trajectories in storage order.
+    pub fn iter(&self) -> impl Iterator<Item = StorageResult<Trajectory>> + '_ {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /// Stream training samples in batches of `batch_size`, in storage order.
+    ///
+    /// The final batch may be smaller.
+    pub fn batches(&self, batch_size: usize, target: ValueTarget) -> SampleBatches<'_> {
+        SampleBatches::new(self, (0..self.len()).collect(), batch_size, target)
+    }
+
+    /// Stream training samples in batches, visiting trajectories in an
+    /// order shuffled deterministically by `seed`.
+    ///
+    /// Samples within a batch are shuffled too.
+    pub fn shuffled_batches(
+        &self,
+        batch_size: usize,
+        target: ValueTarget,
+        seed: u64,
+    ) -> SampleBatches<'_> {
+        let mut rng = GameRng::new(seed);
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        rng.shuffle(&mut order);
+        let mut batches = SampleBatches::new(self, order, batch_size, target);
+        batches.rng = Some(rng);
+        batches
+    }
+}
+
+/// Iterator over training sample batches from a [`TrajectoryReader`].
+///
+/// Loads trajectories lazily, holding at most about one batch of samples
+/// plus one trajectory in memory.
+pub struct SampleBatches<'a> {
+    reader: &'a TrajectoryReader,
+    order: Vec<usize>,
+    position: usize,
+    batch_size: usize,
+    target: ValueTarget,
+    pending: VecDeque<TrainingSample>,
+    rng: Option<GameRng>,
+}
+
+impl<'a> SampleBatches<'a> {
+    fn new(
+        reader: &'a TrajectoryReader,
+        order: Vec<usize>,
+        batch_size: usize,
+        target: ValueTarget,
+    ) -> Self {
+        Self {
+            reader,
+            order,
+            position: 0,
+            batch_size: batch_size.max(1),
+            target,
+            pending: VecDeque::new(),
+            rng: None,
+        }
+    }
+}
+
+impl Iterator for SampleBatches<'_> {
+    type Item = StorageResult<Vec<TrainingSample>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.len() < self.batch_size && self.position < self.order.len() {
+            let index = self.order[self.position];
+            self.position += 1;
+            match self.reader.get(index) {
+                Ok(trajectory) => self
+                    .pending
+                    .extend(trajectory.to_training_samples_with(self.target)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let take = self.batch_size.min(self.pending.len());
+        let mut batch: Vec<TrainingSample> = self.pending.drain(..take).collect();
+        if let Some(rng) = &mut self.rng {
+            rng.shuffle(&mut batch);
+        }
+        Some(Ok(batch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Action, PlayerId, PlayerMap, TemplateId};
+    use crate::nn::EncodedState;
+    use crate::training::Step;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fresh, empty directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "rust-ccg-storage-{}-{}-{}",
+            std::process::id(),
+            name,
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn make_trajectory(seed: u64, len: usize) -> Trajectory {
+        let mut traj = Trajectory::new(seed, 2);
+        for i in 0..len {
+            let action = Action::new(TemplateId::new(i as u16));
+            traj.push(Step::new(
+                EncodedState::new(vec![seed as f32, i as f32], vec![2]),
+                vec![(action.clone(), 1.0)],
+                action,
+                PlayerId::new((i % 2) as u8),
+                i,
+            ));
+        }
+        let mut outcome = PlayerMap::with_value(2, 0.0);
+        outcome[PlayerId::new(0)] = 1.0;
+        traj.set_outcome(outcome);
+        traj
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let dir = temp_dir("roundtrip");
+        let mut writer = TrajectoryWriter::create(&dir, StorageConfig::default()).unwrap();
+        for seed in 0..3 {
+            writer.write(&make_trajectory(seed, 4)).unwrap();
+        }
+        assert_eq!(writer.written(), 3);
+
+        let reader = TrajectoryReader::open(&dir).unwrap();
+        assert_eq!(reader.len(), 3);
+        let traj = reader.get(2).unwrap();
+        assert_eq!(traj.seed, 2);
+        assert_eq!(traj.len(), 4);
+        assert_eq!(traj.steps[3].encoded_state.tensor, vec![2.0, 3.0]);
+        assert!(matches!(
+            reader.get(3),
+            Err(StorageError::OutOfRange { index: 3 })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sharding_and_reopen() {
+        let dir = temp_dir("sharding");
+        let config = StorageConfig::new().with_max_trajectories_per_shard(2);
+        let mut writer = TrajectoryWriter::create(&dir, config.clone()).unwrap();
+        for seed in 0..5 {
+            writer.write(&make_trajectory(seed, 1)).unwrap();
+        }
+        assert_eq!(writer.shard(), 2);
+        drop(writer);
+
+        // A new writer appends in a fresh shard
+        let mut writer = TrajectoryWriter::create(&dir, config).unwrap();
+        assert_eq!(writer.shard(), 3);
+        writer.write(&make_trajectory(5, 1)).unwrap();
+
+        let reader = TrajectoryReader::open(&dir).unwrap();
+        assert_eq!(reader.shard_count(), 4);
+        let seeds: Vec<u64> = reader.iter().map(|t| t.unwrap().seed).collect();
+        assert_eq!(seeds, vec![0, 1, 2, 3, 4, 5]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_refresh_sees_new_trajectories() {
+        let dir = temp_dir("refresh");
+        let config = StorageConfig::new().with_max_trajectories_per_shard(2);
+        let mut writer = TrajectoryWriter::create(&dir, config).unwrap();
+        writer.write(&make_trajectory(0, 1)).unwrap();
+
+        let mut reader = TrajectoryReader::open(&dir).unwrap();
+        assert_eq!(reader.len(), 1);
+
+        writer.write(&make_trajectory(1, 1)).unwrap();
+        writer.write(&make_trajectory(2, 1)).unwrap(); // New shard
+        assert_eq!(reader.refresh().unwrap(), 2);
+        assert_eq!(reader.get(2).unwrap().seed, 2);
+        assert_eq!(reader.refresh().unwrap(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let dir = temp_dir("corrupt");
+        let mut writer = TrajectoryWriter::create(&dir, StorageConfig::default()).unwrap();
+        writer.write(&make_trajectory(0, 3)).unwrap();
+        drop(writer);
+
+        let path = shard_path(&dir, 0);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, bytes).unwrap();
+
+        let reader = TrajectoryReader::open(&dir).unwrap();
+        assert!(matches!(reader.get(0), Err(StorageError::Corrupt { .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_index_is_rebuilt() {
+        let dir = temp_dir("scan");
+        let mut writer = TrajectoryWriter::create(&dir, StorageConfig::default()).unwrap();
+        writer.write(&make_trajectory(0, 2)).unwrap();
+        writer.write(&make_trajectory(1, 2)).unwrap();
+        drop(writer);
+
+        fs::remove_file(index_path(&dir, 0)).unwrap();
+        let reader = TrajectoryReader::open(&dir).unwrap();
+        assert_eq!(reader.len(), 2);
+        assert_eq!(reader.get(1).unwrap().seed, 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_bad_magic() {
+        let dir = temp_dir("magic");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(shard_path(&dir, 0), b"not a shard at all").unwrap();
+
+        assert!(matches!(
+            TrajectoryReader::open(&dir),
+            Err(StorageError::BadMagic { .. })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sample_batches() {
+        let dir = temp_dir("batches");
+        let mut writer = TrajectoryWriter::create(&dir, StorageConfig::default()).unwrap();
+        for seed in 0..3 {
+            writer.write(&make_trajectory(seed, 3)).unwrap();
+        }
+
+        let reader = TrajectoryReader::open(&dir).unwrap();
+        let sizes: Vec<usize> = reader
+            .batches(4, ValueTarget::Outcome)
+            .map(|b| b.unwrap().len())
+            .collect();
+        assert_eq!(sizes, vec![4, 4, 1]);
+
+        let first = |seed| {
+            reader
+                .shuffled_batches(4, ValueTarget::Outcome, seed)
+                .next()
+                .unwrap()
+                .unwrap()
+                .iter()
+                .map(|s| s.state.tensor.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(first(7), first(7));
+        let total: usize = reader
+            .shuffled_batches(4, ValueTarget::Outcome, 7)
+            .map(|b| b.unwrap().len())
+            .sum();
+        assert_eq!(total, 9);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_lz4_roundtrip() {
+        let dir = temp_dir("lz4");
+        let config = StorageConfig::new().with_compression(Compression::Lz4);
+        let mut writer = TrajectoryWriter::create(&dir, config).unwrap();
+        writer.write(&make_trajectory(9, 20)).unwrap();
+
+        let reader = TrajectoryReader::open(&dir).unwrap();
+        let traj = reader.get(0).unwrap();
+        assert_eq!(traj.seed, 9);
+        assert_eq!(traj.len(), 20);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn test_lz4_unavailable() {
+        let dir = temp_dir("nolz4");
+        let config = StorageConfig::new().with_compression(Compression::Lz4);
+        assert!(matches!(
+            TrajectoryWriter::create(&dir, config),
+            Err(StorageError::CompressionUnavailable(Compression::Lz4))
+        ));
+    }
+}
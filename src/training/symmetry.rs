@@ -0,0 +1,245 @@
+//! Data augmentation via game symmetries.
+//!
+//! Many positions have equivalent relabelled forms: in an N-player game
+//! the same position can be seen with seats rotated, and interchangeable
+//! cards can be swapped. A [`Symmetry`] maps a [`TrainingSample`] to one of
+//! these equivalent forms, transforming the state tensor, policy vector,
+//! acting player and per-seat value vector consistently.
+//!
+//! Attach a symmetry to an [`ExperienceBuffer`] with
+//! [`with_symmetry`](super::ExperienceBuffer::with_symmetry) and each
+//! sampled training example gets a random symmetry applied.
+//!
+//! [`ExperienceBuffer`]: super::ExperienceBuffer
+
+use crate::core::PlayerId;
+use crate::nn::EncodedState;
+
+use super::trajectory::TrainingSample;
+
+/// A group of symmetries of a game's encoding.
+///
+/// Symmetries are numbered `0..count()`, with `0` the identity. Games
+/// implement the parts that apply to them; the defaults leave players and
+/// policies unchanged.
+pub trait Symmetry: Send + Sync {
+    /// Number of symmetries, including the identity.
+    fn count(&self) -> usize;
+
+    /// Map a seat under symmetry `index`.
+    fn map_player(&self, _index: usize, player: PlayerId) -> PlayerId {
+        player
+    }
+
+    /// Transform an encoded state under symmetry `index`.
+    fn apply_state(&self, index: usize, state: &EncodedState) -> EncodedState;
+
+    /// Transform a policy vector under symmetry `index`.
+    fn apply_policy(&self, _index: usize, policy: &[f32]) -> Vec<f32> {
+        policy.to_vec()
+    }
+
+    /// Transform a whole sample under symmetry `index`.
+    ///
+    /// The per-seat value vector is permuted with [`map_player`](Self::map_player).
+    fn apply(&self, index: usize, sample: &TrainingSample) -> TrainingSample {
+        if index == 0 {
+            return sample.clone();
+        }
+
+        let mut values = vec![0.0; sample.values.len()];
+        for (seat, value) in sample.values.iter().enumerate() {
+            let mapped = self.map_player(index, PlayerId::new(seat as u8)).index();
+            if let Some(slot) = values.get_mut(mapped) {
+                *slot = *value;
+            }
+        }
+
+        TrainingSample {
+            state: self.apply_state(index, &sample.state),
+            policy: self.apply_policy(index, &sample.policy),
+            value: sample.value,
+            player: self.map_player(index, sample.player),
+            values,
+        }
+    }
+}
+
+impl std::fmt::Debug for dyn Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Symmetry(count={})", self.count())
+    }
+}
+
+/// Seat rotation for encodings laid out as one feature block per seat.
+///
+/// Rotation `k` relabels seat `p` as `(p + k) % player_count`, moving its
+/// feature block accordingly (this matches
+/// [`SimpleGameEncoder`](crate::nn::SimpleGameEncoder)). Features after the
+/// last seat block are left in place. Policies are unchanged, so this is
+/// only valid when actions do not refer to seats by index.
+#[derive(Clone, Debug)]
+pub struct SeatRotation {
+    player_count: usize,
+    features_per_player: usize,
+}
+
+impl SeatRotation {
+    /// Create a seat rotation symmetry.
+    pub fn new(player_count: usize, features_per_player: usize) -> Self {
+        Self {
+            player_count: player_count.max(1),
+            features_per_player,
+        }
+    }
+}
+
+impl Symmetry for SeatRotation {
+    fn count(&self) -> usize {
+        self.player_count
+    }
+
+    fn map_player(&self, index: usize, player: PlayerId) -> PlayerId {
+        PlayerId::new(((player.index() + index) % self.player_count) as u8)
+    }
+
+    fn apply_state(&self, index: usize, state: &EncodedState) -> EncodedState {
+        let block = self.features_per_player;
+        let seats_len = block * self.player_count;
+        if state.len() < seats_len {
+            return state.clone();
+        }
+
+        let mut tensor = state.tensor.clone();
+        for seat in 0..self.player_count {
+            let target = (seat + index) % self.player_count;
+            tensor[target * block..(target + 1) * block]
+                .copy_from_slice(&state.tensor[seat * block..(seat + 1) * block]);
+        }
+        EncodedState::new(tensor, state.shape.clone())
+    }
+}
+
+/// Explicit index permutations, e.g. for interchangeable cards.
+///
+/// Each permutation gathers `out[i] = in[perm[i]]`. A policy permutation is
+/// applied only when its length matches the policy vector (legal action
+/// counts vary between positions); otherwise the policy is unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct PermutationSymmetry {
+    permutations: Vec<(Vec<usize>, Option<Vec<usize>>)>,
+}
+
+impl PermutationSymmetry {
+    /// Create a symmetry group containing only the identity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a permutation of the state tensor and, optionally, of the policy.
+    pub fn with_permutation(mut self, state: Vec<usize>, policy: Option<Vec<usize>>) -> Self {
+        self.permutations.push((state, policy));
+        self
+    }
+
+    fn gather(perm: &[usize], values: &[f32]) -> Option<Vec<f32>> {
+        if perm.len() != values.len() {
+            return None;
+        }
+        perm.iter().map(|&i| values.get(i).copied()).collect()
+    }
+}
+
+impl Symmetry for PermutationSymmetry {
+    fn count(&self) -> usize {
+        self.permutations.len() + 1
+    }
+
+    fn apply_state(&self, index: usize, state: &EncodedState) -> EncodedState {
+        let Some((perm, _)) = index.checked_sub(1).and_then(|i| self.permutations.get(i)) else {
+            return state.clone();
+        };
+        match Self::gather(perm, &state.tensor) {
+            Some(tensor) => EncodedState::new(tensor, state.shape.clone()),
+            None => state.clone(),
+        }
+    }
+
+    fn apply_policy(&self, index: usize, policy: &[f32]) -> Vec<f32> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.permutations.get(i))
+            .and_then(|(_, perm)| perm.as_deref())
+            .and_then(|perm| Self::gather(perm, policy))
+            .unwrap_or_else(|| policy.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(tensor: Vec<f32>, player: u8, values: Vec<f32>) -> TrainingSample {
+        let len = tensor.len();
+        TrainingSample {
+            state: EncodedState::new(tensor, vec![len]),
+            policy: vec![0.25, 0.75],
+            value: values[player as usize],
+            player: PlayerId::new(player),
+            values,
+        }
+    }
+
+    #[test]
+    fn test_seat_rotation() {
+        let rotation = SeatRotation::new(3, 2);
+        assert_eq!(rotation.count(), 3);
+
+        // Seat blocks [a, b, c] plus one global feature
+        let s = sample(vec![0.0, 0.1, 1.0, 1.1, 2.0, 2.1, 9.0], 0, vec![1.0, 0.0, 0.5]);
+        let rotated = rotation.apply(1, &s);
+
+        assert_eq!(rotated.player, PlayerId::new(1));
+        assert_eq!(
+            rotated.state.tensor,
+            vec![2.0, 2.1, 0.0, 0.1, 1.0, 1.1, 9.0]
+        );
+        assert_eq!(rotated.values, vec![0.5, 1.0, 0.0]);
+        assert_eq!(rotated.value, rotated.values[rotated.player.index()]);
+        assert_eq!(rotated.policy, s.policy);
+
+        // Identity
+        let same = rotation.apply(0, &s);
+        assert_eq!(same.state.tensor, s.state.tensor);
+        assert_eq!(same.player, s.player);
+    }
+
+    #[test]
+    fn test_seat_rotation_composes_to_identity() {
+        let rotation = SeatRotation::new(4, 1);
+        let s = sample(vec![0.0, 1.0, 2.0, 3.0], 2, vec![0.0, 0.0, 1.0, 0.0]);
+
+        let back = rotation.apply(1, &rotation.apply(3, &s));
+        assert_eq!(back.state.tensor, s.state.tensor);
+        assert_eq!(back.player, s.player);
+        assert_eq!(back.values, s.values);
+    }
+
+    #[test]
+    fn test_permutation_symmetry() {
+        // Swap two interchangeable card slots and their actions
+        let symmetry = PermutationSymmetry::new()
+            .with_permutation(vec![1, 0, 2], Some(vec![1, 0]));
+        assert_eq!(symmetry.count(), 2);
+
+        let s = sample(vec![0.3, 0.6, 0.9], 1, vec![0.0, 1.0]);
+        let swapped = symmetry.apply(1, &s);
+        assert_eq!(swapped.state.tensor, vec![0.6, 0.3, 0.9]);
+        assert_eq!(swapped.policy, vec![0.75, 0.25]);
+        assert_eq!(swapped.player, s.player);
+        assert_eq!(swapped.values, s.values);
+
+        // Policy of a different length is left alone
+        assert_eq!(symmetry.apply_policy(1, &[0.2, 0.3, 0.5]), vec![0.2, 0.3, 0.5]);
+    }
+}
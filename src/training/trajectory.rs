@@ -4,15 +4,24 @@
 //! - Encoded states at each decision point
 //! - MCTS action probabilities (the "target" policy)
 //! - Actions actually taken
-//! - Final game outcome for value targets
+//! - Final game outcome and per-step root values for value targets
+//!
+//! Value targets default to the final outcome; see [`ValueTarget`] for
+//! n-step, TD(λ) and outcome/Q blended alternatives.
 
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use rustc_hash::FxHashMap;
+
 use crate::core::{Action, PlayerId, PlayerMap};
 use crate::nn::EncodedState;
 
+use super::replay::{self, ReplayConfig, ReplaySample, SampleId, MIN_PRIORITY};
+use super::symmetry::Symmetry;
+
 /// A single step in a trajectory.
 ///
 /// Captures the state, MCTS policy, and action taken at one decision point.
@@ -33,6 +42,10 @@ pub struct Step {
 
     /// Move number in the game (0-indexed).
     pub move_number: usize,
+
+    /// MCTS root value estimate (per player), if recorded.
+    #[serde(default)]
+    pub root_value: Option<PlayerMap<f64>>,
 }
 
 impl Step {
@@ -50,9 +63,16 @@ impl Step {
             action_taken,
             player,
             move_number,
+            root_value: None,
         }
     }
 
+    /// Attach the search's root value estimate.
+    pub fn with_root_value(mut self, root_value: Option<PlayerMap<f64>>) -> Self {
+        self.root_value = root_value;
+        self
+    }
+
     /// Get the probability assigned to the taken action.
     pub fn taken_action_prob(&self) -> f64 {
         self.action_probs
@@ -63,6 +83,65 @@ impl Step {
     }
 }
 
+/// How the value target of a training sample is computed.
+///
+/// Bootstrapped targets read each step's `root_value`; steps without one
+/// fall back to the final outcome, so legacy trajectories behave like
+/// [`ValueTarget::Outcome`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ValueTarget {
+    /// Final game outcome (AlphaZero style).
+    #[default]
+    Outcome,
+
+    /// Bootstrap from the root value `n` steps ahead, or the discounted
+    /// outcome when the game ends first.
+    NStep {
+        /// Number of steps to look ahead (0 = the step's own root value).
+        n: usize,
+        /// Per-step discount factor.
+        discount: f64,
+    },
+
+    /// λ-return over all later root values, ending in the outcome.
+    /// `lambda = 1.0` is the outcome, `lambda = 0.0` is one-step TD.
+    TdLambda {
+        /// Trace decay in `[0, 1]`.
+        lambda: f64,
+        /// Per-step discount factor.
+        discount: f64,
+    },
+
+    /// `outcome_weight * z + (1 - outcome_weight) * q` where `q` is the
+    /// step's own root value.
+    Blend {
+        /// Weight of the final outcome in `[0, 1]`.
+        outcome_weight: f64,
+    },
+}
+
+impl ValueTarget {
+    /// Undiscounted n-step target.
+    pub fn n_step(n: usize) -> Self {
+        ValueTarget::NStep { n, discount: 1.0 }
+    }
+
+    /// Undiscounted TD(λ) target.
+    pub fn td_lambda(lambda: f64) -> Self {
+        ValueTarget::TdLambda {
+            lambda: lambda.clamp(0.0, 1.0),
+            discount: 1.0,
+        }
+    }
+
+    /// Blend of outcome and search Q.
+    pub fn blend(outcome_weight: f64) -> Self {
+        ValueTarget::Blend {
+            outcome_weight: outcome_weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
 /// A complete game trajectory from self-play.
 ///
 /// Contains all decision points and the final outcome,
@@ -132,16 +211,79 @@ impl Trajectory {
     /// - Target policy (MCTS probabilities)
     /// - Target value (game outcome from player's perspective)
     pub fn to_training_samples(&self) -> Vec<TrainingSample> {
+        self.to_training_samples_with(ValueTarget::Outcome)
+    }
+
+    /// Convert to training samples using the given value target.
+    pub fn to_training_samples_with(&self, target: ValueTarget) -> Vec<TrainingSample> {
         self.steps
             .iter()
-            .map(|step| TrainingSample {
-                state: step.encoded_state.clone(),
-                policy: step.action_probs.iter().map(|(_, p)| *p as f32).collect(),
-                value: self.outcome[step.player] as f32,
-                player: step.player,
-            })
+            .zip(self.value_target_maps(target))
+            .map(|(step, values)| TrainingSample::from_step(step, &values))
             .collect()
     }
+
+    /// Compute the value target of every step, from its player's perspective.
+    pub fn value_targets(&self, target: ValueTarget) -> Vec<f64> {
+        self.steps
+            .iter()
+            .zip(self.value_target_maps(target))
+            .map(|(step, values)| values[step.player])
+            .collect()
+    }
+
+    /// Compute the value target of every step for every seat.
+    pub fn value_target_maps(&self, target: ValueTarget) -> Vec<PlayerMap<f64>> {
+        let len = self.steps.len();
+        let player_count = self.outcome.player_count();
+        match target {
+            ValueTarget::Outcome => vec![self.outcome.clone(); len],
+            ValueTarget::NStep { n, discount } => (0..len)
+                .map(|t| {
+                    let (factor, base) = if t + n < len {
+                        (discount.powi(n as i32), t + n)
+                    } else {
+                        (discount.powi((len - t) as i32), len)
+                    };
+                    PlayerMap::new(player_count, |p| factor * self.bootstrap_value(base, p))
+                })
+                .collect(),
+            ValueTarget::TdLambda { lambda, discount } => {
+                // Backward recursion over all players at once:
+                // G_T = z, G_t = γ((1 - λ) V_{t+1} + λ G_{t+1}) with V_T = z.
+                let mut targets = Vec::with_capacity(len);
+                let mut next_return = self.outcome.clone();
+                for t in (0..len).rev() {
+                    let ret = PlayerMap::new(player_count, |p| {
+                        discount
+                            * ((1.0 - lambda) * self.bootstrap_value(t + 1, p)
+                                + lambda * next_return[p])
+                    });
+                    targets.push(ret.clone());
+                    next_return = ret;
+                }
+                targets.reverse();
+                targets
+            }
+            ValueTarget::Blend { outcome_weight } => (0..len)
+                .map(|t| {
+                    PlayerMap::new(player_count, |p| {
+                        outcome_weight * self.outcome[p]
+                            + (1.0 - outcome_weight) * self.bootstrap_value(t, p)
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Root value of step `index` for `player`, falling back to the outcome
+    /// (which is also the value past the last step).
+    fn bootstrap_value(&self, index: usize, player: PlayerId) -> f64 {
+        self.steps
+            .get(index)
+            .and_then(|step| step.root_value.as_ref())
+            .map_or(self.outcome[player], |v| v[player])
+    }
 }
 
 /// A single training sample extracted from a trajectory.
@@ -158,15 +300,50 @@ pub struct TrainingSample {
 
     /// Player whose perspective this is from.
     pub player: PlayerId,
+
+    /// Value targets for every seat, indexed by player (empty if unknown).
+    #[serde(default)]
+    pub values: Vec<f32>,
+}
+
+impl TrainingSample {
+    /// Build a sample from a step and its per-seat value targets.
+    pub fn from_step(step: &Step, values: &PlayerMap<f64>) -> Self {
+        Self {
+            state: step.encoded_state.clone(),
+            policy: step.action_probs.iter().map(|(_, p)| *p as f32).collect(),
+            value: values[step.player] as f32,
+            player: step.player,
+            values: values.iter().map(|(_, v)| *v as f32).collect(),
+        }
+    }
+}
+
+/// Per-trajectory replay bookkeeping, kept parallel to the trajectories.
+#[derive(Clone, Debug)]
+struct ReplayEntry {
+    id: u64,
+    generation: u64,
+    priorities: Vec<f64>,
 }
 
 /// Buffer for storing trajectories during training.
 ///
 /// Uses a FIFO strategy: when full, oldest trajectories are removed.
+/// Sampling is uniform by default; see [`ReplayConfig`] for prioritized,
+/// recency-weighted and stratified replay. An optional [`Symmetry`]
+/// augments sampled batches.
 #[derive(Clone, Debug)]
 pub struct ExperienceBuffer {
     trajectories: VecDeque<Trajectory>,
+    entries: VecDeque<ReplayEntry>,
     max_trajectories: usize,
+    value_target: ValueTarget,
+    replay: ReplayConfig,
+    symmetry: Option<Arc<dyn Symmetry>>,
+    generation: u64,
+    next_id: u64,
+    max_priority: f64,
 }
 
 impl ExperienceBuffer {
@@ -174,17 +351,90 @@ impl ExperienceBuffer {
     pub fn new(max_trajectories: usize) -> Self {
         Self {
             trajectories: VecDeque::with_capacity(max_trajectories),
+            entries: VecDeque::with_capacity(max_trajectories),
             max_trajectories,
+            value_target: ValueTarget::Outcome,
+            replay: ReplayConfig::default(),
+            symmetry: None,
+            generation: 0,
+            next_id: 0,
+            max_priority: 1.0,
         }
     }
 
-    /// Add a trajectory to the buffer.
+    /// Set how sample value targets are computed (builder pattern).
+    pub fn with_value_target(mut self, target: ValueTarget) -> Self {
+        self.value_target = target;
+        self
+    }
+
+    /// Get the value target used when extracting samples.
+    pub fn value_target(&self) -> ValueTarget {
+        self.value_target
+    }
+
+    /// Set the replay sampling config (builder pattern).
+    pub fn with_replay(mut self, replay: ReplayConfig) -> Self {
+        self.replay = replay;
+        self
+    }
+
+    /// Get the replay sampling config.
+    pub fn replay(&self) -> &ReplayConfig {
+        &self.replay
+    }
+
+    /// Set the replay sampling config.
+    pub fn set_replay(&mut self, replay: ReplayConfig) {
+        self.replay = replay;
+    }
+
+    /// Augment sampled batches with random symmetries (builder pattern).
+    pub fn with_symmetry(mut self, symmetry: impl Symmetry + 'static) -> Self {
+        self.symmetry = Some(Arc::new(symmetry));
+        self
+    }
+
+    /// Set or clear the symmetry used to augment sampled batches.
+    pub fn set_symmetry(&mut self, symmetry: Option<Arc<dyn Symmetry>>) {
+        self.symmetry = symmetry;
+    }
+
+    /// Get the symmetry used to augment sampled batches.
+    pub fn symmetry(&self) -> Option<&Arc<dyn Symmetry>> {
+        self.symmetry.as_ref()
+    }
+
+    /// Get the current generation (network version).
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Set the current generation; later pushes are tagged with it.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// Add a trajectory to the buffer, tagged with the current generation.
     ///
     /// If the buffer is full, the oldest trajectory is removed.
+    /// New steps start at the highest priority seen so far.
     pub fn push(&mut self, trajectory: Trajectory) {
+        self.push_with_generation(trajectory, self.generation);
+    }
+
+    /// Add a trajectory produced by a specific generation.
+    pub fn push_with_generation(&mut self, trajectory: Trajectory, generation: u64) {
         if self.trajectories.len() >= self.max_trajectories {
             self.trajectories.pop_front();
+            self.entries.pop_front();
         }
+        self.entries.push_back(ReplayEntry {
+            id: self.next_id,
+            generation,
+            priorities: vec![self.max_priority; trajectory.len()],
+        });
+        self.next_id += 1;
         self.trajectories.push_back(trajectory);
     }
 
@@ -206,6 +456,7 @@ impl ExperienceBuffer {
     /// Clear all trajectories.
     pub fn clear(&mut self) {
         self.trajectories.clear();
+        self.entries.clear();
     }
 
     /// Get an iterator over trajectories.
@@ -213,6 +464,13 @@ impl ExperienceBuffer {
         self.trajectories.iter()
     }
 
+    /// Get a mutable iterator over trajectories (e.g. for reanalyse).
+    ///
+    /// Callers must not add or remove steps.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Trajectory> {
+        self.trajectories.iter_mut()
+    }
+
     /// Get the total number of steps across all trajectories.
     pub fn total_steps(&self) -> usize {
         self.trajectories.iter().map(|t| t.len()).sum()
@@ -222,16 +480,26 @@ impl ExperienceBuffer {
     pub fn to_training_samples(&self) -> Vec<TrainingSample> {
         self.trajectories
             .iter()
-            .flat_map(|t| t.to_training_samples())
+            .flat_map(|t| t.to_training_samples_with(self.value_target))
             .collect()
     }
 
     /// Sample a random batch of training samples.
     ///
-    /// Uses the provided RNG seed for reproducibility.
+    /// Uses the provided RNG seed for reproducibility. With the default
+    /// (uniform) replay config this draws without replacement; otherwise it
+    /// delegates to [`sample_replay`](Self::sample_replay).
     pub fn sample_batch(&self, batch_size: usize, seed: u64) -> Vec<TrainingSample> {
         use crate::core::GameRng;
 
+        if !self.replay.is_uniform() {
+            return self
+                .sample_replay(batch_size, seed)
+                .into_iter()
+                .map(|r| r.sample)
+                .collect();
+        }
+
         let all_samples = self.to_training_samples();
         if all_samples.is_empty() || batch_size == 0 {
             return vec![];
@@ -249,11 +517,116 @@ impl ExperienceBuffer {
             indices.swap(i, j);
         }
 
-        indices
+        let mut batch: Vec<TrainingSample> = indices
             .into_iter()
             .take(batch_size)
             .map(|i| all_samples[i].clone())
-            .collect()
+            .collect();
+        self.augment(&mut batch, seed);
+        batch
+    }
+
+    /// Apply a random symmetry to each sample, if a symmetry is set.
+    ///
+    /// Deterministic given the seed, and independent of the draw itself.
+    fn augment<'a>(&self, samples: impl IntoIterator<Item = &'a mut TrainingSample>, seed: u64) {
+        use crate::core::GameRng;
+
+        let Some(symmetry) = &self.symmetry else {
+            return;
+        };
+        let count = symmetry.count();
+        if count <= 1 {
+            return;
+        }
+
+        let mut rng = GameRng::new(seed).for_context("augment");
+        for sample in samples {
+            let index = rng.gen_range_usize(0..count);
+            if index != 0 {
+                *sample = symmetry.apply(index, sample);
+            }
+        }
+    }
+
+    /// Sample a batch using the replay config, with replacement.
+    ///
+    /// Returns exactly `batch_size` samples (unless the buffer is empty),
+    /// each with its [`SampleId`] and importance weight. Samples are
+    /// augmented if a symmetry is set.
+    pub fn sample_replay(&self, batch_size: usize, seed: u64) -> Vec<ReplaySample> {
+        use crate::core::GameRng;
+
+        let mut locations = Vec::with_capacity(self.total_steps());
+        let mut candidates = Vec::with_capacity(self.total_steps());
+        for (t, (traj, entry)) in self.trajectories.iter().zip(&self.entries).enumerate() {
+            let age = self.generation.saturating_sub(entry.generation);
+            for (i, (step, &priority)) in traj.steps.iter().zip(&entry.priorities).enumerate() {
+                let key = self.replay.stratify.key(step.player.0, i, traj.len());
+                locations.push((t, i));
+                candidates.push((key, self.replay.weight(priority, age)));
+            }
+        }
+
+        let mut rng = GameRng::new(seed);
+        let drawn = replay::draw(&candidates, batch_size, &mut rng);
+
+        // Importance weights (N * P)^-β, normalized by the batch maximum
+        let n = candidates.len() as f64;
+        let beta = self.replay.importance_exponent;
+        let raw: Vec<f64> = drawn.iter().map(|(_, p)| (n * p).powf(-beta)).collect();
+        let max_weight = raw.iter().cloned().fold(0.0, f64::max);
+
+        let mut values: FxHashMap<usize, Vec<PlayerMap<f64>>> = FxHashMap::default();
+        let mut samples: Vec<ReplaySample> = drawn
+            .iter()
+            .zip(raw)
+            .map(|(&(c, _), w)| {
+                let (t, i) = locations[c];
+                let traj = &self.trajectories[t];
+                let maps = values
+                    .entry(t)
+                    .or_insert_with(|| traj.value_target_maps(self.value_target));
+                ReplaySample {
+                    id: SampleId::new(self.entries[t].id, i),
+                    sample: TrainingSample::from_step(&traj.steps[i], &maps[i]),
+                    weight: if max_weight > 0.0 { (w / max_weight) as f32 } else { 1.0 },
+                }
+            })
+            .collect();
+        self.augment(samples.iter_mut().map(|r| &mut r.sample), seed);
+        samples
+    }
+
+    /// Get the current priority of a step, if it is still in the buffer.
+    pub fn priority(&self, id: SampleId) -> Option<f64> {
+        self.entry_index(id.trajectory)
+            .and_then(|t| self.entries[t].priorities.get(id.step).copied())
+    }
+
+    /// Update step priorities (e.g. with new training losses).
+    ///
+    /// Priorities are stored as absolute values with a small floor.
+    /// Ids of evicted trajectories are ignored. Returns the number updated.
+    pub fn update_priorities(&mut self, updates: &[(SampleId, f64)]) -> usize {
+        let mut updated = 0;
+        for &(id, priority) in updates {
+            let Some(t) = self.entry_index(id.trajectory) else {
+                continue;
+            };
+            if let Some(slot) = self.entries[t].priorities.get_mut(id.step) {
+                let priority = priority.abs().max(MIN_PRIORITY);
+                *slot = priority;
+                self.max_priority = self.max_priority.max(priority);
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Index of the trajectory with buffer id `id` (ids are increasing).
+    fn entry_index(&self, id: u64) -> Option<usize> {
+        self.entries.binary_search_by_key(&id, |e| e.id).ok()
     }
 }
 
@@ -267,6 +640,7 @@ impl Default for ExperienceBuffer {
 mod tests {
     use super::*;
     use crate::core::TemplateId;
+    use crate::training::{SeatRotation, Stratify};
     use crate::nn::EncodedState;
 
     fn make_test_step(player: u8, move_num: usize) -> Step {
@@ -362,6 +736,101 @@ mod tests {
         assert_eq!(samples[1].value, 0.0);
     }
 
+    /// Alternating two-player trajectory where player 0 wins and every
+    /// step's root value for player 0 is `q0`.
+    fn make_valued_trajectory(len: usize, q0: f64) -> Trajectory {
+        let mut traj = Trajectory::new(42, 2);
+        for i in 0..len {
+            let mut q = PlayerMap::with_value(2, 1.0 - q0);
+            q[PlayerId::new(0)] = q0;
+            traj.push(make_test_step((i % 2) as u8, i).with_root_value(Some(q)));
+        }
+        let mut outcome = PlayerMap::with_value(2, 0.0);
+        outcome[PlayerId::new(0)] = 1.0;
+        traj.set_outcome(outcome);
+        traj
+    }
+
+    #[test]
+    fn test_value_target_n_step() {
+        let traj = make_valued_trajectory(4, 0.5);
+
+        // n = 2: steps 0 and 1 bootstrap, steps 2 and 3 reach the outcome
+        let targets = traj.value_targets(ValueTarget::n_step(2));
+        assert_eq!(targets, vec![0.5, 0.5, 1.0, 0.0]);
+
+        // Large n is the plain outcome
+        let targets = traj.value_targets(ValueTarget::n_step(10));
+        assert_eq!(targets, traj.value_targets(ValueTarget::Outcome));
+
+        // Discounting
+        let targets = traj.value_targets(ValueTarget::NStep { n: 1, discount: 0.5 });
+        assert!((targets[3] - 0.0).abs() < 1e-9); // 0.5 * z (player 1 lost)
+        assert!((targets[2] - 0.25).abs() < 1e-9); // 0.5 * q(step 3)
+        assert!((targets[0] - 0.25).abs() < 1e-9); // 0.5 * q(step 1)
+    }
+
+    #[test]
+    fn test_value_target_td_lambda() {
+        let traj = make_valued_trajectory(4, 0.5);
+
+        // λ = 1 is Monte Carlo
+        let targets = traj.value_targets(ValueTarget::td_lambda(1.0));
+        assert_eq!(targets, traj.value_targets(ValueTarget::Outcome));
+
+        // λ = 0 is one-step TD
+        let targets = traj.value_targets(ValueTarget::td_lambda(0.0));
+        assert_eq!(targets, traj.value_targets(ValueTarget::n_step(1)));
+
+        // λ = 0.5, step 2 (player 0): 0.5 * q3 + 0.5 * z = 0.75
+        let targets = traj.value_targets(ValueTarget::td_lambda(0.5));
+        assert!((targets[2] - 0.75).abs() < 1e-9);
+        // step 1 (player 1): 0.5 * q2 + 0.5 * G2 where G2 for p1 = 0.25
+        assert!((targets[1] - 0.375).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_value_target_blend() {
+        let traj = make_valued_trajectory(2, 0.2);
+
+        let targets = traj.value_targets(ValueTarget::blend(0.5));
+        assert!((targets[0] - 0.6).abs() < 1e-9); // (1.0 + 0.2) / 2
+        assert!((targets[1] - 0.4).abs() < 1e-9); // (0.0 + 0.8) / 2
+
+        let targets = traj.value_targets(ValueTarget::blend(0.0));
+        assert!((targets[0] - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_value_target_without_root_values_falls_back() {
+        let mut traj = Trajectory::new(42, 2);
+        traj.push(make_test_step(0, 0));
+        traj.push(make_test_step(1, 1));
+        let mut outcome = PlayerMap::with_value(2, 0.0);
+        outcome[PlayerId::new(0)] = 1.0;
+        traj.set_outcome(outcome);
+
+        let expected = traj.value_targets(ValueTarget::Outcome);
+        for target in [
+            ValueTarget::n_step(1),
+            ValueTarget::td_lambda(0.3),
+            ValueTarget::blend(0.5),
+        ] {
+            assert_eq!(traj.value_targets(target), expected);
+        }
+    }
+
+    #[test]
+    fn test_experience_buffer_value_target() {
+        let mut buffer = ExperienceBuffer::new(10).with_value_target(ValueTarget::blend(0.0));
+        assert_eq!(buffer.value_target(), ValueTarget::blend(0.0));
+        buffer.push(make_valued_trajectory(2, 0.25));
+
+        let samples = buffer.to_training_samples();
+        assert_eq!(samples[0].value, 0.25);
+        assert_eq!(samples[1].value, 0.75);
+    }
+
     #[test]
     fn test_experience_buffer_capacity() {
         let mut buffer = ExperienceBuffer::new(3);
@@ -432,6 +901,132 @@ mod tests {
         assert_eq!(batch.len(), 2); // Only 2 samples available
     }
 
+    fn make_buffer_trajectory(seed: u64, len: usize) -> Trajectory {
+        let mut traj = Trajectory::new(seed, 2);
+        for i in 0..len {
+            traj.push(make_test_step((i % 2) as u8, i));
+        }
+        traj
+    }
+
+    #[test]
+    fn test_experience_buffer_sample_replay_deterministic() {
+        let mut buffer = ExperienceBuffer::new(10)
+            .with_replay(ReplayConfig::new().with_priorities(1.0, 0.5));
+        buffer.push(make_buffer_trajectory(1, 6));
+        buffer.push(make_buffer_trajectory(2, 6));
+
+        let a = buffer.sample_replay(20, 9);
+        let b = buffer.sample_replay(20, 9);
+        assert_eq!(a.len(), 20);
+        let ids_a: Vec<_> = a.iter().map(|r| r.id).collect();
+        let ids_b: Vec<_> = b.iter().map(|r| r.id).collect();
+        assert_eq!(ids_a, ids_b);
+        assert!(a.iter().all(|r| r.weight > 0.0 && r.weight <= 1.0));
+    }
+
+    #[test]
+    fn test_experience_buffer_update_priorities() {
+        let mut buffer = ExperienceBuffer::new(2)
+            .with_replay(ReplayConfig::new().with_priorities(1.0, 1.0));
+        buffer.push(make_buffer_trajectory(1, 4));
+
+        let hot = SampleId::new(0, 2);
+        assert_eq!(buffer.priority(hot), Some(1.0));
+        let updates: Vec<_> = (0..4)
+            .map(|i| (SampleId::new(0, i), if i == 2 { 100.0 } else { 0.0 }))
+            .collect();
+        assert_eq!(buffer.update_priorities(&updates), 4);
+
+        let batch = buffer.sample_replay(50, 3);
+        let hot_count = batch.iter().filter(|r| r.id == hot).count();
+        assert!(hot_count > 45);
+        // Rarely drawn samples get the largest importance weight
+        let hot_weight = batch.iter().find(|r| r.id == hot).unwrap().weight;
+        assert!(batch.iter().all(|r| r.weight >= hot_weight));
+
+        // New trajectories start at the max priority seen so far
+        buffer.push(make_buffer_trajectory(2, 1));
+        assert_eq!(buffer.priority(SampleId::new(1, 0)), Some(100.0));
+
+        // Evicted ids are ignored
+        buffer.push(make_buffer_trajectory(3, 1));
+        assert_eq!(buffer.priority(hot), None);
+        assert_eq!(buffer.update_priorities(&[(hot, 1.0)]), 0);
+    }
+
+    #[test]
+    fn test_experience_buffer_recency_weighting() {
+        let mut buffer =
+            ExperienceBuffer::new(10).with_replay(ReplayConfig::new().with_recency_decay(0.1));
+        buffer.push_with_generation(make_buffer_trajectory(1, 4), 0);
+        buffer.set_generation(2);
+        buffer.push(make_buffer_trajectory(2, 4));
+        assert_eq!(buffer.generation(), 2);
+
+        let batch = buffer.sample_replay(100, 11);
+        let recent = batch.iter().filter(|r| r.id.trajectory == 1).count();
+        assert!(recent > 90);
+    }
+
+    #[test]
+    fn test_experience_buffer_stratified_by_player() {
+        let mut buffer = ExperienceBuffer::new(10)
+            .with_replay(ReplayConfig::new().with_stratify(Stratify::Player));
+        // Player 0 has 9 steps, player 1 only one
+        let mut traj = Trajectory::new(1, 2);
+        for i in 0..9 {
+            traj.push(make_test_step(0, i));
+        }
+        traj.push(make_test_step(1, 9));
+        buffer.push(traj);
+
+        let batch = buffer.sample_batch(10, 4);
+        assert_eq!(batch.len(), 10);
+        let p1 = batch.iter().filter(|s| s.player == PlayerId::new(1)).count();
+        assert_eq!(p1, 5);
+    }
+
+    #[test]
+    fn test_experience_buffer_symmetry_augmentation() {
+        let mut buffer = ExperienceBuffer::new(10).with_symmetry(SeatRotation::new(2, 5));
+        let mut traj = Trajectory::new(1, 2);
+        for i in 0..20 {
+            let mut tensor = vec![0.0; 10];
+            tensor[4] = 1.0; // Player 0 marker in seat 0's block
+            let action = Action::new(TemplateId::new(0));
+            traj.push(Step::new(
+                EncodedState::new(tensor, vec![10]),
+                vec![(action.clone(), 1.0)],
+                action,
+                PlayerId::new(0),
+                i,
+            ));
+        }
+        let mut outcome = PlayerMap::with_value(2, 0.0);
+        outcome[PlayerId::new(0)] = 1.0;
+        traj.set_outcome(outcome);
+        buffer.push(traj);
+
+        let batch = buffer.sample_batch(20, 5);
+        let rotated: Vec<_> = batch
+            .iter()
+            .filter(|s| s.player == PlayerId::new(1))
+            .collect();
+        assert!(!rotated.is_empty() && rotated.len() < batch.len());
+        for sample in &rotated {
+            // Marker, player and value vector all moved to seat 1
+            assert_eq!(sample.state.tensor[9], 1.0);
+            assert_eq!(sample.values, vec![0.0, 1.0]);
+            assert_eq!(sample.value, 1.0);
+        }
+
+        let again = buffer.sample_batch(20, 5);
+        let players: Vec<_> = batch.iter().map(|s| s.player).collect();
+        let players_again: Vec<_> = again.iter().map(|s| s.player).collect();
+        assert_eq!(players, players_again);
+    }
+
     #[test]
     fn test_trajectory_serialization() {
         let mut traj = Trajectory::new(42, 2);
@@ -633,6 +1228,7 @@ mod tests {
             policy: vec![0.3, 0.7],
             value: 0.8,
             player: PlayerId::new(0),
+            values: vec![0.8, 0.2],
         };
 
         let json = serde_json::to_string(&sample).unwrap();
@@ -669,6 +1265,24 @@ mod tests {
         assert_eq!(samples[2].player, PlayerId::new(0));
     }
 
+    #[test]
+    fn test_step_root_value_serialization() {
+        let mut value = PlayerMap::with_value(2, 0.0);
+        value[PlayerId::new(0)] = 0.6;
+        value[PlayerId::new(1)] = 0.4;
+        let step = make_test_step(0, 0).with_root_value(Some(value.clone()));
+
+        let json = serde_json::to_string(&step).unwrap();
+        let deserialized: Step = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.root_value, Some(value));
+
+        // Steps serialized before root values existed still load
+        let legacy = json.replace(",\"root_value\":{\"data\":[0.6,0.4]}", "");
+        assert_ne!(legacy, json);
+        let deserialized: Step = serde_json::from_str(&legacy).unwrap();
+        assert!(deserialized.root_value.is_none());
+    }
+
     #[test]
     fn test_experience_buffer_fifo_order() {
         let mut buffer = ExperienceBuffer::new(3);
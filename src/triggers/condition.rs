@@ -3,10 +3,17 @@
 //! Conditions determine when a trigger fires based on event data.
 //! The engine provides common condition types; games can use custom
 //! conditions for game-specific logic.
+//!
+//! The `SourceMatches`, `TargetMatches` and `PlayerMatches` conditions test
+//! the event's entities with an [`EntityPredicate`], the same expressions
+//! targeting uses. Players in them are relative to the trigger's
+//! controller.
 
 use serde::{Deserialize, Serialize};
 
+use crate::cards::CardRegistry;
 use crate::core::{EntityId, GameState, PlayerId, ZoneId};
+use crate::effects::{EntityPredicate, PredicateContext};
 
 use super::event::{EventTypeId, GameEvent};
 
@@ -47,6 +54,15 @@ pub enum TriggerCondition {
     /// Target must be in specified zone.
     TargetInZone(ZoneId),
 
+    /// Source must match the predicate.
+    SourceMatches(EntityPredicate),
+
+    /// Target must match the predicate.
+    TargetMatches(EntityPredicate),
+
+    /// Event's player must match the predicate.
+    PlayerMatches(EntityPredicate),
+
     // === Value Filters ===
 
     /// Value at index must be at least N.
@@ -153,14 +169,28 @@ impl TriggerCondition {
     }
 }
 
+/// Game-provided evaluator for `TriggerCondition::Custom` keys.
+///
+/// The lifetime lets evaluators borrow game data.
+pub type CustomConditionFn<'a> = dyn Fn(&str, &GameEvent, &GameState) -> bool + 'a;
+
 /// Context for evaluating trigger conditions.
+#[derive(Clone, Copy)]
 pub struct ConditionContext<'a> {
     /// The event being checked.
     pub event: &'a GameEvent,
     /// Current game state.
     pub state: &'a GameState,
     /// Custom condition evaluator (provided by game).
-    pub eval_custom: Option<&'a dyn Fn(&str, &GameEvent, &GameState) -> bool>,
+    pub eval_custom: Option<&'a CustomConditionFn<'a>>,
+    /// Card definitions, for card type and attribute predicates.
+    pub cards: Option<&'a CardRegistry>,
+    /// Controller of the trigger being checked ("you" in predicates).
+    ///
+    /// Defaults to player 0 when `None`, like the resolution systems do.
+    pub controller: Option<PlayerId>,
+    /// Source of the trigger being checked (`IsSource` in predicates).
+    pub source: Option<EntityId>,
 }
 
 impl<'a> ConditionContext<'a> {
@@ -170,13 +200,39 @@ impl<'a> ConditionContext<'a> {
             event,
             state,
             eval_custom: None,
+            cards: None,
+            controller: None,
+            source: None,
         }
     }
 
+    /// Read card types and attributes from this registry (builder pattern).
+    #[must_use]
+    pub fn with_cards(mut self, cards: &'a CardRegistry) -> Self {
+        self.cards = Some(cards);
+        self
+    }
+
+    /// Evaluate for a trigger with this controller and source (builder pattern).
+    #[must_use]
+    pub fn for_trigger(mut self, controller: Option<PlayerId>, source: Option<EntityId>) -> Self {
+        self.controller = controller;
+        self.source = source;
+        self
+    }
+
+    /// Check an event entity against a predicate.
+    fn matches(&self, predicate: &EntityPredicate, entity: Option<EntityId>) -> bool {
+        let ctx = PredicateContext::new(self.state, self.controller.unwrap_or(PlayerId::new(0)))
+            .with_source(self.source)
+            .with_cards(self.cards);
+        entity.is_some_and(|entity| predicate.matches(&ctx, entity))
+    }
+
     /// Add a custom condition evaluator.
     pub fn with_custom_eval(
         mut self,
-        eval: &'a dyn Fn(&str, &GameEvent, &GameState) -> bool,
+        eval: &'a CustomConditionFn<'a>,
     ) -> Self {
         self.eval_custom = Some(eval);
         self
@@ -236,6 +292,14 @@ impl ConditionEvaluator {
                 }
             }
 
+            TriggerCondition::SourceMatches(predicate) => ctx.matches(predicate, ctx.event.source),
+
+            TriggerCondition::TargetMatches(predicate) => ctx.matches(predicate, ctx.event.target),
+
+            TriggerCondition::PlayerMatches(predicate) => {
+                ctx.matches(predicate, ctx.event.player.map(EntityId::player))
+            }
+
             TriggerCondition::ValueAtLeast { index, min } => {
                 ctx.event.value(*index, i64::MIN) >= *min
             }
@@ -533,11 +597,48 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_predicate_conditions() {
+        use crate::cards::{CardDefinition, CardTypeId};
+        use crate::effects::PlayerMatch;
+
+        let mut cards = CardRegistry::new();
+        cards.register(CardDefinition::new(CardId::new(1), "Goblin", CardTypeId::new(0)).with_attr("power", 2));
+        let mut state = test_state();
+        state.add_card(CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(1), ZoneId::new(0)));
+
+        let event = GameEvent::for_player(EventTypeId::new(1), PlayerId::new(1)).with_source(EntityId(10));
+        let small_opposing = TriggerCondition::SourceMatches(
+            EntityPredicate::ControlledBy(PlayerMatch::Opponent)
+                .and(EntityPredicate::attribute_in_range("power", None, Some(3))),
+        );
+
+        // Players are relative to the trigger's controller
+        let ctx = ConditionContext::new(&event, &state).with_cards(&cards);
+        let ours = ctx.for_trigger(Some(PlayerId::new(0)), None);
+        let theirs = ctx.for_trigger(Some(PlayerId::new(1)), None);
+        assert!(ConditionEvaluator::evaluate(&small_opposing, &ours));
+        assert!(!ConditionEvaluator::evaluate(&small_opposing, &theirs));
+        // Attribute tests need the registry
+        let bare = ConditionContext::new(&event, &state).for_trigger(Some(PlayerId::new(0)), None);
+        assert!(!ConditionEvaluator::evaluate(&small_opposing, &bare));
+
+        let opponent_event = TriggerCondition::PlayerMatches(EntityPredicate::IsPlayer(PlayerMatch::Opponent));
+        assert!(ConditionEvaluator::evaluate(&opponent_event, &ours));
+        assert!(!ConditionEvaluator::evaluate(&opponent_event, &theirs));
+
+        // The event has no target
+        let own_source = ctx.for_trigger(None, Some(EntityId(10)));
+        assert!(ConditionEvaluator::evaluate(&TriggerCondition::SourceMatches(EntityPredicate::IsSource), &own_source));
+        assert!(!ConditionEvaluator::evaluate(&TriggerCondition::TargetMatches(EntityPredicate::all([])), &own_source));
+    }
+
     #[test]
     fn test_condition_serialization() {
         let condition = TriggerCondition::All(vec![
             TriggerCondition::EventType(EventTypeId::new(1)),
             TriggerCondition::ValueAtLeast { index: 0, min: 5 },
+            TriggerCondition::TargetMatches(EntityPredicate::InZone(ZoneId::new(2)).negate()),
         ]);
 
         let json = serde_json::to_string(&condition).unwrap();
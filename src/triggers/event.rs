@@ -64,7 +64,7 @@ impl std::fmt::Display for EventTypeId {
 /// - `player`: The player associated with the event (if any)
 /// - `values`: Numeric values (damage amount, cards drawn, etc.)
 /// - `zones`: Zone information (source zone, destination zone)
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct GameEvent {
     /// The type of event.
     pub event_type: EventTypeId,
@@ -219,7 +219,7 @@ impl GameEvent {
 /// Configuration for an event type.
 ///
 /// Games provide this at startup to document event types.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EventTypeConfig {
     /// Unique identifier for this event type.
     pub id: EventTypeId,
@@ -228,6 +228,7 @@ pub struct EventTypeConfig {
     pub name: String,
 
     /// Description of when this event fires.
+    #[serde(default)]
     pub description: String,
 }
 
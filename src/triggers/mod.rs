@@ -11,6 +11,7 @@
 //! - [`TriggerCondition`]: Rules for when a trigger fires
 //! - [`Trigger`]: A complete trigger definition
 //! - [`TriggerRegistry`]: Storage and lookup for triggers
+//! - [`EventModifier`]: How a `Before` trigger changes a [`PendingEvent`]
 //!
 //! ## Design Philosophy
 //!
@@ -60,8 +61,10 @@
 
 mod condition;
 mod event;
+mod modifier;
 mod registry;
 
-pub use condition::{ConditionContext, ConditionEvaluator, TriggerCondition};
+pub use condition::{ConditionContext, ConditionEvaluator, CustomConditionFn, TriggerCondition};
 pub use event::{EventTypeConfig, EventTypeId, GameEvent};
+pub use modifier::{EventModifier, PendingEvent};
 pub use registry::{Trigger, TriggerId, TriggerRegistry, TriggerTiming, TriggeredEffect};
@@ -0,0 +1,168 @@
+//! Event modifiers for `Before` triggers.
+//!
+//! A `Before` trigger sees an event before the effect behind it resolves.
+//! Its modifiers change the [`PendingEvent`] - adjust values, retarget,
+//! or cancel - and the resolver applies the modified event instead.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{EntityId, ZoneId};
+
+use super::{GameEvent, TriggeredEffect};
+
+/// An event that is about to happen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingEvent {
+    /// The event as it will happen (if not cancelled).
+    pub event: GameEvent,
+    cancelled: bool,
+}
+
+impl PendingEvent {
+    /// Wrap an event that is about to happen.
+    pub fn new(event: GameEvent) -> Self {
+        Self {
+            event,
+            cancelled: false,
+        }
+    }
+
+    /// Cancel the event; the underlying effect does not happen.
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    /// Check if the event was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+/// A change a `Before` trigger makes to the pending event.
+///
+/// Value and zone indices follow the event layout (see `ResolverEvents`);
+/// modifiers naming an index the event doesn't have do nothing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventModifier {
+    /// Add to a value, saturating at the `i64` bounds.
+    AddValue { index: usize, delta: i64 },
+
+    /// Replace a value.
+    SetValue { index: usize, value: i64 },
+
+    /// Multiply a value (e.g., double damage), saturating at the `i64` bounds.
+    MultiplyValue { index: usize, factor: i64 },
+
+    /// Replace a zone (e.g., the destination of a move).
+    SetZone { index: usize, zone: ZoneId },
+
+    /// Change the affected entity.
+    Retarget(EntityId),
+
+    /// Change the affected entity to the trigger's source.
+    RetargetToSource,
+
+    /// Change the affected entity (and player) to the trigger's controller.
+    RetargetToController,
+
+    /// Cancel the event.
+    Cancel,
+}
+
+impl EventModifier {
+    /// Apply this modifier on behalf of a fired trigger.
+    pub fn apply(&self, pending: &mut PendingEvent, triggered: &TriggeredEffect) {
+        let event = &mut pending.event;
+        match self {
+            EventModifier::AddValue { index, delta } => {
+                if let Some(value) = event.values.get_mut(*index) {
+                    *value = value.saturating_add(*delta);
+                }
+            }
+            EventModifier::SetValue { index, value } => {
+                if let Some(current) = event.values.get_mut(*index) {
+                    *current = *value;
+                }
+            }
+            EventModifier::MultiplyValue { index, factor } => {
+                if let Some(value) = event.values.get_mut(*index) {
+                    *value = value.saturating_mul(*factor);
+                }
+            }
+            EventModifier::SetZone { index, zone } => {
+                if let Some(current) = event.zones.get_mut(*index) {
+                    *current = *zone;
+                }
+            }
+            EventModifier::Retarget(target) => event.target = Some(*target),
+            EventModifier::RetargetToSource => {
+                if let Some(source) = triggered.source {
+                    event.target = Some(source);
+                }
+            }
+            EventModifier::RetargetToController => {
+                if let Some(controller) = triggered.controller {
+                    event.target = Some(EntityId::player(controller));
+                    event.player = Some(controller);
+                }
+            }
+            EventModifier::Cancel => pending.cancel(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PlayerId;
+    use crate::triggers::{EventTypeId, TriggerId, TriggerTiming};
+
+    fn triggered() -> TriggeredEffect {
+        TriggeredEffect {
+            trigger_id: TriggerId::new(1),
+            controller: Some(PlayerId::new(1)),
+            source: Some(EntityId(50)),
+            effects: Vec::new(),
+            target: None,
+            modifiers: Vec::new(),
+            triggering_event: GameEvent::new(EventTypeId::new(1)),
+            timing: TriggerTiming::Before,
+        }
+    }
+
+    #[test]
+    fn test_value_modifiers() {
+        let event = GameEvent::new(EventTypeId::new(1)).with_value(-3).with_value(17);
+        let mut pending = PendingEvent::new(event);
+
+        EventModifier::MultiplyValue { index: 0, factor: 2 }.apply(&mut pending, &triggered());
+        EventModifier::AddValue { index: 0, delta: 1 }.apply(&mut pending, &triggered());
+        EventModifier::SetValue { index: 5, value: 9 }.apply(&mut pending, &triggered());
+
+        assert_eq!(pending.event.values, vec![-5, 17]);
+        assert!(!pending.is_cancelled());
+
+        // Values from card data can be extreme
+        EventModifier::MultiplyValue { index: 0, factor: i64::MAX }.apply(&mut pending, &triggered());
+        EventModifier::AddValue { index: 1, delta: i64::MAX }.apply(&mut pending, &triggered());
+        assert_eq!(pending.event.values, vec![i64::MIN, i64::MAX]);
+    }
+
+    #[test]
+    fn test_retarget_and_cancel() {
+        let event = GameEvent::zone_change(EventTypeId::new(1), EntityId(10), ZoneId::new(0), ZoneId::new(1));
+        let mut pending = PendingEvent::new(event);
+
+        EventModifier::RetargetToSource.apply(&mut pending, &triggered());
+        assert_eq!(pending.event.target, Some(EntityId(50)));
+        EventModifier::RetargetToController.apply(&mut pending, &triggered());
+        assert_eq!(pending.event.target, Some(EntityId::player(PlayerId::new(1))));
+        assert_eq!(pending.event.player, Some(PlayerId::new(1)));
+        EventModifier::SetZone { index: 1, zone: ZoneId::new(7) }.apply(&mut pending, &triggered());
+        assert_eq!(pending.event.zone(1), Some(ZoneId::new(7)));
+
+        EventModifier::Cancel.apply(&mut pending, &triggered());
+        assert!(pending.is_cancelled());
+    }
+}
@@ -8,10 +8,11 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::core::{EntityId, GameState, PlayerId};
-use crate::effects::Effect;
+use crate::effects::{Effect, TargetSpec};
 
-use super::condition::{ConditionContext, ConditionEvaluator, TriggerCondition};
+use super::condition::{ConditionContext, ConditionEvaluator, CustomConditionFn, TriggerCondition};
 use super::event::{EventTypeId, GameEvent};
+use super::modifier::EventModifier;
 
 /// Unique identifier for a trigger.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -38,14 +39,20 @@ impl std::fmt::Display for TriggerId {
 }
 
 /// When in the event resolution process the trigger fires.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum TriggerTiming {
-    /// Before the event resolves (can potentially modify/cancel).
+    /// Before the event resolves (can modify or cancel it).
+    ///
+    /// Applied by the effect resolver in priority order; see
+    /// [`EventModifier`] and `ResolverContext::with_triggers`.
     Before,
     /// After the event resolves (most common).
     #[default]
     After,
     /// Instead of the event (replacement effect).
+    ///
+    /// Applied by the effect resolver before the change happens; see
+    /// `ResolverContext::with_triggers`.
     Instead,
 }
 
@@ -81,6 +88,16 @@ pub struct Trigger {
     /// Effects to execute when triggered.
     pub effects: Vec<Effect>,
 
+    /// Targets the controller chooses for the effects.
+    ///
+    /// `None` applies the effects to the controller.
+    #[serde(default)]
+    pub target: Option<TargetSpec>,
+
+    /// Changes to the pending event (`Before` triggers only).
+    #[serde(default)]
+    pub modifiers: Vec<EventModifier>,
+
     /// Is this trigger currently active?
     pub enabled: bool,
 
@@ -104,6 +121,8 @@ impl Trigger {
             condition: TriggerCondition::Always,
             timing: TriggerTiming::default(),
             effects: Vec::new(),
+            target: None,
+            modifiers: Vec::new(),
             enabled: true,
             uses_remaining: None,
             priority: 0,
@@ -140,6 +159,15 @@ impl Trigger {
         self
     }
 
+    /// Add a pending-event modifier (builder pattern).
+    ///
+    /// Only `Before` triggers modify events; see [`EventModifier`].
+    #[must_use]
+    pub fn with_modifier(mut self, modifier: EventModifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
     /// Set the timing (builder pattern).
     #[must_use]
     pub fn with_timing(mut self, timing: TriggerTiming) -> Self {
@@ -154,6 +182,16 @@ impl Trigger {
         self
     }
 
+    /// Have the controller choose targets for the effects (builder pattern).
+    ///
+    /// The trigger needs a controller too; registering it without one
+    /// panics.
+    #[must_use]
+    pub fn with_target(mut self, spec: TargetSpec) -> Self {
+        self.target = Some(spec);
+        self
+    }
+
     /// Set limited uses (builder pattern).
     #[must_use]
     pub fn with_uses(mut self, uses: u32) -> Self {
@@ -184,7 +222,7 @@ impl Trigger {
 }
 
 /// A triggered effect ready to be resolved.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Hash, Serialize, Deserialize)]
 pub struct TriggeredEffect {
     /// The trigger that fired.
     pub trigger_id: TriggerId,
@@ -198,6 +236,15 @@ pub struct TriggeredEffect {
     /// Effects to execute.
     pub effects: Vec<Effect>,
 
+    /// Targets the controller chooses for the effects. Requires a
+    /// controller.
+    #[serde(default)]
+    pub target: Option<TargetSpec>,
+
+    /// Changes to the pending event (`Before` triggers only).
+    #[serde(default)]
+    pub modifiers: Vec<EventModifier>,
+
     /// The event that caused this trigger.
     pub triggering_event: GameEvent,
 
@@ -228,7 +275,12 @@ impl TriggerRegistry {
     }
 
     /// Register a trigger, returns its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the trigger has a target spec but no controller.
     pub fn register(&mut self, mut trigger: Trigger) -> TriggerId {
+        check_controller(&trigger);
         // Assign ID if not set
         if trigger.id.0 == 0 {
             trigger.id = TriggerId::new(self.next_id);
@@ -250,7 +302,12 @@ impl TriggerRegistry {
     }
 
     /// Register a trigger with a specific ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the trigger has a target spec but no controller.
     pub fn register_with_id(&mut self, trigger: Trigger) -> TriggerId {
+        check_controller(&trigger);
         let id = trigger.id;
 
         // Update next_id if needed
@@ -313,8 +370,24 @@ impl TriggerRegistry {
         event: &GameEvent,
         state: &GameState,
         timing: TriggerTiming,
-        custom_eval: Option<&dyn Fn(&str, &GameEvent, &GameState) -> bool>,
+        custom_eval: Option<&CustomConditionFn<'_>>,
     ) -> Vec<TriggeredEffect> {
+        let ctx = if let Some(eval) = custom_eval {
+            ConditionContext::new(event, state).with_custom_eval(eval)
+        } else {
+            ConditionContext::new(event, state)
+        };
+        self.find_triggers_in(&ctx, timing)
+    }
+
+    /// Find all triggers that should fire for the context's event.
+    ///
+    /// Like [`find_triggers`](Self::find_triggers), but the context can
+    /// carry a card registry for predicate conditions. Each trigger's
+    /// condition is checked with its own controller and source.
+    pub fn find_triggers_in(&self, ctx: &ConditionContext, timing: TriggerTiming) -> Vec<TriggeredEffect> {
+        let event = ctx.event;
+
         // Store (priority, trigger_id, effect) for sorting
         let mut results: Vec<(i32, TriggerId, TriggeredEffect)> = Vec::new();
 
@@ -323,12 +396,6 @@ impl TriggerRegistry {
             return Vec::new();
         };
 
-        let ctx = if let Some(eval) = custom_eval {
-            ConditionContext::new(event, state).with_custom_eval(eval)
-        } else {
-            ConditionContext::new(event, state)
-        };
-
         for &trigger_id in trigger_ids {
             let Some(trigger) = self.triggers.get(&trigger_id) else {
                 continue;
@@ -345,6 +412,7 @@ impl TriggerRegistry {
             }
 
             // Check condition
+            let ctx = ctx.for_trigger(trigger.controller, trigger.source);
             if !ConditionEvaluator::evaluate(&trigger.condition, &ctx) {
                 continue;
             }
@@ -354,6 +422,8 @@ impl TriggerRegistry {
                 controller: trigger.controller,
                 source: trigger.source,
                 effects: trigger.effects.clone(),
+                target: trigger.target.clone(),
+                modifiers: trigger.modifiers.clone(),
                 triggering_event: event.clone(),
                 timing: trigger.timing,
             }));
@@ -414,6 +484,13 @@ impl TriggerRegistry {
     }
 }
 
+/// Targets are chosen by the controller, so a targeted trigger needs one.
+fn check_controller(trigger: &Trigger) {
+    if trigger.target.is_some() && trigger.controller.is_none() {
+        panic!("Trigger '{}' has a target spec but no controller", trigger.name);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -447,6 +524,17 @@ mod tests {
         assert!(trigger.can_fire());
     }
 
+    #[test]
+    #[should_panic(expected = "no controller")]
+    fn test_targeted_trigger_needs_controller() {
+        let mut registry = TriggerRegistry::new();
+        registry.register(
+            Trigger::new(TriggerId::new(1), "Aimless", EventTypeId::new(1))
+                .with_target(TargetSpec::single_opponent())
+                .with_effect(Effect::damage(1)),
+        );
+    }
+
     #[test]
     fn test_trigger_uses() {
         let mut trigger = Trigger::new(TriggerId::new(1), "Limited", EventTypeId::new(1))
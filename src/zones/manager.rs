@@ -16,7 +16,7 @@ use crate::core::rng::GameRng;
 use serde::{Deserialize, Serialize};
 
 /// Position for inserting a card into an ordered zone.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ZonePosition {
     /// Add to top of zone (e.g., top of library).
     Top,
@@ -248,6 +248,19 @@ impl ZoneManager {
     }
 }
 
+impl std::hash::Hash for ZoneManager {
+    fn hash<H: std::hash::Hasher>(&self, hasher: &mut H) {
+        // Hash entries in sorted order for determinism
+        let mut locations: Vec<_> = self.locations.iter().collect();
+        locations.sort_by_key(|(entity, _)| entity.0);
+        locations.hash(hasher);
+
+        let mut order: Vec<_> = self.zone_order.iter().collect();
+        order.sort_by_key(|(zone, _)| zone.0);
+        order.hash(hasher);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,206 @@
+//! Declarative game definition integration tests.
+//!
+//! These tests load a game from a definition file and check the result
+//! plugs into the trigger and effect systems.
+
+use rust_ccg::cards::CardInstance;
+use rust_ccg::core::{EntityId, GameState, PlayerId};
+use rust_ccg::data::{CardSet, DefinitionError, DefinitionFormat, GameDefinition, LoadedGame};
+use rust_ccg::effects::{EffectResolver, ResolverContext, TargetSelector};
+use rust_ccg::triggers::{GameEvent, TriggerTiming};
+
+const DEFINITION: &str = r#"{
+    "name": "Ambush",
+    "player_count": 3,
+    "zones": [
+        { "id": 0, "name": "Deck", "owner": 0, "visibility": "Hidden", "ordered": true },
+        { "id": 1, "name": "Deck", "owner": 1, "visibility": "Hidden", "ordered": true },
+        { "id": 2, "name": "Deck", "owner": 2, "visibility": "Hidden", "ordered": true },
+        { "id": 3, "name": "Battlefield" },
+        { "id": 4, "name": "Graveyard", "ordered": true }
+    ],
+    "templates": [{ "id": 0, "name": "Pass" }],
+    "phases": [{ "id": 1, "name": "Main" }],
+    "event_types": [
+        { "id": 1, "name": "TurnStart" },
+        { "id": 2, "name": "CreatureDied" }
+    ],
+    "card_types": [{ "id": 0, "name": "Creature" }],
+    "cards": [{
+        "id": 7,
+        "name": "Vampire",
+        "card_type": 0,
+        "attributes": { "power": 3, "flying": true },
+        "abilities": [{
+            "name": "Feast",
+            "events": [2],
+            "condition": { "TargetInZone": 4 },
+            "effects": [{ "ModifyPlayerState": { "key": "life", "delta": 2 } }]
+        }]
+    }],
+    "triggers": [{
+        "name": "Upkeep",
+        "events": [1],
+        "effects": [{ "ModifyPlayerState": { "key": "life", "delta": -1 } }]
+    }]
+}"#;
+
+fn load() -> LoadedGame {
+    let path = std::env::temp_dir().join(format!("ccg_ambush_{}.json", std::process::id()));
+    std::fs::write(&path, DEFINITION).unwrap();
+    let game = LoadedGame::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    game
+}
+
+/// A loaded definition drives triggers and effects for N players.
+#[test]
+fn test_loaded_game_plays() {
+    let game = load();
+    let player_count = game.config.player_count;
+    let mut state = GameState::new(player_count, 42);
+    for player in 0..player_count {
+        state.public.set_player_state(PlayerId::new(player as u8), "life", 20);
+    }
+
+    // Player 1 controls a Vampire on the battlefield
+    let vampire = game.card("Vampire").unwrap().id;
+    let battlefield = game.zone("Battlefield", None).unwrap();
+    let entity = state.alloc_entity();
+    state.add_card(CardInstance::new(entity, vampire, PlayerId::new(1), battlefield));
+    let mut triggers = game.triggers.clone();
+    game.register_abilities(&mut triggers, vampire, entity, PlayerId::new(1));
+
+    // Global upkeep trigger hits whoever starts their turn
+    let turn_start = game.event_type("TurnStart").unwrap();
+    let context = ResolverContext::simple(player_count);
+    for player in 0..player_count {
+        let player = PlayerId::new(player as u8);
+        let event = GameEvent::for_player(turn_start, player);
+        for fired in triggers.find_triggers(&event, &state, TriggerTiming::After, None) {
+            for effect in &fired.effects {
+                EffectResolver::resolve_single(&mut state, effect, EntityId::player(player), &context);
+            }
+        }
+        assert_eq!(state.public.get_player_state(player, "life", 0), 19);
+    }
+
+    // The Vampire's controller gains life when a creature lands in the graveyard
+    let graveyard = game.zone("Graveyard", None).unwrap();
+    let victim = state.alloc_entity();
+    state.add_card(CardInstance::new(victim, vampire, PlayerId::new(2), graveyard));
+    let died = GameEvent::new(game.event_type("CreatureDied").unwrap()).with_target(victim);
+    let fired = triggers.find_triggers(&died, &state, TriggerTiming::After, None);
+    assert_eq!(fired.len(), 1);
+    let controller = fired[0].controller.unwrap();
+    for effect in &fired[0].effects {
+        EffectResolver::resolve_single(&mut state, effect, EntityId::player(controller), &context);
+    }
+    assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 21);
+}
+
+/// Bad cross-references are reported together with their location.
+#[test]
+fn test_invalid_definition_reports_context() {
+    let broken = DEFINITION
+        .replace(r#""TargetInZone": 4"#, r#""TargetInZone": 40"#)
+        .replace(r#""card_type": 0"#, r#""card_type": 3"#);
+    let def = GameDefinition::parse(&broken, DefinitionFormat::Json).unwrap();
+
+    let error = def.build().unwrap_err();
+    let DefinitionError::Invalid(errors) = &error else {
+        panic!("expected validation errors, got {}", error);
+    };
+    assert_eq!(errors.len(), 2);
+    let message = error.to_string();
+    assert!(message.contains("card 'Vampire' refers to unknown card type 3"));
+    assert!(message.contains("card 'Vampire' ability 'Feast' refers to unknown zone 40"));
+}
+
+/// Card sets saved from one registry load back into a game definition.
+#[test]
+fn test_card_sets_round_trip_through_files() {
+    let game = load();
+    let dir = std::env::temp_dir().join(format!("ccg_sets_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut formats = vec!["json"];
+    if cfg!(feature = "csv") {
+        formats.push("csv");
+    }
+    for extension in formats {
+        let path = dir.join(format!("Vampires.{}", extension));
+        CardSet::from_registry("Vampires", &game.cards).save(&path).unwrap();
+        let set = CardSet::load(&path).unwrap();
+        assert_eq!(set.name, "Vampires");
+
+        let registry = CardSet::merge([&set]).unwrap();
+        let vampire = registry.find_by_attr(CardSet::SET_ATTRIBUTE, "Vampires").next().unwrap();
+        assert_eq!(vampire.get_int("power", 0), 3);
+        assert!(vampire.get_bool("flying", false));
+
+        // The same IDs clash with the definition's own cards
+        let mut def = GameDefinition::parse(DEFINITION, DefinitionFormat::Json).unwrap();
+        def.add_card_set(&set);
+        assert!(matches!(def.build(), Err(DefinitionError::Invalid(_))));
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Card data can describe targets and conditions with predicate
+/// expressions.
+#[test]
+fn test_predicates_from_definition() {
+    let text = DEFINITION.replace(
+        r#""condition": { "TargetInZone": 4 },"#,
+        r#""condition": { "TargetMatches": { "All": [{ "InZone": 4 }, { "OwnedBy": "Opponent" }] } },
+            "target": {
+                "target_type": "Any",
+                "filters": [{ "Matches": { "Any": [
+                    { "All": [
+                        { "InZone": 3 },
+                        { "CardType": 0 },
+                        { "ControlledBy": "Opponent" },
+                        { "AttributeInRange": { "key": "power", "max": 3 } }
+                    ] },
+                    { "IsPlayer": "Any" }
+                ] } }],
+                "count": { "Exactly": 1 },
+                "optional": false
+            },"#,
+    );
+    let game = GameDefinition::parse(&text, DefinitionFormat::Json).unwrap().build().unwrap();
+    let mut state = GameState::new(game.config.player_count, 42);
+
+    let vampire = game.card("Vampire").unwrap().id;
+    let battlefield = game.zone("Battlefield", None).unwrap();
+    let graveyard = game.zone("Graveyard", None).unwrap();
+    let ours = state.alloc_entity();
+    state.add_card(CardInstance::new(ours, vampire, PlayerId::new(1), battlefield));
+    let theirs = state.alloc_entity();
+    state.add_card(CardInstance::new(theirs, vampire, PlayerId::new(2), battlefield));
+    let mut triggers = game.triggers.clone();
+    game.register_abilities(&mut triggers, vampire, ours, PlayerId::new(1));
+
+    // Only an opponent's creature dying feeds the Vampire
+    let died = game.event_type("CreatureDied").unwrap();
+    let own_victim = state.alloc_entity();
+    state.add_card(CardInstance::new(own_victim, vampire, PlayerId::new(1), graveyard));
+    let event = GameEvent::new(died).with_target(own_victim);
+    assert!(triggers.find_triggers(&event, &state, TriggerTiming::After, None).is_empty());
+
+    let victim = state.alloc_entity();
+    state.add_card(CardInstance::new(victim, vampire, PlayerId::new(2), graveyard));
+    let event = GameEvent::new(died).with_target(victim);
+    let fired = triggers.find_triggers(&event, &state, TriggerTiming::After, None);
+    assert_eq!(fired.len(), 1);
+
+    // Any player, or the opponent's Vampire on the battlefield
+    let spec = fired[0].target.clone().unwrap();
+    let selector = TargetSelector::new(spec, PlayerId::new(1))
+        .with_cards(&game.cards)
+        .with_config(&game.config);
+    let mut expected: Vec<EntityId> = PlayerId::all(3).map(EntityId::player).collect();
+    expected.push(theirs);
+    assert_eq!(selector.valid_targets(&state), expected);
+}
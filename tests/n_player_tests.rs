@@ -243,7 +243,7 @@ fn test_turn_order_n_players() {
         .player_count(5)
         .build(42);
 
-    let mut seen_players = vec![false; 5];
+    let mut seen_players = [false; 5];
     let mut actions_taken = 0;
 
     // Play enough actions to see each player act
@@ -330,3 +330,57 @@ fn test_deterministic_replay_n_players() {
         }
     }
 }
+
+/// Test that the game runner seats one agent per player in N-player games.
+#[test]
+fn test_game_runner_n_players() {
+    use rust_ccg::agents::{Agent, GreedyAgent, RandomAgent};
+    use rust_ccg::games::simple::SimpleGame;
+    use rust_ccg::runner::{EndReason, EventLog, GameRunner, RunnerConfig, RunnerEvent};
+
+    for player_count in [2, 3, 5] {
+        let (engine, mut state) = SimpleGameBuilder::new()
+            .player_count(player_count)
+            .starting_life(3)
+            .build(9);
+
+        let log = EventLog::new();
+        let mut runner = GameRunner::new(engine, RunnerConfig::new().with_max_moves(400))
+            .with_observer(log.clone());
+
+        // Greedy in seat 0, random elsewhere
+        let mut agents: Vec<Box<dyn Agent<SimpleGame>>> = (0..player_count)
+            .map(|seat| -> Box<dyn Agent<SimpleGame>> {
+                if seat == 0 {
+                    Box::new(GreedyAgent::new("greedy", |s: &GameState, p: PlayerId| {
+                        s.public.get_player_state(p, "life", 0) as f64
+                    }))
+                } else {
+                    Box::new(RandomAgent::new(format!("random-{}", seat)))
+                }
+            })
+            .collect();
+        let outcome = runner.run(&mut state, 9, &mut agents);
+
+        assert!(matches!(outcome.reason, EndReason::Terminal | EndReason::MaxMoves));
+        assert_eq!(outcome.rewards.player_count(), player_count);
+        let total: f64 = outcome.rewards.iter().map(|(_, r)| r).sum();
+        if outcome.is_finished() {
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+        assert!(outcome
+            .history
+            .iter()
+            .all(|record| record.player.index() < player_count));
+
+        let moves: Vec<_> = log
+            .events()
+            .into_iter()
+            .filter_map(|e| match e {
+                RunnerEvent::MoveMade { record, .. } => Some(record),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(moves, outcome.history);
+    }
+}
@@ -4,9 +4,9 @@
 //! systems work correctly with the effect system.
 
 use rust_ccg::core::{GameState, PlayerId, TemplateId, Action};
-use rust_ccg::effects::{Effect, EffectBatch, ResolverContext};
+use rust_ccg::effects::{Effect, EffectBatch, ResolverContext, ResolverEvents};
 use rust_ccg::stack::{
-    ImmediateResolution, PriorityStack, ResolutionStatus, ResolutionSystem,
+    EventLoop, ImmediateResolution, PriorityStack, ResolutionStatus, ResolutionSystem,
     StackSource,
 };
 use rust_ccg::triggers::{
@@ -385,3 +385,304 @@ fn test_clear_resolvers() {
     stack.clear();
     assert!(stack.is_complete());
 }
+
+// =============================================================================
+// Event Loop Tests
+// =============================================================================
+
+/// Test that resolver events chain triggers through the event loop.
+#[test]
+fn test_event_loop_chains_triggers() {
+    const PLAYER_STATE_CHANGED: EventTypeId = EventTypeId::new(1);
+
+    let mut triggers = TriggerRegistry::new();
+    // When player 1 drops to 10 life or less, they gain a shield
+    triggers.register_with_id(
+        Trigger::new(TriggerId::new(1), "Last Stand", PLAYER_STATE_CHANGED)
+            .with_controller(PlayerId::new(1))
+            .with_uses(1)
+            .with_condition(TriggerCondition::All(vec![
+                TriggerCondition::ForPlayer(PlayerId::new(1)),
+                TriggerCondition::HasTag("life".to_string()),
+                TriggerCondition::ValueAtMost { index: 1, max: 10 },
+            ]))
+            .with_effect(Effect::modify_player("shield", 1)),
+    );
+    // Gaining a shield costs player 2 a life
+    triggers.register_with_id(
+        Trigger::new(TriggerId::new(2), "Backlash", PLAYER_STATE_CHANGED)
+            .with_controller(PlayerId::new(2))
+            .with_condition(TriggerCondition::HasTag("shield".to_string()))
+            .with_effect(Effect::damage(1)),
+    );
+
+    let mut state = GameState::new(3, 42);
+    for player in 0..3 {
+        state.public.set_player_state(PlayerId::new(player), "life", 20);
+    }
+    let context = ResolverContext::simple(3)
+        .with_events(ResolverEvents::new().with_player_state_changed(PLAYER_STATE_CHANGED));
+    let event_loop = EventLoop::new();
+
+    for _ in 0..2 {
+        let mut resolver = ImmediateResolution::new();
+        let mut batch = EffectBatch::new();
+        batch.add_player(Effect::damage(6), PlayerId::new(1));
+        resolver.queue_action(Action::new(TemplateId::new(1)), batch, PlayerId::new(0));
+        let status = event_loop.run(&mut resolver, &mut state, &context, &mut triggers);
+        assert_eq!(status, ResolutionStatus::Complete);
+    }
+
+    assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 8);
+    assert_eq!(state.public.get_player_state(PlayerId::new(1), "shield", 0), 1);
+    assert_eq!(state.public.get_player_state(PlayerId::new(2), "life", 0), 19);
+}
+
+/// Test that triggered effects on the stack compute amounts from their
+/// source and triggering event.
+#[test]
+fn test_triggered_amounts_use_origin() {
+    use rust_ccg::cards::{CardId, CardInstance};
+    use rust_ccg::core::{EntityId, ZoneId};
+    use rust_ccg::effects::{CardRef, ValueExpr};
+
+    const PLAYER_STATE_CHANGED: EventTypeId = EventTypeId::new(1);
+
+    let mut state = GameState::new(2, 42);
+    let battlefield = ZoneId::new(4);
+    state.add_card(CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(1), battlefield));
+    state.get_card_mut(EntityId(10)).unwrap().set_state("power", 2);
+
+    let mut triggers = TriggerRegistry::new();
+    // Whenever player 1 loses life, they gain that much "rage" plus the
+    // source card's power
+    let lost = -ValueExpr::EventValue(0);
+    triggers.register_with_id(
+        Trigger::new(TriggerId::new(1), "Enrage", PLAYER_STATE_CHANGED)
+            .with_controller(PlayerId::new(1))
+            .with_source(EntityId(10))
+            .with_condition(TriggerCondition::All(vec![
+                TriggerCondition::ForPlayer(PlayerId::new(1)),
+                TriggerCondition::HasTag("life".to_string()),
+            ]))
+            .with_effect(Effect::modify_player("rage", lost + ValueExpr::card_state(CardRef::Source, "power"))),
+    );
+
+    let context = ResolverContext::simple(2)
+        .with_events(ResolverEvents::new().with_player_state_changed(PLAYER_STATE_CHANGED));
+    let mut stack = PriorityStack::new(2);
+    let mut batch = EffectBatch::new();
+    batch.add_player(Effect::damage(3), PlayerId::new(1));
+    stack.queue_action(Action::new(TemplateId::new(1)), batch, PlayerId::new(0));
+
+    let event_loop = EventLoop::new();
+    for _ in 0..2 {
+        stack.pass(PlayerId::new(0));
+        stack.pass(PlayerId::new(1));
+        event_loop.run(&mut stack, &mut state, &context, &mut triggers);
+    }
+
+    assert!(stack.is_complete());
+    assert_eq!(state.public.get_player_state(PlayerId::new(1), "rage", 0), 5);
+}
+
+/// Test that the stack waits while a player makes a choice mid-resolution.
+#[test]
+fn test_priority_stack_waits_for_choice() {
+    use rust_ccg::cards::{CardId, CardInstance};
+    use rust_ccg::core::{EntityId, ZoneId};
+    use rust_ccg::effects::{EffectResolver, TargetSpec};
+
+    let mut state = GameState::new(2, 42);
+    state.public.set_player_state(PlayerId::new(0), "life", 20);
+    let hand = ZoneId::new(3);
+    for i in 0..2 {
+        state.add_card(CardInstance::new(EntityId(10 + i), CardId::new(1), PlayerId::new(1), hand));
+    }
+
+    // Player 1 discards a card of their choice, then player 0 gains 2 life
+    let mut stack = PriorityStack::new(2);
+    let mut batch = EffectBatch::new();
+    batch.add_player(
+        Effect::choose(TargetSpec::single_card([hand]), Effect::RemoveFromGame),
+        PlayerId::new(1),
+    );
+    batch.add_player(Effect::heal(2), PlayerId::new(0));
+    stack.queue_action(Action::new(TemplateId::new(1)), batch, PlayerId::new(0));
+
+    let context = ResolverContext::simple(2);
+    stack.pass(PlayerId::new(0));
+    stack.pass(PlayerId::new(1));
+    let status = stack.process(&mut state, &context);
+    assert_eq!(status, ResolutionStatus::WaitingForChoice(PlayerId::new(1)));
+    assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), 20);
+
+    // The choice travels with copies of the state
+    let copy = state.snapshot();
+    assert_eq!(copy.pending_choice().unwrap().options, vec![EntityId(10), EntityId(11)]);
+    assert_eq!(copy.state_hash(), state.state_hash());
+
+    EffectResolver::resolve_choice(&mut state, &[EntityId(11)], &context).unwrap();
+    assert_ne!(copy.state_hash(), state.state_hash());
+    assert!(state.get_card(EntityId(11)).is_none());
+    assert_eq!(state.public.get_player_state(PlayerId::new(0), "life", 0), 22);
+    assert_eq!(stack.process(&mut state, &context), ResolutionStatus::Complete);
+}
+
+/// Test that a trigger's controller chooses its targets before it goes on
+/// the stack.
+#[test]
+fn test_trigger_targets_chosen_by_controller() {
+    use rust_ccg::core::EntityId;
+    use rust_ccg::effects::TargetSpec;
+
+    const PLAYER_STATE_CHANGED: EventTypeId = EventTypeId::new(1);
+
+    let mut state = GameState::new(3, 42);
+    for player in 0..3 {
+        state.public.set_player_state(PlayerId::new(player), "life", 20);
+    }
+
+    // Whenever player 0 gains life, they deal 1 damage to an opponent
+    let mut triggers = TriggerRegistry::new();
+    triggers.register_with_id(
+        Trigger::new(TriggerId::new(1), "Drain", PLAYER_STATE_CHANGED)
+            .with_controller(PlayerId::new(0))
+            .with_condition(TriggerCondition::ForPlayer(PlayerId::new(0)))
+            .with_target(TargetSpec::single_opponent())
+            .with_effect(Effect::damage(1)),
+    );
+
+    let context = ResolverContext::simple(3)
+        .with_events(ResolverEvents::new().with_player_state_changed(PLAYER_STATE_CHANGED));
+    let mut stack = PriorityStack::new(3);
+    let mut batch = EffectBatch::new();
+    batch.add_player(Effect::heal(3), PlayerId::new(0));
+    stack.queue_action(Action::new(TemplateId::new(1)), batch, PlayerId::new(0));
+
+    let event_loop = EventLoop::new();
+    for player in 0..3 {
+        stack.pass(PlayerId::new(player));
+    }
+    let status = event_loop.run(&mut stack, &mut state, &context, &mut triggers);
+    assert_eq!(status, ResolutionStatus::WaitingForTargets(PlayerId::new(0)));
+
+    // A rules engine offers the targets as pointers, one at a time
+    let pending = state.pending_targets().unwrap();
+    let first = pending.legal_pointers(&[]);
+    assert_eq!(first, vec![EntityId::player_id(1), EntityId::player_id(2)]);
+    assert!(pending.legal_pointers(&first[1..]).is_empty());
+
+    stack.choose_targets(&mut state, &first[1..]).unwrap();
+    assert!(state.pending_targets().is_none());
+    assert_eq!(stack.peek_top().unwrap().targets, vec![EntityId::player_id(2)]);
+
+    for player in 0..3 {
+        stack.pass(PlayerId::new(player));
+    }
+    let status = event_loop.run(&mut stack, &mut state, &context, &mut triggers);
+    assert_eq!(status, ResolutionStatus::Complete);
+    assert_eq!(state.public.get_player_state(PlayerId::new(1), "life", 0), 20);
+    assert_eq!(state.public.get_player_state(PlayerId::new(2), "life", 0), 19);
+}
+
+/// Test that an entry fizzles when its target is removed in response, and
+/// that games can react to the fizzle.
+#[test]
+fn test_entry_fizzles_when_target_removed() {
+    use rust_ccg::cards::{CardId, CardInstance};
+    use rust_ccg::core::{EntityId, ZoneId};
+    use rust_ccg::effects::TargetSpec;
+
+    const ENTRY_FIZZLED: EventTypeId = EventTypeId::new(1);
+
+    let battlefield = ZoneId::new(4);
+    let mut state = GameState::new(2, 42);
+    state.add_card(CardInstance::new(EntityId(10), CardId::new(1), PlayerId::new(1), battlefield));
+
+    // Whenever player 0's spell fizzles, they gain a consolation point
+    let mut triggers = TriggerRegistry::new();
+    triggers.register_with_id(
+        Trigger::new(TriggerId::new(1), "Consolation", ENTRY_FIZZLED)
+            .with_controller(PlayerId::new(0))
+            .with_condition(TriggerCondition::ForPlayer(PlayerId::new(0)))
+            .with_effect(Effect::modify_player("consolation", 1)),
+    );
+
+    // Player 0 marks player 1's creature; player 1 removes it in response
+    let context = ResolverContext::simple(2).with_events(ResolverEvents::new().with_entry_fizzled(ENTRY_FIZZLED));
+    let mut stack = PriorityStack::new(2);
+    let mut batch = EffectBatch::new();
+    batch.add_single(Effect::modify_card("marked", 1), EntityId(10));
+    stack.queue_targeted(
+        Action::new(TemplateId::new(1)),
+        batch,
+        PlayerId::new(0),
+        TargetSpec::single_card([battlefield]),
+        vec![EntityId(10)],
+    );
+    let mut response = EffectBatch::new();
+    response.add_single(Effect::RemoveFromGame, EntityId(10));
+    stack.pass(PlayerId::new(0));
+    stack.respond(response, PlayerId::new(1), "Banish".to_string());
+
+    let event_loop = EventLoop::new();
+    while !stack.is_complete() {
+        let player = stack.priority_player().unwrap();
+        stack.pass(player);
+        event_loop.run(&mut stack, &mut state, &context, &mut triggers);
+    }
+
+    assert!(state.get_card(EntityId(10)).is_none());
+    assert_eq!(state.public.get_player_state(PlayerId::new(0), "consolation", 0), 1);
+}
+
+/// Test that trigger targets are filtered by card definitions and game
+/// callbacks from the resolver context.
+#[test]
+fn test_trigger_targets_use_registry_and_custom_filters() {
+    use rust_ccg::cards::{CardDefinition, CardId, CardInstance, CardRegistry, CardTypeId};
+    use rust_ccg::core::{EntityId, ZoneId};
+    use rust_ccg::effects::{TargetFilter, TargetSpec};
+
+    let creature = CardTypeId::new(0);
+    let mut cards = CardRegistry::new();
+    cards.register(CardDefinition::new(CardId::new(1), "Goblin", creature).with_attr("power", 2));
+    cards.register(CardDefinition::new(CardId::new(2), "Wall", creature).with_attr("power", 0));
+    cards.register(CardDefinition::new(CardId::new(3), "Bolt", CardTypeId::new(1)));
+
+    let battlefield = ZoneId::new(4);
+    let mut state = GameState::new(2, 42);
+    for id in 1..=3 {
+        state.add_card(CardInstance::new(EntityId(10 + id), CardId::new(id), PlayerId::new(1), battlefield));
+    }
+    state.get_card_mut(EntityId(11)).unwrap().set_state("tapped", 1);
+
+    // Only untapped creatures can be targeted: the Goblin is tapped and
+    // the Bolt is not a creature
+    let spec = TargetSpec::single_card([battlefield])
+        .with_filter(TargetFilter::HasCardType(creature))
+        .with_filter(TargetFilter::Custom("untapped".to_string()));
+    let trigger = Trigger::new(TriggerId::new(1), "Shrink", EventTypeId::new(1))
+        .with_controller(PlayerId::new(0))
+        .with_target(spec.clone())
+        .with_effect(Effect::modify_card("shrunk", 1));
+    let mut triggers = TriggerRegistry::new();
+    triggers.register_with_id(trigger);
+
+    let context = ResolverContext::simple(2)
+        .with_cards(&cards)
+        .with_target_filter("untapped", |state: &GameState, entity: EntityId, _: PlayerId| {
+            state.get_card(entity).is_some_and(|card| card.get_state("tapped", 0) == 0)
+        });
+    let mut resolver = ImmediateResolution::new();
+    for triggered in triggers.find_triggers(&GameEvent::new(EventTypeId::new(1)), &state, TriggerTiming::After, None) {
+        resolver.queue_triggered(triggered);
+    }
+
+    // One legal target, so it is chosen automatically
+    assert_eq!(resolver.process(&mut state, &context), ResolutionStatus::Complete);
+    assert_eq!(state.get_card(EntityId(12)).unwrap().get_state("shrunk", 0), 1);
+    assert_eq!(state.get_card(EntityId(11)).unwrap().get_state("shrunk", 0), 0);
+    assert_eq!(state.get_card(EntityId(13)).unwrap().get_state("shrunk", 0), 0);
+}
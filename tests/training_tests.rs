@@ -7,7 +7,12 @@ use rust_ccg::nn::{
     EncodedState, PolicyNetwork, PolicyValueNetwork, SimpleGameEncoder, StateEncoder, UniformPolicy,
     UniformPolicyZeroValue, ZeroEncoder,
 };
-use rust_ccg::training::{ExperienceBuffer, SelfPlayConfig, SelfPlayWorker, Step, Trajectory};
+use rust_ccg::agents::{MctsAgent, NetworkMctsAgent, RandomAgent, ReplayAgent};
+use rust_ccg::training::{
+    Arena, ArenaConfig,
+    ExperienceBuffer, ReanalyseConfig, Reanalyser, ReplayConfig, SelfPlayConfig, SelfPlayWorker,
+    Step, StorageConfig, Stratify, Trajectory, TrajectoryReader, TrajectoryWriter, ValueTarget,
+};
 
 // =============================================================================
 // Encoder Tests
@@ -211,6 +216,41 @@ fn test_experience_buffer_sampling() {
     assert_eq!(samples.len(), 3);
 }
 
+#[test]
+fn test_experience_buffer_prioritized_replay() {
+    let (engine, mut state) = SimpleGameBuilder::new()
+        .player_count(2)
+        .starting_life(3)
+        .build(42);
+    let encoder = Box::new(SimpleGameEncoder::new(2, 10));
+    let config = SelfPlayConfig::default()
+        .with_mcts_iterations(5)
+        .with_max_moves(20);
+    let worker = SelfPlayWorker::new(engine, encoder, config);
+
+    let mut buffer = ExperienceBuffer::new(10).with_replay(
+        ReplayConfig::new()
+            .with_priorities(0.6, 0.4)
+            .with_stratify(Stratify::Player),
+    );
+    let trajectory = worker.play_game(&mut state, 42);
+    let both_players = trajectory.player_steps(PlayerId::new(1)).count() > 0;
+    buffer.push(trajectory);
+
+    // Trainer loop: sample, compute losses, feed priorities back
+    let batch = buffer.sample_replay(16, 1);
+    assert_eq!(batch.len(), 16);
+    let updates: Vec<_> = batch.iter().map(|r| (r.id, 2.0)).collect();
+    assert!(buffer.update_priorities(&updates) > 0);
+    assert_eq!(buffer.priority(batch[0].id), Some(2.0));
+
+    let again = buffer.sample_replay(16, 2);
+    assert_eq!(again.len(), 16);
+    let p0 = again.iter().filter(|r| r.sample.player == PlayerId::new(0)).count();
+    // Stratification splits the batch evenly between the acting players
+    assert_eq!(p0, if both_players { 8 } else { 16 });
+}
+
 // =============================================================================
 // Self-Play Tests
 // =============================================================================
@@ -372,6 +412,140 @@ fn test_training_pipeline_integration() {
     }
 }
 
+#[test]
+fn test_reanalyse_buffer_pipeline() {
+    // self-play -> buffer -> reanalyse with a newer network -> samples
+    let build = |seed| {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(3)
+            .build(seed)
+    };
+
+    let (engine, _) = build(0);
+    let encoder = Box::new(SimpleGameEncoder::new(2, 10));
+    let config = SelfPlayConfig::default()
+        .with_mcts_iterations(5)
+        .with_max_moves(20);
+    let worker = SelfPlayWorker::new(engine, encoder, config);
+
+    let mut buffer = ExperienceBuffer::new(10);
+    for trajectory in worker.play_games(build, 2) {
+        buffer.push(trajectory);
+    }
+
+    let reanalyser = Reanalyser::new(ReanalyseConfig::new().with_mcts_iterations(10));
+    let network = UniformPolicyZeroValue::new(10, 2);
+    let summary = reanalyser.reanalyse_buffer(&mut buffer, build, &network);
+
+    assert_eq!(summary.steps, buffer.total_steps());
+    assert!(summary.failures.is_empty());
+    for sample in buffer.to_training_samples() {
+        let sum: f32 = sample.policy.iter().sum();
+        assert!((sum - 1.0).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_trajectory_storage_pipeline() {
+    // Self-play process writes shards, training process streams batches
+    let dir = std::env::temp_dir().join(format!("rust-ccg-pipeline-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let build = |seed| {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(3)
+            .build(seed)
+    };
+    let (engine, _) = build(0);
+    let encoder = Box::new(SimpleGameEncoder::new(2, 10));
+    let config = SelfPlayConfig::default()
+        .with_mcts_iterations(5)
+        .with_max_moves(20);
+    let worker = SelfPlayWorker::new(engine, encoder, config);
+    let trajectories = worker.play_games(build, 3);
+
+    let storage = StorageConfig::new().with_max_trajectories_per_shard(2);
+    let mut writer = TrajectoryWriter::create(&dir, storage).unwrap();
+    writer.write_all(&trajectories).unwrap();
+
+    let reader = TrajectoryReader::open(&dir).unwrap();
+    assert_eq!(reader.len(), 3);
+    assert_eq!(reader.shard_count(), 2);
+
+    let expected: usize = trajectories.iter().map(|t| t.len()).sum();
+    let mut total = 0;
+    for batch in reader.shuffled_batches(8, ValueTarget::td_lambda(0.8), 1) {
+        let batch = batch.unwrap();
+        assert!(batch.len() <= 8);
+        total += batch.len();
+    }
+    assert_eq!(total, expected);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_arena_network_agent_vs_random() {
+    let build = |seed| {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(seed)
+    };
+
+    let network = NetworkMctsAgent::new(
+        "network",
+        UniformPolicyZeroValue::new(10, 2),
+        Box::new(SimpleGameEncoder::new(2, 10)),
+        50,
+    );
+    let mut arena = Arena::new(ArenaConfig::new().with_rounds(2).with_seed(11))
+        .with_agent(network)
+        .with_agent(MctsAgent::new("mcts", 50))
+        .with_agent(RandomAgent::new("random"));
+
+    let report = arena.run(build);
+
+    // 3 agents in 2 seats: 6 seat assignments per round
+    assert_eq!(report.games.len(), 12);
+    for i in 0..3 {
+        assert_eq!(report.total_record(i).games(), 8);
+    }
+    assert_eq!(report.elo.len(), 3);
+    assert!(report.elo.iter().all(|e| e.lower <= e.elo && e.elo <= e.upper));
+}
+
+#[test]
+fn test_replay_agents_reproduce_self_play_game() {
+    let build = || {
+        SimpleGameBuilder::new()
+            .player_count(2)
+            .starting_life(5)
+            .build(21)
+    };
+    let config = SelfPlayConfig::default()
+        .with_mcts_iterations(10)
+        .with_max_moves(60);
+
+    let (engine, mut state) = build();
+    let worker = SelfPlayWorker::new(engine, Box::new(SimpleGameEncoder::new(2, 10)), config);
+    let original = worker.play_game(&mut state, 21);
+
+    // Each seat replays its own recorded actions from the same start
+    let mut agents: Vec<ReplayAgent> = PlayerId::all(2)
+        .map(|p| ReplayAgent::from_trajectory(format!("replay-{}", p.0), &original, p))
+        .collect();
+    let (_engine, mut state) = build();
+    let replayed = worker.play_game_with_agents(&mut state, 21, &mut agents);
+
+    let actions = |t: &Trajectory| t.steps.iter().map(|s| s.action_taken.clone()).collect::<Vec<_>>();
+    assert_eq!(actions(&replayed), actions(&original));
+    assert_eq!(replayed.outcome, original.outcome);
+    assert!(agents.iter().all(|a| a.remaining() == 0));
+}
+
 // =============================================================================
 // Serialization Tests
 // =============================================================================
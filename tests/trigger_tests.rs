@@ -5,7 +5,7 @@
 
 use rust_ccg::cards::{CardId, CardInstance};
 use rust_ccg::core::{EntityId, GameState, PlayerId, ZoneId};
-use rust_ccg::effects::Effect;
+use rust_ccg::effects::{Effect, EffectResolver, ResolverContext, ResolverEvents};
 use rust_ccg::triggers::{
     EventTypeId, GameEvent, Trigger, TriggerCondition,
     TriggerId, TriggerRegistry, TriggerTiming,
@@ -437,3 +437,108 @@ fn test_tag_based_triggers() {
         1
     );
 }
+
+/// Test a replacement effect ("exile it instead") alongside After triggers.
+#[test]
+fn test_replacement_redirects_zone_change() {
+    const ZONE_CHANGE: EventTypeId = EventTypeId::new(6);
+    let battlefield = ZoneId::new(10);
+    let graveyard = ZoneId::new(11);
+    let exile = ZoneId::new(12);
+
+    let mut state = GameState::new(3, 42);
+    let creature = EntityId(100);
+    state.add_card(CardInstance::new(creature, CardId::new(1), PlayerId::new(2), battlefield));
+
+    // Player 0: "If a creature of player 2's would die, exile it instead"
+    let mut replacements = TriggerRegistry::new();
+    replacements.register_with_id(
+        Trigger::new(TriggerId::new(1), "Rest in Peace", ZONE_CHANGE)
+            .with_controller(PlayerId::new(0))
+            .with_timing(TriggerTiming::Instead)
+            .with_condition(
+                TriggerCondition::TargetControlledBy(PlayerId::new(2))
+                    .and(TriggerCondition::Custom("dying".to_string())),
+            )
+            .with_effect(Effect::move_to_top(exile)),
+    );
+
+    // Custom conditions on replacements use the resolver's evaluator
+    let context = ResolverContext::simple(3)
+        .with_events(ResolverEvents::new().with_card_moved(ZONE_CHANGE))
+        .with_triggers(&replacements)
+        .with_condition_eval(|key, state| state.public.get_turn_state(key, 0) != 0);
+
+    state.public.set_turn_state("dying", 1);
+    EffectResolver::resolve_single(&mut state, &Effect::move_to_top(graveyard), creature, &context);
+
+    assert!(state.zones.is_in_zone(creature, exile));
+    assert_eq!(state.get_card(creature).unwrap().zone, exile);
+    let events = context.take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].zones, vec![battlefield, exile]);
+    assert_eq!(context.take_applied_triggers(), vec![TriggerId::new(1)]);
+}
+
+/// Test that a death trigger can create a token, and that a replacement
+/// can remove the token from the game instead of letting it die.
+#[test]
+fn test_tokens_created_and_removed() {
+    use rust_ccg::core::{Action, TemplateId};
+    use rust_ccg::effects::EffectBatch;
+    use rust_ccg::stack::{EventLoop, ImmediateResolution, ResolutionSystem};
+
+    const CARD_MOVED: EventTypeId = EventTypeId::new(6);
+    const CARD_CREATED: EventTypeId = EventTypeId::new(7);
+    const CARD_REMOVED: EventTypeId = EventTypeId::new(8);
+    let battlefield = ZoneId::new(10);
+    let graveyard = ZoneId::new(11);
+    let token_card = CardId::new(50);
+
+    let mut state = GameState::new(2, 42);
+    let spawner = EntityId(100);
+    state.add_card(CardInstance::new(spawner, CardId::new(1), PlayerId::new(1), battlefield));
+    let events = ResolverEvents::new()
+        .with_card_moved(CARD_MOVED)
+        .with_card_created(CARD_CREATED)
+        .with_card_removed(CARD_REMOVED);
+
+    // "When this dies, create a token for its controller"
+    let mut triggers = TriggerRegistry::new();
+    triggers.register_with_id(
+        Trigger::new(TriggerId::new(1), "Spawn", CARD_MOVED)
+            .with_controller(PlayerId::new(1))
+            .with_source(spawner)
+            .with_condition(TriggerCondition::TargetIs(spawner))
+            .with_effect(Effect::create_card(token_card, battlefield)),
+    );
+    let context = ResolverContext::simple(2).with_events(events);
+    let mut resolution = ImmediateResolution::new();
+    let mut batch = EffectBatch::new();
+    batch.add_single(Effect::move_to_top(graveyard), spawner);
+    resolution.queue_action(Action::new(TemplateId::new(0)), batch, PlayerId::new(0));
+    EventLoop::new().run(&mut resolution, &mut state, &context, &mut triggers);
+
+    let token = state.zones.cards_in_zone(battlefield).next().unwrap();
+    assert_eq!(state.get_card(token).unwrap().card_id, token_card);
+    assert_eq!(state.get_card(token).unwrap().owner, Some(PlayerId::new(1)));
+
+    // "If the token would die, remove it from the game instead"
+    let mut replacements = TriggerRegistry::new();
+    replacements.register_with_id(
+        Trigger::new(TriggerId::new(2), "Fade", CARD_MOVED)
+            .with_timing(TriggerTiming::Instead)
+            .with_condition(TriggerCondition::TargetIs(token))
+            .with_effect(Effect::RemoveFromGame),
+    );
+    let context = ResolverContext::simple(2).with_events(events).with_triggers(&replacements);
+    EffectResolver::resolve_single(&mut state, &Effect::move_to_top(graveyard), token, &context);
+
+    assert!(state.get_card(token).is_none());
+    assert!(state.zones.is_in_zone(spawner, graveyard));
+    assert_eq!(state.zones.zone_size(graveyard), 1);
+    let events = context.take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, CARD_REMOVED);
+    assert_eq!(events[0].zones, vec![battlefield]);
+}